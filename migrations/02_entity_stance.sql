@@ -0,0 +1,31 @@
+-- Entity stance toward the claim under investigation.
+-- Rows written before this migration default to 'neutral'.
+ALTER TABLE entity
+  ADD COLUMN stance TEXT NOT NULL DEFAULT 'neutral'
+  CHECK (stance IN ('supports','refutes','neutral'));
+
+DROP VIEW IF EXISTS v_artifact_entities;
+DROP VIEW IF EXISTS v_entity;
+
+CREATE VIEW IF NOT EXISTS v_entity AS
+SELECT
+  id,
+  article_id,         -- FK -> normalized_artifact.internal_id
+  name,
+  credibility,
+  stance,
+  substr(reasoning, 1, 2000) AS reasoning,
+  created_at,
+  updated_at
+FROM entity;
+
+CREATE VIEW IF NOT EXISTS v_artifact_entities AS
+SELECT
+  a.internal_id   AS artifact_id,
+  a.external_id   AS artifact_external_id,
+  e.id            AS entity_id,
+  e.name          AS entity_name,
+  e.credibility   AS entity_credibility,
+  e.stance        AS entity_stance
+FROM v_artifact a
+JOIN v_entity   e ON e.article_id = a.internal_id;
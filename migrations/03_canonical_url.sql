@@ -0,0 +1,20 @@
+-- Canonical source URL (see nowhere_common::urls::url_key), so a page ingested through
+-- more than one source (a tweet linking an article, and the article itself) can be linked.
+ALTER TABLE normalized_artifact ADD COLUMN canonical_url TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_artifact_canonical_url ON normalized_artifact(canonical_url);
+
+DROP VIEW IF EXISTS v_artifact;
+
+CREATE VIEW IF NOT EXISTS v_artifact AS
+SELECT
+  internal_id,
+  external_id,
+  claim_relevance,
+  substr(reasoning, 1, 2000)       AS reasoning,
+  substr(provenance_info, 1, 2000) AS provenance_info,
+  claim_id,
+  canonical_url,
+  created_at,
+  updated_at
+FROM normalized_artifact;
@@ -0,0 +1,25 @@
+-- Structured provenance (see nowhere_actors::Provenance), so sources can be rendered
+-- reliably instead of parsed out of the free-form provenance_info note.
+ALTER TABLE normalized_artifact ADD COLUMN source_url TEXT;
+ALTER TABLE normalized_artifact ADD COLUMN author TEXT;
+ALTER TABLE normalized_artifact ADD COLUMN captured_at TEXT;
+ALTER TABLE normalized_artifact ADD COLUMN platform TEXT;
+
+DROP VIEW IF EXISTS v_artifact;
+
+CREATE VIEW IF NOT EXISTS v_artifact AS
+SELECT
+  internal_id,
+  external_id,
+  claim_relevance,
+  substr(reasoning, 1, 2000)       AS reasoning,
+  substr(provenance_info, 1, 2000) AS provenance_info,
+  claim_id,
+  canonical_url,
+  source_url,
+  author,
+  captured_at,
+  platform,
+  created_at,
+  updated_at
+FROM normalized_artifact;
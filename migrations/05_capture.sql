@@ -0,0 +1,25 @@
+-- Archived copies of a web page (see nowhere_web::capture), kept with the provenance so
+-- the evidence can be opened later even if the page changes or disappears.
+ALTER TABLE normalized_artifact ADD COLUMN html_path TEXT;
+ALTER TABLE normalized_artifact ADD COLUMN screenshot_path TEXT;
+
+DROP VIEW IF EXISTS v_artifact;
+
+CREATE VIEW IF NOT EXISTS v_artifact AS
+SELECT
+  internal_id,
+  external_id,
+  claim_relevance,
+  substr(reasoning, 1, 2000)       AS reasoning,
+  substr(provenance_info, 1, 2000) AS provenance_info,
+  claim_id,
+  canonical_url,
+  source_url,
+  author,
+  captured_at,
+  platform,
+  html_path,
+  screenshot_path,
+  created_at,
+  updated_at
+FROM normalized_artifact;
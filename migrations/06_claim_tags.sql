@@ -0,0 +1,2 @@
+-- Free-form labels for grouping claims, stored as a JSON array of strings.
+ALTER TABLE claim ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,41 @@
+-- One row per real-world person/org across artifacts. `key` is the normalized name
+-- (casefolded, leading '@' and punctuation/whitespace dropped), so "Terry McLaurin"
+-- and "@TerryMcLaurin" share a row. Entities written before this migration keep a
+-- NULL canonical_id until their artifact is re-ingested.
+CREATE TABLE IF NOT EXISTS canonical_entity (
+  id          TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+  key         TEXT NOT NULL UNIQUE,
+  name        TEXT NOT NULL,                                          -- first spelling seen
+  created_at  TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+);
+
+ALTER TABLE entity ADD COLUMN canonical_id TEXT REFERENCES canonical_entity(id);
+CREATE INDEX IF NOT EXISTS idx_entity_canonical ON entity(canonical_id);
+
+DROP VIEW IF EXISTS v_artifact_entities;
+DROP VIEW IF EXISTS v_entity;
+
+CREATE VIEW IF NOT EXISTS v_entity AS
+SELECT
+  id,
+  article_id,         -- FK -> normalized_artifact.internal_id
+  canonical_id,       -- FK -> canonical_entity.id (NULL if unresolved)
+  name,
+  credibility,
+  stance,
+  substr(reasoning, 1, 2000) AS reasoning,
+  created_at,
+  updated_at
+FROM entity;
+
+CREATE VIEW IF NOT EXISTS v_artifact_entities AS
+SELECT
+  a.internal_id   AS artifact_id,
+  a.external_id   AS artifact_external_id,
+  e.id            AS entity_id,
+  e.canonical_id  AS canonical_entity_id,
+  e.name          AS entity_name,
+  e.credibility   AS entity_credibility,
+  e.stance        AS entity_stance
+FROM v_artifact a
+JOIN v_entity   e ON e.article_id = a.internal_id;
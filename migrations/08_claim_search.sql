@@ -0,0 +1,12 @@
+-- The search each claim's collection ran with (the LLM-built query and its date window),
+-- one row per search round, so what was searched can be shown and reproduced later.
+CREATE TABLE IF NOT EXISTS claim_search (
+  id         INTEGER PRIMARY KEY AUTOINCREMENT,
+  claim_id   TEXT NOT NULL,
+  query      TEXT NOT NULL,
+  date_from  TEXT NOT NULL, -- RFC 3339, UTC
+  date_to    TEXT NOT NULL, -- RFC 3339, UTC
+  created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_claim_search_claim ON claim_search(claim_id, id);
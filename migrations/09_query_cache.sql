@@ -0,0 +1,10 @@
+-- Search queries built from claims, keyed by a hash of the prompts that built them (which
+-- include the claim text), so resubmitting an identical claim can reuse the query instead
+-- of asking the LLM again. See LlmActor::with_query_cache.
+CREATE TABLE IF NOT EXISTS query_cache (
+  key        TEXT PRIMARY KEY,
+  query      TEXT NOT NULL,
+  date_from  TEXT NOT NULL, -- RFC 3339, UTC
+  date_to    TEXT NOT NULL, -- RFC 3339, UTC
+  created_at TEXT NOT NULL  -- RFC 3339, UTC; entries older than the TTL are ignored
+);
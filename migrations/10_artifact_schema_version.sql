@@ -0,0 +1,26 @@
+-- Layout version of each normalized artifact (see nowhere_actors::ARTIFACT_SCHEMA_VERSION),
+-- so reads can fill in fields that older rows never recorded. Rows stored before this
+-- migration are version 1; the store writes the current version on every upsert.
+ALTER TABLE normalized_artifact ADD COLUMN schema_version INTEGER NOT NULL DEFAULT 1;
+
+DROP VIEW IF EXISTS v_artifact;
+
+CREATE VIEW IF NOT EXISTS v_artifact AS
+SELECT
+  internal_id,
+  external_id,
+  claim_relevance,
+  substr(reasoning, 1, 2000)       AS reasoning,
+  substr(provenance_info, 1, 2000) AS provenance_info,
+  claim_id,
+  canonical_url,
+  source_url,
+  author,
+  captured_at,
+  platform,
+  html_path,
+  screenshot_path,
+  schema_version,
+  created_at,
+  updated_at
+FROM normalized_artifact;
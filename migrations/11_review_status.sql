@@ -0,0 +1,28 @@
+-- An investigator's verdict on each artifact (see nowhere_actors::ReviewStatus), set with
+-- `/review`. Chat retrieval prefers confirmed artifacts and skips dismissed ones.
+ALTER TABLE normalized_artifact
+  ADD COLUMN review_status TEXT NOT NULL DEFAULT 'pending'
+  CHECK (review_status IN ('pending','confirmed','dismissed'));
+
+DROP VIEW IF EXISTS v_artifact;
+
+CREATE VIEW IF NOT EXISTS v_artifact AS
+SELECT
+  internal_id,
+  external_id,
+  claim_relevance,
+  substr(reasoning, 1, 2000)       AS reasoning,
+  substr(provenance_info, 1, 2000) AS provenance_info,
+  claim_id,
+  canonical_url,
+  source_url,
+  author,
+  captured_at,
+  platform,
+  html_path,
+  screenshot_path,
+  schema_version,
+  review_status,
+  created_at,
+  updated_at
+FROM normalized_artifact;
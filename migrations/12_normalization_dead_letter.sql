@@ -0,0 +1,14 @@
+-- Raw artifacts whose normalization reply could not be parsed, kept with the (truncated)
+-- reply and the parse error so they can be inspected and normalized again later. See
+-- LlmActor::with_dead_letter.
+CREATE TABLE IF NOT EXISTS normalization_dead_letter (
+  id           INTEGER PRIMARY KEY AUTOINCREMENT,
+  external_id  TEXT NOT NULL,
+  claim_id     TEXT NOT NULL,
+  payload      TEXT NOT NULL, -- the raw artifact JSON sent to the model
+  error        TEXT NOT NULL,
+  model_output TEXT NOT NULL,
+  created_at   TEXT NOT NULL  -- RFC 3339, UTC
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letter_claim ON normalization_dead_letter(claim_id);
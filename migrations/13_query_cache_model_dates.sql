@@ -0,0 +1,12 @@
+-- The query cache keeps the dates the model gave (NULL when it gave none) instead of the
+-- window they resolved to, so a cache hit resolves them again against the current date.
+-- Cached queries are cheap to build again, so the table is recreated rather than converted.
+DROP TABLE IF EXISTS query_cache;
+
+CREATE TABLE query_cache (
+  key        TEXT PRIMARY KEY,
+  query      TEXT NOT NULL,
+  date_from  TEXT,          -- as the model gave it
+  date_to    TEXT,          -- as the model gave it
+  created_at TEXT NOT NULL  -- RFC 3339, UTC; entries older than the TTL are ignored
+);
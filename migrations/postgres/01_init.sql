@@ -0,0 +1,106 @@
+-- Postgres schema for `nowhere_actors::pg::PgStoreActor` (build with `--features postgres`).
+-- Equivalent to the SQLite migrations 01-08 in the parent directory, folded into one file;
+-- ids stay TEXT so rows read back the same way on either backend. Needs Postgres 13+
+-- (gen_random_uuid).
+
+CREATE TABLE IF NOT EXISTS claim (
+  id         TEXT PRIMARY KEY,
+  text       TEXT NOT NULL DEFAULT '',
+  tags       TEXT[] NOT NULL DEFAULT '{}',                          -- free-form labels
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_claim_tags ON claim USING GIN (tags);
+
+-- Main artifact table: 1 row per normalized artifact.
+CREATE TABLE IF NOT EXISTS normalized_artifact (
+  internal_id     TEXT PRIMARY KEY,                                  -- UUID as text
+  external_id     TEXT NOT NULL UNIQUE,                              -- source-side identifier
+  claim_relevance BOOLEAN NOT NULL,
+  reasoning       TEXT NOT NULL DEFAULT '',
+  provenance_info TEXT NOT NULL DEFAULT '',
+  claim_id        TEXT NOT NULL,
+  canonical_url   TEXT,                                              -- see nowhere_common::urls::url_key
+
+  -- Structured provenance (see nowhere_actors::Provenance).
+  source_url      TEXT,
+  author          TEXT,
+  captured_at     TIMESTAMPTZ,
+  platform        TEXT,
+  html_path       TEXT,
+  screenshot_path TEXT,
+
+  -- Full-text search document, kept up to date by Postgres itself.
+  search_doc      tsvector GENERATED ALWAYS AS (
+                    to_tsvector('english',
+                      external_id || ' ' || reasoning || ' ' || provenance_info)
+                  ) STORED,
+
+  created_at      TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at      TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_claim ON normalized_artifact(claim_id);
+CREATE INDEX IF NOT EXISTS idx_artifact_canonical_url ON normalized_artifact(canonical_url);
+CREATE INDEX IF NOT EXISTS idx_artifact_search ON normalized_artifact USING GIN (search_doc);
+
+-- One row per real-world person/org across artifacts; `key` is the normalized name
+-- (see nowhere_actors::store::canonical_entity_key).
+CREATE TABLE IF NOT EXISTS canonical_entity (
+  id         TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+  key        TEXT NOT NULL UNIQUE,
+  name       TEXT NOT NULL,                                          -- first spelling seen
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Entities extracted from an artifact.
+CREATE TABLE IF NOT EXISTS entity (
+  id           TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+  article_id   TEXT NOT NULL REFERENCES normalized_artifact(internal_id) ON DELETE CASCADE,
+  external_id  TEXT NOT NULL,
+  canonical_id TEXT REFERENCES canonical_entity(id),
+  name         TEXT NOT NULL,
+  credibility  TEXT NOT NULL CHECK (credibility IN ('strong','weak','unknown')),
+  stance       TEXT NOT NULL DEFAULT 'neutral' CHECK (stance IN ('supports','refutes','neutral')),
+  reasoning    TEXT NOT NULL DEFAULT '',
+
+  created_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at   TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  UNIQUE (article_id, external_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_entity_article ON entity(article_id);
+CREATE INDEX IF NOT EXISTS idx_entity_name ON entity(name);
+CREATE INDEX IF NOT EXISTS idx_entity_canonical ON entity(canonical_id);
+
+-- Evidence graph: edges between artifacts and/or entities.
+CREATE TABLE IF NOT EXISTS graph_edge (
+  id          TEXT PRIMARY KEY,                                    -- deterministic UUID (v5)
+  src_id      TEXT NOT NULL,
+  dst_id      TEXT NOT NULL,
+  relation    TEXT NOT NULL CHECK (relation IN
+                 ('supports','contradicts','mentions','same_event')),
+  confidence  DOUBLE PRECISION NOT NULL CHECK (confidence BETWEEN 0.0 AND 1.0),
+  rationale   TEXT NOT NULL,
+  produced_by TEXT NOT NULL,
+  created_at  TIMESTAMPTZ NOT NULL DEFAULT now(),
+
+  UNIQUE (src_id, dst_id, relation, produced_by)
+);
+
+CREATE INDEX IF NOT EXISTS idx_graph_edge_src ON graph_edge(src_id);
+CREATE INDEX IF NOT EXISTS idx_graph_edge_dst ON graph_edge(dst_id);
+
+-- The search each claim's collection ran with, one row per search round.
+CREATE TABLE IF NOT EXISTS claim_search (
+  id         BIGSERIAL PRIMARY KEY,
+  claim_id   TEXT NOT NULL,
+  query      TEXT NOT NULL,
+  date_from  TIMESTAMPTZ NOT NULL,
+  date_to    TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_claim_search_claim ON claim_search(claim_id, id);
@@ -0,0 +1,9 @@
+-- Search queries built from claims, keyed by a hash of the prompts that built them; see
+-- migrations/09_query_cache.sql.
+CREATE TABLE IF NOT EXISTS query_cache (
+  key        TEXT PRIMARY KEY,
+  query      TEXT NOT NULL,
+  date_from  TIMESTAMPTZ NOT NULL,
+  date_to    TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL
+);
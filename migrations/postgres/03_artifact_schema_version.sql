@@ -0,0 +1,2 @@
+-- Layout version of each normalized artifact; see migrations/10_artifact_schema_version.sql.
+ALTER TABLE normalized_artifact ADD COLUMN IF NOT EXISTS schema_version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+-- An investigator's verdict on each artifact; see migrations/11_review_status.sql.
+ALTER TABLE normalized_artifact
+  ADD COLUMN IF NOT EXISTS review_status TEXT NOT NULL DEFAULT 'pending'
+  CHECK (review_status IN ('pending','confirmed','dismissed'));
@@ -0,0 +1,13 @@
+-- Raw artifacts whose normalization reply could not be parsed; see
+-- migrations/12_normalization_dead_letter.sql.
+CREATE TABLE IF NOT EXISTS normalization_dead_letter (
+  id           BIGSERIAL PRIMARY KEY,
+  external_id  TEXT NOT NULL,
+  claim_id     TEXT NOT NULL,
+  payload      JSONB NOT NULL,
+  error        TEXT NOT NULL,
+  model_output TEXT NOT NULL,
+  created_at   TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letter_claim ON normalization_dead_letter(claim_id);
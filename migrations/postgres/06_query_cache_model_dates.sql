@@ -0,0 +1,11 @@
+-- The query cache keeps the dates the model gave instead of the window they resolved to;
+-- see migrations/13_query_cache_model_dates.sql.
+DROP TABLE IF EXISTS query_cache;
+
+CREATE TABLE query_cache (
+  key        TEXT PRIMARY KEY,
+  query      TEXT NOT NULL,
+  date_from  TEXT,
+  date_to    TEXT,
+  created_at TIMESTAMPTZ NOT NULL
+);
@@ -152,6 +152,25 @@ impl<A: Actor> Addr<A> {
         self.0.try_send(msg).map_err(|e| e.into_inner())
     }
 
+    /// Like [`Addr::send`], but gives up once the mailbox has stayed full for `timeout`.
+    /// Returns the message if it was not delivered; check [`Addr::is_closed`] to tell a
+    /// stalled actor from a stopped one.
+    pub async fn send_timeout(
+        &self,
+        msg: A::Msg,
+        timeout: std::time::Duration,
+    ) -> std::result::Result<(), A::Msg> {
+        self.0
+            .send_timeout(msg, timeout)
+            .await
+            .map_err(|e| e.into_inner())
+    }
+
+    /// Whether the actor has stopped and dropped its mailbox.
+    pub fn is_closed(&self) -> bool {
+        self.0.is_closed()
+    }
+
     /// Bounded mailbox capacity.
     ///
     /// ```
@@ -294,6 +313,17 @@ impl<A: Actor> Reserved<A> {
         self.addr.clone()
     }
 
+    /// Hand the reserved mailbox to another actor type that speaks the same messages, so
+    /// every `Addr<A>` already given out reaches it (e.g. the Postgres store serving
+    /// `Addr<StoreActor>`).
+    pub fn into_actor<B: Actor<Msg = A::Msg>>(self) -> Reserved<B> {
+        Reserved {
+            name: self.name,
+            addr: Addr(self.addr.0),
+            rx: self.rx,
+        }
+    }
+
     /// Start the actor task using the reserved mailbox (panic if called twice).
     ///
     /// ```
@@ -417,3 +447,18 @@ pub fn spawn_actor_reserved<A: Actor>(name: impl Into<String>, capacity: usize)
         rx: Some(rx),
     }
 }
+
+/// Test-only mailbox: an `Addr<A>` whose messages land in a plain receiver instead of a
+/// running actor, so tests can assert on what another actor sent.
+#[cfg(any(test, feature = "test-util"))]
+pub fn probe<A: Actor>(capacity: usize) -> (Addr<A>, mpsc::Receiver<A::Msg>) {
+    let (tx, rx) = mpsc::channel::<A::Msg>(capacity);
+    (Addr(tx), rx)
+}
+
+/// Test-only context around `addr` (typically a [`probe`]), for calling `handle` directly
+/// and observing the messages an actor sends to itself.
+#[cfg(test)]
+pub(crate) fn context<A: Actor>(addr: Addr<A>) -> Context<A> {
+    Context { addr, stop: false }
+}
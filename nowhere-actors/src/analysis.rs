@@ -0,0 +1,274 @@
+use crate::actor::{Actor, Addr, Context};
+use crate::llm::store_search_artifacts;
+use crate::rate::{acquire_rate_permit, Priority, RateKey, RateLimiter};
+use crate::store::StoreActor;
+use crate::{AnalysisMsg, ArtifactRow, ClaimContext};
+use anyhow::{anyhow, Result};
+use nowhere_llm::traits::LlmClient;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// Exposes the `LlmClient` analysis helpers (synthesis, inconsistency detection,
+/// relevance and credibility scoring) to the rest of the actor system.
+pub struct AnalysisActor {
+    llm_client: Arc<dyn LlmClient + Send + Sync>,
+    rate_limiter: Addr<RateLimiter>,
+    rate_key: RateKey,
+}
+
+impl AnalysisActor {
+    pub fn new(
+        rate_limiter: Addr<RateLimiter>,
+        rate_key: RateKey,
+        llm_client: Arc<dyn LlmClient + Send + Sync>,
+    ) -> Self {
+        Self {
+            llm_client,
+            rate_limiter,
+            rate_key,
+        }
+    }
+
+    pub fn with_rate_key(mut self, key: RateKey) -> Self {
+        self.rate_key = key;
+        self
+    }
+}
+
+/// One evidence line per artifact, in the form the analysis prompts number and list.
+fn evidence_lines(artifacts: &[ArtifactRow]) -> Vec<String> {
+    artifacts
+        .iter()
+        .map(|a| {
+            format!(
+                "[{}] {} (provenance: {})",
+                a.external_id, a.reasoning, a.provenance_info
+            )
+        })
+        .collect()
+}
+
+/// A one-shot "what do we know" summary of a claim's stored evidence.
+#[derive(Debug, Clone)]
+pub struct ClaimSynthesis {
+    pub summary: String,
+    pub red_flags: Vec<String>,
+    pub artifact_count: usize,
+}
+
+/// Gather up to `limit` relevant artifacts for `claim`, synthesize them and list any
+/// inconsistencies. Returns `None` when the claim has no stored evidence yet.
+pub async fn synthesize_claim(
+    store: &Addr<StoreActor>,
+    analysis: &Addr<AnalysisActor>,
+    claim: &ClaimContext,
+    limit: i64,
+) -> Result<Option<ClaimSynthesis>> {
+    let artifacts = store_search_artifacts(store, claim.id, "", limit).await?;
+    if artifacts.is_empty() {
+        return Ok(None);
+    }
+    let artifact_count = artifacts.len();
+
+    let (tx, rx) = oneshot::channel();
+    analysis
+        .send(AnalysisMsg::Synthesize {
+            claim: claim.clone(),
+            artifacts: artifacts.clone(),
+            reply: tx,
+        })
+        .await
+        .map_err(|_| anyhow!("analysis mailbox dropped"))?;
+    let summary = rx.await.map_err(|_| anyhow!("analysis reply dropped"))??;
+
+    let (tx, rx) = oneshot::channel();
+    analysis
+        .send(AnalysisMsg::DetectInconsistencies {
+            artifacts,
+            reply: tx,
+        })
+        .await
+        .map_err(|_| anyhow!("analysis mailbox dropped"))?;
+    let red_flags = rx.await.map_err(|_| anyhow!("analysis reply dropped"))??;
+
+    Ok(Some(ClaimSynthesis {
+        summary,
+        red_flags,
+        artifact_count,
+    }))
+}
+
+#[async_trait::async_trait]
+impl Actor for AnalysisActor {
+    type Msg = AnalysisMsg;
+
+    async fn handle(&mut self, msg: Self::Msg, _ctx: &mut Context<Self>) -> Result<()> {
+        acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Interactive).await?;
+
+        match msg {
+            AnalysisMsg::Synthesize {
+                claim,
+                artifacts,
+                reply,
+            } => {
+                let res = self
+                    .llm_client
+                    .synthesize_evidence(&evidence_lines(&artifacts), &claim.text)
+                    .await;
+                let _ = reply.send(res.map_err(Into::into));
+            }
+            AnalysisMsg::DetectInconsistencies { artifacts, reply } => {
+                let res = self
+                    .llm_client
+                    .detect_inconsistencies(&evidence_lines(&artifacts))
+                    .await;
+                let _ = reply.send(res.map_err(Into::into));
+            }
+            AnalysisMsg::AnalyzeRelevance {
+                claim,
+                evidence,
+                reply,
+            } => {
+                let res = self
+                    .llm_client
+                    .analyze_relevance(&claim.text, &evidence)
+                    .await;
+                let _ = reply.send(res.map_err(Into::into));
+            }
+            AnalysisMsg::AnalyzeCredibility {
+                content,
+                source_info,
+                reply,
+            } => {
+                let res = self
+                    .llm_client
+                    .analyze_credibility(&content, source_info.as_deref())
+                    .await;
+                let _ = reply.send(res.map_err(Into::into));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::{probe, spawn_actor};
+    use crate::rate::RateMsg;
+    use crate::StoreMsg;
+    use nowhere_llm::mock::MockLlmClient;
+    use uuid::Uuid;
+
+    fn artifact(external_id: &str, reasoning: &str) -> ArtifactRow {
+        ArtifactRow {
+            internal_id: Uuid::new_v4().to_string(),
+            external_id: external_id.into(),
+            claim_relevance: true,
+            reasoning: reasoning.into(),
+            provenance_info: "twitter".into(),
+            claim_id: None,
+            provenance: Default::default(),
+            review_status: Default::default(),
+        }
+    }
+
+    fn claim() -> ClaimContext {
+        ClaimContext {
+            id: Uuid::new_v4(),
+            text: "The bridge closed on Monday".into(),
+            tags: Vec::new(),
+        }
+    }
+
+    fn spawn_analysis(client: Arc<MockLlmClient>) -> Addr<AnalysisActor> {
+        let rate = spawn_actor(RateLimiter::new(), 8).addr;
+        rate.try_send(RateMsg::Upsert {
+            key: RateKey("test".into()),
+            qps: 1000.0,
+            burst: 1000,
+        })
+        .unwrap();
+        spawn_actor(AnalysisActor::new(rate, RateKey("test".into()), client), 8).addr
+    }
+
+    /// A store that answers every artifact search with `rows`.
+    fn mock_store(rows: Vec<ArtifactRow>) -> Addr<StoreActor> {
+        let (addr, mut rx) = probe::<StoreActor>(8);
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                if let StoreMsg::SearchArtifacts { reply, .. } = msg {
+                    let _ = reply.send(Ok(rows.clone()));
+                }
+            }
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn synthesize_returns_the_synthesis_text() {
+        let client = Arc::new(MockLlmClient::new().replies(["Both reports agree on the date."]));
+        let analysis = spawn_analysis(client.clone());
+
+        let (tx, rx) = oneshot::channel();
+        analysis
+            .send(AnalysisMsg::Synthesize {
+                claim: claim(),
+                artifacts: vec![
+                    artifact("tw:1", "reports the closure"),
+                    artifact("tw:2", "confirms Monday"),
+                ],
+                reply: tx,
+            })
+            .await
+            .ok()
+            .unwrap();
+
+        let text = rx.await.unwrap().unwrap();
+        assert_eq!(text, "Both reports agree on the date.");
+        assert!(client.calls()[0]
+            .system_prompt
+            .as_deref()
+            .unwrap_or_default()
+            .contains("Synthesize the provided evidence"));
+        let prompt = client.prompts()[0].clone();
+        assert!(prompt.contains("INVESTIGATION: The bridge closed on Monday"));
+        assert!(prompt.contains("2. [tw:2] confirms Monday"));
+    }
+
+    #[tokio::test]
+    async fn claim_synthesis_includes_red_flags() {
+        let client = Arc::new(MockLlmClient::new().replies([
+            "Two sources report the closure.",
+            "- tw:2 gives a different date\nno bullet here",
+        ]));
+        let analysis = spawn_analysis(client.clone());
+        let store = mock_store(vec![
+            artifact("tw:1", "reports the closure"),
+            artifact("tw:2", "says it closed Tuesday"),
+        ]);
+
+        let synthesis = synthesize_claim(&store, &analysis, &claim(), 20)
+            .await
+            .unwrap()
+            .expect("evidence was stored");
+
+        assert_eq!(synthesis.summary, "Two sources report the closure.");
+        assert_eq!(synthesis.red_flags, vec!["tw:2 gives a different date"]);
+        assert_eq!(synthesis.artifact_count, 2);
+    }
+
+    #[tokio::test]
+    async fn claim_without_evidence_skips_the_llm() {
+        let client = Arc::new(MockLlmClient::new());
+        let analysis = spawn_analysis(client.clone());
+        let store = mock_store(Vec::new());
+
+        let synthesis = synthesize_claim(&store, &analysis, &claim(), 20)
+            .await
+            .unwrap();
+
+        assert!(synthesis.is_none());
+        assert!(client.prompts().is_empty());
+    }
+}
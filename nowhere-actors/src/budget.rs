@@ -0,0 +1,67 @@
+//! Wall-clock budget for a claim investigation.
+//!
+//! A `ClaimBudget` wraps the claim's cancellation token: once the budget runs out the token
+//! is cancelled, so pagination and normalization stop exactly as they do for `/cancel`.
+//! Expiry is noticed either by [`ClaimBudget::run_out`] (a timer the owner spawns) or by
+//! [`ClaimBudget::enforce`], which workers call before starting new work.
+use crate::rate::{Clock, SystemClock};
+use std::{fmt, sync::Arc, time::Duration};
+use tokio::time::{sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
+
+#[derive(Clone)]
+pub struct ClaimBudget {
+    cancel: CancellationToken,
+    deadline: Instant,
+    clock: Arc<dyn Clock>,
+}
+
+impl fmt::Debug for ClaimBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClaimBudget")
+            .field("deadline", &self.deadline)
+            .field("cancelled", &self.cancel.is_cancelled())
+            .finish()
+    }
+}
+
+impl ClaimBudget {
+    /// Budget of `budget` from now, measured on the tokio clock.
+    pub fn new(cancel: CancellationToken, budget: Duration) -> Self {
+        Self::with_clock(cancel, budget, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(cancel: CancellationToken, budget: Duration, clock: Arc<dyn Clock>) -> Self {
+        let deadline = clock.now() + budget;
+        Self {
+            cancel,
+            deadline,
+            clock,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.clock.now() >= self.deadline
+    }
+
+    /// Cancel the claim if its budget is spent. Returns `true` when it was.
+    pub fn enforce(&self) -> bool {
+        if self.is_expired() {
+            self.cancel.cancel();
+            return true;
+        }
+        false
+    }
+
+    /// Wait for the deadline and cancel the claim. Resolves early if the claim is cancelled
+    /// for another reason; returns whether the budget was the cause.
+    pub async fn run_out(&self) -> bool {
+        tokio::select! {
+            _ = sleep_until(self.deadline) => {
+                self.cancel.cancel();
+                true
+            }
+            _ = self.cancel.cancelled() => self.is_expired(),
+        }
+    }
+}
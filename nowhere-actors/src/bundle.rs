@@ -0,0 +1,205 @@
+//! Investigation bundles: one self-contained JSON document per claim for sharing and
+//! archiving.
+//!
+//! A bundle holds the claim, the search it last ran with and every stored artifact with
+//! its entities and provenance. With captures on, archived page HTML is embedded as text
+//! and screenshots as base64 PNG, so the document stands alone once the store and the
+//! capture directory are gone. `schema` and `version` head the document; readers should
+//! check both before trusting the layout.
+use crate::actor::Addr;
+use crate::store::StoreActor;
+use crate::{ArtifactWithEntities, ClaimContext, RecordedSearch, StoreMsg};
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+
+/// Value of every bundle's `schema` field.
+pub const BUNDLE_SCHEMA: &str = "nowhere.investigation-bundle";
+
+/// Layout version; bumped whenever a field changes meaning or goes away.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvestigationBundle {
+    pub schema: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub claim: ClaimContext,
+    /// The claim's most recently recorded search, if it ever ran one.
+    pub search: Option<RecordedSearch>,
+    /// Every stored artifact of the claim, relevant or not, oldest first.
+    pub artifacts: Vec<BundledArtifact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledArtifact {
+    #[serde(flatten)]
+    pub evidence: ArtifactWithEntities,
+    /// The archived page at `provenance.html_path`, when captures were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,
+    /// Base64 of the PNG at `provenance.screenshot_path`, when captures were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub screenshot_png_base64: Option<String>,
+}
+
+/// Collect `claim`'s bundle from `store`, embedding page captures if `captures` is set.
+/// A capture file that can no longer be read is logged and left out.
+pub async fn assemble(
+    store: &Addr<StoreActor>,
+    claim: ClaimContext,
+    captures: bool,
+) -> Result<InvestigationBundle> {
+    let (reply, rx) = oneshot::channel();
+    store
+        .send(StoreMsg::LastSearch {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .map_err(|_| anyhow!("store mailbox dropped"))?;
+    let search = rx.await??;
+
+    let (reply, rx) = oneshot::channel();
+    store
+        .send(StoreMsg::ClaimArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .map_err(|_| anyhow!("store mailbox dropped"))?;
+    let evidence = rx.await??;
+
+    let mut artifacts = Vec::with_capacity(evidence.len());
+    for evidence in evidence {
+        let provenance = &evidence.artifact.provenance;
+        let (mut html, mut screenshot_png_base64) = (None, None);
+        if captures {
+            if let Some(path) = &provenance.html_path {
+                html = read_capture(path)
+                    .await
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned());
+            }
+            if let Some(path) = &provenance.screenshot_path {
+                screenshot_png_base64 = read_capture(path)
+                    .await
+                    .map(|png| base64::engine::general_purpose::STANDARD.encode(png));
+            }
+        }
+        artifacts.push(BundledArtifact {
+            evidence,
+            html,
+            screenshot_png_base64,
+        });
+    }
+    info!(claim_id = %claim.id, artifacts = artifacts.len(), captures, "bundle.assembled");
+    Ok(InvestigationBundle {
+        schema: BUNDLE_SCHEMA.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now(),
+        claim,
+        search,
+        artifacts,
+    })
+}
+
+/// Write `bundle` to `path` as pretty-printed JSON, replacing any file there.
+pub async fn write(bundle: &InvestigationBundle, path: &Path) -> Result<()> {
+    let json = serde_json::to_vec_pretty(bundle)?;
+    tokio::fs::write(path, json).await?;
+    info!(path = %path.display(), claim_id = %bundle.claim.id, "bundle.written");
+    Ok(())
+}
+
+async fn read_capture(path: &str) -> Option<Vec<u8>> {
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Some(bytes),
+        Err(err) => {
+            warn!(path, error = %err, "bundle.capture_unreadable");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::tests::{artifact, claim, entity, test_store};
+    use crate::Stance;
+
+    #[tokio::test]
+    async fn bundle_of_a_small_claim_has_every_section() {
+        let (store, pool) = test_store().await;
+        let ferry = claim("the ferry sank");
+        crate::store::insert_claim(&pool, ferry.clone())
+            .await
+            .unwrap();
+        let search = RecordedSearch {
+            query: "ferry sank".into(),
+            date_from: "2024-03-01T00:00:00Z".parse().unwrap(),
+            date_to: "2024-03-08T00:00:00Z".parse().unwrap(),
+        };
+        crate::store::record_search(&pool, ferry.id, &search)
+            .await
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("nowhere-bundle-{}", ferry.id));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let html_path = dir.join("page.html");
+        tokio::fs::write(&html_path, "<p>ferry</p>").await.unwrap();
+        let mut captured = artifact(
+            &ferry,
+            "web:1",
+            vec![entity("Coast Guard", Stance::Supports)],
+        );
+        captured.provenance.html_path = Some(html_path.display().to_string());
+        captured.provenance.screenshot_path = Some(dir.join("gone.png").display().to_string());
+        let mut irrelevant = artifact(&ferry, "tw:2", vec![]);
+        irrelevant.claim_relevance = false;
+        for a in [captured, irrelevant] {
+            crate::store::upsert_normalized(&pool, a).await.unwrap();
+        }
+
+        let bundle = assemble(&store, ferry.clone(), true).await.unwrap();
+        let path = dir.join("bundle.json");
+        write(&bundle, &path).await.unwrap();
+        let json: serde_json::Value =
+            serde_json::from_slice(&tokio::fs::read(&path).await.unwrap()).unwrap();
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+
+        assert_eq!(json["schema"], BUNDLE_SCHEMA);
+        assert_eq!(json["version"], BUNDLE_VERSION);
+        assert!(json["exported_at"].is_string());
+        assert_eq!(json["claim"]["text"], "the ferry sank");
+        assert_eq!(json["search"]["query"], "ferry sank");
+        let artifacts = json["artifacts"].as_array().unwrap();
+        assert_eq!(artifacts.len(), 2, "irrelevant evidence is bundled too");
+        let by_id = |id: &str| {
+            artifacts
+                .iter()
+                .find(|a| a["artifact"]["external_id"] == id)
+                .unwrap()
+        };
+        let web = by_id("web:1");
+        assert_eq!(web["entities"][0]["name"], "Coast Guard");
+        assert!(web["artifact"]["provenance"]["html_path"].is_string());
+        assert_eq!(web["html"], "<p>ferry</p>");
+        assert!(
+            web.get("screenshot_png_base64").is_none(),
+            "missing file is skipped"
+        );
+        assert!(by_id("tw:2").get("html").is_none());
+
+        // Without captures only the paths are kept.
+        let bare = assemble(&store, ferry, false).await.unwrap();
+        assert!(bare.artifacts.iter().all(|a| a.html.is_none()));
+        assert!(bare
+            .artifacts
+            .iter()
+            .any(|a| a.evidence.artifact.provenance.html_path.is_some()));
+    }
+}
@@ -6,8 +6,13 @@
 //! cross-crate dependencies (e.g. `builder`, `system`, `store`), so keep this entrypoint
 //! as the hub for future docs.
 pub mod actor;
+pub mod analysis;
+pub mod budget;
 pub mod builder;
+pub mod bundle;
 pub mod llm;
+#[cfg(feature = "postgres")]
+pub mod pg;
 pub mod rate;
 pub mod registry;
 pub mod store;
@@ -15,17 +20,23 @@ pub mod supervise;
 pub mod system;
 pub mod twitter;
 
+use crate::budget::ClaimBudget;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use tokio::sync::oneshot;
+use std::{collections::HashMap, path::PathBuf, time::Duration};
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ClaimContext {
     pub id: Uuid,
     pub text: String,
+    /// Labels for grouping claims, e.g. `election` or `case-42`.
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -34,6 +45,29 @@ pub struct SearchCmd {
     pub date_from: DateTime<Utc>,
     pub date_to: DateTime<Utc>,
     pub claim: ClaimContext,
+    /// Cancelled when the user aborts the claim; stops pagination and downstream LLM calls.
+    #[serde(skip)]
+    pub cancel: CancellationToken,
+    /// Receives how the search ended. Cancelled searches report nothing.
+    #[serde(skip)]
+    pub report: Option<mpsc::Sender<SearchOutcome>>,
+    /// Wall-clock budget for the claim; checked before each page is requested.
+    #[serde(skip)]
+    pub budget: Option<ClaimBudget>,
+}
+
+/// Result of a `SearchCmd`, so callers can tell "nothing matched" from "the API failed".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// Every page was fetched; ids of the tweets forwarded for normalization (maybe none).
+    Done(Vec<String>),
+    /// A search request failed (bad token, quota, network); carries the error text.
+    Failed(String),
+    /// The claim reached its artifact cap; ids forwarded before the search stopped.
+    CapReached(Vec<String>),
+    /// The search ran out of its time budget with pages left; ids forwarded from the
+    /// pages it fetched in time.
+    Partial(Vec<String>),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -41,35 +75,107 @@ pub struct RawArtifact {
     pub external_id: String,
     pub payload: serde_json::Value,
     pub claim: ClaimContext,
+    /// Inherited from the `SearchCmd` that produced this artifact.
+    #[serde(skip)]
+    pub cancel: CancellationToken,
 }
 
-#[derive(Debug, FromRow)]
+/// Also the record shape for `StoreMsg::ImportJsonl`, where ids and claim default.
+#[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct NormalizedArtifact {
     pub external_id: String,
+    #[serde(default = "Uuid::new_v4")]
     pub internal_id: Uuid,
+    #[serde(default)]
     pub claim_id: Uuid,
     pub claim_relevance: bool,
+    #[serde(default)]
     pub reasoning: String,
+    #[serde(default)]
     pub provenance_info: String,
+    /// `url_key` of the page this artifact is about, used to link the same source across feeds.
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    #[serde(default)]
+    pub provenance: Provenance,
+    #[serde(default)]
     pub entities: Vec<Entity>,
 }
 
-#[derive(Debug, FromRow)]
+/// Where an artifact came from, stored in typed columns next to the free-form
+/// `provenance_info` note the model writes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Link to the original post or page.
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Author handle or id as reported by the platform.
+    #[serde(default)]
+    pub author: Option<String>,
+    /// When the artifact was fetched.
+    #[serde(default)]
+    pub captured_at: Option<DateTime<Utc>>,
+    /// `twitter`, `web`, ...
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// Archived HTML of the page, for web artifacts that were captured.
+    #[serde(default)]
+    pub html_path: Option<String>,
+    /// Archived screenshot (PNG) of the page.
+    #[serde(default)]
+    pub screenshot_path: Option<String>,
+}
+
+/// Layout version stamped on every stored normalized artifact. Version 1 rows predate
+/// structured provenance; reads bring them up to date with `Provenance::upgrade`.
+pub const ARTIFACT_SCHEMA_VERSION: i32 = 2;
+
+impl Provenance {
+    /// Fill in what an artifact stored at `schema_version` could not record, keeping any
+    /// field that is already set. Before version 2 only Twitter search stored artifacts,
+    /// so the platform and the status link follow from the tweet id.
+    pub fn upgrade(mut self, schema_version: i32, external_id: &str) -> Self {
+        if schema_version < 2 {
+            self.platform.get_or_insert_with(|| "twitter".to_string());
+            if !external_id.is_empty() && external_id.bytes().all(|b| b.is_ascii_digit()) {
+                self.source_url
+                    .get_or_insert_with(|| format!("https://x.com/i/web/status/{external_id}"));
+            }
+        }
+        self
+    }
+}
+
+#[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct Entity {
+    #[serde(default)]
     pub article_id: Uuid,
+    #[serde(default)]
     pub external_id: String,
     pub name: String,
+    #[serde(default)]
     pub credibility: Credibility,
+    #[serde(default)]
+    pub stance: Stance,
+    #[serde(default)]
     pub reasoning: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", from = "String")]
 pub enum Credibility {
     Strong,
     Weak,
+    #[default]
     Unknown,
 }
 
+impl From<String> for Credibility {
+    fn from(s: String) -> Self {
+        Credibility::from(s.as_str())
+    }
+}
+
 impl Credibility {
     fn from(s: &str) -> Self {
         match s.to_ascii_lowercase().as_str() {
@@ -78,15 +184,108 @@ impl Credibility {
             _ => Credibility::Unknown,
         }
     }
+
+    /// Ranking weight used when trimming chat context: strong > unknown > weak.
+    pub fn weight(&self) -> f64 {
+        match self {
+            Credibility::Strong => 1.0,
+            Credibility::Unknown => 0.5,
+            Credibility::Weak => 0.0,
+        }
+    }
+}
+
+/// Position an entity takes toward the claim under investigation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", from = "String")]
+pub enum Stance {
+    Supports,
+    Refutes,
+    #[default]
+    Neutral,
+}
+
+impl From<String> for Stance {
+    fn from(s: String) -> Self {
+        Stance::from(s.as_str())
+    }
+}
+
+impl Stance {
+    /// Parses a model-provided label, defaulting to `Neutral` for anything unrecognized.
+    fn from(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "supports" => Stance::Supports,
+            "refutes" => Stance::Refutes,
+            _ => Stance::Neutral,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stance::Supports => "supports",
+            Stance::Refutes => "refutes",
+            Stance::Neutral => "neutral",
+        }
+    }
+}
+
+/// An investigator's verdict on an artifact, set with `StoreMsg::SetReview`. Chat
+/// retrieval prefers confirmed artifacts and leaves dismissed ones out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", from = "String")]
+pub enum ReviewStatus {
+    #[default]
+    Pending,
+    Confirmed,
+    Dismissed,
+}
+
+impl From<String> for ReviewStatus {
+    fn from(s: String) -> Self {
+        ReviewStatus::from(s.as_str())
+    }
+}
+
+/// Parses a stored label, defaulting to `Pending` for anything unrecognized.
+impl From<&str> for ReviewStatus {
+    fn from(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "confirmed" => ReviewStatus::Confirmed,
+            "dismissed" => ReviewStatus::Dismissed,
+            _ => ReviewStatus::Pending,
+        }
+    }
+}
+
+impl ReviewStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewStatus::Pending => "pending",
+            ReviewStatus::Confirmed => "confirmed",
+            ReviewStatus::Dismissed => "dismissed",
+        }
+    }
 }
 
 pub enum StoreMsg {
     InsertClaim(ClaimContext),
-    UpsertArtifact(NormalizedArtifact),
+    /// The permit, if any, is a write slot the sender already took from the store's write
+    /// semaphore; the store holds it for the write instead of waiting for another.
+    UpsertArtifact(Box<NormalizedArtifact>, Option<OwnedSemaphorePermit>),
+    /// Write several artifacts (e.g. one search page) in a single transaction, with one
+    /// `ArtifactUpserted` per claim afterwards. The permit works as for `UpsertArtifact`.
+    UpsertBatch(Vec<NormalizedArtifact>, Option<OwnedSemaphorePermit>),
     GetArtifact {
         internal_id: Uuid,
         reply: oneshot::Sender<Result<ArtifactWithEntities>>,
     },
+    /// Several artifacts with their entities in one round trip, keyed by internal id.
+    /// Ids that are not stored are absent from the map.
+    GetArtifacts {
+        ids: Vec<Uuid>,
+        reply: oneshot::Sender<Result<HashMap<Uuid, ArtifactWithEntities>>>,
+    },
     SearchArtifacts {
         claim: Uuid,
         query: String,
@@ -105,6 +304,148 @@ pub enum StoreMsg {
         limit: i64,
         reply: oneshot::Sender<Result<Vec<EntityRow>>>,
     },
+    /// Every artifact stored for `claim`, relevant or not, oldest first, with its entities.
+    ClaimArtifacts {
+        claim: Uuid,
+        reply: oneshot::Sender<Result<Vec<ArtifactWithEntities>>>,
+    },
+    /// How many artifacts are stored for `claim`, relevant or not.
+    CountArtifacts {
+        claim: Uuid,
+        reply: oneshot::Sender<Result<usize>>,
+    },
+    /// Relevant artifacts of `claim` and their entities' credibility, per platform.
+    SourceBreakdown {
+        claim: Uuid,
+        reply: oneshot::Sender<Result<Vec<PlatformSources>>>,
+    },
+    /// Entities and sources the relevant evidence of claims `a` and `b` has in common.
+    ClaimOverlap {
+        a: Uuid,
+        b: Uuid,
+        reply: oneshot::Sender<Result<EvidenceOverlap>>,
+    },
+    /// Add `tag` to the claim's tags (if not already there); replies with all its tags.
+    TagClaim {
+        claim: Uuid,
+        tag: String,
+        reply: oneshot::Sender<Result<Vec<String>>>,
+    },
+    /// Claims carrying `tag`, newest first.
+    ListClaimsByTag {
+        tag: String,
+        limit: i64,
+        reply: oneshot::Sender<Result<Vec<ClaimContext>>>,
+    },
+    /// Claims whose id's slug starts with `slug` (see `nowhere_common::slug`), in id
+    /// order; more than one means the prefix is ambiguous.
+    ListClaimsBySlug {
+        slug: String,
+        limit: i64,
+        reply: oneshot::Sender<Result<Vec<ClaimContext>>>,
+    },
+    /// Claims whose UUID starts with the hex `prefix` (see
+    /// `nowhere_common::slug::uuid_prefix_range`), in id order.
+    ListClaimsByUuidPrefix {
+        prefix: String,
+        limit: i64,
+        reply: oneshot::Sender<Result<Vec<ClaimContext>>>,
+    },
+    /// Ids of the artifacts whose slug or UUID starts with `prefix`, in id order; more
+    /// than one means the prefix is ambiguous.
+    ListArtifactIdsByPrefix {
+        prefix: String,
+        limit: i64,
+        reply: oneshot::Sender<Result<Vec<Uuid>>>,
+    },
+    /// Rebuild the full-text index from `normalized_artifact`, for when it drifted (e.g.
+    /// rows written with the sync triggers bypassed). Replies with the rows indexed.
+    RebuildFts {
+        reply: oneshot::Sender<Result<usize>>,
+    },
+    /// Set an artifact's review status; fails if no artifact has that id.
+    SetReview {
+        artifact: Uuid,
+        status: ReviewStatus,
+        reply: oneshot::Sender<Result<()>>,
+    },
+    /// Other artifacts sharing the given artifact's canonical URL.
+    ListLinkedArtifacts {
+        internal_id: Uuid,
+        reply: oneshot::Sender<Result<Vec<ArtifactRow>>>,
+    },
+    /// Upsert `NormalizedArtifact` records from a JSON-lines file under `claim`, in one
+    /// transaction. Each record's `claim_id` is replaced by `claim.id`.
+    ImportJsonl {
+        path: PathBuf,
+        claim: ClaimContext,
+        reply: oneshot::Sender<Result<ImportCounts>>,
+    },
+    /// Record the query and date window a search round for `claim` runs with.
+    RecordSearch {
+        claim: Uuid,
+        search: RecordedSearch,
+    },
+    /// The most recently recorded search for `claim`, if any.
+    LastSearch {
+        claim: Uuid,
+        reply: oneshot::Sender<Result<Option<RecordedSearch>>>,
+    },
+    /// The search query cached under `key` by `CacheQuery`, unless it is older than
+    /// `max_age`.
+    CachedQuery {
+        key: String,
+        max_age: Duration,
+        reply: oneshot::Sender<Result<Option<CachedSearchQuery>>>,
+    },
+    /// Cache the search query built for a claim under `key` (see
+    /// `LlmActor::with_query_cache`), replacing any older entry.
+    CacheQuery {
+        key: String,
+        search: CachedSearchQuery,
+    },
+    /// Keep an artifact whose normalization reply could not be parsed, for reprocessing
+    /// (see `LlmActor::with_dead_letter`).
+    DeadLetter(Box<DeadLetter>),
+}
+
+/// A raw artifact normalization gave up on, as stored by `StoreMsg::DeadLetter`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub external_id: String,
+    pub claim_id: Uuid,
+    /// The raw artifact payload the model was asked to normalize.
+    pub payload: serde_json::Value,
+    /// Why the reply was rejected.
+    pub error: String,
+    /// The start of the model's reply.
+    pub model_output: String,
+}
+
+/// A search as stored by `StoreMsg::RecordSearch`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedSearch {
+    pub query: String,
+    pub date_from: DateTime<Utc>,
+    pub date_to: DateTime<Utc>,
+}
+
+/// A built search query as `StoreMsg::CacheQuery` keeps it: the dates are the model's own
+/// (`None` when it gave none), resolved to a window again on every cache hit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedSearchQuery {
+    pub query: String,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+}
+
+/// Outcome of a `StoreMsg::ImportJsonl`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportCounts {
+    pub artifacts: usize,
+    pub entities: usize,
+    /// Blank lines are ignored; lines that fail to parse are skipped and counted here.
+    pub skipped: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,14 +456,67 @@ pub struct ArtifactRow {
     pub reasoning: String,
     pub provenance_info: String,
     pub claim_id: Option<String>,
+    #[serde(default)]
+    pub provenance: Provenance,
+    #[serde(default)]
+    pub review_status: ReviewStatus,
+}
+
+/// One platform's share of a claim's evidence, from `StoreMsg::SourceBreakdown`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformSources {
+    /// `twitter`, `web`, ...; `unknown` for artifacts stored without provenance.
+    pub platform: String,
+    pub artifacts: usize,
+    /// Entities cited by those artifacts, by credibility tier.
+    pub strong: usize,
+    pub weak: usize,
+    pub unknown: usize,
+}
+
+/// What two claims' relevant evidence has in common, from `StoreMsg::ClaimOverlap`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceOverlap {
+    /// Entities cited by both claims' artifacts, under the first spelling seen; spelling
+    /// variants count as one entity (see `EntityRow::canonical_id`).
+    pub entities: Vec<String>,
+    /// Canonical URLs both claims collected artifacts from.
+    pub sources: Vec<String>,
+}
+
+/// Outcome of one dependency check in a startup readiness probe.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessCheck {
+    /// What was checked, e.g. `llm:main`, `twitter:ingest` or `db`.
+    pub name: String,
+    pub healthy: bool,
+    /// Why it is unhealthy: the error, or that the credentials were rejected.
+    pub detail: Option<String>,
+}
+
+/// Every check of a readiness probe, in the order they were registered.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub checks: Vec<ReadinessCheck>,
+}
+
+impl ReadinessReport {
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|c| c.healthy)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EntityRow {
     pub id: String,
     pub article_id: String,
+    /// Shared id for spelling variants of the same name across artifacts; `None` for
+    /// rows stored before entity resolution existed.
+    #[serde(default)]
+    pub canonical_id: Option<String>,
     pub name: String,
     pub credibility: String,
+    pub stance: String,
     pub reasoning: String,
 }
 
@@ -134,17 +528,51 @@ pub struct ArtifactWithEntities {
 
 pub enum LlmMsg {
     NormalizeArtifact(RawArtifact),
+    /// Normalize several artifacts of the same claim with one LLM round-trip.
+    NormalizeBatch(Vec<RawArtifact>),
     BuildSearchQuery {
         claim: ClaimContext,
+        /// Build a new query even if one is cached for this claim text (see
+        /// `LlmActor::with_query_cache`); the new one replaces it.
+        fresh: bool,
         reply: oneshot::Sender<BuiltSearchQuery>,
     },
 }
 
+/// Requests for the `LlmClient` analysis helpers, answered over `reply`.
+pub enum AnalysisMsg {
+    /// Summarize the given artifacts as evidence for `claim`.
+    Synthesize {
+        claim: ClaimContext,
+        artifacts: Vec<ArtifactRow>,
+        reply: oneshot::Sender<Result<String>>,
+    },
+    /// List contradictions or red flags across the given artifacts.
+    DetectInconsistencies {
+        artifacts: Vec<ArtifactRow>,
+        reply: oneshot::Sender<Result<Vec<String>>>,
+    },
+    AnalyzeRelevance {
+        claim: ClaimContext,
+        evidence: String,
+        reply: oneshot::Sender<Result<bool>>,
+    },
+    /// Score `content` from 0.0 (not credible) to 1.0.
+    AnalyzeCredibility {
+        content: String,
+        source_info: Option<String>,
+        reply: oneshot::Sender<Result<f64>>,
+    },
+}
+
 pub struct ChatCmd {
     pub user_text: String,
     pub k: i64,
     pub reply: oneshot::Sender<ChatResponse>,
     pub claim: ClaimContext,
+    /// Language to answer this question in, over the actor's
+    /// `ChatLlmActor::with_answer_language`.
+    pub language: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -155,11 +583,26 @@ pub struct ChatResponse {
     pub caveats: Vec<String>,
 }
 
+/// The model's reply to `BuildSearchQuery`. Dates stay loose here: a missing or malformed
+/// one falls back to the default search window instead of failing the claim, and a
+/// missing or non-string rationale is simply dropped.
 #[derive(Serialize, Deserialize)]
 pub struct SearchQueryResponse {
     query: String,
-    date_from: DateTime<Utc>,
-    date_to: DateTime<Utc>,
+    #[serde(default)]
+    date_from: Option<serde_json::Value>,
+    #[serde(default)]
+    date_to: Option<serde_json::Value>,
+    #[serde(default)]
+    rationale: Option<serde_json::Value>,
+}
+
+impl SearchQueryResponse {
+    /// The model's reason for the query, if it gave a non-empty one.
+    pub fn rationale(&self) -> Option<String> {
+        let text = self.rationale.as_ref()?.as_str()?.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -167,5 +610,8 @@ pub struct BuiltSearchQuery {
     pub query: String,
     pub date_from: DateTime<Utc>,
     pub date_to: DateTime<Utc>,
+    /// Why the model chose `query`; `None` for cached queries or templates that don't ask.
+    #[serde(default)]
+    pub rationale: Option<String>,
     pub claim: ClaimContext,
 }
@@ -1,26 +1,150 @@
 use crate::actor::Context;
 use crate::actor::{Actor, Addr};
 use crate::rate::RateKey;
-use crate::rate::{RateLimiter, RateMsg};
+use crate::rate::{acquire_rate_permit, Priority, RateLimiter};
 use crate::store::StoreActor;
 use crate::{
-    ArtifactRow, ArtifactWithEntities, BuiltSearchQuery, ChatCmd, ChatResponse, Credibility,
-    Entity, LlmMsg, NormalizedArtifact, SearchQueryResponse, StoreMsg,
+    ArtifactRow, ArtifactWithEntities, BuiltSearchQuery, CachedSearchQuery, ChatCmd, ChatResponse,
+    Credibility, DeadLetter, Entity, LlmMsg, NormalizedArtifact, Provenance, RawArtifact,
+    ReviewStatus, SearchQueryResponse, Stance, StoreMsg,
 };
 use anyhow::{anyhow, Result};
-use nowhere_llm::traits::LlmClient;
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use nowhere_llm::traits::{raw_responses_enabled, LlmClient, LlmError, LlmResponse};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::oneshot;
+use std::time::Duration;
+use tokio::sync::{broadcast, oneshot, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+/// How often the task started by [`spawn_parse_failure_summary`] reports new failures.
+pub const PARSE_FAILURE_SUMMARY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Every `every`, log how many normalization replies `failures` (from
+/// [`LlmActor::parse_failures`]) has counted, until `shutdown` fires. Quiet intervals log
+/// nothing, so the summary only shows up while replies keep failing.
+pub fn spawn_parse_failure_summary(
+    model: String,
+    failures: Arc<AtomicU64>,
+    every: Duration,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(every);
+        // The first tick is immediate, before anything could have failed.
+        ticks.tick().await;
+        let mut reported = 0;
+        loop {
+            tokio::select! {
+                _ = shutdown.recv() => return,
+                _ = ticks.tick() => {}
+            }
+            let total = failures.load(Ordering::Relaxed);
+            if total > reported {
+                tracing::warn!(
+                    model = %model,
+                    total,
+                    new = total - reported,
+                    "llm.normalize.parse_failures"
+                );
+                reported = total;
+            }
+        }
+    })
+}
+
 pub struct LlmActor {
     llm_client: Arc<dyn LlmClient + Send + Sync>,
     rate_limiter: Addr<RateLimiter>,
     rate_key: RateKey,
     out: Addr<StoreActor>,
+    min_relevance: Option<f64>,
+    rate_limit_backoff: Duration,
+    write_limit: Option<Arc<Semaphore>>,
+    search_window: chrono::Duration,
+    prompt_json: PromptJson,
+    query_prompt: QueryPrompt,
+    query_cache_ttl: Option<Duration>,
+    parse_failures: Arc<AtomicU64>,
+    dead_letter: bool,
+}
+
+/// How JSON embedded in prompts (raw artifacts, chat context) is serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PromptJson {
+    /// No whitespace; the fewest tokens.
+    #[default]
+    Compact,
+    /// Indented, for reading prompts while debugging.
+    Pretty,
+}
+
+impl PromptJson {
+    fn render<T: Serialize + ?Sized>(self, value: &T) -> serde_json::Result<String> {
+        match self {
+            PromptJson::Compact => serde_json::to_string(value),
+            PromptJson::Pretty => serde_json::to_string_pretty(value),
+        }
+    }
+}
+
+/// Prompts for `BuildSearchQuery`. `{claim}` in the template becomes the claim text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPrompt {
+    /// System prompt; `None` uses the client's default OSINT prompt.
+    pub system: Option<String>,
+    pub template: String,
+}
+
+/// Built-in query-building directions; the reply must still match `SearchQueryResponse`.
+pub const DEFAULT_QUERY_TEMPLATE: &str = concat!(
+    "Investigation claim: \"{claim}\"\n\n directions: ",
+    r#"
+You must respond with a single JSON object that matches this schema exactly:
+{
+  "query": string,
+  "date_from": string,
+  "date_to": string,
+  "rationale": string
+}
+The JSON must be valid. Do not include any additional commentary or code fences.
+The query must be a string representing a twitter search query based the attached claim. Ideally, this would include the key entity and perhaps the most
+important action or object involved. For example, if the claim is "Terry McLaurin signed a contract for 500 million USD in 2024.", the search would be
+'"Terry McLaurin" contract'. The date values must be deserializable into chrono::DateTime<Utc> values.
+The rationale is one short sentence on why this query should surface evidence for or against the claim."#
+);
+
+impl Default for QueryPrompt {
+    fn default() -> Self {
+        Self {
+            system: None,
+            template: DEFAULT_QUERY_TEMPLATE.to_string(),
+        }
+    }
 }
 
+impl QueryPrompt {
+    fn render(&self, claim: &str) -> String {
+        self.template.replace("{claim}", claim)
+    }
+}
+
+/// Pause before the single retry of a call the provider rejected as rate limited.
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Most output tokens a normalization call asks for, however large the batch; about what
+/// the smaller supported models can return in one reply.
+const MAX_OUTPUT_TOKENS: u32 = 8192;
+
+/// Characters of an unparseable normalization reply that are raw-logged and dead-lettered.
+const UNPARSED_OUTPUT_CHARS: usize = 4000;
+
+/// How far back Twitter's recent search reaches, and so the default search window.
+pub const DEFAULT_SEARCH_WINDOW_DAYS: i64 = 7;
+
 impl LlmActor {
     pub fn new(
         rate_limiter: Addr<RateLimiter>,
@@ -33,6 +157,15 @@ impl LlmActor {
             rate_limiter,
             rate_key,
             out,
+            min_relevance: None,
+            rate_limit_backoff: RATE_LIMIT_BACKOFF,
+            write_limit: None,
+            search_window: chrono::Duration::days(DEFAULT_SEARCH_WINDOW_DAYS),
+            prompt_json: PromptJson::default(),
+            query_prompt: QueryPrompt::default(),
+            query_cache_ttl: None,
+            parse_failures: Arc::new(AtomicU64::new(0)),
+            dead_letter: false,
         }
     }
 
@@ -41,22 +174,99 @@ impl LlmActor {
         self.rate_key = key;
         self
     }
-}
-#[async_trait::async_trait]
-impl Actor for LlmActor {
-    type Msg = LlmMsg;
 
-    async fn handle(&mut self, msg: Self::Msg, _ctx: &mut Context<Self>) -> Result<()> {
-        match msg {
-            LlmMsg::NormalizeArtifact(raw_artifact) => {
-                acquire_rate_permit(&self.rate_limiter, &self.rate_key).await?;
-                let artifact_json = serde_json::to_string_pretty(&raw_artifact.payload)?;
+    /// Drop normalized artifacts whose `relevance_score` falls below `threshold` instead of
+    /// persisting them. Unset by default, which stores everything.
+    pub fn with_min_relevance(mut self, threshold: f64) -> Self {
+        self.min_relevance = Some(threshold);
+        self
+    }
 
-                let system_prompt = self.llm_client.default_osint_system_prompt().to_string();
-                let schema_description = r#"
-You must respond with a single JSON object that matches this schema exactly:
+    /// Take a slot from `limit` before each upsert and hand it to the store, which releases
+    /// it once the write finishes. Share the store's semaphore (`StoreActor::with_write_limit`)
+    /// so normalization waits instead of queueing writes while the store is saturated.
+    pub fn with_write_limit(mut self, limit: Arc<Semaphore>) -> Self {
+        self.write_limit = Some(limit);
+        self
+    }
+
+    /// Search window used when the model's `date_from`/`date_to` are missing, unparseable
+    /// or outside it: the last `window` up to now. Defaults to
+    /// [`DEFAULT_SEARCH_WINDOW_DAYS`]; a longer window only helps searches that reach
+    /// further back than Twitter's recent search.
+    pub fn with_search_window(mut self, window: chrono::Duration) -> Self {
+        self.search_window = window;
+        self
+    }
+
+    /// Serialize artifact payloads in normalization prompts this way (default compact).
+    pub fn with_prompt_json(mut self, style: PromptJson) -> Self {
+        self.prompt_json = style;
+        self
+    }
+
+    /// Build search queries from these prompts instead of [`DEFAULT_QUERY_TEMPLATE`].
+    pub fn with_query_prompt(mut self, prompt: QueryPrompt) -> Self {
+        self.query_prompt = prompt;
+        self
+    }
+
+    /// Keep each built search query in the store for `ttl` and answer a claim with the
+    /// same text (under the same query prompts) from there instead of the LLM, unless the
+    /// request asks for a fresh one. Off by default.
+    pub fn with_query_cache(mut self, ttl: Duration) -> Self {
+        self.query_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Send artifacts whose normalization reply can't be parsed to the store's dead-letter
+    /// table (`StoreMsg::DeadLetter`) instead of only logging them. Off by default.
+    pub fn with_dead_letter(mut self, on: bool) -> Self {
+        self.dead_letter = on;
+        self
+    }
+
+    /// Normalization replies that failed to parse so far. Take it before spawning the
+    /// actor; the count keeps rising as it runs.
+    pub fn parse_failures(&self) -> Arc<AtomicU64> {
+        self.parse_failures.clone()
+    }
+
+    /// The query cached under `key`, if any. A failed lookup only costs a fresh build.
+    async fn cached_query(&self, key: &str, max_age: Duration) -> Option<CachedSearchQuery> {
+        let (reply, rx) = oneshot::channel();
+        let msg = StoreMsg::CachedQuery {
+            key: key.to_string(),
+            max_age,
+            reply,
+        };
+        if self.out.send(msg).await.is_err() {
+            return None;
+        }
+        match rx.await {
+            Ok(Ok(hit)) => hit,
+            Ok(Err(err)) => {
+                tracing::warn!(error = ?err, "llm.query_cache.lookup_failed");
+                None
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+/// Cache key for a built search query: a hash of the prompts that build it, so the same
+/// claim text shares an entry and changing the query prompts starts over.
+fn query_cache_key(system: &str, prompt: &str) -> String {
+    Uuid::new_v5(
+        &Uuid::NAMESPACE_OID,
+        format!("{system}\n{prompt}").as_bytes(),
+    )
+    .to_string()
+}
+const NORMALIZATION_SCHEMA: &str = r#"
 {
   "claim_relevance": boolean,
+  "relevance_score": number between 0.0 and 1.0,
   "reasoning": string,
   "provenance_info": string,
   "entities": [
@@ -64,93 +274,502 @@ You must respond with a single JSON object that matches this schema exactly:
       "external_id": string | null,
       "name": string,
       "credibility": "strong" | "weak" | "unknown",
+      "stance": "supports" | "refutes" | "neutral",
       "reasoning": string
     }
   ]
-}
-The JSON must be valid. Do not include any additional commentary or code fences. Entities can include extracted entities from text, as well as twitter users
-including the author of the tweet or those mentioned."#;
-
-                let prompt = format!(
-            "Investigation claim: \"{}\"\n\nNormalize the following raw artifact from Twitter into the schema described.\nArtifact external_id: {}\nRaw artifact JSON:\n{}\n{}",
-            raw_artifact.claim.text, raw_artifact.external_id, artifact_json, schema_description
-        );
-
-                let response = self
-                    .llm_client
-                    .generate(&prompt, Some(&system_prompt), Some(600), Some(0.2))
-                    .await
-                    .map_err(anyhow::Error::from)?;
-
-                let parsed = parse_llm_normalization(&response.text)?;
-                let internal_id = Uuid::new_v4();
-                let entities = parsed
-                    .entities
-                    .into_iter()
-                    .enumerate()
-                    .map(|(idx, entity)| Entity {
-                        article_id: internal_id,
-                        external_id: entity.external_id.unwrap_or_else(|| {
-                            format!("{}:entity:{idx}", raw_artifact.external_id)
-                        }),
-                        name: entity.name,
-                        credibility: Credibility::from(entity.credibility.as_str()),
-                        reasoning: entity.reasoning,
-                    })
-                    .collect();
-
-                let normalized = NormalizedArtifact {
-                    external_id: raw_artifact.external_id.clone(),
-                    internal_id,
-                    claim_id: raw_artifact.claim.id,
-                    claim_relevance: parsed.claim_relevance,
-                    reasoning: parsed.reasoning,
-                    provenance_info: parsed.provenance_info,
-                    entities,
-                };
+}"#;
 
-                self.out
-                    .send(StoreMsg::UpsertArtifact(normalized))
-                    .await
-                    .map_err(|_| {
-                        anyhow!(
-                            "store actor mailbox dropped (artifact={})",
-                            raw_artifact.external_id
-                        )
-                    })?;
+const NORMALIZATION_RULES: &str = "The JSON must be valid. Do not include any additional commentary or code fences. Entities can include extracted entities from text, as well as twitter users
+including the author of the tweet or those mentioned.";
+
+impl LlmActor {
+    async fn normalize_one(&self, raw_artifact: RawArtifact) -> Result<()> {
+        if skip_cancelled(&raw_artifact) {
+            return Ok(());
+        }
+        acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Background).await?;
+        let artifact_json = self.prompt_json.render(&raw_artifact.payload)?;
+
+        let system_prompt = self.llm_client.default_osint_system_prompt().to_string();
+        let prompt = format!(
+            "Investigation claim: \"{}\"\n\nNormalize the following raw artifact from Twitter into the schema described.\nArtifact external_id: {}\nRaw artifact JSON:\n{}\n\nYou must respond with a single JSON object that matches this schema exactly:{}\n{}",
+            raw_artifact.claim.text,
+            raw_artifact.external_id,
+            artifact_json,
+            NORMALIZATION_SCHEMA,
+            NORMALIZATION_RULES
+        );
+        tracing::debug!(
+            artifact = %raw_artifact.external_id,
+            prompt_bytes = prompt.len(),
+            "llm.normalize.prompt"
+        );
+
+        let Some(response) = self
+            .generate_with_retry(&prompt, &system_prompt, 600, &raw_artifact.cancel)
+            .await?
+        else {
+            let cancelled = skip_cancelled(&raw_artifact);
+            debug_assert!(
+                cancelled,
+                "generate_with_retry only gives up on cancellation"
+            );
+            return Ok(());
+        };
+
+        match parse_llm_normalization(&response.text) {
+            Ok(parsed) => self.persist(&raw_artifact, parsed).await,
+            Err(err) => self.parse_failed(raw_artifact, &response.text, err).await,
+        }
+    }
+
+    /// Count and log a reply that did not parse and, with dead-lettering on, hand the raw
+    /// artifact to the store. The artifact goes unnormalized, but the actor keeps serving.
+    async fn parse_failed(
+        &self,
+        raw_artifact: RawArtifact,
+        output: &str,
+        err: anyhow::Error,
+    ) -> Result<()> {
+        let total = self.parse_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        tracing::warn!(
+            claim_id = %raw_artifact.claim.id,
+            artifact = %raw_artifact.external_id,
+            error = %err,
+            total,
+            "llm.normalize.parse_failed"
+        );
+        let output: String = output.chars().take(UNPARSED_OUTPUT_CHARS).collect();
+        if raw_responses_enabled() {
+            tracing::debug!(
+                target: "http.raw",
+                artifact = %raw_artifact.external_id,
+                output = %output,
+                "llm.normalize.unparsed_output"
+            );
+        }
+        if !self.dead_letter {
+            return Ok(());
+        }
+        let letter = DeadLetter {
+            external_id: raw_artifact.external_id,
+            claim_id: raw_artifact.claim.id,
+            payload: raw_artifact.payload,
+            error: format!("{err:#}"),
+            model_output: output,
+        };
+        let id = letter.external_id.clone();
+        self.out
+            .send(StoreMsg::DeadLetter(Box::new(letter)))
+            .await
+            .map_err(|_| anyhow!("store actor mailbox dropped (dead letter {id})"))
+    }
+
+    /// Normalize several artifacts with a single `generate` call.
+    ///
+    /// The model is asked for a JSON array tagged with each artifact's external id and the
+    /// results are stored with one `UpsertBatch`. If the reply can't be parsed, or an
+    /// artifact is missing from it, those artifacts go back through the single-item path.
+    async fn normalize_batch(&self, mut batch: Vec<RawArtifact>) -> Result<()> {
+        batch.retain(|raw_artifact| !skip_cancelled(raw_artifact));
+        if batch.len() <= 1 {
+            for raw_artifact in batch {
+                self.normalize_one(raw_artifact).await?;
             }
-            LlmMsg::BuildSearchQuery { claim, reply } => {
-                let system_prompt = self.llm_client.default_osint_system_prompt().to_string();
-                let user_directions = r#"
-You must respond with a single JSON object that matches this schema exactly:
-{
-  "query": string,
-  "date_from": string,
-  "date_to": string,
-}
-The JSON must be valid. Do not include any additional commentary or code fences.
-The query must be a string representing a twitter search query based the attached claim. Ideally, this would include the key entity and perhaps the most
-important action or object involved. For example, if the claim is "Terry McLaurin signed a contract for 500 million USD in 2024.", the search would be
-'"Terry McLaurin" contract'. The date values must be deserializable into chrono::DateTime<Utc> values."#;
-                let prompt = format!(
-                    "Investigation claim: \"{}\"\n\n directions: {}",
-                    claim.text, user_directions
+            return Ok(());
+        }
+
+        acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Background).await?;
+        let claim_text = batch[0].claim.text.clone();
+        let mut artifacts_block = String::new();
+        for raw_artifact in &batch {
+            artifacts_block.push_str(&format!(
+                "Artifact external_id: {}\nRaw artifact JSON:\n{}\n\n",
+                raw_artifact.external_id,
+                self.prompt_json.render(&raw_artifact.payload)?
+            ));
+        }
+
+        let system_prompt = self.llm_client.default_osint_system_prompt().to_string();
+        let prompt = format!(
+            "Investigation claim: \"{}\"\n\nNormalize each of the following {} raw artifacts from Twitter into the schema described.\n\n{}You must respond with a single JSON array containing one object per artifact, in the same order. Each object must match this schema exactly, plus an \"artifact_id\" string holding the artifact's external_id:{}\n{}",
+            claim_text,
+            batch.len(),
+            artifacts_block,
+            NORMALIZATION_SCHEMA,
+            NORMALIZATION_RULES
+        );
+        tracing::debug!(
+            batch = batch.len(),
+            prompt_bytes = prompt.len(),
+            "llm.normalize_batch.prompt"
+        );
+
+        let max_tokens = 600u32
+            .saturating_mul(batch.len() as u32)
+            .min(MAX_OUTPUT_TOKENS);
+        // A batch comes from a single search, so every item shares the same token.
+        let cancel = batch[0].cancel.clone();
+        let parsed = match self
+            .generate_with_retry(&prompt, &system_prompt, max_tokens, &cancel)
+            .await
+        {
+            Ok(Some(response)) => parse_llm_normalization_batch(&response.text),
+            Ok(None) => {
+                tracing::info!(
+                    claim_id = %batch[0].claim.id,
+                    batch = batch.len(),
+                    "llm.normalize_batch.cancelled"
+                );
+                return Ok(());
+            }
+            Err(err) => Err(err),
+        };
+        let mut matched = match parsed {
+            Ok(items) => match_batch(&batch, items),
+            Err(err) => {
+                tracing::warn!(
+                    batch = batch.len(),
+                    error = %err,
+                    "llm.normalize_batch.fallback"
+                );
+                Vec::new()
+            }
+        };
+        matched.resize_with(batch.len(), || None);
+
+        let mut normalized = Vec::new();
+        let mut fallback = Vec::new();
+        for (raw_artifact, parsed) in batch.into_iter().zip(matched) {
+            match parsed {
+                Some(parsed) => normalized.extend(self.to_normalized(&raw_artifact, parsed)),
+                None => fallback.push(raw_artifact),
+            }
+        }
+        if !normalized.is_empty() {
+            let count = normalized.len();
+            let permit = self.write_slot().await?;
+            self.out
+                .send(StoreMsg::UpsertBatch(normalized, permit))
+                .await
+                .map_err(|_| anyhow!("store actor mailbox dropped (batch of {count})"))?;
+        }
+        for raw_artifact in fallback {
+            self.normalize_one(raw_artifact).await?;
+        }
+        Ok(())
+    }
+
+    /// One `generate` call; with `NOWHERE_HTTP_RAW` set, fetches the provider body too
+    /// and logs it, so a reply that fails to parse can be diagnosed.
+    async fn generate_once(
+        &self,
+        prompt: &str,
+        system_prompt: &str,
+        budget: u32,
+    ) -> nowhere_common::Result<LlmResponse> {
+        if !raw_responses_enabled() {
+            return self
+                .llm_client
+                .generate(prompt, Some(system_prompt), Some(budget), Some(0.2))
+                .await;
+        }
+        let (response, raw) = self
+            .llm_client
+            .generate_raw(prompt, Some(system_prompt), Some(budget), Some(0.2))
+            .await?;
+        tracing::debug!(
+            target: "http.raw",
+            model = self.llm_client.model_name(),
+            raw = %raw,
+            "llm.normalize.raw_response"
+        );
+        Ok(response)
+    }
+
+    /// Call the model, retrying once with twice the token budget if the reply came back
+    /// truncated, and once after a short backoff if the failure was transient (see
+    /// [`LlmError::is_retryable`]).
+    /// Returns `None` if `cancel` fires first.
+    async fn generate_with_retry(
+        &self,
+        prompt: &str,
+        system_prompt: &str,
+        max_tokens: u32,
+        cancel: &CancellationToken,
+    ) -> Result<Option<LlmResponse>> {
+        let mut budget = max_tokens;
+        let mut throttled = false;
+        loop {
+            let result = tokio::select! {
+                response = self.generate_once(prompt, system_prompt, budget) => response,
+                _ = cancel.cancelled() => return Ok(None),
+            };
+            let response = match result {
+                Ok(response) => response,
+                Err(err)
+                    if !throttled
+                        && LlmError::from_nowhere(&err).is_some_and(LlmError::is_retryable) =>
+                {
+                    throttled = true;
+                    tracing::warn!(
+                        backoff_ms = self.rate_limit_backoff.as_millis() as u64,
+                        error = %err,
+                        "llm.normalize.retrying"
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(self.rate_limit_backoff) => {}
+                        _ = cancel.cancelled() => return Ok(None),
+                    }
+                    acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Background)
+                        .await?;
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if !response.truncated || budget > max_tokens || budget >= MAX_OUTPUT_TOKENS {
+                return Ok(Some(response));
+            }
+            tracing::warn!(
+                max_tokens = budget,
+                finish_reason = ?response.finish_reason,
+                "llm.normalize.truncated_retry"
+            );
+            budget = budget.saturating_mul(2).min(MAX_OUTPUT_TOKENS);
+            acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Background).await?;
+        }
+    }
+
+    async fn persist(&self, raw_artifact: &RawArtifact, parsed: LlmNormalization) -> Result<()> {
+        let Some(normalized) = self.to_normalized(raw_artifact, parsed) else {
+            return Ok(());
+        };
+        let permit = self.write_slot().await?;
+        self.out
+            .send(StoreMsg::UpsertArtifact(Box::new(normalized), permit))
+            .await
+            .map_err(|_| {
+                anyhow!(
+                    "store actor mailbox dropped (artifact={})",
+                    raw_artifact.external_id
+                )
+            })
+    }
+
+    async fn write_slot(&self) -> Result<Option<OwnedSemaphorePermit>> {
+        match &self.write_limit {
+            Some(limit) => Ok(Some(limit.clone().acquire_owned().await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Build the stored artifact from the model's reply, or `None` if it falls below
+    /// `min_relevance`.
+    fn to_normalized(
+        &self,
+        raw_artifact: &RawArtifact,
+        parsed: LlmNormalization,
+    ) -> Option<NormalizedArtifact> {
+        if let Some(min) = self.min_relevance {
+            let score = parsed.score();
+            if score < min {
+                tracing::info!(
+                    external_id=%raw_artifact.external_id,
+                    claim_id=%raw_artifact.claim.id,
+                    score,
+                    min,
+                    "llm.normalize.below_min_relevance"
                 );
+                return None;
+            }
+        }
+        let internal_id = Uuid::new_v4();
+        let entities = parsed
+            .entities
+            .into_iter()
+            .enumerate()
+            .map(|(idx, entity)| Entity {
+                article_id: internal_id,
+                external_id: entity
+                    .external_id
+                    .unwrap_or_else(|| format!("{}:entity:{idx}", raw_artifact.external_id)),
+                name: entity.name,
+                credibility: Credibility::from(entity.credibility.as_str()),
+                stance: entity
+                    .stance
+                    .as_deref()
+                    .map(Stance::from)
+                    .unwrap_or(Stance::Neutral),
+                reasoning: entity.reasoning,
+            })
+            .collect();
 
-                acquire_rate_permit(&self.rate_limiter, &self.rate_key).await?;
+        Some(NormalizedArtifact {
+            external_id: raw_artifact.external_id.clone(),
+            internal_id,
+            claim_id: raw_artifact.claim.id,
+            claim_relevance: parsed.claim_relevance,
+            reasoning: parsed.reasoning,
+            provenance_info: parsed.provenance_info,
+            canonical_url: canonical_url_from_payload(&raw_artifact.payload),
+            provenance: provenance_from_payload(&raw_artifact.payload),
+            entities,
+        })
+    }
+}
+
+/// Log and report artifacts whose claim was cancelled so callers can drop them.
+fn skip_cancelled(raw_artifact: &RawArtifact) -> bool {
+    if raw_artifact.cancel.is_cancelled() {
+        tracing::info!(
+            external_id = %raw_artifact.external_id,
+            claim_id = %raw_artifact.claim.id,
+            "llm.normalize.cancelled"
+        );
+        return true;
+    }
+    false
+}
 
-                let resp = self
-                    .llm_client
-                    .generate(&prompt, Some(&system_prompt), Some(600), Some(0.2))
-                    .await?;
+/// The page a raw payload points at: a web capture's own `url`, or the first outbound link
+/// in a tweet's `entities.urls` (links back to twitter.com/x.com are quote/self links).
+fn canonical_url_from_payload(payload: &serde_json::Value) -> Option<String> {
+    if let Some(url) = payload.get("url").and_then(|v| v.as_str()) {
+        return nowhere_common::urls::canonical_url(url);
+    }
+    payload
+        .pointer("/entities/urls")?
+        .as_array()?
+        .iter()
+        .filter_map(|u| u.get("expanded_url")?.as_str())
+        .filter_map(nowhere_common::urls::canonical_url)
+        .find(|u| {
+            let host = u.split("://").nth(1).unwrap_or_default();
+            ![
+                "twitter.com/",
+                "x.com/",
+                "www.twitter.com/",
+                "mobile.twitter.com/",
+            ]
+            .iter()
+            .any(|p| host.starts_with(p))
+        })
+}
 
-                let search_query_response =
-                    serde_json::from_str::<SearchQueryResponse>(&resp.text)?;
+/// Source link, author and platform read from the hydrated payload: pages carry a `url`,
+/// anything else is treated as a tweet. `captured_at` is the time of normalization, and a
+/// page archived before normalization brings its `capture` paths along.
+fn provenance_from_payload(payload: &serde_json::Value) -> Provenance {
+    let field = |key: &str| {
+        payload
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    let (platform, source_url, author) = match field("url") {
+        Some(url) => ("web", Some(url), field("author")),
+        None => (
+            "twitter",
+            field("id").map(|id| format!("https://x.com/i/web/status/{id}")),
+            field("username").or_else(|| field("author_id")),
+        ),
+    };
+    let capture = |key: &str| {
+        payload
+            .get("capture")?
+            .get(key)?
+            .as_str()
+            .map(str::to_string)
+    };
+    Provenance {
+        source_url,
+        author,
+        captured_at: Some(Utc::now()),
+        platform: Some(platform.to_string()),
+        html_path: capture("html_path"),
+        screenshot_path: capture("screenshot_path"),
+    }
+}
 
+#[async_trait::async_trait]
+impl Actor for LlmActor {
+    type Msg = LlmMsg;
+
+    async fn handle(&mut self, msg: Self::Msg, _ctx: &mut Context<Self>) -> Result<()> {
+        match msg {
+            LlmMsg::NormalizeArtifact(raw_artifact) => {
+                self.normalize_one(raw_artifact).await?;
+            }
+            LlmMsg::NormalizeBatch(batch) => {
+                self.normalize_batch(batch).await?;
+            }
+            LlmMsg::BuildSearchQuery {
+                claim,
+                fresh,
+                reply,
+            } => {
+                let system_prompt =
+                    self.query_prompt.system.clone().unwrap_or_else(|| {
+                        self.llm_client.default_osint_system_prompt().to_string()
+                    });
+                let prompt = self.query_prompt.render(&claim.text);
+
+                let cache_key = self
+                    .query_cache_ttl
+                    .map(|ttl| (query_cache_key(&system_prompt, &prompt), ttl));
+                let hit = match cache_key.as_ref().filter(|_| !fresh) {
+                    Some((key, ttl)) => self.cached_query(key, *ttl).await,
+                    None => None,
+                };
+                let (built, rationale) = match hit {
+                    Some(hit) => {
+                        tracing::info!(claim_id = %claim.id, query = %hit.query, "llm.build_query.cache_hit");
+                        (hit, None)
+                    }
+                    None => {
+                        acquire_rate_permit(
+                            &self.rate_limiter,
+                            &self.rate_key,
+                            Priority::Interactive,
+                        )
+                        .await?;
+
+                        let resp = self
+                            .llm_client
+                            .generate(&prompt, Some(&system_prompt), Some(600), Some(0.2))
+                            .await?;
+
+                        let search_query_response =
+                            serde_json::from_str::<SearchQueryResponse>(&resp.text)?;
+                        let date = |v: &Option<serde_json::Value>| {
+                            v.as_ref()?.as_str().map(str::to_string)
+                        };
+                        let built = CachedSearchQuery {
+                            query: search_query_response.query.clone(),
+                            date_from: date(&search_query_response.date_from),
+                            date_to: date(&search_query_response.date_to),
+                        };
+                        if let Some((key, _)) = cache_key {
+                            let search = built.clone();
+                            let _ = self.out.send(StoreMsg::CacheQuery { key, search }).await;
+                        }
+                        (built, search_query_response.rationale())
+                    }
+                };
+
+                // Resolved on every build, cached or not, so relative windows stay current.
+                let (date_from, date_to) = search_window(
+                    built.date_from.as_deref(),
+                    built.date_to.as_deref(),
+                    Utc::now(),
+                    self.search_window,
+                );
                 let _ = reply.send(BuiltSearchQuery {
-                    query: search_query_response.query,
-                    date_from: search_query_response.date_from,
-                    date_to: search_query_response.date_to,
+                    query: built.query,
+                    date_from,
+                    date_to,
+                    rationale,
                     claim,
                 });
             }
@@ -164,6 +783,30 @@ pub struct ChatLlmActor {
     rate_limiter: Addr<RateLimiter>,
     rate_key: RateKey,
     store: Addr<StoreActor>,
+    prompt_json: PromptJson,
+    min_context_artifacts: usize,
+    answer_language: Option<String>,
+}
+
+/// Prepended to chat answers built from fewer artifacts than `min_context_artifacts`.
+pub const LIMITED_EVIDENCE_CAVEAT: &str = "Answer based on limited evidence";
+
+const CHAT_SYSTEM_PROMPT: &str =
+    "You answer questions strictly using the provided artifacts and entities. \
+     Always include artifact internal_ids and entity ids you relied on. \
+     Note entity credibility labels (strong/weak/unknown) and stance toward the claim \
+     (supports/refutes/neutral). \
+     If uncertain, state caveats briefly.";
+
+/// The chat system prompt, with a directive to answer in `language` when one is given.
+fn chat_system_prompt(language: Option<&str>) -> String {
+    match language.map(str::trim).filter(|l| !l.is_empty()) {
+        Some(language) => format!(
+            "{CHAT_SYSTEM_PROMPT} Respond in {language}, whatever language the question and \
+             evidence are in; keep ids and citations unchanged."
+        ),
+        None => CHAT_SYSTEM_PROMPT.to_string(),
+    }
 }
 
 impl ChatLlmActor {
@@ -178,6 +821,9 @@ impl ChatLlmActor {
             rate_limiter,
             rate_key,
             store,
+            prompt_json: PromptJson::default(),
+            min_context_artifacts: 0,
+            answer_language: None,
         }
     }
 
@@ -185,6 +831,26 @@ impl ChatLlmActor {
         self.rate_key = key;
         self
     }
+
+    /// Serialize the retrieved context in chat prompts this way (default compact).
+    pub fn with_prompt_json(mut self, style: PromptJson) -> Self {
+        self.prompt_json = style;
+        self
+    }
+
+    /// Flag answers built from fewer than `min` artifacts with `LIMITED_EVIDENCE_CAVEAT`
+    /// (default 0, never flagged).
+    pub fn with_min_context_artifacts(mut self, min: usize) -> Self {
+        self.min_context_artifacts = min;
+        self
+    }
+
+    /// Answer in `language` (e.g. `Spanish`) unless a `ChatCmd` asks for another. By
+    /// default the model picks, usually the language of the question.
+    pub fn with_answer_language(mut self, language: impl Into<String>) -> Self {
+        self.answer_language = Some(language.into());
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -197,6 +863,7 @@ impl Actor for ChatLlmActor {
             k,
             reply,
             claim,
+            language,
         } = msg;
 
         let hits = store_search_artifacts(&self.store, claim.id, &user_text, k)
@@ -204,20 +871,15 @@ impl Actor for ChatLlmActor {
             // FIXME: plumb store errors back to the TUI so users know retrieval failed instead of silently falling back to an empty set.
             .unwrap_or_default();
 
-        let mut bundles = Vec::new();
-        for artifact in hits.iter().take(6) {
-            // FIXME: make the retrieval depth configurable instead of hard-coding 6 artifacts.
-            if let Ok(bundle) = store_get_artifact(&self.store, &artifact.internal_id).await {
-                bundles.push(bundle);
-            }
-        }
+        let mut bundles = store_get_artifacts(&self.store, &hits)
+            .await
+            .unwrap_or_default();
+        // Search stopped at `k` hits, so every artifact hydrated here goes into the context.
+        rank_by_credibility(&mut bundles);
 
-        acquire_rate_permit(&self.rate_limiter, &self.rate_key).await?;
+        acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Interactive).await?;
 
-        let sys = "You answer questions strictly using the provided artifacts and entities. \
-                   Always include artifact internal_ids and entity ids you relied on. \
-                   Note entity credibility labels (strong/weak/unknown). \
-                   If uncertain, state caveats briefly.";
+        let sys = chat_system_prompt(language.as_deref().or(self.answer_language.as_deref()));
         let context = serde_json::json!({
             "artifacts": bundles.iter().map(|b| {
                 serde_json::json!({
@@ -225,11 +887,13 @@ impl Actor for ChatLlmActor {
                   "external_id": b.artifact.external_id,
                   "reasoning": b.artifact.reasoning,
                   "provenance_info": b.artifact.provenance_info,
+                  "source_url": b.artifact.provenance.source_url,
                   "entities": b.entities.iter().map(|e| {
                     serde_json::json!({
                       "id": e.id,
                       "name": e.name,
-                      "credibility": e.credibility
+                      "credibility": e.credibility,
+                      "stance": e.stance
                     })
                   }).collect::<Vec<_>>()
                 })
@@ -241,15 +905,37 @@ impl Actor for ChatLlmActor {
              \nInstructions: Answer concisely. When you mention a fact, add citations like [A:<artifact_id>] \
              and optionally [E:<entity_id>] right after the sentence. Do not invent data.",
             user_text,
-            serde_json::to_string(&context)?
+            self.prompt_json.render(&context)?
+        );
+        tracing::debug!(
+            claim_id = %claim.id,
+            artifacts = bundles.len(),
+            prompt_bytes = prompt.len(),
+            "llm.chat.prompt"
         );
 
         let resp = self
             .llm_client
             // FIXME: surface temperature/max token choices from config rather than hard-coding generation parameters here.
-            .generate(&prompt, Some(sys), Some(1000), Some(0.5))
+            .generate(&prompt, Some(&sys), Some(1000), Some(0.5))
             .await?;
-        let answer = resp.text.trim().to_string();
+        let mut answer = resp.text.trim().to_string();
+        let mut caveats = Vec::new();
+        if bundles.len() < self.min_context_artifacts {
+            let caveat = format!(
+                "{LIMITED_EVIDENCE_CAVEAT}: {} of the {} artifacts wanted.",
+                bundles.len(),
+                self.min_context_artifacts
+            );
+            tracing::info!(
+                claim_id = %claim.id,
+                artifacts = bundles.len(),
+                min = self.min_context_artifacts,
+                "llm.chat.limited_evidence"
+            );
+            answer = format!("⚠ {caveat}\n\n{answer}");
+            caveats.push(caveat);
+        }
 
         let used_artifacts = bundles
             .iter()
@@ -265,33 +951,41 @@ impl Actor for ChatLlmActor {
             text: answer,
             used_artifacts,
             used_entities,
-            // FIXME: capture explicit caveats from the model response instead of always returning an empty list.
-            caveats: vec![],
+            // FIXME: capture explicit caveats from the model response, not just our own.
+            caveats,
         };
         let _ = reply.send(out);
         Ok(())
     }
 }
 
-async fn acquire_rate_permit(rate_limiter: &Addr<RateLimiter>, rate_key: &RateKey) -> Result<()> {
-    let (permit_tx, permit_rx) = oneshot::channel();
-    rate_limiter
-        .send(RateMsg::Acquire {
-            key: rate_key.clone(),
-            cost: 1,
-            reply: permit_tx,
-        })
-        .await
-        .map_err(|_| anyhow!("rate limiter actor dropped"))?;
-
-    permit_rx
-        .await
-        .map_err(|_| anyhow!("failed to receive rate permit from limiter"))?;
+/// Mean credibility weight of an artifact's entities; artifacts without entities count
+/// as `unknown`.
+fn credibility_score(bundle: &ArtifactWithEntities) -> f64 {
+    if bundle.entities.is_empty() {
+        return Credibility::Unknown.weight();
+    }
+    let total: f64 = bundle
+        .entities
+        .iter()
+        .map(|e| Credibility::from(&e.credibility).weight())
+        .sum();
+    total / bundle.entities.len() as f64
+}
 
-    Ok(())
+/// Order bundles by credibility (strong > unknown > weak), with artifacts an investigator
+/// confirmed ahead of the rest. The sort is stable, so equally ranked artifacts keep their
+/// search ranking.
+fn rank_by_credibility(bundles: &mut [ArtifactWithEntities]) {
+    let confirmed = |b: &ArtifactWithEntities| b.artifact.review_status == ReviewStatus::Confirmed;
+    bundles.sort_by(|a, b| {
+        confirmed(b)
+            .cmp(&confirmed(a))
+            .then(credibility_score(b).total_cmp(&credibility_score(a)))
+    });
 }
 
-async fn store_search_artifacts(
+pub(crate) async fn store_search_artifacts(
     store: &Addr<StoreActor>,
     claim: Uuid,
     query: &str,
@@ -320,21 +1014,56 @@ async fn store_search_artifacts(
     res
 }
 
-async fn store_get_artifact(
+/// Hydrate `hits` with one `GetArtifacts` round trip, keeping their search order.
+async fn store_get_artifacts(
     store: &Addr<StoreActor>,
-    id: &str,
-) -> anyhow::Result<ArtifactWithEntities> {
+    hits: &[ArtifactRow],
+) -> anyhow::Result<Vec<ArtifactWithEntities>> {
+    let ids = hits
+        .iter()
+        .map(|a| Uuid::parse_str(&a.internal_id))
+        .collect::<Result<Vec<_>, _>>()?;
     let (tx, rx) = oneshot::channel();
-    let uid = Uuid::parse_str(id)?;
     store
-        .send(StoreMsg::GetArtifact {
-            internal_id: uid,
+        .send(StoreMsg::GetArtifacts {
+            ids: ids.clone(),
             reply: tx,
         })
         .await
         .map_err(|_| anyhow::anyhow!("store mailbox dropped"))?;
-    rx.await
-        .map_err(|_| anyhow::anyhow!("store reply dropped"))?
+    let mut found = rx
+        .await
+        .map_err(|_| anyhow::anyhow!("store reply dropped"))??;
+    Ok(ids.iter().filter_map(|id| found.remove(id)).collect())
+}
+
+/// The model's dates if they parse as RFC 3339 and form a window inside
+/// `[now - window, now]` (what Twitter's recent search accepts); otherwise that whole
+/// default window, with a warning.
+fn search_window(
+    from: Option<&str>,
+    to: Option<&str>,
+    now: DateTime<Utc>,
+    window: chrono::Duration,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let parse = |v: Option<&str>| {
+        DateTime::parse_from_rfc3339(v?)
+            .ok()
+            .map(|d| d.with_timezone(&Utc))
+    };
+    let earliest = now - window;
+    match (parse(from), parse(to)) {
+        (Some(from), Some(to)) if earliest <= from && from < to && to <= now => (from, to),
+        _ => {
+            tracing::warn!(
+                date_from = ?from,
+                date_to = ?to,
+                window_days = window.num_days(),
+                "llm.build_search_query.default_window"
+            );
+            (earliest, now)
+        }
+    }
 }
 
 fn parse_llm_normalization(raw: &str) -> Result<LlmNormalization> {
@@ -354,20 +1083,955 @@ fn parse_llm_normalization(raw: &str) -> Result<LlmNormalization> {
     Ok(parsed)
 }
 
+/// Pair batch results with their artifacts: by `artifact_id` when the model tagged them,
+/// otherwise by position when the counts line up. Unmatched artifacts yield `None`.
+fn match_batch(
+    batch: &[RawArtifact],
+    items: Vec<LlmNormalization>,
+) -> Vec<Option<LlmNormalization>> {
+    if items.iter().all(|item| item.artifact_id.is_some()) {
+        let mut by_id: std::collections::HashMap<String, LlmNormalization> = items
+            .into_iter()
+            .filter_map(|item| item.artifact_id.clone().map(|id| (id, item)))
+            .collect();
+        batch
+            .iter()
+            .map(|raw_artifact| by_id.remove(&raw_artifact.external_id))
+            .collect()
+    } else if items.len() == batch.len() {
+        items.into_iter().map(Some).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+fn parse_llm_normalization_batch(raw: &str) -> Result<Vec<LlmNormalization>> {
+    if let Ok(parsed) = serde_json::from_str::<Vec<LlmNormalization>>(raw) {
+        return Ok(parsed);
+    }
+
+    let start = raw
+        .find('[')
+        .ok_or_else(|| anyhow!("no JSON array found"))?;
+    let end = raw
+        .rfind(']')
+        .ok_or_else(|| anyhow!("incomplete JSON array"))?;
+    let parsed = serde_json::from_str::<Vec<LlmNormalization>>(&raw[start..=end])?;
+    Ok(parsed)
+}
+
 #[derive(Debug, Deserialize)]
 struct LlmNormalization {
+    /// Only present in batch replies; echoes the artifact's external id.
+    #[serde(default)]
+    artifact_id: Option<String>,
     claim_relevance: bool,
+    #[serde(default)]
+    relevance_score: Option<f64>,
     reasoning: String,
     provenance_info: String,
     #[serde(default)]
     entities: Vec<LlmEntity>,
 }
 
+impl LlmNormalization {
+    /// Numeric relevance, falling back to the boolean when the model omits a score.
+    fn score(&self) -> f64 {
+        self.relevance_score
+            .unwrap_or(if self.claim_relevance { 1.0 } else { 0.0 })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct LlmEntity {
     #[serde(default)]
     external_id: Option<String>,
     name: String,
     credibility: String,
+    // Older prompts/models omit stance; treat that as neutral.
+    #[serde(default)]
+    stance: Option<String>,
     reasoning: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::{probe, spawn_actor};
+    use crate::rate::RateMsg;
+    use crate::{ClaimContext, EntityRow, RawArtifact};
+    use nowhere_llm::mock::{completion, MockLlmClient};
+    use std::time::Duration;
+
+    fn scripted<I>(replies: I) -> Arc<MockLlmClient>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Arc::new(MockLlmClient::new().replies(replies))
+    }
+
+    fn rate_limiter() -> Addr<RateLimiter> {
+        let handle = spawn_actor(RateLimiter::new(), 8);
+        handle
+            .addr
+            .try_send(RateMsg::Upsert {
+                key: RateKey("test".into()),
+                qps: 1000.0,
+                burst: 1000,
+            })
+            .unwrap();
+        handle.addr
+    }
+
+    fn raw(external_id: &str, claim: &ClaimContext) -> RawArtifact {
+        RawArtifact {
+            external_id: external_id.to_string(),
+            payload: serde_json::json!({ "text": "tweet" }),
+            claim: claim.clone(),
+            cancel: CancellationToken::new(),
+        }
+    }
+
+    fn normalization(score: f64) -> String {
+        serde_json::json!({
+            "claim_relevance": true,
+            "relevance_score": score,
+            "reasoning": "r",
+            "provenance_info": "p",
+            "entities": []
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn below_min_relevance_is_not_upserted() {
+        let (store, mut upserts) = probe::<StoreActor>(8);
+        let client = scripted([normalization(0.2), normalization(0.9)]);
+        let actor = LlmActor::new(rate_limiter(), RateKey("test".into()), store, client)
+            .with_min_relevance(0.5);
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = claim();
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:low", &claim)))
+            .await
+            .ok()
+            .unwrap();
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:high", &claim)))
+            .await
+            .ok()
+            .unwrap();
+
+        assert_eq!(recv_upsert(&mut upserts).await.external_id, "tw:high");
+        assert!(upserts.try_recv().is_err(), "low-relevance artifact leaked");
+    }
+
+    async fn recv_upsert(rx: &mut tokio::sync::mpsc::Receiver<StoreMsg>) -> NormalizedArtifact {
+        let msg = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .expect("upsert in time")
+            .expect("store mailbox open");
+        match msg {
+            StoreMsg::UpsertArtifact(n, _) => *n,
+            _ => panic!("expected UpsertArtifact"),
+        }
+    }
+
+    fn claim() -> ClaimContext {
+        ClaimContext {
+            id: Uuid::new_v4(),
+            text: "claim".into(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn unparseable_dates_fall_back_to_the_default_window() {
+        let (store, _upserts) = probe::<StoreActor>(8);
+        let client = scripted([serde_json::json!({
+            "query": "\"bridge\" closed",
+            "date_from": "last Tuesday",
+            "date_to": "2024-13-45"
+        })
+        .to_string()]);
+        let actor = LlmActor::new(rate_limiter(), RateKey("test".into()), store, client)
+            .with_search_window(chrono::Duration::days(3));
+        let llm = spawn_actor(actor, 8).addr;
+
+        let (reply, rx) = oneshot::channel();
+        let before = Utc::now();
+        llm.send(LlmMsg::BuildSearchQuery {
+            claim: claim(),
+            fresh: false,
+            reply,
+        })
+        .await
+        .ok()
+        .unwrap();
+        let built = rx.await.expect("query built despite bad dates");
+
+        assert_eq!(built.query, "\"bridge\" closed");
+        assert_eq!(built.date_to - built.date_from, chrono::Duration::days(3));
+        assert_eq!(built.rationale, None, "no rationale is not an error");
+        assert!(built.date_to >= before && built.date_to <= Utc::now());
+    }
+
+    #[tokio::test]
+    async fn custom_query_template_reaches_the_client_with_the_claim() {
+        let (store, _upserts) = probe::<StoreActor>(8);
+        let client = scripted([serde_json::json!({
+            "query": "\"tariff\" steel",
+            "date_from": null,
+            "date_to": null,
+            "rationale": "  trade press reports tariff changes  "
+        })
+        .to_string()]);
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        )
+        .with_query_prompt(QueryPrompt {
+            system: Some("You search trade news.".into()),
+            template: "Claim: {claim}\nReply with JSON {query, date_from, date_to}.".into(),
+        });
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = ClaimContext {
+            text: "steel tariffs doubled".into(),
+            ..claim()
+        };
+        let (reply, rx) = oneshot::channel();
+        llm.send(LlmMsg::BuildSearchQuery {
+            claim,
+            fresh: false,
+            reply,
+        })
+        .await
+        .ok()
+        .unwrap();
+        let built = rx.await.unwrap();
+        assert_eq!(built.query, "\"tariff\" steel");
+        assert_eq!(
+            built.rationale.as_deref(),
+            Some("trade press reports tariff changes")
+        );
+
+        assert_eq!(
+            client.prompts()[0],
+            "Claim: steel tariffs doubled\nReply with JSON {query, date_from, date_to}."
+        );
+        assert_eq!(
+            client.calls()[0].system_prompt.as_deref(),
+            Some("You search trade news.")
+        );
+    }
+
+    async fn build_query(llm: &Addr<LlmActor>, text: &str, fresh: bool) -> BuiltSearchQuery {
+        let claim = ClaimContext {
+            text: text.into(),
+            ..claim()
+        };
+        let (reply, rx) = oneshot::channel();
+        llm.send(LlmMsg::BuildSearchQuery {
+            claim,
+            fresh,
+            reply,
+        })
+        .await
+        .ok()
+        .unwrap();
+        rx.await.expect("query built")
+    }
+
+    #[tokio::test]
+    async fn identical_claim_reuses_the_cached_query_until_asked_for_a_fresh_one() {
+        let (store, pool) = crate::store::tests::test_store().await;
+        let reply_with = |query: &str| {
+            serde_json::json!({"query": query, "date_from": null, "date_to": null}).to_string()
+        };
+        let client = scripted([reply_with("bridge closed"), reply_with("bridge shut")]);
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        )
+        .with_query_cache(Duration::from_secs(3600));
+        let llm = spawn_actor(actor, 8).addr;
+        // The cache write is fire-and-forget; wait for it before asking again.
+        let cached = |query: &'static str| {
+            let pool = pool.clone();
+            async move {
+                tokio::time::timeout(Duration::from_secs(2), async {
+                    loop {
+                        let stored: Option<String> =
+                            sqlx::query_scalar("SELECT query FROM query_cache")
+                                .fetch_optional(&pool)
+                                .await
+                                .unwrap();
+                        if stored.as_deref() == Some(query) {
+                            break;
+                        }
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                    }
+                })
+                .await
+                .expect("query cached");
+            }
+        };
+
+        let first = build_query(&llm, "the bridge closed", false).await;
+        assert_eq!(first.query, "bridge closed");
+        cached("bridge closed").await;
+
+        // The model gave no dates, so each build gets the default window ending at its own
+        // "now"; a hit must not replay the first build's window.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let between = Utc::now();
+        let again = build_query(&llm, "the bridge closed", false).await;
+        assert_eq!(again.query, "bridge closed");
+        assert_ne!(
+            again.claim.id, first.claim.id,
+            "the reply carries the new claim"
+        );
+        assert!(first.date_to < between && again.date_to >= between);
+        assert_eq!(
+            again.date_to - again.date_from,
+            first.date_to - first.date_from
+        );
+        assert_eq!(client.calls().len(), 1, "the second build hit the cache");
+
+        let fresh = build_query(&llm, "the bridge closed", true).await;
+        assert_eq!(fresh.query, "bridge shut");
+        assert_eq!(client.calls().len(), 2);
+        cached("bridge shut").await;
+        assert_eq!(
+            build_query(&llm, "the bridge closed", false).await.query,
+            "bridge shut",
+            "a fresh build replaces the cached query"
+        );
+        assert_eq!(client.calls().len(), 2);
+    }
+
+    #[test]
+    fn default_query_template_keeps_the_built_in_directions() {
+        let prompt = QueryPrompt::default().render("the bridge is closed");
+        assert!(prompt.starts_with("Investigation claim: \"the bridge is closed\""));
+        assert!(prompt.contains("\"date_from\": string"));
+        assert!(!prompt.contains("{claim}"));
+    }
+
+    #[test]
+    fn search_window_keeps_only_windows_inside_the_default_range() {
+        let now: DateTime<Utc> = "2025-03-10T12:00:00Z".parse().unwrap();
+        let week = chrono::Duration::days(7);
+        let window = |from: &str, to: &str| search_window(Some(from), Some(to), now, week);
+        let default = (now - week, now);
+
+        let from: DateTime<Utc> = "2025-03-08T00:00:00Z".parse().unwrap();
+        let to: DateTime<Utc> = "2025-03-09T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            window("2025-03-08T00:00:00Z", "2025-03-09T00:00:00Z"),
+            (from, to)
+        );
+        // Reversed, too old, in the future, or missing.
+        assert_eq!(
+            window("2025-03-09T00:00:00Z", "2025-03-08T00:00:00Z"),
+            default
+        );
+        assert_eq!(
+            window("2019-01-01T00:00:00Z", "2025-03-09T00:00:00Z"),
+            default
+        );
+        assert_eq!(
+            window("2025-03-08T00:00:00Z", "2030-01-01T00:00:00Z"),
+            default
+        );
+        assert_eq!(search_window(None, None, now, week), default);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_call_is_retried_once() {
+        let (store, mut upserts) = probe::<StoreActor>(8);
+        let client = Arc::new(
+            MockLlmClient::new()
+                .fail(LlmError::RateLimit)
+                .reply(normalization(0.8)),
+        );
+        let mut actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        );
+        actor.rate_limit_backoff = Duration::ZERO;
+        let llm = spawn_actor(actor, 8).addr;
+
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:1", &claim())))
+            .await
+            .ok()
+            .unwrap();
+
+        assert_eq!(recv_upsert(&mut upserts).await.external_id, "tw:1");
+        assert_eq!(client.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn truncated_reply_is_retried_with_a_larger_budget() {
+        let (store, mut upserts) = probe::<StoreActor>(8);
+        let client = Arc::new(
+            MockLlmClient::new()
+                .respond(LlmResponse {
+                    finish_reason: Some("length".into()),
+                    truncated: true,
+                    ..completion("{\"claim_relevance\": tr")
+                })
+                .reply(normalization(0.8)),
+        );
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        );
+        let llm = spawn_actor(actor, 8).addr;
+
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:1", &claim())))
+            .await
+            .ok()
+            .unwrap();
+
+        assert_eq!(recv_upsert(&mut upserts).await.external_id, "tw:1");
+        let budgets: Vec<_> = client.calls().iter().map(|c| c.max_tokens).collect();
+        assert_eq!(budgets, [Some(600), Some(1200)]);
+    }
+
+    #[tokio::test]
+    async fn cancelled_artifacts_skip_the_llm() {
+        let (store, mut upserts) = probe::<StoreActor>(8);
+        let client = scripted([normalization(0.8)]);
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        );
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = claim();
+        let cancelled = raw("tw:cancelled", &claim);
+        cancelled.cancel.cancel();
+        llm.send(LlmMsg::NormalizeArtifact(cancelled))
+            .await
+            .ok()
+            .unwrap();
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:live", &claim)))
+            .await
+            .ok()
+            .unwrap();
+
+        assert_eq!(recv_upsert(&mut upserts).await.external_id, "tw:live");
+        assert_eq!(client.calls().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn compact_prompt_json_is_smaller_than_pretty() {
+        let claim = claim();
+        let artifact = RawArtifact {
+            payload: serde_json::json!({
+                "id": "1850",
+                "text": "bridge closed",
+                "public_metrics": { "like_count": 3, "reply_count": 1 },
+                "entities": { "mentions": [{ "username": "roads" }] }
+            }),
+            ..raw("tw:1", &claim)
+        };
+
+        let mut prompts = Vec::new();
+        for style in [PromptJson::Compact, PromptJson::Pretty] {
+            let (store, mut upserts) = probe::<StoreActor>(8);
+            let client = scripted([normalization(0.8)]);
+            let actor = LlmActor::new(
+                rate_limiter(),
+                RateKey("test".into()),
+                store,
+                client.clone(),
+            )
+            .with_prompt_json(style);
+            let llm = spawn_actor(actor, 8).addr;
+            llm.send(LlmMsg::NormalizeArtifact(artifact.clone()))
+                .await
+                .ok()
+                .unwrap();
+            recv_upsert(&mut upserts).await;
+            prompts.push(client.prompts()[0].clone());
+        }
+
+        let [compact, pretty] = &prompts[..] else {
+            unreachable!()
+        };
+        assert!(compact.contains(r#""entities":{"mentions":[{"username":"roads"}]}"#));
+        assert!(
+            compact.len() < pretty.len(),
+            "compact {} vs pretty {} bytes",
+            compact.len(),
+            pretty.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_normalization_upserts_every_item() {
+        let ids = ["tw:1", "tw:2", "tw:3"];
+        let reply = serde_json::Value::Array(
+            ids.iter()
+                .map(|id| {
+                    let mut item: serde_json::Value =
+                        serde_json::from_str(&normalization(0.8)).unwrap();
+                    item["artifact_id"] = serde_json::json!(id);
+                    item
+                })
+                .collect(),
+        );
+        let (store, mut upserts) = probe::<StoreActor>(8);
+        let client = scripted([format!("```json\n{reply}\n```")]);
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        );
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = claim();
+        let batch = ids.iter().map(|id| raw(id, &claim)).collect();
+        llm.send(LlmMsg::NormalizeBatch(batch)).await.ok().unwrap();
+
+        let msg = tokio::time::timeout(Duration::from_secs(2), upserts.recv())
+            .await
+            .expect("upsert in time")
+            .expect("store mailbox open");
+        let StoreMsg::UpsertBatch(batch, _) = msg else {
+            panic!("expected UpsertBatch");
+        };
+        let seen: Vec<_> = batch.iter().map(|n| n.external_id.as_str()).collect();
+        assert_eq!(seen, ids);
+        assert_eq!(
+            client.calls().len(),
+            1,
+            "batch should use a single generate call"
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_budget_is_capped_and_not_grown_past_the_cap() {
+        let (store, mut upserts) = probe::<StoreActor>(32);
+        let ids: Vec<String> = (0..15).map(|i| format!("tw:{i}")).collect();
+        let client = Arc::new(
+            MockLlmClient::new()
+                .respond(LlmResponse {
+                    finish_reason: Some("length".into()),
+                    truncated: true,
+                    ..completion("[{\"artifact_id\": \"tw:0\"")
+                })
+                .replies(ids.iter().map(|_| normalization(0.8))),
+        );
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        );
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = claim();
+        let batch = ids.iter().map(|id| raw(id, &claim)).collect();
+        llm.send(LlmMsg::NormalizeBatch(batch)).await.ok().unwrap();
+        for id in &ids {
+            assert_eq!(&recv_upsert(&mut upserts).await.external_id, id);
+        }
+
+        let budgets: Vec<_> = client.calls().iter().map(|c| c.max_tokens).collect();
+        assert_eq!(
+            budgets[0],
+            Some(MAX_OUTPUT_TOKENS),
+            "15 x 600 is over the cap"
+        );
+        assert_eq!(
+            budgets[1],
+            Some(600),
+            "a truncated reply at the cap falls back to single items"
+        );
+        assert_eq!(budgets.len(), 1 + ids.len());
+    }
+
+    #[tokio::test]
+    async fn batch_parse_failure_falls_back_to_single_items() {
+        let (store, mut upserts) = probe::<StoreActor>(8);
+        let client = scripted([
+            "sorry, I can't do arrays".to_string(),
+            normalization(0.8),
+            normalization(0.8),
+        ]);
+        let actor = LlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            store,
+            client.clone(),
+        );
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = claim();
+        let batch = vec![raw("tw:a", &claim), raw("tw:b", &claim)];
+        llm.send(LlmMsg::NormalizeBatch(batch)).await.ok().unwrap();
+
+        assert_eq!(recv_upsert(&mut upserts).await.external_id, "tw:a");
+        assert_eq!(recv_upsert(&mut upserts).await.external_id, "tw:b");
+        assert_eq!(client.calls().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unparseable_reply_is_counted_and_dead_lettered() {
+        let (store, mut sent) = probe::<StoreActor>(8);
+        let client = scripted([
+            "I'm sorry, I can't help with that.".to_string(),
+            normalization(0.8),
+        ]);
+        let actor = LlmActor::new(rate_limiter(), RateKey("test".into()), store, client)
+            .with_dead_letter(true);
+        let failures = actor.parse_failures();
+        let llm = spawn_actor(actor, 8).addr;
+
+        let claim = claim();
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:bad", &claim)))
+            .await
+            .ok()
+            .unwrap();
+
+        let msg = tokio::time::timeout(Duration::from_secs(2), sent.recv())
+            .await
+            .expect("dead letter in time")
+            .expect("store mailbox open");
+        let StoreMsg::DeadLetter(letter) = msg else {
+            panic!("expected DeadLetter");
+        };
+        assert_eq!(letter.external_id, "tw:bad");
+        assert_eq!(letter.claim_id, claim.id);
+        assert_eq!(letter.model_output, "I'm sorry, I can't help with that.");
+        assert_eq!(letter.payload, raw("tw:bad", &claim).payload);
+        assert_eq!(failures.load(Ordering::Relaxed), 1);
+
+        // A bad reply no longer stops the normalizer.
+        llm.send(LlmMsg::NormalizeArtifact(raw("tw:good", &claim)))
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!(recv_upsert(&mut sent).await.external_id, "tw:good");
+    }
+
+    #[test]
+    fn canonical_url_skips_self_links_in_tweets() {
+        let tweet = serde_json::json!({
+            "text": "read this",
+            "entities": { "urls": [
+                { "expanded_url": "https://twitter.com/someone/status/1" },
+                { "expanded_url": "https://news.example/story/#top" }
+            ]}
+        });
+        assert_eq!(
+            canonical_url_from_payload(&tweet).as_deref(),
+            Some("https://news.example/story")
+        );
+        let page = serde_json::json!({ "url": "https://news.example/story" });
+        assert_eq!(
+            canonical_url_from_payload(&page).as_deref(),
+            Some("https://news.example/story")
+        );
+        assert_eq!(canonical_url_from_payload(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn tweet_provenance_links_to_the_status() {
+        let tweet = serde_json::json!({ "id": "123", "text": "t", "author_id": "42" });
+        let p = provenance_from_payload(&tweet);
+        assert_eq!(p.platform.as_deref(), Some("twitter"));
+        assert_eq!(
+            p.source_url.as_deref(),
+            Some("https://x.com/i/web/status/123")
+        );
+        assert_eq!(p.author.as_deref(), Some("42"));
+        assert!(p.captured_at.is_some());
+
+        let page = serde_json::json!({ "url": "https://news.example/story" });
+        let p = provenance_from_payload(&page);
+        assert_eq!(p.platform.as_deref(), Some("web"));
+        assert_eq!(p.source_url.as_deref(), Some("https://news.example/story"));
+        assert_eq!(p.html_path, None);
+
+        let captured = serde_json::json!({
+            "url": "https://news.example/story",
+            "capture": { "html_path": "captures/a.html", "screenshot_path": "captures/a.png" }
+        });
+        let p = provenance_from_payload(&captured);
+        assert_eq!(p.html_path.as_deref(), Some("captures/a.html"));
+        assert_eq!(p.screenshot_path.as_deref(), Some("captures/a.png"));
+    }
+
+    fn bundle(external_id: &str, credibility: &[&str]) -> ArtifactWithEntities {
+        ArtifactWithEntities {
+            artifact: ArtifactRow {
+                internal_id: Uuid::new_v4().to_string(),
+                external_id: external_id.into(),
+                claim_relevance: true,
+                reasoning: String::new(),
+                provenance_info: String::new(),
+                claim_id: None,
+                provenance: Default::default(),
+                review_status: Default::default(),
+            },
+            entities: credibility
+                .iter()
+                .enumerate()
+                .map(|(i, c)| EntityRow {
+                    id: format!("{external_id}:e{i}"),
+                    article_id: String::new(),
+                    canonical_id: None,
+                    name: format!("source {i}"),
+                    credibility: c.to_string(),
+                    stance: "neutral".into(),
+                    reasoning: String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn strong_sources_lead_the_context() {
+        // Search order puts the weak-sourced artifact first.
+        let mut bundles = vec![
+            bundle("tw:weak", &["weak", "weak"]),
+            bundle("tw:none", &[]),
+            bundle("tw:mixed", &["strong", "weak"]),
+            bundle("tw:strong", &["strong"]),
+        ];
+
+        rank_by_credibility(&mut bundles);
+
+        let order: Vec<_> = bundles
+            .iter()
+            .map(|b| b.artifact.external_id.as_str())
+            .collect();
+        assert_eq!(order, vec!["tw:strong", "tw:none", "tw:mixed", "tw:weak"]);
+    }
+
+    #[test]
+    fn confirmed_artifacts_rank_ahead_of_credibility() {
+        let mut confirmed = bundle("tw:confirmed", &["weak"]);
+        confirmed.artifact.review_status = ReviewStatus::Confirmed;
+        let mut bundles = vec![bundle("tw:strong", &["strong"]), confirmed];
+
+        rank_by_credibility(&mut bundles);
+
+        assert_eq!(bundles[0].artifact.external_id, "tw:confirmed");
+    }
+
+    /// A store that answers chat retrieval with `bundles`.
+    fn chat_store(bundles: Vec<ArtifactWithEntities>) -> Addr<StoreActor> {
+        let (store, mut rx) = probe::<StoreActor>(8);
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                match msg {
+                    StoreMsg::SearchArtifacts { reply, .. } => {
+                        let _ =
+                            reply.send(Ok(bundles.iter().map(|b| b.artifact.clone()).collect()));
+                    }
+                    StoreMsg::GetArtifacts { reply, .. } => {
+                        let _ = reply.send(Ok(bundles
+                            .iter()
+                            .map(|b| (Uuid::parse_str(&b.artifact.internal_id).unwrap(), b.clone()))
+                            .collect()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+        store
+    }
+
+    #[tokio::test]
+    async fn thin_context_adds_a_limited_evidence_caveat() {
+        for (found, flagged) in [(1, true), (3, false)] {
+            let bundles = (0..found)
+                .map(|i| bundle(&format!("tw:{i}"), &["weak"]))
+                .collect();
+            let client = scripted(["The bridge is closed.".to_string()]);
+            let actor = ChatLlmActor::new(
+                rate_limiter(),
+                RateKey("test".into()),
+                chat_store(bundles),
+                client,
+            )
+            .with_min_context_artifacts(2);
+            let chat = spawn_actor(actor, 8).addr;
+
+            let (reply, rx) = oneshot::channel();
+            chat.send(ChatCmd {
+                user_text: "is the bridge closed?".into(),
+                k: 6,
+                reply,
+                claim: claim(),
+                language: None,
+            })
+            .await
+            .ok()
+            .unwrap();
+            let resp = rx.await.unwrap();
+
+            assert_eq!(
+                resp.text.contains(LIMITED_EVIDENCE_CAVEAT),
+                flagged,
+                "{found} artifacts: {}",
+                resp.text
+            );
+            assert_eq!(resp.caveats.len(), usize::from(flagged));
+            assert!(resp.text.ends_with("The bridge is closed."));
+        }
+    }
+
+    #[tokio::test]
+    async fn chat_context_holds_k_artifacts() {
+        let bundles = (0..8)
+            .map(|i| bundle(&format!("tw:{i}"), &["strong"]))
+            .collect();
+        let client = scripted(["The bridge is closed.".to_string()]);
+        let actor = ChatLlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            chat_store(bundles),
+            client.clone(),
+        );
+        let chat = spawn_actor(actor, 8).addr;
+
+        let (reply, rx) = oneshot::channel();
+        chat.send(ChatCmd {
+            user_text: "is the bridge closed?".into(),
+            k: 8,
+            reply,
+            claim: claim(),
+            language: None,
+        })
+        .await
+        .ok()
+        .unwrap();
+        rx.await.unwrap();
+
+        let prompt = &client.calls()[0].prompt;
+        for i in 0..8 {
+            assert!(prompt.contains(&format!("\"tw:{i}\"")), "tw:{i} missing");
+        }
+    }
+
+    #[tokio::test]
+    async fn answer_language_reaches_the_chat_system_prompt() {
+        let client = scripted(["Sí.".to_string(), "Oui.".to_string()]);
+        let actor = ChatLlmActor::new(
+            rate_limiter(),
+            RateKey("test".into()),
+            chat_store(vec![bundle("tw:1", &["strong"])]),
+            client.clone(),
+        )
+        .with_answer_language("Spanish");
+        let chat = spawn_actor(actor, 8).addr;
+
+        for language in [None, Some("French".to_string())] {
+            let (reply, rx) = oneshot::channel();
+            chat.send(ChatCmd {
+                user_text: "is the bridge closed?".into(),
+                k: 6,
+                reply,
+                claim: claim(),
+                language,
+            })
+            .await
+            .ok()
+            .unwrap();
+            rx.await.unwrap();
+        }
+
+        let systems: Vec<_> = client
+            .calls()
+            .into_iter()
+            .map(|c| c.system_prompt.unwrap())
+            .collect();
+        assert!(systems[0].contains("Respond in Spanish"), "{}", systems[0]);
+        assert!(
+            systems[1].contains("Respond in French"),
+            "a question's language wins"
+        );
+        assert!(!chat_system_prompt(None).contains("Respond in"));
+        assert_eq!(chat_system_prompt(Some("  ")), chat_system_prompt(None));
+    }
+
+    #[tokio::test]
+    async fn shared_write_limit_caps_concurrent_upserts() {
+        const SLOTS: usize = 2;
+        let limit = Arc::new(Semaphore::new(SLOTS));
+        let (store, mut upserts) = probe::<StoreActor>(64);
+        let claim = claim();
+        let mut expected = 0;
+        for worker in 0..3 {
+            let client = scripted((0..4).map(|_| normalization(0.8)));
+            let actor = LlmActor::new(
+                rate_limiter(),
+                RateKey("test".into()),
+                store.clone(),
+                client,
+            )
+            .with_write_limit(limit.clone());
+            let llm = spawn_actor(actor, 8).addr;
+            for i in 0..4 {
+                llm.send(LlmMsg::NormalizeArtifact(raw(
+                    &format!("tw:{worker}:{i}"),
+                    &claim,
+                )))
+                .await
+                .ok()
+                .unwrap();
+                expected += 1;
+            }
+        }
+
+        // Stand-in store: each write holds its slot for a while, like a slow SQLite commit.
+        let in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let peak = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut writes = Vec::new();
+        for _ in 0..expected {
+            let msg = tokio::time::timeout(Duration::from_secs(5), upserts.recv())
+                .await
+                .expect("upsert in time")
+                .expect("store mailbox open");
+            let StoreMsg::UpsertArtifact(_, Some(permit)) = msg else {
+                panic!("expected UpsertArtifact with a write slot");
+            };
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            writes.push(tokio::spawn(async move {
+                let now = in_flight.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                peak.fetch_max(now, std::sync::atomic::Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                in_flight.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+                drop(permit);
+            }));
+        }
+        for write in writes {
+            write.await.unwrap();
+        }
+
+        let peak = peak.load(std::sync::atomic::Ordering::SeqCst);
+        assert!(
+            peak <= SLOTS,
+            "{peak} concurrent upserts with {SLOTS} slots"
+        );
+        assert_eq!(limit.available_permits(), SLOTS);
+    }
+}
@@ -0,0 +1,1927 @@
+//! Postgres-backed alternative to [`StoreActor`](crate::store::StoreActor), behind the
+//! `postgres` feature.
+//!
+//! It answers the same `StoreMsg` protocol, so it can be started on a mailbox reserved for
+//! `StoreActor` (see `Reserved::into_actor`) and every existing `Addr<StoreActor>` keeps
+//! working. The schema lives in `migrations/postgres/`; full-text search runs on the
+//! generated `search_doc` tsvector column instead of an FTS5 table, so there is no index to
+//! keep in sync by hand. [`spawn_retention`] enforces `store.artifact_ttl_days` as the
+//! SQLite store's does.
+use crate::actor::{Actor, Context};
+use crate::store::{
+    canonical_entity_key, prune_watchers, remove_captures, sanitize_fts_query, write_slot,
+    FtsQueryOptions, SAME_SOURCE_PRODUCER,
+};
+use crate::{
+    ArtifactRow, ArtifactWithEntities, CachedSearchQuery, ClaimContext, Credibility, DeadLetter,
+    EntityRow, EvidenceOverlap, ImportCounts, NormalizedArtifact, PlatformSources, Provenance,
+    RecordedSearch, ReviewStatus, StoreMsg, ARTIFACT_SCHEMA_VERSION,
+};
+use anyhow::Result;
+use chrono::Utc;
+use nowhere_common::slug;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::Row;
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+use tokio::sync::{broadcast, oneshot, Semaphore};
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+pub struct PgStoreActor {
+    pool: PgPool,
+    write_limit: Arc<Semaphore>,
+    watchers: HashMap<Uuid, Vec<oneshot::Sender<()>>>,
+    fts: Arc<FtsQueryOptions>,
+}
+
+impl PgStoreActor {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            write_limit: Arc::new(Semaphore::new(1)),
+            watchers: HashMap::new(),
+            fts: Arc::new(FtsQueryOptions::default()),
+        }
+    }
+
+    /// As `StoreActor::with_write_limit`.
+    pub fn with_write_limit(mut self, limit: Arc<Semaphore>) -> Self {
+        self.write_limit = limit;
+        self
+    }
+
+    /// Override how free-text searches are tokenized; the terms and phrases are handed to
+    /// `websearch_to_tsquery`.
+    pub fn with_fts_options(mut self, fts: FtsQueryOptions) -> Self {
+        self.fts = Arc::new(fts);
+        self
+    }
+}
+
+/// As [`crate::store::spawn_retention`]: every `every`, delete artifacts stored more than
+/// `ttl_days` days ago (see [`purge_expired_artifacts`]) until `shutdown` fires. The first
+/// sweep runs immediately.
+pub fn spawn_retention(
+    pool: PgPool,
+    ttl_days: u32,
+    every: Duration,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = shutdown.recv() => return,
+                _ = ticks.tick() => {}
+            }
+            if let Err(err) = purge_expired_artifacts(&pool, ttl_days).await {
+                warn!(error = ?err, "pg.retention.failed");
+            }
+        }
+    })
+}
+
+/// Delete artifacts (with their entities and archived page captures) whose `created_at` is
+/// more than `ttl_days` days old. Returns how many artifacts were removed.
+pub async fn purge_expired_artifacts(pool: &PgPool, ttl_days: u32) -> Result<usize> {
+    let days = i32::try_from(ttl_days)?;
+    let mut tx = pool.begin().await?;
+    // `now()` is fixed for the transaction, so both statements agree on the cutoff.
+    let entities = sqlx::query(
+        r#"DELETE FROM entity WHERE article_id IN (
+             SELECT internal_id FROM normalized_artifact
+             WHERE created_at < now() - make_interval(days => $1))"#,
+    )
+    .bind(days)
+    .execute(&mut *tx)
+    .await?
+    .rows_affected();
+    let expired = sqlx::query(
+        r#"DELETE FROM normalized_artifact
+           WHERE created_at < now() - make_interval(days => $1)
+           RETURNING html_path, screenshot_path"#,
+    )
+    .bind(days)
+    .fetch_all(&mut *tx)
+    .await?;
+    if expired.is_empty() {
+        debug!(ttl_days, "pg.retention.nothing_expired");
+        return Ok(0);
+    }
+    tx.commit().await?;
+
+    let mut captures = Vec::new();
+    for row in &expired {
+        for col in ["html_path", "screenshot_path"] {
+            if let Some(path) = row.try_get::<Option<String>, _>(col)? {
+                captures.push(path);
+            }
+        }
+    }
+    let files = remove_captures(captures).await;
+    info!(
+        ttl_days,
+        artifacts = expired.len(),
+        entities,
+        files,
+        "pg.retention.purged"
+    );
+    Ok(expired.len())
+}
+
+#[async_trait::async_trait]
+impl Actor for PgStoreActor {
+    type Msg = StoreMsg;
+
+    async fn handle(&mut self, msg: Self::Msg, ctx: &mut Context<Self>) -> Result<()> {
+        match msg {
+            StoreMsg::InsertClaim(c) => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => insert_claim(&pool, c).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(error = ?err, "pg.insert_claim.failed");
+                    }
+                });
+            }
+            StoreMsg::UpsertArtifact(n, held) => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                let me = ctx.addr();
+                let claim_id = n.claim_id;
+                let relevant = n.claim_relevance;
+                tokio::spawn(async move {
+                    let permit = match write_slot(permit_src, held).await {
+                        Ok(permit) => permit,
+                        Err(err) => {
+                            error!(error = ?err, "pg.upsert.acquire_failed");
+                            return;
+                        }
+                    };
+                    if let Err(err) = upsert_batch(&pool, std::slice::from_ref(&*n)).await {
+                        error!(error = ?err, "pg.upsert.failed");
+                    } else if relevant {
+                        let _ = me
+                            .send(StoreMsg::ArtifactUpserted { claim: claim_id })
+                            .await;
+                    }
+                    drop(permit);
+                });
+            }
+            StoreMsg::UpsertBatch(batch, held) => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                let me = ctx.addr();
+                tokio::spawn(async move {
+                    let permit = match write_slot(permit_src, held).await {
+                        Ok(permit) => permit,
+                        Err(err) => {
+                            error!(error = ?err, "pg.upsert_batch.acquire_failed");
+                            return;
+                        }
+                    };
+                    let res = upsert_batch(&pool, &batch).await;
+                    drop(permit);
+                    match res {
+                        Ok(claims) => {
+                            for claim in claims {
+                                let _ = me.send(StoreMsg::ArtifactUpserted { claim }).await;
+                            }
+                        }
+                        Err(err) => error!(error = ?err, "pg.upsert_batch.failed"),
+                    }
+                });
+            }
+            StoreMsg::ImportJsonl { path, claim, reply } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                let me = ctx.addr();
+                let claim_id = claim.id;
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => import_jsonl(&pool, &path, claim).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if matches!(&res, Ok(counts) if counts.artifacts > 0) {
+                        let _ = me
+                            .send(StoreMsg::ArtifactUpserted { claim: claim_id })
+                            .await;
+                    }
+                    if reply.send(res).is_err() {
+                        debug!("pg.import_jsonl.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::RecordSearch { claim, search } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => record_search(&pool, claim, &search).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(claim_id = %claim, error = ?err, "pg.record_search.failed");
+                    }
+                });
+            }
+            StoreMsg::LastSearch { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = last_search(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.last_search.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::CachedQuery {
+                key,
+                max_age,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = cached_query(&pool, &key, max_age).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.cached_query.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::CacheQuery { key, search } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => cache_query(&pool, &key, &search).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(key, error = ?err, "pg.cache_query.failed");
+                    }
+                });
+            }
+            StoreMsg::DeadLetter(letter) => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => dead_letter(&pool, &letter).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(artifact = %letter.external_id, error = ?err, "pg.dead_letter.failed");
+                    }
+                });
+            }
+            StoreMsg::GetArtifact { internal_id, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res =
+                        get_artifacts_with_entities(&pool, &[internal_id], Some(VIEW_TEXT_CHARS))
+                            .await
+                            .and_then(|mut found| {
+                                found
+                                    .remove(&internal_id)
+                                    .ok_or_else(|| anyhow::anyhow!("artifact not found"))
+                            });
+                    if reply.send(res).is_err() {
+                        debug!("pg.get_artifact.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::GetArtifacts { ids, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = get_artifacts_with_entities(&pool, &ids, Some(VIEW_TEXT_CHARS)).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.get_artifacts.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::WatchArtifacts { claim, reply } => {
+                prune_watchers(&mut self.watchers);
+                self.watchers.entry(claim).or_default().push(reply);
+            }
+            StoreMsg::ArtifactUpserted { claim } => {
+                prune_watchers(&mut self.watchers);
+                if let Some(listeners) = self.watchers.remove(&claim) {
+                    for tx in listeners {
+                        let _ = tx.send(());
+                    }
+                }
+            }
+            StoreMsg::SearchArtifacts {
+                claim,
+                query,
+                limit,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                let fts = self.fts.clone();
+                tokio::spawn(async move {
+                    let res = search_artifacts(&pool, &query, claim, limit, &fts).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.search_artifacts.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListEntitiesByName { name, limit, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_entities_by_name(&pool, &name, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.list_entities.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::CountArtifacts { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = count_artifacts(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.count_artifacts.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::SourceBreakdown { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = source_breakdown(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.source_breakdown.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ClaimArtifacts { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = claim_artifacts(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.claim_artifacts.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ClaimOverlap { a, b, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = claim_overlap(&pool, a, b).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.claim_overlap.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::TagClaim { claim, tag, reply } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => tag_claim(&pool, claim, &tag).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if reply.send(res).is_err() {
+                        debug!("pg.tag_claim.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::SetReview {
+                artifact,
+                status,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => set_review(&pool, artifact, status).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if reply.send(res).is_err() {
+                        debug!("pg.set_review.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListClaimsByTag { tag, limit, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_claims_by_tag(&pool, &tag, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.list_claims_by_tag.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListClaimsBySlug { slug, limit, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_claims_by_slug(&pool, &slug, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.list_claims_by_slug.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListClaimsByUuidPrefix {
+                prefix,
+                limit,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_claims_by_uuid_prefix(&pool, &prefix, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.list_claims_by_uuid_prefix.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListArtifactIdsByPrefix {
+                prefix,
+                limit,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_artifact_ids_by_prefix(&pool, &prefix, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.list_artifact_ids_by_prefix.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::RebuildFts { reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = rebuild_fts(&pool).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.rebuild_fts.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListLinkedArtifacts { internal_id, reply } => {
+                let pool = self.pool.clone();
+                let id = internal_id.to_string();
+                tokio::spawn(async move {
+                    let res = list_linked_artifacts(&pool, &id).await;
+                    if reply.send(res).is_err() {
+                        debug!("pg.list_linked.reply_dropped");
+                    }
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Columns every artifact query selects, with `reasoning` and `provenance_info` cut to
+/// `$1` characters (NULL keeps them whole).
+const ARTIFACT_COLUMNS: &str = r#"
+    a.internal_id, a.external_id, a.claim_relevance,
+    left(a.reasoning, coalesce($1, length(a.reasoning)))             AS reasoning,
+    left(a.provenance_info, coalesce($1, length(a.provenance_info))) AS provenance_info,
+    a.claim_id,
+    a.source_url, a.author, a.captured_at, a.platform,
+    a.html_path, a.screenshot_path, a.schema_version, a.review_status"#;
+
+/// What `GetArtifact(s)` and linked-artifact lookups return, as SQLite's `v_artifact` view.
+const VIEW_TEXT_CHARS: i32 = 2000;
+
+/// Relevant artifacts of `claim` matching `q`, confirmed ones first, then best `ts_rank`.
+/// Falls back to the most recently updated ones when the query has no searchable terms or
+/// matches nothing. Dismissed artifacts are never returned.
+async fn search_artifacts(
+    pool: &PgPool,
+    q: &str,
+    claim_id: Uuid,
+    limit: i64,
+    fts: &FtsQueryOptions,
+) -> Result<Vec<ArtifactRow>> {
+    let terms = sanitize_fts_query(q, fts);
+    let text_chars = fts.max_text_chars.and_then(|n| i32::try_from(n).ok());
+    let mut rows = match &terms {
+        Some(terms) => {
+            sqlx::query(&format!(
+                r#"SELECT {ARTIFACT_COLUMNS}
+                   FROM normalized_artifact a, websearch_to_tsquery('english', $3) q
+                   WHERE a.claim_relevance AND a.claim_id = $2 AND a.search_doc @@ q
+                     AND a.review_status <> 'dismissed'
+                   ORDER BY a.review_status = 'confirmed' DESC, ts_rank(a.search_doc, q) DESC
+                   LIMIT $4"#
+            ))
+            .bind(text_chars)
+            .bind(claim_id.to_string())
+            .bind(terms)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?
+        }
+        None => Vec::new(),
+    };
+    let fallback = rows.is_empty();
+    if fallback {
+        rows = sqlx::query(&format!(
+            r#"SELECT {ARTIFACT_COLUMNS}
+               FROM normalized_artifact a
+               WHERE a.claim_relevance AND a.claim_id = $2 AND a.review_status <> 'dismissed'
+               ORDER BY a.review_status = 'confirmed' DESC, a.updated_at DESC
+               LIMIT $3"#
+        ))
+        .bind(text_chars)
+        .bind(claim_id.to_string())
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+    }
+    info!(
+        claim_id = %claim_id,
+        query = %q,
+        terms = ?terms,
+        rows = rows.len(),
+        fallback,
+        "pg.search_artifacts"
+    );
+    rows.iter().map(artifact_from_row).collect()
+}
+
+async fn set_review(pool: &PgPool, artifact: Uuid, status: ReviewStatus) -> Result<()> {
+    let res = sqlx::query(
+        "UPDATE normalized_artifact SET review_status = $2, updated_at = now() WHERE internal_id = $1",
+    )
+    .bind(artifact.to_string())
+    .bind(status.as_str())
+    .execute(pool)
+    .await?;
+    if res.rows_affected() == 0 {
+        anyhow::bail!("artifact {artifact} not found");
+    }
+    info!(artifact_id = %artifact, status = status.as_str(), "pg.set_review");
+    Ok(())
+}
+
+async fn insert_claim(pool: &PgPool, c: ClaimContext) -> Result<()> {
+    let res = sqlx::query("INSERT INTO claim (id, text, tags) VALUES ($1, $2, $3)")
+        .bind(c.id.to_string())
+        .bind(c.text)
+        .bind(c.tags)
+        .execute(pool)
+        .await?;
+    info!(claim_id = %c.id, rows = res.rows_affected(), "pg.insert_claim");
+    Ok(())
+}
+
+/// Add `tag` to the claim's tags unless it is already there, returning them all.
+async fn tag_claim(pool: &PgPool, claim: Uuid, tag: &str) -> Result<Vec<String>> {
+    let mut tx = pool.begin().await?;
+    let stored: Option<Vec<String>> =
+        sqlx::query_scalar("SELECT tags FROM claim WHERE id = $1 FOR UPDATE")
+            .bind(claim.to_string())
+            .fetch_optional(&mut *tx)
+            .await?;
+    let Some(mut tags) = stored else {
+        anyhow::bail!("claim {claim} not found");
+    };
+    if !tags.iter().any(|t| t == tag) {
+        sqlx::query(
+            "UPDATE claim SET tags = array_append(tags, $2), updated_at = now() WHERE id = $1",
+        )
+        .bind(claim.to_string())
+        .bind(tag)
+        .execute(&mut *tx)
+        .await?;
+        tags.push(tag.to_string());
+    }
+    tx.commit().await?;
+    info!(claim_id = %claim, tag, "pg.tag_claim");
+    Ok(tags)
+}
+
+async fn list_claims_by_tag(pool: &PgPool, tag: &str, limit: i64) -> Result<Vec<ClaimContext>> {
+    let rows = sqlx::query(
+        r#"SELECT id, text, tags FROM claim
+           WHERE $1 = ANY(tags)
+           ORDER BY created_at DESC
+           LIMIT $2"#,
+    )
+    .bind(tag)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|r| {
+            let id: String = r.try_get("id")?;
+            Ok(ClaimContext {
+                id: Uuid::parse_str(&id)?,
+                text: r.try_get("text")?,
+                tags: r.try_get("tags")?,
+            })
+        })
+        .collect()
+}
+
+async fn list_claims_by_slug(pool: &PgPool, slug: &str, limit: i64) -> Result<Vec<ClaimContext>> {
+    match slug::prefix_range(slug) {
+        Some((lo, hi)) => list_claims_between(pool, lo, hi, limit).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn list_claims_by_uuid_prefix(
+    pool: &PgPool,
+    prefix: &str,
+    limit: i64,
+) -> Result<Vec<ClaimContext>> {
+    match slug::uuid_prefix_range(prefix) {
+        Some((lo, hi)) => list_claims_between(pool, lo, hi, limit).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Artifact ids in the slug range and the UUID range `prefix` spells, merged.
+async fn list_artifact_ids_by_prefix(pool: &PgPool, prefix: &str, limit: i64) -> Result<Vec<Uuid>> {
+    let mut ids = Vec::new();
+    for (lo, hi) in [slug::prefix_range(prefix), slug::uuid_prefix_range(prefix)]
+        .into_iter()
+        .flatten()
+    {
+        let rows: Vec<String> = sqlx::query_scalar(
+            r#"SELECT internal_id FROM normalized_artifact
+               WHERE internal_id COLLATE "C" BETWEEN $1 AND $2
+               ORDER BY internal_id COLLATE "C"
+               LIMIT $3"#,
+        )
+        .bind(lo.to_string())
+        .bind(hi.to_string())
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+        for id in rows {
+            ids.push(Uuid::parse_str(&id)?);
+        }
+    }
+    ids.sort();
+    ids.dedup();
+    ids.truncate(usize::try_from(limit).unwrap_or(0));
+    Ok(ids)
+}
+
+async fn list_claims_between(
+    pool: &PgPool,
+    lo: Uuid,
+    hi: Uuid,
+    limit: i64,
+) -> Result<Vec<ClaimContext>> {
+    // Byte-wise collation, so hyphenated UUIDs compare as text the way they do as numbers.
+    let rows = sqlx::query(
+        r#"SELECT id, text, tags FROM claim
+           WHERE id COLLATE "C" BETWEEN $1 AND $2
+           ORDER BY id COLLATE "C"
+           LIMIT $3"#,
+    )
+    .bind(lo.to_string())
+    .bind(hi.to_string())
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|r| {
+            let id: String = r.try_get("id")?;
+            Ok(ClaimContext {
+                id: Uuid::parse_str(&id)?,
+                text: r.try_get("text")?,
+                tags: r.try_get("tags")?,
+            })
+        })
+        .collect()
+}
+
+async fn record_search(pool: &PgPool, claim: Uuid, search: &RecordedSearch) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO claim_search (claim_id, query, date_from, date_to) VALUES ($1, $2, $3, $4)",
+    )
+    .bind(claim.to_string())
+    .bind(&search.query)
+    .bind(search.date_from)
+    .bind(search.date_to)
+    .execute(pool)
+    .await?;
+    info!(claim_id = %claim, query = %search.query, "pg.record_search");
+    Ok(())
+}
+
+async fn last_search(pool: &PgPool, claim: Uuid) -> Result<Option<RecordedSearch>> {
+    let row = sqlx::query(
+        r#"SELECT query, date_from, date_to FROM claim_search
+           WHERE claim_id = $1
+           ORDER BY id DESC
+           LIMIT 1"#,
+    )
+    .bind(claim.to_string())
+    .fetch_optional(pool)
+    .await?;
+    row.map(|r| {
+        Ok(RecordedSearch {
+            query: r.try_get("query")?,
+            date_from: r.try_get("date_from")?,
+            date_to: r.try_get("date_to")?,
+        })
+    })
+    .transpose()
+}
+
+/// The query cached under `key`, if it was cached no more than `max_age` ago.
+async fn cached_query(
+    pool: &PgPool,
+    key: &str,
+    max_age: Duration,
+) -> Result<Option<CachedSearchQuery>> {
+    let row = sqlx::query(
+        "SELECT query, date_from, date_to FROM query_cache WHERE key = $1 AND created_at >= $2",
+    )
+    .bind(key)
+    .bind(Utc::now() - chrono::Duration::from_std(max_age)?)
+    .fetch_optional(pool)
+    .await?;
+    row.map(|r| {
+        Ok(CachedSearchQuery {
+            query: r.try_get("query")?,
+            date_from: r.try_get("date_from")?,
+            date_to: r.try_get("date_to")?,
+        })
+    })
+    .transpose()
+}
+
+async fn cache_query(pool: &PgPool, key: &str, search: &CachedSearchQuery) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO query_cache (key, query, date_from, date_to, created_at)
+           VALUES ($1, $2, $3, $4, now())
+           ON CONFLICT (key) DO UPDATE SET
+             query = excluded.query,
+             date_from = excluded.date_from,
+             date_to = excluded.date_to,
+             created_at = excluded.created_at"#,
+    )
+    .bind(key)
+    .bind(&search.query)
+    .bind(&search.date_from)
+    .bind(&search.date_to)
+    .execute(pool)
+    .await?;
+    debug!(key, query = %search.query, "pg.cache_query");
+    Ok(())
+}
+
+async fn dead_letter(pool: &PgPool, letter: &DeadLetter) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO normalization_dead_letter
+             (external_id, claim_id, payload, error, model_output, created_at)
+           VALUES ($1, $2, $3::jsonb, $4, $5, now())"#,
+    )
+    .bind(&letter.external_id)
+    .bind(letter.claim_id.to_string())
+    .bind(letter.payload.to_string())
+    .bind(&letter.error)
+    .bind(&letter.model_output)
+    .execute(pool)
+    .await?;
+    debug!(artifact = %letter.external_id, claim_id = %letter.claim_id, "pg.dead_letter");
+    Ok(())
+}
+
+/// Write `batch` in one transaction. Returns the claims that gained a relevant artifact, in
+/// first-seen order, so each can be notified once.
+async fn upsert_batch(pool: &PgPool, batch: &[NormalizedArtifact]) -> Result<Vec<Uuid>> {
+    let mut tx = pool.begin().await?;
+    let mut claims = Vec::new();
+    for n in batch {
+        upsert_normalized_tx(&mut tx, n).await?;
+        if n.claim_relevance && !claims.contains(&n.claim_id) {
+            claims.push(n.claim_id);
+        }
+    }
+    tx.commit().await?;
+    info!(artifacts = batch.len(), "pg.upsert_batch.done");
+    Ok(claims)
+}
+
+/// Write one artifact and its entities inside `tx`.
+async fn upsert_normalized_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    n: &NormalizedArtifact,
+) -> Result<()> {
+    // On conflict the row keeps its original internal_id; RETURNING gives us the one stored.
+    let stored_id: String = sqlx::query_scalar(
+        r#"INSERT INTO normalized_artifact AS a
+           (internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id, canonical_url,
+            source_url, author, captured_at, platform, html_path, screenshot_path, schema_version)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+           ON CONFLICT (external_id) DO UPDATE SET
+             schema_version = excluded.schema_version,
+             claim_relevance = excluded.claim_relevance,
+             reasoning = excluded.reasoning,
+             provenance_info = excluded.provenance_info,
+             claim_id = excluded.claim_id,
+             canonical_url = COALESCE(excluded.canonical_url, a.canonical_url),
+             source_url = COALESCE(excluded.source_url, a.source_url),
+             author = COALESCE(excluded.author, a.author),
+             captured_at = COALESCE(excluded.captured_at, a.captured_at),
+             platform = COALESCE(excluded.platform, a.platform),
+             html_path = COALESCE(excluded.html_path, a.html_path),
+             screenshot_path = COALESCE(excluded.screenshot_path, a.screenshot_path),
+             updated_at = now()
+           RETURNING internal_id"#,
+    )
+    .bind(n.internal_id.to_string())
+    .bind(n.external_id.as_str())
+    .bind(n.claim_relevance)
+    .bind(n.reasoning.as_str())
+    .bind(n.provenance_info.as_str())
+    .bind(n.claim_id.to_string())
+    .bind(n.canonical_url.as_deref())
+    .bind(n.provenance.source_url.as_deref())
+    .bind(n.provenance.author.as_deref())
+    .bind(n.provenance.captured_at)
+    .bind(n.provenance.platform.as_deref())
+    .bind(n.provenance.html_path.as_deref())
+    .bind(n.provenance.screenshot_path.as_deref())
+    .bind(ARTIFACT_SCHEMA_VERSION)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    if let Some(url) = n.canonical_url.as_deref() {
+        let links = link_same_source(tx, &stored_id, url).await?;
+        if links > 0 {
+            info!(internal_id = %stored_id, canonical_url = %url, links, "pg.upsert.linked_same_source");
+        }
+    }
+
+    for e in &n.entities {
+        let credibility = match &e.credibility {
+            Credibility::Strong => "strong",
+            Credibility::Weak => "weak",
+            Credibility::Unknown => "unknown",
+        };
+        let canonical_id = resolve_canonical_entity(tx, &e.name).await?;
+        sqlx::query(
+            r#"INSERT INTO entity (article_id, external_id, name, credibility, stance, reasoning, canonical_id)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT (article_id, external_id) DO UPDATE SET
+                 name = excluded.name,
+                 credibility = excluded.credibility,
+                 stance = excluded.stance,
+                 reasoning = excluded.reasoning,
+                 canonical_id = excluded.canonical_id,
+                 updated_at = now()"#,
+        )
+        .bind(stored_id.as_str())
+        .bind(e.external_id.as_str())
+        .bind(e.name.as_str())
+        .bind(credibility)
+        .bind(e.stance.as_str())
+        .bind(e.reasoning.as_str())
+        .bind(canonical_id.as_deref())
+        .execute(&mut **tx)
+        .await?;
+    }
+    info!(
+        internal_id = %stored_id,
+        external_id = %n.external_id,
+        claim_id = %n.claim_id,
+        entities = n.entities.len(),
+        "pg.upsert.artifact"
+    );
+    Ok(())
+}
+
+/// Find or create the `canonical_entity` row for `name`, as the SQLite store does.
+async fn resolve_canonical_entity(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    name: &str,
+) -> Result<Option<String>> {
+    let key = canonical_entity_key(name);
+    if key.is_empty() {
+        return Ok(None);
+    }
+    sqlx::query(
+        "INSERT INTO canonical_entity (key, name) VALUES ($1, $2) ON CONFLICT (key) DO NOTHING",
+    )
+    .bind(key.as_str())
+    .bind(name.trim())
+    .execute(&mut **tx)
+    .await?;
+    let id: String = sqlx::query_scalar("SELECT id FROM canonical_entity WHERE key = $1")
+        .bind(key.as_str())
+        .fetch_one(&mut **tx)
+        .await?;
+    Ok(Some(id))
+}
+
+/// Join `internal_id` to every other artifact stored under `canonical_url` with a
+/// `same_event` edge. Returns the number of new edges.
+async fn link_same_source(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    internal_id: &str,
+    canonical_url: &str,
+) -> Result<u64> {
+    let others: Vec<String> = sqlx::query_scalar(
+        "SELECT internal_id FROM normalized_artifact WHERE canonical_url = $1 AND internal_id <> $2",
+    )
+    .bind(canonical_url)
+    .bind(internal_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let mut written = 0u64;
+    for other in others {
+        // Same pair ordering and edge id as the SQLite store.
+        let (src, dst) = if internal_id < other.as_str() {
+            (internal_id, other.as_str())
+        } else {
+            (other.as_str(), internal_id)
+        };
+        let edge_id = Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            format!("{src}|{dst}|same_event|{SAME_SOURCE_PRODUCER}").as_bytes(),
+        );
+        let res = sqlx::query(
+            r#"INSERT INTO graph_edge (id, src_id, dst_id, relation, confidence, rationale, produced_by)
+               VALUES ($1, $2, $3, 'same_event', 1.0, $4, $5)
+               ON CONFLICT DO NOTHING"#,
+        )
+        .bind(edge_id.to_string())
+        .bind(src)
+        .bind(dst)
+        .bind(format!("shared canonical url {canonical_url}"))
+        .bind(SAME_SOURCE_PRODUCER)
+        .execute(&mut **tx)
+        .await?;
+        written += res.rows_affected();
+    }
+    Ok(written)
+}
+
+async fn import_jsonl(pool: &PgPool, path: &Path, claim: ClaimContext) -> Result<ImportCounts> {
+    let body = tokio::fs::read_to_string(path).await?;
+    let mut counts = ImportCounts::default();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("INSERT INTO claim (id, text) VALUES ($1, $2) ON CONFLICT (id) DO NOTHING")
+        .bind(claim.id.to_string())
+        .bind(claim.text.as_str())
+        .execute(&mut *tx)
+        .await?;
+    for (idx, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut n = match serde_json::from_str::<NormalizedArtifact>(line) {
+            Ok(n) => n,
+            Err(err) => {
+                warn!(path = %path.display(), line = idx + 1, error = %err, "pg.import_jsonl.skip_line");
+                counts.skipped += 1;
+                continue;
+            }
+        };
+        n.claim_id = claim.id;
+        upsert_normalized_tx(&mut tx, &n).await?;
+        counts.artifacts += 1;
+        counts.entities += n.entities.len();
+    }
+    tx.commit().await?;
+    info!(
+        path = %path.display(),
+        claim_id = %claim.id,
+        artifacts = counts.artifacts,
+        entities = counts.entities,
+        skipped = counts.skipped,
+        "pg.import_jsonl.done"
+    );
+    Ok(counts)
+}
+
+/// Artifacts and their entities for every id in `ids` that exists, in two queries, with
+/// text cut to `text_chars` characters (`None` keeps it whole).
+async fn get_artifacts_with_entities(
+    pool: &PgPool,
+    ids: &[Uuid],
+    text_chars: Option<i32>,
+) -> Result<HashMap<Uuid, ArtifactWithEntities>> {
+    let ids: Vec<String> = ids.iter().map(Uuid::to_string).collect();
+    let artifacts = sqlx::query(&format!(
+        "SELECT {ARTIFACT_COLUMNS} FROM normalized_artifact a WHERE a.internal_id = ANY($2)"
+    ))
+    .bind(text_chars)
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+    let entity_rows = sqlx::query(
+        r#"SELECT id, article_id, canonical_id, name, credibility, stance,
+                  left(reasoning, coalesce($1, length(reasoning))) AS reasoning
+           FROM entity
+           WHERE article_id = ANY($2)
+           ORDER BY created_at ASC"#,
+    )
+    .bind(text_chars)
+    .bind(&ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_artifact: HashMap<String, Vec<EntityRow>> = HashMap::new();
+    for row in &entity_rows {
+        let entity = entity_from_row(row);
+        by_artifact
+            .entry(entity.article_id.clone())
+            .or_default()
+            .push(entity);
+    }
+    let mut out = HashMap::with_capacity(artifacts.len());
+    for row in &artifacts {
+        let artifact = artifact_from_row(row)?;
+        let id = Uuid::parse_str(&artifact.internal_id)?;
+        let entities = by_artifact
+            .remove(&artifact.internal_id)
+            .unwrap_or_default();
+        out.insert(id, ArtifactWithEntities { artifact, entities });
+    }
+    info!(requested = ids.len(), found = out.len(), "pg.get_artifacts");
+    Ok(out)
+}
+
+/// Entities stored as `name`, plus spelling variants resolved to the same canonical entity.
+async fn list_entities_by_name(pool: &PgPool, name: &str, limit: i64) -> Result<Vec<EntityRow>> {
+    let rows = sqlx::query(
+        r#"SELECT id, article_id, canonical_id, name, credibility, stance,
+                  left(reasoning, 2000) AS reasoning
+           FROM entity
+           WHERE name = $1
+              OR canonical_id = (SELECT id FROM canonical_entity WHERE key = $2)
+           ORDER BY created_at DESC
+           LIMIT $3"#,
+    )
+    .bind(name)
+    .bind(canonical_entity_key(name))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.iter().map(entity_from_row).collect())
+}
+
+/// `search_doc` is a generated column, so the index cannot drift; this only reports how
+/// many rows it covers.
+async fn rebuild_fts(pool: &PgPool) -> Result<usize> {
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM normalized_artifact")
+        .fetch_one(pool)
+        .await?;
+    info!(rows, "pg.rebuild_fts.nothing_to_rebuild");
+    Ok(rows as usize)
+}
+
+async fn count_artifacts(pool: &PgPool, claim: Uuid) -> Result<usize> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM normalized_artifact WHERE claim_id = $1")
+            .bind(claim.to_string())
+            .fetch_one(pool)
+            .await?;
+    Ok(count as usize)
+}
+
+/// Per-platform artifact and entity-credibility counts for a claim's relevant artifacts,
+/// largest platform first.
+async fn source_breakdown(pool: &PgPool, claim: Uuid) -> Result<Vec<PlatformSources>> {
+    let rows = sqlx::query(
+        r#"SELECT COALESCE(a.platform, 'unknown') AS platform,
+                  COUNT(DISTINCT a.internal_id) AS artifacts,
+                  COUNT(e.id) FILTER (WHERE e.credibility = 'strong') AS strong,
+                  COUNT(e.id) FILTER (WHERE e.credibility = 'weak') AS weak,
+                  COUNT(e.id) FILTER (WHERE e.credibility = 'unknown') AS unknown
+           FROM normalized_artifact a
+           LEFT JOIN entity e ON e.article_id = a.internal_id
+           WHERE a.claim_id = $1 AND a.claim_relevance
+           GROUP BY 1
+           ORDER BY artifacts DESC, platform ASC"#,
+    )
+    .bind(claim.to_string())
+    .fetch_all(pool)
+    .await?;
+    let count = |r: &PgRow, col: &str| r.try_get::<i64, _>(col).unwrap_or(0) as usize;
+    Ok(rows
+        .iter()
+        .map(|r| PlatformSources {
+            platform: r.try_get("platform").unwrap_or_default(),
+            artifacts: count(r, "artifacts"),
+            strong: count(r, "strong"),
+            weak: count(r, "weak"),
+            unknown: count(r, "unknown"),
+        })
+        .collect())
+}
+
+/// Every artifact of `claim` with its entities, oldest first, with their text in full.
+async fn claim_artifacts(pool: &PgPool, claim: Uuid) -> Result<Vec<ArtifactWithEntities>> {
+    let ids: Vec<String> = sqlx::query_scalar(
+        "SELECT internal_id FROM normalized_artifact WHERE claim_id = $1 ORDER BY created_at, internal_id",
+    )
+    .bind(claim.to_string())
+    .fetch_all(pool)
+    .await?;
+    let ids = ids
+        .iter()
+        .map(|id| Uuid::parse_str(id))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut found = get_artifacts_with_entities(pool, &ids, None).await?;
+    Ok(ids.iter().filter_map(|id| found.remove(id)).collect())
+}
+
+/// Canonical entities and URLs that relevant artifacts of both `a` and `b` carry, sorted.
+async fn claim_overlap(pool: &PgPool, a: Uuid, b: Uuid) -> Result<EvidenceOverlap> {
+    let entities = sqlx::query_scalar(
+        r#"SELECT ce.name
+           FROM canonical_entity ce
+           JOIN entity e ON e.canonical_id = ce.id
+           JOIN normalized_artifact a ON a.internal_id = e.article_id AND a.claim_relevance
+           WHERE a.claim_id IN ($1, $2)
+           GROUP BY ce.id, ce.name
+           HAVING COUNT(DISTINCT a.claim_id) = 2
+           ORDER BY ce.name"#,
+    )
+    .bind(a.to_string())
+    .bind(b.to_string())
+    .fetch_all(pool)
+    .await?;
+    let sources = sqlx::query_scalar(
+        r#"SELECT DISTINCT a.canonical_url
+           FROM normalized_artifact a
+           JOIN normalized_artifact b ON b.canonical_url = a.canonical_url
+           WHERE a.claim_id = $1 AND a.claim_relevance
+             AND b.claim_id = $2 AND b.claim_relevance
+           ORDER BY 1"#,
+    )
+    .bind(a.to_string())
+    .bind(b.to_string())
+    .fetch_all(pool)
+    .await?;
+    Ok(EvidenceOverlap { entities, sources })
+}
+
+async fn list_linked_artifacts(pool: &PgPool, id: &str) -> Result<Vec<ArtifactRow>> {
+    let rows = sqlx::query(&format!(
+        r#"SELECT {ARTIFACT_COLUMNS}
+           FROM graph_edge ge
+           JOIN normalized_artifact a
+             ON a.internal_id = CASE WHEN ge.src_id = $2 THEN ge.dst_id ELSE ge.src_id END
+           WHERE ge.relation = 'same_event'
+             AND ge.produced_by = $3
+             AND (ge.src_id = $2 OR ge.dst_id = $2)
+           ORDER BY a.created_at ASC"#
+    ))
+    .bind(VIEW_TEXT_CHARS)
+    .bind(id)
+    .bind(SAME_SOURCE_PRODUCER)
+    .fetch_all(pool)
+    .await?;
+    rows.iter().map(artifact_from_row).collect()
+}
+
+fn artifact_from_row(r: &PgRow) -> Result<ArtifactRow> {
+    Ok(ArtifactRow {
+        internal_id: r.try_get("internal_id")?,
+        external_id: r.try_get("external_id")?,
+        claim_relevance: r.try_get("claim_relevance")?,
+        reasoning: r.try_get("reasoning")?,
+        provenance_info: r.try_get("provenance_info")?,
+        claim_id: r.try_get("claim_id")?,
+        provenance: Provenance {
+            source_url: r.try_get("source_url")?,
+            author: r.try_get("author")?,
+            captured_at: r.try_get("captured_at")?,
+            platform: r.try_get("platform")?,
+            html_path: r.try_get("html_path")?,
+            screenshot_path: r.try_get("screenshot_path")?,
+        }
+        .upgrade(
+            r.try_get("schema_version")?,
+            r.try_get::<&str, _>("external_id")?,
+        ),
+        review_status: ReviewStatus::from(r.try_get::<&str, _>("review_status")?),
+    })
+}
+
+fn entity_from_row(r: &PgRow) -> EntityRow {
+    EntityRow {
+        id: r.try_get("id").unwrap_or_default(),
+        article_id: r.try_get("article_id").unwrap_or_default(),
+        canonical_id: r.try_get("canonical_id").unwrap_or_default(),
+        name: r.try_get("name").unwrap_or_default(),
+        credibility: r.try_get("credibility").unwrap_or_default(),
+        stance: r.try_get("stance").unwrap_or_else(|_| "neutral".into()),
+        reasoning: r.try_get("reasoning").unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! These need a live server, so they are ignored by default: set `NOWHERE_TEST_PG_URL`
+    //! (e.g. `postgres://postgres@localhost/nowhere_test`) and run them with
+    //! `cargo test -p nowhere-actors --features postgres -- --ignored`. Each test works in a
+    //! fresh schema that it drops afterwards.
+    use super::*;
+    use crate::actor::{spawn_actor, Addr};
+    use crate::{Entity, Stance};
+    use chrono::{TimeZone, Utc};
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use std::str::FromStr;
+
+    const MIGRATIONS: &[&str] = &[
+        include_str!("../../migrations/postgres/01_init.sql"),
+        include_str!("../../migrations/postgres/02_query_cache.sql"),
+        include_str!("../../migrations/postgres/03_artifact_schema_version.sql"),
+        include_str!("../../migrations/postgres/04_review_status.sql"),
+        include_str!("../../migrations/postgres/05_normalization_dead_letter.sql"),
+        include_str!("../../migrations/postgres/06_query_cache_model_dates.sql"),
+    ];
+
+    /// A pool confined to a new, migrated schema, and that schema's name.
+    async fn test_pool() -> (PgPool, String) {
+        let url = std::env::var("NOWHERE_TEST_PG_URL")
+            .expect("NOWHERE_TEST_PG_URL must name a test server to run the Postgres tests");
+        let schema = format!("nowhere_test_{}", Uuid::new_v4().simple());
+        let admin = PgPool::connect(&url)
+            .await
+            .expect("connect to test postgres");
+        sqlx::query(&format!("CREATE SCHEMA {schema}"))
+            .execute(&admin)
+            .await
+            .expect("create schema");
+        let opts = PgConnectOptions::from_str(&url)
+            .expect("test url")
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new()
+            .max_connections(4)
+            .connect_with(opts)
+            .await
+            .expect("connect to test schema");
+        for sql in MIGRATIONS {
+            sqlx::raw_sql(sql).execute(&pool).await.expect("migration");
+        }
+        (pool, schema)
+    }
+
+    async fn drop_schema(pool: PgPool, schema: &str) {
+        sqlx::query(&format!("DROP SCHEMA {schema} CASCADE"))
+            .execute(&pool)
+            .await
+            .expect("drop schema");
+    }
+
+    async fn ask<A: Actor<Msg = StoreMsg>, T>(
+        store: &Addr<A>,
+        msg: impl FnOnce(oneshot::Sender<T>) -> StoreMsg,
+    ) -> T {
+        let (reply, rx) = oneshot::channel();
+        store.send(msg(reply)).await.ok().unwrap();
+        rx.await.unwrap()
+    }
+
+    fn artifact(claim: &ClaimContext, external_id: &str, reasoning: &str) -> NormalizedArtifact {
+        let internal_id = Uuid::new_v4();
+        NormalizedArtifact {
+            external_id: external_id.to_string(),
+            internal_id,
+            claim_id: claim.id,
+            claim_relevance: true,
+            reasoning: reasoning.to_string(),
+            provenance_info: "twitter".to_string(),
+            canonical_url: None,
+            provenance: Provenance {
+                platform: Some("twitter".into()),
+                ..Provenance::default()
+            },
+            entities: vec![Entity {
+                article_id: internal_id,
+                external_id: format!("ent:{external_id}"),
+                name: "@CityTransit".to_string(),
+                credibility: Credibility::Strong,
+                stance: Stance::Supports,
+                reasoning: String::new(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs NOWHERE_TEST_PG_URL"]
+    async fn serves_the_store_protocol_end_to_end() {
+        let (pool, schema) = test_pool().await;
+        let store = spawn_actor(PgStoreActor::new(pool.clone()), 64).addr;
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the bridge closed".into(),
+            tags: vec!["transit".into()],
+        };
+        store
+            .send(StoreMsg::InsertClaim(claim.clone()))
+            .await
+            .ok()
+            .unwrap();
+
+        let (seen_tx, seen_rx) = oneshot::channel();
+        store
+            .send(StoreMsg::WatchArtifacts {
+                claim: claim.id,
+                reply: seen_tx,
+            })
+            .await
+            .ok()
+            .unwrap();
+        let closed = artifact(&claim, "tw:1", "The bridge closed for repairs on Monday");
+        let weather = artifact(&claim, "tw:2", "Sunny weather downtown");
+        let closed_id = closed.internal_id;
+        store
+            .send(StoreMsg::UpsertBatch(vec![closed, weather], None))
+            .await
+            .ok()
+            .unwrap();
+        seen_rx.await.expect("watcher notified");
+
+        let hits = ask(&store, |reply| StoreMsg::SearchArtifacts {
+            claim: claim.id,
+            query: "bridge repairs".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            hits.iter()
+                .map(|h| h.external_id.as_str())
+                .collect::<Vec<_>>(),
+            ["tw:1"]
+        );
+        let fallback = ask(&store, |reply| StoreMsg::SearchArtifacts {
+            claim: claim.id,
+            query: "the".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            fallback.len(),
+            2,
+            "no searchable terms lists recent artifacts"
+        );
+
+        let found = ask(&store, |reply| StoreMsg::GetArtifacts {
+            ids: vec![closed_id, Uuid::new_v4()],
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(found.len(), 1);
+        let got = &found[&closed_id];
+        assert_eq!(got.artifact.external_id, "tw:1");
+        assert_eq!(got.entities.len(), 1);
+        assert_eq!(got.entities[0].stance, "supports");
+        let all = ask(&store, |reply| StoreMsg::ClaimArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(all
+            .iter()
+            .any(|a| a.artifact.external_id == "tw:1" && a.entities.len() == 1));
+        let by_slug = ask(&store, |reply| StoreMsg::ListArtifactIdsByPrefix {
+            prefix: slug::short(closed_id),
+            limit: 5,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(by_slug, [closed_id]);
+
+        ask(&store, |reply| StoreMsg::SetReview {
+            artifact: closed_id,
+            status: ReviewStatus::Dismissed,
+            reply,
+        })
+        .await
+        .unwrap();
+        let hits = ask(&store, |reply| StoreMsg::SearchArtifacts {
+            claim: claim.id,
+            query: "bridge repairs".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert!(
+            hits.iter().all(|h| h.external_id != "tw:1"),
+            "dismissed artifacts are not retrieved"
+        );
+        let missing = ask(&store, |reply| StoreMsg::SetReview {
+            artifact: Uuid::new_v4(),
+            status: ReviewStatus::Confirmed,
+            reply,
+        })
+        .await;
+        assert!(missing.is_err());
+
+        let letter = DeadLetter {
+            external_id: "tw:bad".into(),
+            claim_id: claim.id,
+            payload: serde_json::json!({ "text": "bridge is shut" }),
+            error: "expected value".into(),
+            model_output: "I'm sorry".into(),
+        };
+        dead_letter(&pool, &letter).await.unwrap();
+        let (payload,): (String,) = sqlx::query_as(
+            "SELECT payload::text FROM normalization_dead_letter WHERE claim_id = $1",
+        )
+        .bind(claim.id.to_string())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&payload).unwrap(),
+            letter.payload
+        );
+
+        let variants = ask(&store, |reply| StoreMsg::ListEntitiesByName {
+            name: "City Transit".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(variants.len(), 2, "both spellings share a canonical entity");
+
+        let breakdown = ask(&store, |reply| StoreMsg::SourceBreakdown {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(breakdown.len(), 1);
+        assert_eq!((breakdown[0].artifacts, breakdown[0].strong), (2, 2));
+        let by_slug = ask(&store, |reply| StoreMsg::ListClaimsBySlug {
+            slug: slug::short(claim.id),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(by_slug.iter().map(|c| c.id).collect::<Vec<_>>(), [claim.id]);
+        let by_uuid = ask(&store, |reply| StoreMsg::ListClaimsByUuidPrefix {
+            prefix: claim.id.to_string(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(by_uuid.iter().map(|c| c.id).collect::<Vec<_>>(), [claim.id]);
+
+        let other = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the tunnel flooded".into(),
+            tags: Vec::new(),
+        };
+        store
+            .send(StoreMsg::InsertClaim(other.clone()))
+            .await
+            .ok()
+            .unwrap();
+        upsert_batch(&pool, &[artifact(&other, "tw:other", "tunnel flooded")])
+            .await
+            .unwrap();
+        let overlap = ask(&store, |reply| StoreMsg::ClaimOverlap {
+            a: claim.id,
+            b: other.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(overlap.entities.len(), 1, "both cite the transit authority");
+        assert!(overlap.sources.is_empty());
+
+        let tags = ask(&store, |reply| StoreMsg::TagClaim {
+            claim: claim.id,
+            tag: "bridges".into(),
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(tags, ["transit", "bridges"]);
+        let tagged = ask(&store, |reply| StoreMsg::ListClaimsByTag {
+            tag: "bridges".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            tagged,
+            vec![ClaimContext {
+                tags,
+                ..claim.clone()
+            }]
+        );
+
+        let search = RecordedSearch {
+            query: "bridge closed".into(),
+            date_from: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+            date_to: Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 0).unwrap(),
+        };
+        store
+            .send(StoreMsg::RecordSearch {
+                claim: claim.id,
+                search: search.clone(),
+            })
+            .await
+            .ok()
+            .unwrap();
+        let mut last = None;
+        for _ in 0..50 {
+            last = ask(&store, |reply| StoreMsg::LastSearch {
+                claim: claim.id,
+                reply,
+            })
+            .await
+            .unwrap();
+            if last.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(last, Some(search));
+
+        let count = ask(&store, |reply| StoreMsg::CountArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!(count, 2);
+
+        drop_schema(pool, &schema).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs NOWHERE_TEST_PG_URL"]
+    async fn artifacts_sharing_a_canonical_url_are_linked() {
+        let (pool, schema) = test_pool().await;
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the bridge closed".into(),
+            tags: Vec::new(),
+        };
+        let mut tweet = artifact(&claim, "tw:1", "links the article");
+        tweet.canonical_url = Some("example.com/bridge".into());
+        let mut page = artifact(&claim, "web:1", "the article");
+        page.canonical_url = Some("example.com/bridge".into());
+        let (tweet_id, page_id) = (tweet.internal_id, page.internal_id);
+        upsert_batch(&pool, &[tweet, page]).await.unwrap();
+
+        let linked = list_linked_artifacts(&pool, &tweet_id.to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            linked
+                .iter()
+                .map(|a| a.internal_id.clone())
+                .collect::<Vec<_>>(),
+            [page_id.to_string()]
+        );
+
+        drop_schema(pool, &schema).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs NOWHERE_TEST_PG_URL"]
+    async fn claim_artifacts_keep_text_lookups_cut() {
+        let (pool, schema) = test_pool().await;
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the dam burst".into(),
+            tags: Vec::new(),
+        };
+        let mut a = artifact(&claim, "tw:1", &"r".repeat(2500));
+        a.provenance_info = "p".repeat(2500);
+        a.entities[0].reasoning = "w".repeat(2500);
+        let id = a.internal_id;
+        upsert_batch(&pool, &[a]).await.unwrap();
+
+        let exported = claim_artifacts(&pool, claim.id).await.unwrap();
+        assert_eq!(exported[0].artifact.reasoning.len(), 2500);
+        assert_eq!(exported[0].artifact.provenance_info.len(), 2500);
+        assert_eq!(exported[0].entities[0].reasoning.len(), 2500);
+        let hydrated = get_artifacts_with_entities(&pool, &[id], Some(VIEW_TEXT_CHARS))
+            .await
+            .unwrap();
+        assert_eq!(hydrated[&id].artifact.reasoning.len(), 2000);
+        assert_eq!(hydrated[&id].entities[0].reasoning.len(), 2000);
+
+        drop_schema(pool, &schema).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs NOWHERE_TEST_PG_URL"]
+    async fn retention_removes_only_artifacts_older_than_the_ttl() {
+        let (pool, schema) = test_pool().await;
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the bridge closed".into(),
+            tags: Vec::new(),
+        };
+        let capture = std::env::temp_dir().join(format!("nowhere-capture-{}.html", Uuid::new_v4()));
+        std::fs::write(&capture, "<html></html>").unwrap();
+        let mut old = artifact(&claim, "tw:old", "closed last year");
+        old.provenance.html_path = Some(capture.to_string_lossy().into_owned());
+        let old_id = old.internal_id;
+        upsert_batch(&pool, &[old, artifact(&claim, "tw:fresh", "closed today")])
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE normalized_artifact SET created_at = now() - interval '31 days' WHERE external_id = 'tw:old'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(purge_expired_artifacts(&pool, 30).await.unwrap(), 1);
+
+        let remaining: Vec<String> =
+            sqlx::query_scalar("SELECT external_id FROM normalized_artifact")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(remaining, ["tw:fresh"]);
+        let orphans: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM entity WHERE article_id = $1")
+            .bind(old_id.to_string())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(orphans, 0);
+        assert!(!capture.exists());
+        assert_eq!(purge_expired_artifacts(&pool, 30).await.unwrap(), 0);
+
+        drop_schema(pool, &schema).await;
+    }
+
+    /// Runs one script of store messages against `store` and renders each answer, leaving
+    /// out the ids each backend generates itself, so both backends' runs can be compared.
+    async fn protocol_answers<A: Actor<Msg = StoreMsg>>(
+        store: &Addr<A>,
+        claim: &ClaimContext,
+        batch: Vec<NormalizedArtifact>,
+    ) -> Vec<String> {
+        let artifact_ids: Vec<Uuid> = batch.iter().map(|a| a.internal_id).collect();
+        let entity = |e: &EntityRow| format!("{} {} {}", e.name, e.credibility, e.stance);
+        let mut answers = Vec::new();
+
+        store
+            .send(StoreMsg::InsertClaim(claim.clone()))
+            .await
+            .ok()
+            .unwrap();
+        let (seen_tx, seen_rx) = oneshot::channel();
+        store
+            .send(StoreMsg::WatchArtifacts {
+                claim: claim.id,
+                reply: seen_tx,
+            })
+            .await
+            .ok()
+            .unwrap();
+        store
+            .send(StoreMsg::UpsertBatch(batch, None))
+            .await
+            .ok()
+            .unwrap();
+        seen_rx.await.expect("watcher notified");
+
+        let count = ask(store, |reply| StoreMsg::CountArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        answers.push(format!("count: {count}"));
+        let hits = ask(store, |reply| StoreMsg::SearchArtifacts {
+            claim: claim.id,
+            query: "bridge repairs".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        let mut hits: Vec<_> = hits.into_iter().map(|h| h.external_id).collect();
+        hits.sort();
+        answers.push(format!("search: {hits:?}"));
+        let found = ask(store, |reply| StoreMsg::GetArtifacts {
+            ids: vec![artifact_ids[0], Uuid::new_v4()],
+            reply,
+        })
+        .await
+        .unwrap();
+        for bundle in found.values() {
+            let a = &bundle.artifact;
+            answers.push(format!(
+                "get: {} {} {:?} {:?} {:?}",
+                a.external_id,
+                a.claim_relevance,
+                a.reasoning,
+                a.provenance,
+                bundle.entities.iter().map(entity).collect::<Vec<_>>()
+            ));
+        }
+        let all = ask(store, |reply| StoreMsg::ClaimArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        let mut all: Vec<_> = all
+            .iter()
+            .map(|b| (b.artifact.external_id.clone(), b.entities.len()))
+            .collect();
+        all.sort();
+        answers.push(format!("claim artifacts: {all:?}"));
+        let by_prefix = ask(store, |reply| StoreMsg::ListArtifactIdsByPrefix {
+            prefix: slug::short(artifact_ids[0]),
+            limit: 5,
+            reply,
+        })
+        .await
+        .unwrap();
+        answers.push(format!("by prefix: {}", by_prefix == artifact_ids[..1]));
+
+        ask(store, |reply| StoreMsg::SetReview {
+            artifact: artifact_ids[0],
+            status: ReviewStatus::Dismissed,
+            reply,
+        })
+        .await
+        .unwrap();
+        let hits = ask(store, |reply| StoreMsg::SearchArtifacts {
+            claim: claim.id,
+            query: "bridge repairs".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        let mut hits: Vec<_> = hits.into_iter().map(|h| h.external_id).collect();
+        hits.sort();
+        answers.push(format!("search after dismissal: {hits:?}"));
+
+        let variants = ask(store, |reply| StoreMsg::ListEntitiesByName {
+            name: "City Transit".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        let mut variants: Vec<_> = variants.iter().map(entity).collect();
+        variants.sort();
+        answers.push(format!("entities: {variants:?}"));
+        let breakdown = ask(store, |reply| StoreMsg::SourceBreakdown {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        answers.push(format!("breakdown: {breakdown:?}"));
+
+        let tags = ask(store, |reply| StoreMsg::TagClaim {
+            claim: claim.id,
+            tag: "bridges".into(),
+            reply,
+        })
+        .await
+        .unwrap();
+        answers.push(format!("tags: {tags:?}"));
+        let tagged = ask(store, |reply| StoreMsg::ListClaimsByTag {
+            tag: "bridges".into(),
+            limit: 10,
+            reply,
+        })
+        .await
+        .unwrap();
+        answers.push(format!("tagged: {tagged:?}"));
+
+        store
+            .send(StoreMsg::RecordSearch {
+                claim: claim.id,
+                search: RecordedSearch {
+                    query: "bridge closed".into(),
+                    date_from: Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap(),
+                    date_to: Utc.with_ymd_and_hms(2025, 1, 8, 0, 0, 0).unwrap(),
+                },
+            })
+            .await
+            .ok()
+            .unwrap();
+        store
+            .send(StoreMsg::CacheQuery {
+                key: "bridge".into(),
+                search: CachedSearchQuery {
+                    query: "bridge closed".into(),
+                    date_from: Some("2025-01-01".into()),
+                    date_to: None,
+                },
+            })
+            .await
+            .ok()
+            .unwrap();
+        // Both are fire-and-forget; wait for the writes to land.
+        let mut last = None;
+        let mut cached = None;
+        for _ in 0..50 {
+            last = ask(store, |reply| StoreMsg::LastSearch {
+                claim: claim.id,
+                reply,
+            })
+            .await
+            .unwrap();
+            cached = ask(store, |reply| StoreMsg::CachedQuery {
+                key: "bridge".into(),
+                max_age: Duration::from_secs(3600),
+                reply,
+            })
+            .await
+            .unwrap();
+            if last.is_some() && cached.is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        answers.push(format!("last search: {last:?}"));
+        answers.push(format!("cached query: {cached:?}"));
+        answers
+    }
+
+    #[tokio::test]
+    #[ignore = "needs NOWHERE_TEST_PG_URL"]
+    async fn both_backends_answer_the_store_protocol_alike() {
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the bridge closed".into(),
+            tags: vec!["transit".into()],
+        };
+        // Fresh internal ids for each backend; the answers leave them out.
+        let batch = || {
+            let mut page = artifact(&claim, "web:1", "Sunny weather downtown");
+            page.provenance.platform = Some("web".into());
+            page.entities[0].name = "City Transit".into();
+            page.entities[0].credibility = Credibility::Weak;
+            vec![
+                artifact(&claim, "tw:1", "The bridge closed for repairs on Monday"),
+                artifact(&claim, "tw:2", "Repairs to the bridge run until Friday"),
+                page,
+            ]
+        };
+
+        let (sqlite, sqlite_pool) = crate::store::tests::test_store().await;
+        let expected = protocol_answers(&sqlite, &claim, batch()).await;
+        let (pool, schema) = test_pool().await;
+        let pg = spawn_actor(PgStoreActor::new(pool.clone()), 64).addr;
+        let got = protocol_answers(&pg, &claim, batch()).await;
+        assert_eq!(got, expected);
+
+        // Retention: the same artifact expires on both.
+        sqlx::query(
+            "UPDATE normalized_artifact SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now', '-31 days') WHERE external_id = 'tw:2'",
+        )
+        .execute(&sqlite_pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "UPDATE normalized_artifact SET created_at = now() - interval '31 days' WHERE external_id = 'tw:2'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            purge_expired_artifacts(&pool, 30).await.unwrap(),
+            crate::store::purge_expired_artifacts(&sqlite_pool, 30)
+                .await
+                .unwrap()
+        );
+        let pg_remaining = ask(&pg, |reply| StoreMsg::CountArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        let sqlite_remaining = ask(&sqlite, |reply| StoreMsg::CountArtifacts {
+            claim: claim.id,
+            reply,
+        })
+        .await
+        .unwrap();
+        assert_eq!((pg_remaining, sqlite_remaining), (2, 2));
+
+        drop_schema(pool, &schema).await;
+    }
+}
@@ -1,6 +1,13 @@
-use crate::actor::{Actor, Context};
-use anyhow::Result;
-use std::{collections::HashMap, time::Duration};
+use crate::actor::{Actor, Addr, Context};
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tokio::{
     sync::oneshot,
     time::{sleep, Instant},
@@ -13,22 +20,85 @@ pub struct RateKey(pub String);
 pub enum RateMsg {
     /// Insert/update bucket config.
     Upsert { key: RateKey, qps: f64, burst: u32 },
-    /// Acquire `cost` tokens; replies when allowed.
+    /// Acquire `cost` tokens; replies when allowed. While tokens are short, waiting
+    /// `Interactive` acquires are granted before `Background` ones.
     Acquire {
         key: RateKey,
         cost: u32,
+        priority: Priority,
         reply: oneshot::Sender<RatePermit>,
     },
+    /// Grant the acquires queued on `key` that its refilled bucket can now cover. The
+    /// limiter sends this to itself once the next waiter's tokens are due.
+    Drain { key: RateKey },
 }
 
 #[derive(Debug)]
 pub struct RatePermit; // no-op token (ack)
 
+/// Which waiters an `Acquire` jumps ahead of when tokens are short.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Batch work nobody is watching, e.g. normalizing fetched artifacts.
+    #[default]
+    Background,
+    /// Someone is waiting on the answer, e.g. a chat reply.
+    Interactive,
+}
+
+/// Time source for bucket refills; swap in [`MockClock`] to step time by hand in tests.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// The tokio clock (honours `tokio::time::pause` as well).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when [`MockClock::advance`] is called.
+#[derive(Debug)]
+pub struct MockClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.elapsed.lock().unwrap() += by;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+}
+
 /// Token-bucket rate limiter as an actor.
 ///
 /// Semantics:
 /// - `Upsert` creates or updates the bucket for a `RateKey`.
-/// - `Acquire` waits (off-actor) until `cost` tokens are available, then replies.
+/// - `Acquire` replies once `cost` tokens are available; until then it waits (off-actor)
+///   in a per-key queue ordered by `Priority`, then arrival.
 ///
 /// Throughput: controlled by `qps` (steady rate) and `burst` (bucket capacity).
 
@@ -46,38 +116,96 @@ struct BucketState {
 }
 
 impl BucketState {
-    fn new(cfg: BucketCfg) -> Self {
+    fn new(cfg: BucketCfg, now: Instant) -> Self {
         Self {
             cfg,
             tokens: cfg.burst,
-            last: Instant::now(),
+            last: now,
         }
     }
 
-    /// Returns wait time needed to have `need` tokens available (0 if ready).
-    fn needed_wait(&mut self, need: f64, now: Instant) -> Duration {
-        // refill
+    fn refill(&mut self, now: Instant) {
         let dt = now.duration_since(self.last).as_secs_f64();
         self.last = now;
         self.tokens = (self.tokens + dt * self.cfg.qps).min(self.cfg.burst);
+    }
 
+    /// Returns wait time needed to have `need` tokens available (0 if ready).
+    fn needed_wait(&self, need: f64) -> Duration {
         if self.tokens >= need {
-            self.tokens -= need;
             Duration::from_millis(0)
         } else {
             let deficit = need - self.tokens;
             // FIXME: guard against zero or extremely low qps values to avoid inf/nan wait computations.
             let secs = deficit / self.cfg.qps;
-            // Reserve the tokens to avoid stampede after sleep
-            self.tokens = 0.0;
             Duration::from_secs_f64(secs.max(0.0))
         }
     }
 }
 
+/// An `Acquire` waiting for tokens.
+#[derive(Debug)]
+struct Waiter {
+    priority: Priority,
+    /// Arrival order, so equal priorities are served first come, first served.
+    seq: u64,
+    cost: u32,
+    reply: oneshot::Sender<RatePermit>,
+}
+
+impl Ord for Waiter {
+    // `BinaryHeap` pops the greatest: highest priority, then earliest arrival.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+#[derive(Debug, Default)]
+struct WaitQueue {
+    waiters: BinaryHeap<Waiter>,
+    /// A `Drain` for this key is already on its way.
+    drain_scheduled: bool,
+}
+
+/// One bucket as written by [`RateLimiter::save_state`].
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedBucket {
+    key: String,
+    qps: f64,
+    burst: f64,
+    tokens: f64,
+    /// Wall-clock time of the last refill, in Unix milliseconds.
+    last_refill_ms: u64,
+}
+
+fn unix_ms(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
 // FIXME: add unit tests covering bursts, refill timing, and multiple concurrent `Acquire` callers so rate limiting regressions surface quickly.
 pub struct RateLimiter {
     buckets: HashMap<RateKey, BucketState>,
+    queues: HashMap<RateKey, WaitQueue>,
+    next_seq: u64,
+    clock: Arc<dyn Clock>,
+    // saved to on drop when set; see `with_state_file`
+    state_file: Option<PathBuf>,
 }
 
 impl Default for RateLimiter {
@@ -88,9 +216,78 @@ impl Default for RateLimiter {
 
 impl RateLimiter {
     pub fn new() -> Self {
+        Self::new_with_clock(Arc::new(SystemClock))
+    }
+
+    pub fn new_with_clock(clock: Arc<dyn Clock>) -> Self {
         Self {
             buckets: HashMap::new(),
+            queues: HashMap::new(),
+            next_seq: 0,
+            clock,
+            state_file: None,
+        }
+    }
+
+    /// Restore bucket levels from `path` if it exists, and write them back there when the
+    /// limiter is dropped (i.e. on shutdown), so a restart does not hand out a fresh burst
+    /// while upstream limits are still recovering. A missing or unreadable file starts
+    /// every bucket full, as without a state file.
+    pub fn with_state_file(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        if path.exists() {
+            match self.load_state(&path) {
+                Ok(n) => tracing::info!(path = %path.display(), buckets = n, "rate.state.loaded"),
+                Err(err) => {
+                    tracing::warn!(path = %path.display(), error = %err, "rate.state.load_failed")
+                }
+            }
         }
+        self.state_file = Some(path);
+        self
+    }
+
+    /// Write every bucket's available tokens and last refill time to `path` as JSON.
+    pub fn save_state(&self, path: &Path) -> Result<()> {
+        let now = self.clock.now();
+        let wall = SystemTime::now();
+        let saved: Vec<SavedBucket> = self
+            .buckets
+            .iter()
+            .map(|(key, b)| SavedBucket {
+                key: key.0.clone(),
+                qps: b.cfg.qps,
+                burst: b.cfg.burst,
+                tokens: b.tokens,
+                last_refill_ms: unix_ms(wall - now.saturating_duration_since(b.last)),
+            })
+            .collect();
+        std::fs::write(path, serde_json::to_vec_pretty(&saved)?)?;
+        Ok(())
+    }
+
+    /// Replace buckets with those saved in `path`, refilled for the time since their last
+    /// refill. Returns how many were loaded. A later `Upsert` updates a loaded bucket's
+    /// rate but keeps its tokens.
+    pub fn load_state(&mut self, path: &Path) -> Result<usize> {
+        let saved: Vec<SavedBucket> = serde_json::from_slice(&std::fs::read(path)?)?;
+        let now = self.clock.now();
+        let wall_ms = unix_ms(SystemTime::now());
+        let count = saved.len();
+        for b in saved {
+            let cfg = BucketCfg {
+                qps: b.qps,
+                burst: b.burst,
+            };
+            let idle = wall_ms.saturating_sub(b.last_refill_ms) as f64 / 1000.0;
+            let state = BucketState {
+                cfg,
+                tokens: (b.tokens + idle * cfg.qps).min(cfg.burst),
+                last: now,
+            };
+            self.buckets.insert(RateKey(b.key), state);
+        }
+        Ok(count)
     }
 
     fn upsert(&mut self, key: RateKey, qps: f64, burst: u32) {
@@ -98,10 +295,96 @@ impl RateLimiter {
             qps,
             burst: burst as f64,
         };
+        let now = self.clock.now();
         self.buckets
             .entry(key)
             .and_modify(|b| b.cfg = cfg)
-            .or_insert_with(|| BucketState::new(cfg));
+            .or_insert_with(|| BucketState::new(cfg, now));
+    }
+
+    /// Queue an acquire of `cost` tokens from `key`'s bucket.
+    fn enqueue(
+        &mut self,
+        key: RateKey,
+        cost: u32,
+        priority: Priority,
+        reply: oneshot::Sender<RatePermit>,
+    ) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.queues.entry(key).or_default().waiters.push(Waiter {
+            priority,
+            seq,
+            cost,
+            reply,
+        });
+    }
+
+    /// Grant `key`'s queued acquires, in priority order, while its bucket covers them.
+    /// Returns how long until the next waiter's tokens are due, or `None` once the queue
+    /// is empty.
+    fn drain(&mut self, key: &RateKey) -> Option<Duration> {
+        let now = self.clock.now();
+        let bucket = self.buckets.entry(key.clone()).or_insert_with(|| {
+            BucketState::new(
+                BucketCfg {
+                    qps: 1.0,
+                    burst: 1.0,
+                },
+                now,
+            )
+        });
+        bucket.refill(now);
+        let queue = self.queues.get_mut(key)?;
+        while let Some(next) = queue.waiters.peek() {
+            // A caller that gave up (e.g. a cancelled search) gets no tokens.
+            if next.reply.is_closed() {
+                queue.waiters.pop();
+                continue;
+            }
+            let wait = bucket.needed_wait(next.cost as f64);
+            if !wait.is_zero() {
+                return Some(wait);
+            }
+            let waiter = queue.waiters.pop().expect("peeked");
+            bucket.tokens -= waiter.cost as f64;
+            let _ = waiter.reply.send(RatePermit);
+        }
+        self.queues.remove(key);
+        None
+    }
+
+    /// Drain `key` and, if acquires are still waiting, have `ctx`'s limiter drain it again
+    /// once the next one's tokens are due.
+    fn drain_and_schedule(&mut self, key: RateKey, ctx: &Context<Self>) {
+        let Some(wait) = self.drain(&key) else {
+            return;
+        };
+        let queue = self.queues.get_mut(&key).expect("waiters remain");
+        if queue.drain_scheduled {
+            return;
+        }
+        queue.drain_scheduled = true;
+        let addr = ctx.addr();
+        // FIXME: attach tracing instrumentation or cancellation so these detached tasks don't accumulate unbounded on long waits.
+        tokio::spawn(async move {
+            sleep(wait).await;
+            let _ = addr.send(RateMsg::Drain { key }).await;
+        });
+    }
+}
+
+impl Drop for RateLimiter {
+    fn drop(&mut self) {
+        let Some(path) = self.state_file.take() else {
+            return;
+        };
+        match self.save_state(&path) {
+            Ok(()) => tracing::info!(path = %path.display(), "rate.state.saved"),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), error = %err, "rate.state.save_failed")
+            }
+        }
     }
 }
 
@@ -109,30 +392,201 @@ impl RateLimiter {
 impl Actor for RateLimiter {
     type Msg = RateMsg;
 
-    async fn handle(&mut self, msg: Self::Msg, _ctx: &mut Context<Self>) -> Result<()> {
+    async fn handle(&mut self, msg: Self::Msg, ctx: &mut Context<Self>) -> Result<()> {
         match msg {
             RateMsg::Upsert { key, qps, burst } => {
                 self.upsert(key, qps, burst);
             }
-            RateMsg::Acquire { key, cost, reply } => {
-                let now = Instant::now();
-                let state = self.buckets.entry(key.clone()).or_insert_with(|| {
-                    BucketState::new(BucketCfg {
-                        qps: 1.0,
-                        burst: 1.0,
-                    })
-                });
-                let wait = state.needed_wait(cost as f64, now);
-                // Do not block the actor; wait and reply in a detached task.
-                // FIXME: attach tracing instrumentation or cancellation so these detached tasks don't accumulate unbounded on long waits.
-                tokio::spawn(async move {
-                    if !wait.is_zero() {
-                        sleep(wait).await;
-                    }
-                    let _ = reply.send(RatePermit);
-                });
+            RateMsg::Acquire {
+                key,
+                cost,
+                priority,
+                reply,
+            } => {
+                self.enqueue(key.clone(), cost, priority, reply);
+                self.drain_and_schedule(key, ctx);
+            }
+            RateMsg::Drain { key } => {
+                if let Some(queue) = self.queues.get_mut(&key) {
+                    queue.drain_scheduled = false;
+                }
+                self.drain_and_schedule(key, ctx);
             }
         }
         Ok(())
     }
 }
+
+/// Wait for one token from `key`'s bucket, ahead of any lower-`priority` waiters.
+pub(crate) async fn acquire_rate_permit(
+    rate_limiter: &Addr<RateLimiter>,
+    rate_key: &RateKey,
+    priority: Priority,
+) -> Result<()> {
+    let (permit_tx, permit_rx) = oneshot::channel();
+    rate_limiter
+        .send(RateMsg::Acquire {
+            key: rate_key.clone(),
+            cost: 1,
+            priority,
+            reply: permit_tx,
+        })
+        .await
+        .map_err(|_| anyhow!("rate limiter actor dropped"))?;
+
+    permit_rx
+        .await
+        .map_err(|_| anyhow!("failed to receive rate permit from limiter"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::spawn_actor;
+
+    /// Queue a one-token acquire and drain `key`, returning the permit's receiver and the
+    /// wait until the next waiter is due.
+    fn acquire(
+        limiter: &mut RateLimiter,
+        key: &RateKey,
+        priority: Priority,
+    ) -> (oneshot::Receiver<RatePermit>, Option<Duration>) {
+        let (tx, rx) = oneshot::channel();
+        limiter.enqueue(key.clone(), 1, priority, tx);
+        (rx, limiter.drain(key))
+    }
+
+    #[test]
+    fn mock_clock_refills_one_token_per_second() {
+        let clock = Arc::new(MockClock::new());
+        let mut limiter = RateLimiter::new_with_clock(clock.clone());
+        let key = RateKey("test".into());
+        limiter.upsert(key.clone(), 1.0, 1);
+
+        let (mut first, wait) = acquire(&mut limiter, &key, Priority::Background);
+        assert!(first.try_recv().is_ok());
+        assert_eq!(wait, None);
+        let tokens = |l: &RateLimiter| l.buckets[&key].tokens;
+        assert_eq!(tokens(&limiter), 0.0);
+
+        clock.advance(Duration::from_secs(1));
+        let (mut second, _) = acquire(&mut limiter, &key, Priority::Background);
+        assert!(second.try_recv().is_ok());
+        assert_eq!(tokens(&limiter), 0.0);
+        let (mut third, wait) = acquire(&mut limiter, &key, Priority::Background);
+        assert!(third.try_recv().is_err(), "no token left");
+        assert_eq!(wait, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn interactive_acquire_is_granted_before_queued_background_ones() {
+        let clock = Arc::new(MockClock::new());
+        let mut limiter = RateLimiter::new_with_clock(clock.clone());
+        let key = RateKey("llm:main".into());
+        limiter.upsert(key.clone(), 1.0, 1);
+
+        let (mut first, _) = acquire(&mut limiter, &key, Priority::Background);
+        assert!(first.try_recv().is_ok());
+        // A normalization batch queues up behind the spent bucket, then a chat arrives.
+        let (mut batch_a, _) = acquire(&mut limiter, &key, Priority::Background);
+        let (mut batch_b, _) = acquire(&mut limiter, &key, Priority::Background);
+        let (mut chat, _) = acquire(&mut limiter, &key, Priority::Interactive);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(limiter.drain(&key), Some(Duration::from_secs(1)));
+        assert!(chat.try_recv().is_ok(), "the freed token goes to chat");
+        assert!(batch_a.try_recv().is_err());
+
+        // Background waiters keep their arrival order.
+        clock.advance(Duration::from_secs(1));
+        limiter.drain(&key);
+        assert!(batch_a.try_recv().is_ok());
+        assert!(batch_b.try_recv().is_err());
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(limiter.drain(&key), None);
+        assert!(batch_b.try_recv().is_ok());
+    }
+
+    #[test]
+    fn abandoned_waiters_get_no_tokens() {
+        let clock = Arc::new(MockClock::new());
+        let mut limiter = RateLimiter::new_with_clock(clock.clone());
+        let key = RateKey("test".into());
+        limiter.upsert(key.clone(), 1.0, 1);
+
+        let _ = acquire(&mut limiter, &key, Priority::Background);
+        let (gone, _) = acquire(&mut limiter, &key, Priority::Interactive);
+        let (mut kept, _) = acquire(&mut limiter, &key, Priority::Background);
+        drop(gone);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(limiter.drain(&key), None);
+        assert!(kept.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn queued_acquires_are_granted_as_tokens_refill() {
+        let handle = spawn_actor(RateLimiter::new(), 8);
+        let key = RateKey("test".into());
+        handle
+            .addr
+            .send(RateMsg::Upsert {
+                key: key.clone(),
+                qps: 20.0,
+                burst: 1,
+            })
+            .await
+            .ok()
+            .unwrap();
+
+        let start = Instant::now();
+        let acquires: Vec<_> = (0..3)
+            .map(|_| {
+                let (addr, key) = (handle.addr.clone(), key.clone());
+                tokio::spawn(
+                    async move { acquire_rate_permit(&addr, &key, Priority::Background).await },
+                )
+            })
+            .collect();
+        for granted in acquires {
+            granted.await.unwrap().unwrap();
+        }
+        // One token at once, then one every 50ms.
+        assert!(
+            start.elapsed() >= Duration::from_millis(90),
+            "{:?}",
+            start.elapsed()
+        );
+    }
+
+    #[test]
+    fn saved_token_levels_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("nowhere-rate-{}.json", uuid::Uuid::new_v4()));
+        let key = RateKey("llm:main".into());
+        {
+            let mut limiter =
+                RateLimiter::new_with_clock(Arc::new(MockClock::new())).with_state_file(&path);
+            // Slow enough that the test's own runtime adds no measurable tokens.
+            limiter.upsert(key.clone(), 0.001, 10);
+            for _ in 0..7 {
+                let (mut permit, _) = acquire(&mut limiter, &key, Priority::Background);
+                assert!(permit.try_recv().is_ok());
+            }
+        } // dropped: state written
+
+        let restarted =
+            RateLimiter::new_with_clock(Arc::new(MockClock::new())).with_state_file(&path);
+        let tokens = restarted.buckets[&key].tokens;
+        assert!((tokens - 3.0).abs() < 0.01, "restored {tokens} tokens");
+
+        // Without a state file the bucket would start full again.
+        let mut fresh = RateLimiter::new_with_clock(Arc::new(MockClock::new()));
+        fresh.upsert(key.clone(), 0.001, 10);
+        assert_eq!(fresh.buckets[&key].tokens, 10.0);
+
+        drop(restarted);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,23 +4,33 @@
 //! watcher fan-out when artifacts relevant to a claim arrive. More detailed docs should
 //! describe the schema expectations, concurrency model, and error propagation strategy.
 use crate::actor::Actor;
-use crate::actor::Context;
+use crate::actor::{Addr, Context};
 use crate::ClaimContext;
 use crate::{
-    ArtifactRow, ArtifactWithEntities, Credibility, EntityRow, NormalizedArtifact, StoreMsg,
+    ArtifactRow, ArtifactWithEntities, CachedSearchQuery, Credibility, DeadLetter, EntityRow,
+    EvidenceOverlap, ImportCounts, NormalizedArtifact, PlatformSources, Provenance, RecordedSearch,
+    ReviewStatus, StoreMsg, ARTIFACT_SCHEMA_VERSION,
 };
 use anyhow::Result;
-use sqlx::{Row, SqlitePool};
-use std::{collections::HashMap, sync::Arc};
-use tokio::sync::{oneshot, Semaphore};
+use chrono::{DateTime, Utc};
+use nowhere_common::slug;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::{broadcast, oneshot, AcquireError, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 pub struct StoreActor {
     pool: SqlitePool,
-    // FIXME: expose the write semaphore size via configuration so heavy ingest can batch more than one write at a time.
     write_limit: Arc<Semaphore>,
     watchers: HashMap<Uuid, Vec<oneshot::Sender<()>>>,
+    fts: Arc<FtsQueryOptions>,
 }
 
 impl StoreActor {
@@ -29,6 +39,183 @@ impl StoreActor {
             pool,
             write_limit: Arc::new(Semaphore::new(1)),
             watchers: HashMap::new(),
+            fts: Arc::new(FtsQueryOptions::default()),
+        }
+    }
+
+    /// Share `limit` as the write semaphore, so producers that take a slot before sending an
+    /// upsert (see `LlmActor::with_write_limit`) wait while the store is saturated. Writes
+    /// run one at a time by default.
+    pub fn with_write_limit(mut self, limit: Arc<Semaphore>) -> Self {
+        self.write_limit = limit;
+        self
+    }
+
+    /// Override how free-text searches are turned into FTS5 queries.
+    pub fn with_fts_options(mut self, fts: FtsQueryOptions) -> Self {
+        self.fts = Arc::new(fts);
+        self
+    }
+}
+
+/// Ceiling on how many artifacts one claim may collect, checked against the store so every
+/// worker searching for the claim sees the same total.
+#[derive(Clone)]
+pub struct ArtifactCap {
+    store: Addr<StoreActor>,
+    pub max: usize,
+}
+
+impl ArtifactCap {
+    pub fn new(store: Addr<StoreActor>, max: usize) -> Self {
+        Self { store, max }
+    }
+
+    /// Artifacts stored for `claim` so far.
+    pub async fn stored(&self, claim: Uuid) -> Result<usize> {
+        let (reply, rx) = oneshot::channel();
+        self.store
+            .send(StoreMsg::CountArtifacts { claim, reply })
+            .await
+            .map_err(|_| anyhow::anyhow!("store actor mailbox dropped (count)"))?;
+        rx.await?
+    }
+}
+
+/// How often the retention task started by [`spawn_retention`] looks for expired artifacts.
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Every `every`, delete artifacts stored more than `ttl_days` days ago (see
+/// [`purge_expired_artifacts`]) until `shutdown` fires. The first sweep runs immediately.
+pub fn spawn_retention(
+    pool: SqlitePool,
+    ttl_days: u32,
+    every: Duration,
+    mut shutdown: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(every);
+        loop {
+            tokio::select! {
+                _ = shutdown.recv() => return,
+                _ = ticks.tick() => {}
+            }
+            if let Err(err) = purge_expired_artifacts(&pool, ttl_days).await {
+                warn!(error = ?err, "store.retention.failed");
+            }
+        }
+    })
+}
+
+/// Delete artifacts (with their entities and archived page captures) whose `created_at` is
+/// more than `ttl_days` days old. Returns how many artifacts were removed.
+pub async fn purge_expired_artifacts(pool: &SqlitePool, ttl_days: u32) -> Result<usize> {
+    let cutoff = format!("-{ttl_days} days");
+    let mut tx = pool.begin().await?;
+    let expired = sqlx::query(
+        r#"SELECT internal_id, html_path, screenshot_path
+           FROM normalized_artifact
+           WHERE created_at < strftime('%Y-%m-%dT%H:%M:%fZ', 'now', ?1)"#,
+    )
+    .bind(&cutoff)
+    .fetch_all(&mut *tx)
+    .await?;
+    if expired.is_empty() {
+        debug!(ttl_days, "store.retention.nothing_expired");
+        return Ok(0);
+    }
+
+    let mut entities = 0;
+    let mut captures = Vec::new();
+    for row in &expired {
+        let id: String = row.try_get("internal_id")?;
+        // Explicit rather than relying on the connection having `foreign_keys` on.
+        entities += sqlx::query("DELETE FROM entity WHERE article_id = ?1")
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        sqlx::query("DELETE FROM normalized_artifact WHERE internal_id = ?1")
+            .bind(&id)
+            .execute(&mut *tx)
+            .await?;
+        for col in ["html_path", "screenshot_path"] {
+            if let Some(path) = row.try_get::<Option<String>, _>(col)? {
+                captures.push(path);
+            }
+        }
+    }
+    tx.commit().await?;
+
+    let files = remove_captures(captures).await;
+    info!(
+        ttl_days,
+        artifacts = expired.len(),
+        entities,
+        files,
+        "store.retention.purged"
+    );
+    Ok(expired.len())
+}
+
+/// Delete the archived page captures of purged artifacts, returning how many files went.
+/// Files already gone are skipped; other failures are logged and leave the file behind.
+pub(crate) async fn remove_captures(paths: Vec<String>) -> usize {
+    let mut files = 0;
+    for path in paths {
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => files += 1,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => warn!(path, error = %err, "store.retention.capture_delete_failed"),
+        }
+    }
+    files
+}
+
+/// Words dropped from unquoted search text by default.
+pub const DEFAULT_FTS_STOPWORDS: &[&str] = &[
+    "a", "about", "an", "and", "are", "as", "at", "be", "by", "did", "do", "does", "for", "from",
+    "had", "has", "have", "he", "her", "his", "how", "i", "in", "is", "it", "its", "of", "on",
+    "or", "said", "she", "that", "the", "their", "they", "this", "to", "was", "were", "what",
+    "when", "where", "which", "who", "why", "will", "with",
+];
+
+/// Characters of `reasoning` and `provenance_info` that searches return by default.
+pub const DEFAULT_SEARCH_TEXT_CHARS: usize = 2000;
+
+/// Words a search's `MATCH` expression keeps by default; see [`FtsQueryOptions::max_terms`].
+pub const DEFAULT_FTS_MAX_TERMS: usize = 20;
+
+/// How `search_artifacts_fts` tokenizes free text into an FTS5 `MATCH` expression, and
+/// how much of each hit's text it returns.
+#[derive(Debug, Clone)]
+pub struct FtsQueryOptions {
+    /// Pass `"quoted text"` through as an FTS phrase instead of matching its words anywhere.
+    pub keep_phrases: bool,
+    /// Lowercase words dropped from unquoted text. Words inside phrases are always kept.
+    pub stopwords: HashSet<String>,
+    /// Cut `reasoning` and `provenance_info` to this many characters; `None` returns them
+    /// whole. Search hits feed synthesis prompts as they are, so a longer cut gives the
+    /// model more context per artifact at the cost of a bigger prompt. This does not reach
+    /// chat: it hydrates its hits with `GetArtifacts`, which reads the `v_artifact` and
+    /// `v_entity` views and so always sees at most 2000 characters.
+    pub max_text_chars: Option<usize>,
+    /// Most words the `MATCH` expression may hold, counting each word of a phrase. Longer
+    /// input keeps its leading words, which for chat questions and claims are usually the
+    /// subject, so a pasted paragraph can't build an expression SQLite refuses.
+    pub max_terms: usize,
+}
+
+impl Default for FtsQueryOptions {
+    fn default() -> Self {
+        Self {
+            keep_phrases: true,
+            stopwords: DEFAULT_FTS_STOPWORDS
+                .iter()
+                .map(|w| w.to_string())
+                .collect(),
+            max_text_chars: Some(DEFAULT_SEARCH_TEXT_CHARS),
+            max_terms: DEFAULT_FTS_MAX_TERMS,
         }
     }
 }
@@ -58,7 +245,7 @@ impl Actor for StoreActor {
                     drop(permit);
                 });
             }
-            StoreMsg::UpsertArtifact(n) => {
+            StoreMsg::UpsertArtifact(n, held) => {
                 let pool = self.pool.clone();
                 let permit_src = self.write_limit.clone();
                 let me = ctx.addr();
@@ -66,14 +253,14 @@ impl Actor for StoreActor {
                 let relevant = n.claim_relevance;
                 // FIXME: restructure to propagate errors back to callers rather than only logging them.
                 tokio::spawn(async move {
-                    let permit = match permit_src.acquire_owned().await {
+                    let permit = match write_slot(permit_src, held).await {
                         Ok(permit) => permit,
                         Err(err) => {
                             error!(error = ?err, "store.upsert.acquire_failed");
                             return;
                         }
                     };
-                    if let Err(err) = upsert_normalized(&pool, n).await {
+                    if let Err(err) = upsert_normalized(&pool, *n).await {
                         error!(error = ?err, "store.upsert.failed");
                     } else if relevant {
                         let _ = me
@@ -83,6 +270,120 @@ impl Actor for StoreActor {
                     drop(permit);
                 });
             }
+            StoreMsg::UpsertBatch(batch, held) => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                let me = ctx.addr();
+                tokio::spawn(async move {
+                    let permit = match write_slot(permit_src, held).await {
+                        Ok(permit) => permit,
+                        Err(err) => {
+                            error!(error = ?err, "store.upsert_batch.acquire_failed");
+                            return;
+                        }
+                    };
+                    let res = upsert_batch(&pool, batch).await;
+                    drop(permit);
+                    match res {
+                        Ok(claims) => {
+                            for claim in claims {
+                                let _ = me.send(StoreMsg::ArtifactUpserted { claim }).await;
+                            }
+                        }
+                        Err(err) => error!(error = ?err, "store.upsert_batch.failed"),
+                    }
+                });
+            }
+
+            StoreMsg::ImportJsonl { path, claim, reply } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                let me = ctx.addr();
+                let claim_id = claim.id;
+                tokio::spawn(async move {
+                    let permit = match permit_src.acquire_owned().await {
+                        Ok(permit) => permit,
+                        Err(err) => {
+                            error!(error = ?err, "store.import_jsonl.acquire_failed");
+                            return;
+                        }
+                    };
+                    let res = import_jsonl(&pool, &path, claim).await;
+                    drop(permit);
+                    if matches!(&res, Ok(counts) if counts.artifacts > 0) {
+                        let _ = me
+                            .send(StoreMsg::ArtifactUpserted { claim: claim_id })
+                            .await;
+                    }
+                    if reply.send(res).is_err() {
+                        debug!("store.import_jsonl.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::RecordSearch { claim, search } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => record_search(&pool, claim, &search).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(claim_id = %claim, error = ?err, "store.record_search.failed");
+                    }
+                });
+            }
+
+            StoreMsg::LastSearch { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = last_search(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.last_search.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::CachedQuery {
+                key,
+                max_age,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = cached_query(&pool, &key, max_age).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.cached_query.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::CacheQuery { key, search } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => cache_query(&pool, &key, &search).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(key, error = ?err, "store.cache_query.failed");
+                    }
+                });
+            }
+            StoreMsg::DeadLetter(letter) => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => dead_letter(&pool, &letter).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if let Err(err) = res {
+                        error!(artifact = %letter.external_id, error = ?err, "store.dead_letter.failed");
+                    }
+                });
+            }
 
             StoreMsg::GetArtifact { internal_id, reply } => {
                 let pool = self.pool.clone();
@@ -94,12 +395,21 @@ impl Actor for StoreActor {
                     }
                 });
             }
+            StoreMsg::GetArtifacts { ids, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = get_artifacts_with_entities(&pool, &ids, TextSource::Views).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.get_artifacts.reply_dropped");
+                    }
+                });
+            }
             StoreMsg::WatchArtifacts { claim, reply } => {
-                let entry = self.watchers.entry(claim).or_default();
-                entry.retain(|tx| !tx.is_closed());
-                entry.push(reply);
+                prune_watchers(&mut self.watchers);
+                self.watchers.entry(claim).or_default().push(reply);
             }
             StoreMsg::ArtifactUpserted { claim } => {
+                prune_watchers(&mut self.watchers);
                 if let Some(listeners) = self.watchers.remove(&claim) {
                     for tx in listeners {
                         let _ = tx.send(());
@@ -114,8 +424,9 @@ impl Actor for StoreActor {
                 reply,
             } => {
                 let pool = self.pool.clone();
+                let fts = self.fts.clone();
                 tokio::spawn(async move {
-                    let res = search_artifacts_fts(&pool, &query, claim, limit).await;
+                    let res = search_artifacts_fts(&pool, &query, claim, limit, &fts).await;
                     if reply.send(res).is_err() {
                         debug!("store.search_artifacts.reply_dropped");
                     }
@@ -131,16 +442,163 @@ impl Actor for StoreActor {
                     }
                 });
             }
+
+            StoreMsg::CountArtifacts { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = count_artifacts(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.count_artifacts.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::SourceBreakdown { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = source_breakdown(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.source_breakdown.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::ClaimArtifacts { claim, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = claim_artifacts(&pool, claim).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.claim_artifacts.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ClaimOverlap { a, b, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = claim_overlap(&pool, a, b).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.claim_overlap.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::TagClaim { claim, tag, reply } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => tag_claim(&pool, claim, &tag).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if reply.send(res).is_err() {
+                        debug!("store.tag_claim.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::SetReview {
+                artifact,
+                status,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => set_review(&pool, artifact, status).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if reply.send(res).is_err() {
+                        debug!("store.set_review.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::ListClaimsByTag { tag, limit, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_claims_by_tag(&pool, &tag, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.list_claims_by_tag.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::ListClaimsBySlug { slug, limit, reply } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_claims_by_slug(&pool, &slug, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.list_claims_by_slug.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::ListClaimsByUuidPrefix {
+                prefix,
+                limit,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_claims_by_uuid_prefix(&pool, &prefix, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.list_claims_by_uuid_prefix.reply_dropped");
+                    }
+                });
+            }
+            StoreMsg::ListArtifactIdsByPrefix {
+                prefix,
+                limit,
+                reply,
+            } => {
+                let pool = self.pool.clone();
+                tokio::spawn(async move {
+                    let res = list_artifact_ids_by_prefix(&pool, &prefix, limit).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.list_artifact_ids_by_prefix.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::RebuildFts { reply } => {
+                let pool = self.pool.clone();
+                let permit_src = self.write_limit.clone();
+                tokio::spawn(async move {
+                    let res = match permit_src.acquire_owned().await {
+                        Ok(_permit) => rebuild_fts(&pool).await,
+                        Err(err) => Err(err.into()),
+                    };
+                    if reply.send(res).is_err() {
+                        debug!("store.rebuild_fts.reply_dropped");
+                    }
+                });
+            }
+
+            StoreMsg::ListLinkedArtifacts { internal_id, reply } => {
+                let pool = self.pool.clone();
+                let id = internal_id.to_string();
+                tokio::spawn(async move {
+                    let res = list_linked_artifacts(&pool, &id).await;
+                    if reply.send(res).is_err() {
+                        debug!("store.list_linked.reply_dropped");
+                    }
+                });
+            }
         }
         Ok(())
     }
 }
 
+/// Relevant artifacts of `claim_id` matching `q`, confirmed ones first, then best `bm25`.
+/// Falls back to the most recently updated ones when `q` has nothing searchable or matches
+/// nothing. Dismissed artifacts are never returned.
 pub async fn search_artifacts_fts(
     pool: &SqlitePool,
     q: &str,
     claim_id: Uuid,
     limit: i64,
+    fts: &FtsQueryOptions,
 ) -> anyhow::Result<Vec<ArtifactRow>> {
     tracing::debug!(
         claim_id=%claim_id,
@@ -148,7 +606,9 @@ pub async fn search_artifacts_fts(
         limit,
         "store.search_artifacts_fts.start"
     );
-    let sanitized = sanitize_fts_query(q);
+    let sanitized = sanitize_fts_query(q, fts);
+    // NULL keeps the whole text (see the `coalesce` below).
+    let text_chars = fts.max_text_chars.and_then(|n| i64::try_from(n).ok());
     if sanitized.is_none() {
         tracing::info!(
             claim_id=%claim_id,
@@ -164,19 +624,23 @@ pub async fn search_artifacts_fts(
               a.internal_id,
               a.external_id,
               a.claim_relevance,
-              substr(a.reasoning, 1, 2000)       AS reasoning,
-              substr(a.provenance_info, 1, 2000) AS provenance_info,
-              a.claim_id
+              substr(a.reasoning, 1, coalesce(?1, length(a.reasoning)))             AS reasoning,
+              substr(a.provenance_info, 1, coalesce(?1, length(a.provenance_info))) AS provenance_info,
+              a.claim_id,
+              a.source_url, a.author, a.captured_at, a.platform,
+              a.html_path, a.screenshot_path, a.schema_version, a.review_status
             FROM fts_artifact
             JOIN normalized_artifact a ON a.rowid = fts_artifact.rowid
             WHERE a.claim_relevance = 1
-              AND a.claim_id = ?
-              AND fts_artifact MATCH ?
+              AND a.claim_id = ?2
+              AND a.review_status != 'dismissed'
+              AND fts_artifact MATCH ?3
             -- If your SQLite supports it, this gives nicer relevance ordering:
-            ORDER BY bm25(fts_artifact) ASC
-            LIMIT ?
+            ORDER BY a.review_status = 'confirmed' DESC, bm25(fts_artifact) ASC
+            LIMIT ?4
             "#,
         )
+        .bind(text_chars)
         .bind(claim_id.to_string())
         .bind(fts_query)
         .bind(limit)
@@ -206,16 +670,20 @@ pub async fn search_artifacts_fts(
               internal_id,
               external_id,
               claim_relevance,
-              substr(reasoning, 1, 2000)       AS reasoning,
-              substr(provenance_info, 1, 2000) AS provenance_info,
-              claim_id
+              substr(reasoning, 1, coalesce(?1, length(reasoning)))             AS reasoning,
+              substr(provenance_info, 1, coalesce(?1, length(provenance_info))) AS provenance_info,
+              claim_id,
+              source_url, author, captured_at, platform,
+              html_path, screenshot_path, schema_version, review_status
             FROM normalized_artifact
             WHERE claim_relevance = 1
-              AND claim_id = ?
-            ORDER BY updated_at DESC
-            LIMIT ?
+              AND claim_id = ?2
+              AND review_status != 'dismissed'
+            ORDER BY review_status = 'confirmed' DESC, updated_at DESC
+            LIMIT ?3
             "#,
         )
+        .bind(text_chars)
         .bind(claim_id.to_string())
         .bind(limit)
         .fetch_all(pool)
@@ -262,6 +730,8 @@ pub async fn search_artifacts_fts(
                 .unwrap_or_default(),
             // NOTE: claim_id is nullable in the schema
             claim_id: r.try_get::<Option<String>, _>("claim_id").unwrap_or(None),
+            provenance: provenance_from_row(&r),
+            review_status: review_from_row(&r),
         })
         .collect())
 }
@@ -286,7 +756,9 @@ pub async fn search_artifacts_like(
           a.claim_relevance,
           substr(a.reasoning, 1, 2000)       AS reasoning,
           substr(a.provenance_info, 1, 2000) AS provenance_info,
-          a.claim_id
+          a.claim_id,
+          a.source_url, a.author, a.captured_at, a.platform,
+          a.html_path, a.screenshot_path, a.schema_version, a.review_status
         FROM normalized_artifact a
         WHERE a.claim_relevance = 1
           AND (?1 IS NULL OR a.claim_id = ?2)
@@ -319,20 +791,72 @@ pub async fn search_artifacts_like(
                 .try_get::<String, _>("provenance_info")
                 .unwrap_or_default(),
             claim_id: r.try_get::<Option<String>, _>("claim_id").unwrap_or(None),
+            provenance: provenance_from_row(&r),
+            review_status: review_from_row(&r),
         })
         .collect())
 }
 
-async fn insert_claim(pool: &SqlitePool, c: ClaimContext) -> Result<()> {
+/// Read the typed provenance columns, filling in what a row stored at an older
+/// `schema_version` could not record (see `Provenance::upgrade`).
+fn provenance_from_row(r: &SqliteRow) -> Provenance {
+    Provenance {
+        source_url: r.try_get("source_url").unwrap_or(None),
+        author: r.try_get("author").unwrap_or(None),
+        captured_at: r.try_get("captured_at").unwrap_or(None),
+        platform: r.try_get("platform").unwrap_or(None),
+        html_path: r.try_get("html_path").unwrap_or(None),
+        screenshot_path: r.try_get("screenshot_path").unwrap_or(None),
+    }
+    .upgrade(
+        r.try_get("schema_version")
+            .unwrap_or(ARTIFACT_SCHEMA_VERSION),
+        &r.try_get::<String, _>("external_id").unwrap_or_default(),
+    )
+}
+
+/// Read `review_status`; rows from queries that don't select it count as pending.
+fn review_from_row(r: &SqliteRow) -> ReviewStatus {
+    r.try_get::<&str, _>("review_status")
+        .map(ReviewStatus::from)
+        .unwrap_or_default()
+}
+
+/// Drop watchers whose receiver is gone, and claims left without any, so claims the TUI
+/// moved away from don't keep entries until they happen to get another artifact.
+pub(crate) fn prune_watchers(watchers: &mut HashMap<Uuid, Vec<oneshot::Sender<()>>>) {
+    let before = watchers.len();
+    watchers.retain(|_, listeners| {
+        listeners.retain(|tx| !tx.is_closed());
+        !listeners.is_empty()
+    });
+    if watchers.len() < before {
+        debug!(pruned = before - watchers.len(), "store.watchers.pruned");
+    }
+}
+
+/// The slot the sender brought along, or the next free one.
+pub(crate) async fn write_slot(
+    limit: Arc<Semaphore>,
+    held: Option<OwnedSemaphorePermit>,
+) -> Result<OwnedSemaphorePermit, AcquireError> {
+    match held {
+        Some(permit) => Ok(permit),
+        None => limit.acquire_owned().await,
+    }
+}
+
+pub(crate) async fn insert_claim(pool: &SqlitePool, c: ClaimContext) -> Result<()> {
     let mut tx = pool.begin().await?;
     let res = sqlx::query(
         r#"INSERT INTO claim
-        (id, text)
-        VALUES (?1, ?2)
+        (id, text, tags)
+        VALUES (?1, ?2, ?3)
     "#,
     )
     .bind(c.id.to_string())
     .bind(c.text)
+    .bind(serde_json::to_string(&c.tags)?)
     .execute(&mut *tx)
     .await?;
     info!(
@@ -344,36 +868,351 @@ async fn insert_claim(pool: &SqlitePool, c: ClaimContext) -> Result<()> {
     Ok(())
 }
 
-async fn upsert_normalized(pool: &SqlitePool, n: NormalizedArtifact) -> Result<()> {
-    // Single txn for artifact + entities (faster + atomic)
+/// Add `tag` to the claim's tag list unless it is already there, returning the list.
+async fn tag_claim(pool: &SqlitePool, claim: Uuid, tag: &str) -> Result<Vec<String>> {
     let mut tx = pool.begin().await?;
+    let stored: Option<String> = sqlx::query_scalar("SELECT tags FROM claim WHERE id = ?1")
+        .bind(claim.to_string())
+        .fetch_optional(&mut *tx)
+        .await?;
+    let Some(stored) = stored else {
+        anyhow::bail!("claim {claim} not found");
+    };
+    let mut tags: Vec<String> = serde_json::from_str(&stored)?;
+    if !tags.iter().any(|t| t == tag) {
+        tags.push(tag.to_string());
+        sqlx::query(
+            "UPDATE claim SET tags = ?2, updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now') WHERE id = ?1",
+        )
+        .bind(claim.to_string())
+        .bind(serde_json::to_string(&tags)?)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    info!(claim_id=%claim, tag, "store.tag_claim");
+    Ok(tags)
+}
 
-    let res_artifact = sqlx::query(
-        r#"INSERT INTO normalized_artifact
-           (internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id)
-           VALUES (?1, ?2, ?3, ?4, ?5, ?6)
-           ON CONFLICT(external_id) DO UPDATE SET
-             claim_relevance=excluded.claim_relevance,
-             reasoning=excluded.reasoning,
-             provenance_info=excluded.provenance_info,
-             claim_id=excluded.claim_id"#,
+pub(crate) async fn set_review(
+    pool: &SqlitePool,
+    artifact: Uuid,
+    status: ReviewStatus,
+) -> Result<()> {
+    let res = sqlx::query(
+        "UPDATE normalized_artifact SET review_status = ?2, updated_at = strftime('%Y-%m-%dT%H:%M:%fZ','now') WHERE internal_id = ?1",
     )
-    .bind(n.internal_id.to_string())
-    .bind(n.external_id.as_str())
-    .bind(n.claim_relevance)
+    .bind(artifact.to_string())
+    .bind(status.as_str())
+    .execute(pool)
+    .await?;
+    if res.rows_affected() == 0 {
+        anyhow::bail!("artifact {artifact} not found");
+    }
+    info!(artifact_id=%artifact, status=status.as_str(), "store.set_review");
+    Ok(())
+}
+
+async fn list_claims_by_tag(pool: &SqlitePool, tag: &str, limit: i64) -> Result<Vec<ClaimContext>> {
+    let rows = sqlx::query(
+        r#"SELECT c.id, c.text, c.tags
+           FROM claim c
+           WHERE EXISTS (SELECT 1 FROM json_each(c.tags) t WHERE t.value = ?1)
+           ORDER BY c.created_at DESC
+           LIMIT ?2"#,
+    )
+    .bind(tag)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|r| {
+            let id: String = r.try_get("id")?;
+            let tags: String = r.try_get("tags")?;
+            Ok(ClaimContext {
+                id: Uuid::parse_str(&id)?,
+                text: r.try_get("text")?,
+                tags: serde_json::from_str(&tags)?,
+            })
+        })
+        .collect()
+}
+
+async fn list_claims_by_slug(
+    pool: &SqlitePool,
+    slug: &str,
+    limit: i64,
+) -> Result<Vec<ClaimContext>> {
+    match slug::prefix_range(slug) {
+        Some((lo, hi)) => list_claims_between(pool, lo, hi, limit).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn list_claims_by_uuid_prefix(
+    pool: &SqlitePool,
+    prefix: &str,
+    limit: i64,
+) -> Result<Vec<ClaimContext>> {
+    match slug::uuid_prefix_range(prefix) {
+        Some((lo, hi)) => list_claims_between(pool, lo, hi, limit).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Artifact ids in the slug range and the UUID range `prefix` spells, merged.
+async fn list_artifact_ids_by_prefix(
+    pool: &SqlitePool,
+    prefix: &str,
+    limit: i64,
+) -> Result<Vec<Uuid>> {
+    let mut ids = Vec::new();
+    for (lo, hi) in [slug::prefix_range(prefix), slug::uuid_prefix_range(prefix)]
+        .into_iter()
+        .flatten()
+    {
+        let rows: Vec<String> = sqlx::query_scalar(
+            r#"SELECT internal_id FROM normalized_artifact
+               WHERE internal_id BETWEEN ?1 AND ?2
+               ORDER BY internal_id
+               LIMIT ?3"#,
+        )
+        .bind(lo.to_string())
+        .bind(hi.to_string())
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+        for id in rows {
+            ids.push(Uuid::parse_str(&id)?);
+        }
+    }
+    ids.sort();
+    ids.dedup();
+    ids.truncate(usize::try_from(limit).unwrap_or(0));
+    Ok(ids)
+}
+
+async fn list_claims_between(
+    pool: &SqlitePool,
+    lo: Uuid,
+    hi: Uuid,
+    limit: i64,
+) -> Result<Vec<ClaimContext>> {
+    // Hyphenated lowercase UUIDs compare as text the way they do as numbers.
+    let rows = sqlx::query(
+        r#"SELECT id, text, tags FROM claim
+           WHERE id BETWEEN ?1 AND ?2
+           ORDER BY id
+           LIMIT ?3"#,
+    )
+    .bind(lo.to_string())
+    .bind(hi.to_string())
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    rows.iter()
+        .map(|r| {
+            let id: String = r.try_get("id")?;
+            let tags: String = r.try_get("tags")?;
+            Ok(ClaimContext {
+                id: Uuid::parse_str(&id)?,
+                text: r.try_get("text")?,
+                tags: serde_json::from_str(&tags)?,
+            })
+        })
+        .collect()
+}
+
+pub(crate) async fn record_search(
+    pool: &SqlitePool,
+    claim: Uuid,
+    search: &RecordedSearch,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO claim_search (claim_id, query, date_from, date_to) VALUES (?1, ?2, ?3, ?4)",
+    )
+    .bind(claim.to_string())
+    .bind(&search.query)
+    .bind(search.date_from)
+    .bind(search.date_to)
+    .execute(pool)
+    .await?;
+    info!(claim_id = %claim, query = %search.query, "store.record_search");
+    Ok(())
+}
+
+async fn last_search(pool: &SqlitePool, claim: Uuid) -> Result<Option<RecordedSearch>> {
+    let row = sqlx::query(
+        r#"SELECT query, date_from, date_to
+           FROM claim_search
+           WHERE claim_id = ?1
+           ORDER BY id DESC
+           LIMIT 1"#,
+    )
+    .bind(claim.to_string())
+    .fetch_optional(pool)
+    .await?;
+    row.map(|r| {
+        Ok(RecordedSearch {
+            query: r.try_get("query")?,
+            date_from: r.try_get("date_from")?,
+            date_to: r.try_get("date_to")?,
+        })
+    })
+    .transpose()
+}
+
+/// The query cached under `key`, if it was cached no more than `max_age` ago.
+async fn cached_query(
+    pool: &SqlitePool,
+    key: &str,
+    max_age: Duration,
+) -> Result<Option<CachedSearchQuery>> {
+    let row =
+        sqlx::query("SELECT query, date_from, date_to, created_at FROM query_cache WHERE key = ?1")
+            .bind(key)
+            .fetch_optional(pool)
+            .await?;
+    let Some(r) = row else {
+        return Ok(None);
+    };
+    let cached_at: DateTime<Utc> = r.try_get("created_at")?;
+    if Utc::now() - cached_at > chrono::Duration::from_std(max_age)? {
+        debug!(key, %cached_at, "store.cached_query.expired");
+        return Ok(None);
+    }
+    Ok(Some(CachedSearchQuery {
+        query: r.try_get("query")?,
+        date_from: r.try_get("date_from")?,
+        date_to: r.try_get("date_to")?,
+    }))
+}
+
+async fn cache_query(pool: &SqlitePool, key: &str, search: &CachedSearchQuery) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO query_cache (key, query, date_from, date_to, created_at)
+           VALUES (?1, ?2, ?3, ?4, ?5)
+           ON CONFLICT(key) DO UPDATE SET
+             query=excluded.query,
+             date_from=excluded.date_from,
+             date_to=excluded.date_to,
+             created_at=excluded.created_at"#,
+    )
+    .bind(key)
+    .bind(&search.query)
+    .bind(&search.date_from)
+    .bind(&search.date_to)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+    debug!(key, query = %search.query, "store.cache_query");
+    Ok(())
+}
+
+async fn dead_letter(pool: &SqlitePool, letter: &DeadLetter) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO normalization_dead_letter
+             (external_id, claim_id, payload, error, model_output, created_at)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+    )
+    .bind(&letter.external_id)
+    .bind(letter.claim_id.to_string())
+    .bind(letter.payload.to_string())
+    .bind(&letter.error)
+    .bind(&letter.model_output)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+    debug!(artifact = %letter.external_id, claim_id = %letter.claim_id, "store.dead_letter");
+    Ok(())
+}
+
+pub(crate) async fn upsert_normalized(pool: &SqlitePool, n: NormalizedArtifact) -> Result<()> {
+    // Single txn for artifact + entities (faster + atomic)
+    let mut tx = pool.begin().await?;
+    upsert_normalized_tx(&mut tx, &n).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Write `batch` in one transaction. Returns the claims that gained a relevant artifact, in
+/// first-seen order, so each can be notified once.
+async fn upsert_batch(pool: &SqlitePool, batch: Vec<NormalizedArtifact>) -> Result<Vec<Uuid>> {
+    let mut tx = pool.begin().await?;
+    let mut claims = Vec::new();
+    for n in &batch {
+        upsert_normalized_tx(&mut tx, n).await?;
+        if n.claim_relevance && !claims.contains(&n.claim_id) {
+            claims.push(n.claim_id);
+        }
+    }
+    tx.commit().await?;
+    info!(artifacts = batch.len(), "store.upsert_batch.done");
+    Ok(claims)
+}
+
+/// Write one artifact and its entities inside `tx`.
+async fn upsert_normalized_tx(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    n: &NormalizedArtifact,
+) -> Result<()> {
+    // On conflict the row keeps its original internal_id; RETURNING gives us the one stored.
+    let stored_id: String = sqlx::query_scalar(
+        r#"INSERT INTO normalized_artifact
+           (internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id, canonical_url,
+            source_url, author, captured_at, platform, html_path, screenshot_path, schema_version)
+           VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+           ON CONFLICT(external_id) DO UPDATE SET
+             schema_version=excluded.schema_version,
+             claim_relevance=excluded.claim_relevance,
+             reasoning=excluded.reasoning,
+             provenance_info=excluded.provenance_info,
+             claim_id=excluded.claim_id,
+             canonical_url=COALESCE(excluded.canonical_url, canonical_url),
+             source_url=COALESCE(excluded.source_url, source_url),
+             author=COALESCE(excluded.author, author),
+             captured_at=COALESCE(excluded.captured_at, captured_at),
+             platform=COALESCE(excluded.platform, platform),
+             html_path=COALESCE(excluded.html_path, html_path),
+             screenshot_path=COALESCE(excluded.screenshot_path, screenshot_path)
+           RETURNING internal_id"#,
+    )
+    .bind(n.internal_id.to_string())
+    .bind(n.external_id.as_str())
+    .bind(n.claim_relevance)
     .bind(n.reasoning.as_str())
     .bind(n.provenance_info.as_str())
     .bind(n.claim_id.to_string())
-    .execute(&mut *tx)
+    .bind(n.canonical_url.as_deref())
+    .bind(n.provenance.source_url.as_deref())
+    .bind(n.provenance.author.as_deref())
+    .bind(n.provenance.captured_at)
+    .bind(n.provenance.platform.as_deref())
+    .bind(n.provenance.html_path.as_deref())
+    .bind(n.provenance.screenshot_path.as_deref())
+    .bind(ARTIFACT_SCHEMA_VERSION)
+    .fetch_one(&mut **tx)
     .await?;
     info!(
-        internal_id=%n.internal_id,
+        internal_id=%stored_id,
         external_id=%n.external_id,
         claim_id=%n.claim_id,
-        rows=res_artifact.rows_affected(),
+        canonical_url=?n.canonical_url,
         "store.upsert_normalized.artifact"
     );
 
+    if let Some(url) = n.canonical_url.as_deref() {
+        let links = link_same_source(tx, &stored_id, url).await?;
+        if links > 0 {
+            info!(
+                internal_id=%stored_id,
+                canonical_url=%url,
+                links,
+                "store.upsert_normalized.linked_same_source"
+            );
+        }
+    }
+
     let mut entity_writes = 0u64;
     let entity_count = n.entities.len();
     for e in &n.entities {
@@ -382,27 +1221,32 @@ async fn upsert_normalized(pool: &SqlitePool, n: NormalizedArtifact) -> Result<(
             Credibility::Weak => "weak",
             Credibility::Unknown => "unknown",
         };
+        let canonical_id = resolve_canonical_entity(tx, &e.name).await?;
         let res_entity = sqlx::query(
-            r#"INSERT INTO entity (article_id, external_id, name, credibility, reasoning)
-               VALUES (?1, ?2, ?3, ?4, ?5)
+            r#"INSERT INTO entity (article_id, external_id, name, credibility, stance, reasoning, canonical_id)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
                ON CONFLICT(article_id, external_id) DO UPDATE SET
                  name=excluded.name,
                  credibility=excluded.credibility,
-                 reasoning=excluded.reasoning"#,
+                 stance=excluded.stance,
+                 reasoning=excluded.reasoning,
+                 canonical_id=excluded.canonical_id"#,
         )
-        .bind(e.article_id.to_string())
+        // Entities hang off the stored row, which keeps its id across re-ingests.
+        .bind(stored_id.as_str())
         .bind(e.external_id.as_str())
         .bind(e.name.as_str())
         .bind(credibility_s)
+        .bind(e.stance.as_str())
         .bind(e.reasoning.as_str())
-        .execute(&mut *tx)
+        .bind(canonical_id.as_deref())
+        .execute(&mut **tx)
         .await?;
         entity_writes += res_entity.rows_affected();
     }
 
-    tx.commit().await?;
     info!(
-        internal_id=%n.internal_id,
+        internal_id=%stored_id,
         entities=entity_count,
         rows_written=entity_writes,
         "store.upsert_normalized.entities"
@@ -410,9 +1254,86 @@ async fn upsert_normalized(pool: &SqlitePool, n: NormalizedArtifact) -> Result<(
     Ok(())
 }
 
+/// Normalized form of an entity name used to match spelling variants across artifacts:
+/// casefolded, leading `@` stripped, and only letters and digits kept, so
+/// `"Terry McLaurin"` and `"@TerryMcLaurin"` both become `"terrymclaurin"`.
+pub fn canonical_entity_key(name: &str) -> String {
+    name.trim()
+        .trim_start_matches('@')
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find or create the `canonical_entity` row for `name`. Names with no letters or digits
+/// are left unresolved.
+async fn resolve_canonical_entity(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    name: &str,
+) -> Result<Option<String>> {
+    let key = canonical_entity_key(name);
+    if key.is_empty() {
+        return Ok(None);
+    }
+    sqlx::query("INSERT OR IGNORE INTO canonical_entity (key, name) VALUES (?1, ?2)")
+        .bind(key.as_str())
+        .bind(name.trim())
+        .execute(&mut **tx)
+        .await?;
+    let id: String = sqlx::query_scalar("SELECT id FROM canonical_entity WHERE key = ?1")
+        .bind(key.as_str())
+        .fetch_one(&mut **tx)
+        .await?;
+    Ok(Some(id))
+}
+
+async fn import_jsonl(pool: &SqlitePool, path: &Path, claim: ClaimContext) -> Result<ImportCounts> {
+    let body = tokio::fs::read_to_string(path).await?;
+    let mut counts = ImportCounts::default();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("INSERT OR IGNORE INTO claim (id, text) VALUES (?1, ?2)")
+        .bind(claim.id.to_string())
+        .bind(claim.text.as_str())
+        .execute(&mut *tx)
+        .await?;
+
+    for (idx, line) in body.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut n = match serde_json::from_str::<NormalizedArtifact>(line) {
+            Ok(n) => n,
+            Err(err) => {
+                warn!(path=%path.display(), line=idx + 1, error=%err, "store.import_jsonl.skip_line");
+                counts.skipped += 1;
+                continue;
+            }
+        };
+        n.claim_id = claim.id;
+        upsert_normalized_tx(&mut tx, &n).await?;
+        counts.artifacts += 1;
+        counts.entities += n.entities.len();
+    }
+
+    tx.commit().await?;
+    info!(
+        path=%path.display(),
+        claim_id=%claim.id,
+        artifacts=counts.artifacts,
+        entities=counts.entities,
+        skipped=counts.skipped,
+        "store.import_jsonl.done"
+    );
+    Ok(counts)
+}
+
 async fn get_artifact_with_entities(pool: &SqlitePool, id: &str) -> Result<ArtifactWithEntities> {
     let a = sqlx::query(
-        r#"SELECT internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id
+        r#"SELECT internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id,
+                  source_url, author, captured_at, platform, html_path, screenshot_path,
+                  schema_version, review_status
            FROM v_artifact WHERE internal_id = ?"#,
     )
     .bind(id)
@@ -430,7 +1351,7 @@ async fn get_artifact_with_entities(pool: &SqlitePool, id: &str) -> Result<Artif
     };
 
     let rows = sqlx::query(
-        r#"SELECT id, article_id, name, credibility, reasoning
+        r#"SELECT id, article_id, canonical_id, name, credibility, stance, reasoning
            FROM v_entity WHERE article_id = ? ORDER BY created_at ASC"#,
     )
     .bind(id)
@@ -443,72 +1364,1574 @@ async fn get_artifact_with_entities(pool: &SqlitePool, id: &str) -> Result<Artif
     );
 
     Ok(ArtifactWithEntities {
-        artifact: ArtifactRow {
-            internal_id: a.try_get("internal_id")?,
-            external_id: a.try_get("external_id")?,
-            claim_relevance: a.try_get::<i64, _>("claim_relevance")? != 0,
-            reasoning: a.try_get("reasoning")?,
-            provenance_info: a.try_get("provenance_info")?,
-            claim_id: a.try_get("claim_id")?,
-        },
-        entities: rows
-            .into_iter()
-            .map(|r| EntityRow {
-                id: r.try_get("id").unwrap_or_default(),
-                article_id: r.try_get("article_id").unwrap_or_default(),
-                name: r.try_get("name").unwrap_or_default(),
-                credibility: r.try_get("credibility").unwrap_or_default(),
-                reasoning: r.try_get("reasoning").unwrap_or_default(),
-            })
-            .collect(),
+        artifact: artifact_from_row(&a)?,
+        entities: rows.into_iter().map(|r| entity_from_row(&r)).collect(),
     })
 }
 
+/// Where artifact and entity text is read from.
+#[derive(Clone, Copy)]
+enum TextSource {
+    /// `v_artifact`/`v_entity`, with reasoning and provenance cut to 2000 characters.
+    Views,
+    /// `normalized_artifact`/`entity`, text in full; for exports.
+    Tables,
+}
+
+/// Artifacts and their entities for every id in `ids` that exists, in two queries
+/// instead of two per artifact. Missing ids are left out of the map.
+async fn get_artifacts_with_entities(
+    pool: &SqlitePool,
+    ids: &[Uuid],
+    source: TextSource,
+) -> Result<HashMap<Uuid, ArtifactWithEntities>> {
+    let (artifact_table, entity_table) = match source {
+        TextSource::Views => ("v_artifact", "v_entity"),
+        TextSource::Tables => ("normalized_artifact", "entity"),
+    };
+    let ids_json = serde_json::to_string(&ids.iter().map(Uuid::to_string).collect::<Vec<_>>())?;
+    let artifacts = sqlx::query(&format!(
+        r#"SELECT internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id,
+                  source_url, author, captured_at, platform, html_path, screenshot_path,
+                  schema_version, review_status
+           FROM {artifact_table}
+           WHERE internal_id IN (SELECT value FROM json_each(?1))"#
+    ))
+    .bind(&ids_json)
+    .fetch_all(pool)
+    .await?;
+    let entity_rows = sqlx::query(&format!(
+        r#"SELECT id, article_id, canonical_id, name, credibility, stance, reasoning
+           FROM {entity_table}
+           WHERE article_id IN (SELECT value FROM json_each(?1))
+           ORDER BY created_at ASC"#
+    ))
+    .bind(&ids_json)
+    .fetch_all(pool)
+    .await?;
+
+    let mut by_artifact: HashMap<String, Vec<EntityRow>> = HashMap::new();
+    for row in &entity_rows {
+        let entity = entity_from_row(row);
+        by_artifact
+            .entry(entity.article_id.clone())
+            .or_default()
+            .push(entity);
+    }
+    let mut out = HashMap::with_capacity(artifacts.len());
+    for row in &artifacts {
+        let artifact = artifact_from_row(row)?;
+        let id = Uuid::parse_str(&artifact.internal_id)?;
+        let entities = by_artifact
+            .remove(&artifact.internal_id)
+            .unwrap_or_default();
+        out.insert(id, ArtifactWithEntities { artifact, entities });
+    }
+    info!(
+        requested = ids.len(),
+        found = out.len(),
+        entity_count = entity_rows.len(),
+        "store.get_artifacts"
+    );
+    Ok(out)
+}
+
+fn artifact_from_row(r: &SqliteRow) -> Result<ArtifactRow> {
+    Ok(ArtifactRow {
+        internal_id: r.try_get("internal_id")?,
+        external_id: r.try_get("external_id")?,
+        claim_relevance: r.try_get::<i64, _>("claim_relevance")? != 0,
+        reasoning: r.try_get("reasoning")?,
+        provenance_info: r.try_get("provenance_info")?,
+        claim_id: r.try_get("claim_id")?,
+        provenance: provenance_from_row(r),
+        review_status: review_from_row(r),
+    })
+}
+
+fn entity_from_row(r: &SqliteRow) -> EntityRow {
+    EntityRow {
+        id: r.try_get("id").unwrap_or_default(),
+        article_id: r.try_get("article_id").unwrap_or_default(),
+        canonical_id: r.try_get("canonical_id").unwrap_or_default(),
+        name: r.try_get("name").unwrap_or_default(),
+        credibility: r.try_get("credibility").unwrap_or_default(),
+        stance: r.try_get("stance").unwrap_or_else(|_| "neutral".into()),
+        reasoning: r.try_get("reasoning").unwrap_or_default(),
+    }
+}
+
+/// Entities stored as `name`, plus spelling variants resolved to the same canonical entity.
 async fn list_entities_by_name(
     pool: &SqlitePool,
     name: &str,
     limit: i64,
 ) -> Result<Vec<EntityRow>> {
     let rows = sqlx::query(
-        r#"SELECT id, article_id, name, credibility, reasoning
-           FROM v_entity WHERE name = ? ORDER BY created_at DESC LIMIT ?"#,
+        r#"SELECT id, article_id, canonical_id, name, credibility, stance, reasoning
+           FROM v_entity
+           WHERE name = ?1
+              OR canonical_id = (SELECT id FROM canonical_entity WHERE key = ?2)
+           ORDER BY created_at DESC LIMIT ?3"#,
     )
     .bind(name)
+    .bind(canonical_entity_key(name))
     .bind(limit)
     .fetch_all(pool)
     .await?;
 
+    Ok(rows.into_iter().map(|r| entity_from_row(&r)).collect())
+}
+
+pub(crate) const SAME_SOURCE_PRODUCER: &str = "store:canonical_url";
+
+/// Cross-link `internal_id` with every other artifact already stored under `canonical_url`.
+///
+/// Both rows are kept (a tweet's commentary and the article it links carry different
+/// evidence); the pair is joined by a `same_event` edge in `graph_edge`. Returns the number of
+/// new edges.
+async fn link_same_source(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    internal_id: &str,
+    canonical_url: &str,
+) -> Result<u64> {
+    let others: Vec<String> = sqlx::query_scalar(
+        r#"SELECT internal_id FROM normalized_artifact
+           WHERE canonical_url = ?1 AND internal_id != ?2"#,
+    )
+    .bind(canonical_url)
+    .bind(internal_id)
+    .fetch_all(&mut **tx)
+    .await?;
+
+    let mut written = 0u64;
+    for other in others {
+        // Order the pair so re-ingesting either side maps onto the same edge.
+        let (src, dst) = if internal_id < other.as_str() {
+            (internal_id, other.as_str())
+        } else {
+            (other.as_str(), internal_id)
+        };
+        let edge_id = Uuid::new_v5(
+            &Uuid::NAMESPACE_URL,
+            format!("{src}|{dst}|same_event|{SAME_SOURCE_PRODUCER}").as_bytes(),
+        );
+        let res = sqlx::query(
+            r#"INSERT OR IGNORE INTO graph_edge
+               (id, src_id, dst_id, relation, confidence, rationale, produced_by)
+               VALUES (?1, ?2, ?3, 'same_event', 1.0, ?4, ?5)"#,
+        )
+        .bind(edge_id.to_string())
+        .bind(src)
+        .bind(dst)
+        .bind(format!("shared canonical url {canonical_url}"))
+        .bind(SAME_SOURCE_PRODUCER)
+        .execute(&mut **tx)
+        .await?;
+        written += res.rows_affected();
+    }
+    Ok(written)
+}
+
+/// Repopulate `fts_artifact` from its content table; returns the number of rows indexed.
+async fn rebuild_fts(pool: &SqlitePool) -> Result<usize> {
+    sqlx::query("INSERT INTO fts_artifact(fts_artifact) VALUES('rebuild')")
+        .execute(pool)
+        .await?;
+    let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM normalized_artifact")
+        .fetch_one(pool)
+        .await?;
+    info!(rows, "store.rebuild_fts");
+    Ok(rows as usize)
+}
+
+async fn count_artifacts(pool: &SqlitePool, claim: Uuid) -> Result<usize> {
+    let count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM normalized_artifact WHERE claim_id = ?")
+            .bind(claim.to_string())
+            .fetch_one(pool)
+            .await?;
+    Ok(count as usize)
+}
+
+/// Per-platform artifact and entity-credibility counts for a claim's relevant artifacts,
+/// largest platform first.
+async fn source_breakdown(pool: &SqlitePool, claim: Uuid) -> Result<Vec<PlatformSources>> {
+    let rows = sqlx::query(
+        r#"SELECT COALESCE(a.platform, 'unknown') AS platform,
+                  COUNT(DISTINCT a.internal_id) AS artifacts,
+                  COALESCE(SUM(e.credibility = 'strong'), 0) AS strong,
+                  COALESCE(SUM(e.credibility = 'weak'), 0) AS weak,
+                  COALESCE(SUM(e.credibility = 'unknown'), 0) AS unknown
+           FROM normalized_artifact a
+           LEFT JOIN entity e ON e.article_id = a.internal_id
+           WHERE a.claim_id = ? AND a.claim_relevance = 1
+           GROUP BY 1
+           ORDER BY artifacts DESC, platform ASC"#,
+    )
+    .bind(claim.to_string())
+    .fetch_all(pool)
+    .await?;
+    let count = |r: &SqliteRow, col: &str| r.try_get::<i64, _>(col).unwrap_or(0) as usize;
+    Ok(rows
+        .iter()
+        .map(|r| PlatformSources {
+            platform: r.try_get("platform").unwrap_or_default(),
+            artifacts: count(r, "artifacts"),
+            strong: count(r, "strong"),
+            weak: count(r, "weak"),
+            unknown: count(r, "unknown"),
+        })
+        .collect())
+}
+
+/// Every artifact of `claim` with its entities, oldest first, with their text in full.
+async fn claim_artifacts(pool: &SqlitePool, claim: Uuid) -> Result<Vec<ArtifactWithEntities>> {
+    let ids: Vec<String> = sqlx::query_scalar(
+        "SELECT internal_id FROM normalized_artifact WHERE claim_id = ?1 ORDER BY created_at, internal_id",
+    )
+    .bind(claim.to_string())
+    .fetch_all(pool)
+    .await?;
+    let ids = ids
+        .iter()
+        .map(|id| Uuid::parse_str(id))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let mut found = get_artifacts_with_entities(pool, &ids, TextSource::Tables).await?;
+    Ok(ids.iter().filter_map(|id| found.remove(id)).collect())
+}
+
+/// Canonical entities and URLs that relevant artifacts of both `a` and `b` carry, sorted.
+/// Entities stored before entity resolution (no `canonical_id`) are not matched.
+async fn claim_overlap(pool: &SqlitePool, a: Uuid, b: Uuid) -> Result<EvidenceOverlap> {
+    let entities = sqlx::query_scalar(
+        r#"SELECT ce.name
+           FROM canonical_entity ce
+           JOIN entity e ON e.canonical_id = ce.id
+           JOIN normalized_artifact a ON a.internal_id = e.article_id AND a.claim_relevance = 1
+           WHERE a.claim_id IN (?1, ?2)
+           GROUP BY ce.id, ce.name
+           HAVING COUNT(DISTINCT a.claim_id) = 2
+           ORDER BY ce.name"#,
+    )
+    .bind(a.to_string())
+    .bind(b.to_string())
+    .fetch_all(pool)
+    .await?;
+    let sources = sqlx::query_scalar(
+        r#"SELECT DISTINCT a.canonical_url
+           FROM normalized_artifact a
+           JOIN normalized_artifact b ON b.canonical_url = a.canonical_url
+           WHERE a.claim_id = ?1 AND a.claim_relevance = 1
+             AND b.claim_id = ?2 AND b.claim_relevance = 1
+           ORDER BY 1"#,
+    )
+    .bind(a.to_string())
+    .bind(b.to_string())
+    .fetch_all(pool)
+    .await?;
+    Ok(EvidenceOverlap { entities, sources })
+}
+
+async fn list_linked_artifacts(pool: &SqlitePool, id: &str) -> Result<Vec<ArtifactRow>> {
+    let rows = sqlx::query(
+        r#"SELECT a.internal_id, a.external_id, a.claim_relevance, a.reasoning,
+                  a.provenance_info, a.claim_id,
+                  a.source_url, a.author, a.captured_at, a.platform,
+                  a.html_path, a.screenshot_path, a.schema_version, a.review_status
+           FROM graph_edge ge
+           JOIN v_artifact a
+             ON a.internal_id = CASE WHEN ge.src_id = ?1 THEN ge.dst_id ELSE ge.src_id END
+           WHERE ge.relation = 'same_event'
+             AND ge.produced_by = ?2
+             AND (ge.src_id = ?1 OR ge.dst_id = ?1)
+           ORDER BY a.created_at ASC"#,
+    )
+    .bind(id)
+    .bind(SAME_SOURCE_PRODUCER)
+    .fetch_all(pool)
+    .await?;
+
     Ok(rows
         .into_iter()
-        .map(|r| EntityRow {
-            id: r.try_get("id").unwrap_or_default(),
-            article_id: r.try_get("article_id").unwrap_or_default(),
-            name: r.try_get("name").unwrap_or_default(),
-            credibility: r.try_get("credibility").unwrap_or_default(),
-            reasoning: r.try_get("reasoning").unwrap_or_default(),
+        .map(|r| ArtifactRow {
+            internal_id: r.try_get::<String, _>("internal_id").unwrap_or_default(),
+            external_id: r.try_get::<String, _>("external_id").unwrap_or_default(),
+            claim_relevance: r.try_get::<i64, _>("claim_relevance").unwrap_or(0) != 0,
+            reasoning: r.try_get::<String, _>("reasoning").unwrap_or_default(),
+            provenance_info: r
+                .try_get::<String, _>("provenance_info")
+                .unwrap_or_default(),
+            claim_id: r.try_get::<Option<String>, _>("claim_id").unwrap_or(None),
+            provenance: provenance_from_row(&r),
+            review_status: review_from_row(&r),
         })
         .collect())
 }
 
-fn sanitize_fts_query(raw: &str) -> Option<String> {
-    let tokens: Vec<String> = raw
-        .split_whitespace()
-        .filter_map(|word| {
-            let cleaned: String = word
-                .chars()
-                .filter(|c| c.is_ascii_alphanumeric() || *c == '_')
-                .collect();
-            if cleaned.is_empty() {
-                None
-            } else {
-                Some(cleaned.to_ascii_lowercase())
+/// Build an FTS5 `MATCH` expression from free text, or `None` if nothing searchable is left.
+///
+/// Every term is emitted as a quoted FTS string so characters FTS5 treats as syntax
+/// (`$`, `-`, `.`) cannot break the query; the table's tokenizer then splits the term the
+/// same way it split the indexed text, so `"$500m"` and `"jean-luc"` still match. At most
+/// `opts.max_terms` words are kept; a phrase that straddles the cap is cut short.
+pub(crate) fn sanitize_fts_query(raw: &str, opts: &FtsQueryOptions) -> Option<String> {
+    let parts: Vec<&str> = raw.split('"').collect();
+    // An unmatched trailing quote leaves its text as ordinary words.
+    let balanced = parts.len() % 2 == 1;
+
+    let mut terms = Vec::new();
+    let (mut kept, mut dropped) = (0, 0);
+    for (i, part) in parts.iter().enumerate() {
+        let is_phrase = opts.keep_phrases && i % 2 == 1 && (balanced || i + 1 < parts.len());
+        let mut words: Vec<String> = part.split_whitespace().filter_map(fts_token).collect();
+        if !is_phrase {
+            words.retain(|w| !opts.stopwords.contains(w));
+        }
+        let room = opts.max_terms - kept;
+        if words.len() > room {
+            dropped += words.len() - room;
+            words.truncate(room);
+        }
+        kept += words.len();
+        if is_phrase {
+            if !words.is_empty() {
+                terms.push(format!("\"{}\"", words.join(" ")));
             }
-        })
-        .collect();
+        } else {
+            terms.extend(words.into_iter().map(|w| format!("\"{w}\"")));
+        }
+    }
+    if dropped > 0 {
+        tracing::info!(kept, dropped, "store.fts_query.truncated");
+    }
 
-    if tokens.is_empty() {
+    if terms.is_empty() {
         None
     } else {
-        Some(tokens.join(" "))
+        Some(terms.join(" "))
+    }
+}
+
+/// Lowercase `word`, keeping punctuation that carries meaning inside it: joiners between
+/// letters or digits (`jean-luc`, `3.5`, `1,000`), a leading currency sign and a trailing `%`.
+fn fts_token(word: &str) -> Option<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let prev_alnum = i > 0 && chars[i - 1].is_alphanumeric();
+        let next_alnum = chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+        let keep = c.is_alphanumeric()
+            || c == '_'
+            || (matches!(c, '-' | '.' | ',' | '\'' | '/') && prev_alnum && next_alnum)
+            || (matches!(c, '$' | '€' | '£' | '¥') && next_alnum)
+            || (c == '%' && prev_alnum);
+        if keep {
+            out.extend(c.to_lowercase());
+        }
+    }
+    out.chars().any(char::is_alphanumeric).then_some(out)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::actor::{context, probe, spawn_actor, Addr};
+    use crate::{Entity, Stance};
+    use nowhere_common::slug::{SHORT_LEN, SLUG_LEN};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    const MIGRATIONS: &[&str] = &[
+        include_str!("../../migrations/01_init.sql"),
+        include_str!("../../migrations/02_entity_stance.sql"),
+        include_str!("../../migrations/03_canonical_url.sql"),
+        include_str!("../../migrations/04_provenance.sql"),
+        include_str!("../../migrations/05_capture.sql"),
+        include_str!("../../migrations/06_claim_tags.sql"),
+        include_str!("../../migrations/07_canonical_entity.sql"),
+        include_str!("../../migrations/08_claim_search.sql"),
+        include_str!("../../migrations/09_query_cache.sql"),
+        include_str!("../../migrations/10_artifact_schema_version.sql"),
+        include_str!("../../migrations/11_review_status.sql"),
+        include_str!("../../migrations/12_normalization_dead_letter.sql"),
+        include_str!("../../migrations/13_query_cache_model_dates.sql"),
+    ];
+
+    // A single connection keeps every query on the same in-memory database.
+    pub(crate) async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("in-memory sqlite");
+        for sql in MIGRATIONS {
+            sqlx::raw_sql(sql).execute(&pool).await.expect("migration");
+        }
+        pool
+    }
+
+    /// A running `StoreActor` over a migrated in-memory database, plus the pool for
+    /// checking what it wrote.
+    pub(crate) async fn test_store() -> (Addr<StoreActor>, SqlitePool) {
+        let pool = test_pool().await;
+        let store = spawn_actor(StoreActor::new(pool.clone()), 64).addr;
+        (store, pool)
+    }
+
+    pub(crate) fn claim(text: &str) -> ClaimContext {
+        ClaimContext {
+            id: Uuid::new_v4(),
+            text: text.to_string(),
+            tags: Vec::new(),
+        }
+    }
+
+    pub(crate) fn artifact(
+        claim: &ClaimContext,
+        external_id: &str,
+        entities: Vec<Entity>,
+    ) -> NormalizedArtifact {
+        let internal_id = Uuid::new_v4();
+        NormalizedArtifact {
+            external_id: external_id.to_string(),
+            internal_id,
+            claim_id: claim.id,
+            claim_relevance: true,
+            reasoning: format!("reasoning for {external_id}"),
+            provenance_info: "twitter".to_string(),
+            canonical_url: None,
+            provenance: Provenance::default(),
+            entities: entities
+                .into_iter()
+                .map(|mut e| {
+                    e.article_id = internal_id;
+                    e
+                })
+                .collect(),
+        }
+    }
+
+    pub(crate) fn entity(name: &str, stance: Stance) -> Entity {
+        Entity {
+            article_id: Uuid::nil(),
+            external_id: format!("ent:{name}"),
+            name: name.to_string(),
+            credibility: Credibility::Strong,
+            stance,
+            reasoning: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn search_text_is_cut_to_the_configured_length() {
+        let pool = test_pool().await;
+        let c = claim("the ferry sank");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let mut a = artifact(&c, "tw:1", vec![]);
+        a.reasoning = "ferry café report ".repeat(200);
+        a.provenance_info = "é".repeat(3000);
+        upsert_normalized(&pool, a).await.unwrap();
+
+        let search = |max_text_chars, query: &'static str| {
+            let pool = pool.clone();
+            let opts = FtsQueryOptions {
+                max_text_chars,
+                ..FtsQueryOptions::default()
+            };
+            async move {
+                search_artifacts_fts(&pool, query, c.id, 5, &opts)
+                    .await
+                    .unwrap()
+                    .remove(0)
+            }
+        };
+
+        // FTS match and the recent-artifacts fallback both honor the limit, in characters.
+        for query in ["ferry", ""] {
+            let hit = search(Some(40), query).await;
+            assert_eq!(hit.reasoning.chars().count(), 40, "query {query:?}");
+            assert_eq!(hit.provenance_info, "é".repeat(40));
+        }
+        let hit = search(None, "ferry").await;
+        assert_eq!(hit.reasoning.chars().count(), 18 * 200);
+        assert_eq!(hit.provenance_info.chars().count(), 3000);
+        let hit = search(Some(DEFAULT_SEARCH_TEXT_CHARS), "ferry").await;
+        assert_eq!(
+            hit.provenance_info.chars().count(),
+            DEFAULT_SEARCH_TEXT_CHARS
+        );
+    }
+
+    #[tokio::test]
+    async fn get_artifacts_hydrates_several_in_one_call() {
+        let (store, pool) = test_store().await;
+        let c = claim("the bridge is closed");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let stored = [
+            artifact(
+                &c,
+                "tw:1",
+                vec![
+                    entity("Roads Dept", Stance::Supports),
+                    entity("Mayor", Stance::Refutes),
+                ],
+            ),
+            artifact(&c, "tw:2", vec![entity("Local News", Stance::Neutral)]),
+            artifact(&c, "tw:3", Vec::new()),
+        ];
+        let mut expected = Vec::new();
+        for a in stored {
+            let mut names: Vec<String> = a.entities.iter().map(|e| e.name.clone()).collect();
+            names.sort();
+            expected.push((a.internal_id, a.external_id.clone(), names));
+            upsert_normalized(&pool, a).await.unwrap();
+        }
+
+        let mut ids: Vec<Uuid> = expected.iter().map(|(id, ..)| *id).collect();
+        ids.push(Uuid::new_v4()); // not stored
+        let (reply, rx) = oneshot::channel();
+        store
+            .send(StoreMsg::GetArtifacts { ids, reply })
+            .await
+            .ok()
+            .unwrap();
+        let found = rx.await.unwrap().unwrap();
+
+        assert_eq!(found.len(), 3);
+        for (id, external_id, names) in expected {
+            let bundle = &found[&id];
+            assert_eq!(bundle.artifact.external_id, external_id);
+            let mut got: Vec<String> = bundle.entities.iter().map(|e| e.name.clone()).collect();
+            got.sort();
+            assert_eq!(got, names, "entities of {external_id}");
+            assert!(bundle
+                .entities
+                .iter()
+                .all(|e| e.article_id == id.to_string()));
+        }
+    }
+
+    #[tokio::test]
+    async fn claim_artifacts_keep_text_the_views_cut() {
+        let (store, pool) = test_store().await;
+        let c = claim("the dam burst");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let mut source = entity("Water Board", Stance::Supports);
+        source.reasoning = "w".repeat(2500);
+        let mut a = artifact(&c, "tw:1", vec![source]);
+        a.reasoning = "r".repeat(2500);
+        a.provenance_info = "p".repeat(2500);
+        let id = a.internal_id;
+        upsert_normalized(&pool, a).await.unwrap();
+
+        let (reply, rx) = oneshot::channel();
+        store
+            .send(StoreMsg::ClaimArtifacts { claim: c.id, reply })
+            .await
+            .ok()
+            .unwrap();
+        let exported = rx.await.unwrap().unwrap();
+        assert_eq!(exported[0].artifact.reasoning.len(), 2500);
+        assert_eq!(exported[0].artifact.provenance_info.len(), 2500);
+        assert_eq!(exported[0].entities[0].reasoning.len(), 2500);
+
+        let (reply, rx) = oneshot::channel();
+        store
+            .send(StoreMsg::GetArtifacts {
+                ids: vec![id],
+                reply,
+            })
+            .await
+            .ok()
+            .unwrap();
+        let hydrated = rx.await.unwrap().unwrap();
+        assert_eq!(hydrated[&id].artifact.reasoning.len(), 2000);
+        assert_eq!(hydrated[&id].entities[0].reasoning.len(), 2000);
+    }
+
+    #[tokio::test]
+    async fn batch_upsert_writes_once_and_notifies_once() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let mut store = StoreActor::new(pool.clone());
+        let (me, mut sent) = probe::<StoreActor>(8);
+        let mut ctx = context(me);
+
+        let batch = (1..=3)
+            .map(|i| {
+                let name = format!("Source {i}");
+                artifact(
+                    &c,
+                    &format!("tw:{i}"),
+                    vec![entity(&name, Stance::Supports)],
+                )
+            })
+            .collect();
+        store
+            .handle(StoreMsg::UpsertBatch(batch, None), &mut ctx)
+            .await
+            .unwrap();
+
+        let note = tokio::time::timeout(std::time::Duration::from_secs(2), sent.recv())
+            .await
+            .expect("notification in time")
+            .expect("mailbox open");
+        assert!(matches!(note, StoreMsg::ArtifactUpserted { claim } if claim == c.id));
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(sent.try_recv().is_err(), "one notification per batch");
+
+        let artifacts: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM normalized_artifact WHERE claim_id = ?")
+                .bind(c.id.to_string())
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let entities: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM entity")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!((artifacts, entities), (3, 3));
+    }
+
+    #[tokio::test]
+    async fn abandoned_watchers_are_pruned_when_artifacts_arrive() {
+        let pool = test_pool().await;
+        let (abandoned, watched, active) = (claim("old"), claim("other"), claim("new"));
+        insert_claim(&pool, active.clone()).await.unwrap();
+        let mut store = StoreActor::new(pool);
+        let (me, mut sent) = probe::<StoreActor>(8);
+        let mut ctx = context(me);
+        let watch = |claim: Uuid| {
+            let (reply, rx) = oneshot::channel();
+            (StoreMsg::WatchArtifacts { claim, reply }, rx)
+        };
+
+        let (msg, gone) = watch(abandoned.id);
+        store.handle(msg, &mut ctx).await.unwrap();
+        let (msg, _kept) = watch(watched.id);
+        store.handle(msg, &mut ctx).await.unwrap();
+        drop(gone);
+
+        // An artifact for a third claim, notified back through the mailbox.
+        let upsert = StoreMsg::UpsertArtifact(Box::new(artifact(&active, "tw:1", vec![])), None);
+        store.handle(upsert, &mut ctx).await.unwrap();
+        let note = tokio::time::timeout(std::time::Duration::from_secs(2), sent.recv())
+            .await
+            .expect("notification in time")
+            .expect("mailbox open");
+        store.handle(note, &mut ctx).await.unwrap();
+
+        assert!(!store.watchers.contains_key(&abandoned.id));
+        assert_eq!(store.watchers[&watched.id].len(), 1, "live watchers stay");
+    }
+
+    #[tokio::test]
+    async fn entity_stance_round_trips() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let a = artifact(&c, "tw:1", vec![entity("Jane Doe", Stance::Supports)]);
+        let id = a.internal_id.to_string();
+        upsert_normalized(&pool, a).await.unwrap();
+
+        let bundle = get_artifact_with_entities(&pool, &id).await.unwrap();
+        assert_eq!(bundle.entities.len(), 1);
+        assert_eq!(bundle.entities[0].stance, "supports");
+
+        let by_name = list_entities_by_name(&pool, "Jane Doe", 10).await.unwrap();
+        assert_eq!(by_name[0].stance, "supports");
+    }
+
+    #[tokio::test]
+    async fn name_variants_resolve_to_one_canonical_entity() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let first = artifact(&c, "tw:1", vec![entity("Terry McLaurin", Stance::Supports)]);
+        let second = artifact(&c, "tw:2", vec![entity("@TerryMcLaurin", Stance::Neutral)]);
+        upsert_normalized(&pool, first).await.unwrap();
+        upsert_normalized(&pool, second).await.unwrap();
+
+        let canonical: Vec<(String, String)> =
+            sqlx::query_as("SELECT key, name FROM canonical_entity")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            canonical,
+            vec![("terrymclaurin".to_string(), "Terry McLaurin".to_string())]
+        );
+
+        let by_name = list_entities_by_name(&pool, "@TerryMcLaurin", 10)
+            .await
+            .unwrap();
+        assert_eq!(by_name.len(), 2, "both spellings are listed");
+        assert!(by_name[0].canonical_id.is_some());
+        assert_eq!(by_name[0].canonical_id, by_name[1].canonical_id);
+    }
+
+    #[tokio::test]
+    async fn review_status_can_be_set_and_changed() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let a = artifact(&c, "tw:1", vec![]);
+        let id = a.internal_id;
+        upsert_normalized(&pool, a).await.unwrap();
+        let status = |pool: SqlitePool| async move {
+            get_artifact_with_entities(&pool, &id.to_string())
+                .await
+                .unwrap()
+                .artifact
+                .review_status
+        };
+        assert_eq!(status(pool.clone()).await, ReviewStatus::Pending);
+
+        sqlx::query("UPDATE normalized_artifact SET updated_at = '2000-01-01T00:00:00.000Z'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        set_review(&pool, id, ReviewStatus::Confirmed)
+            .await
+            .unwrap();
+        assert_eq!(status(pool.clone()).await, ReviewStatus::Confirmed);
+        let updated: String =
+            sqlx::query_scalar("SELECT updated_at FROM normalized_artifact WHERE internal_id = ?1")
+                .bind(id.to_string())
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(updated.as_str() > "2000-01-01T00:00:00.000Z", "{updated}");
+        set_review(&pool, id, ReviewStatus::Dismissed)
+            .await
+            .unwrap();
+        assert_eq!(status(pool.clone()).await, ReviewStatus::Dismissed);
+
+        // Re-normalizing the artifact keeps the investigator's verdict.
+        upsert_normalized(&pool, artifact(&c, "tw:1", vec![]))
+            .await
+            .unwrap();
+        assert_eq!(status(pool.clone()).await, ReviewStatus::Dismissed);
+
+        let missing = set_review(&pool, Uuid::new_v4(), ReviewStatus::Confirmed).await;
+        assert!(missing.unwrap_err().to_string().contains("not found"));
+
+        // Serde goes through the same lenient parse as stored labels.
+        let parsed: Vec<ReviewStatus> =
+            serde_json::from_str(r#"["Confirmed", "dismissed", "later"]"#).unwrap();
+        assert_eq!(
+            parsed,
+            [
+                ReviewStatus::Confirmed,
+                ReviewStatus::Dismissed,
+                ReviewStatus::Pending
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn retrieval_skips_dismissed_and_prefers_confirmed_artifacts() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let mut ids = HashMap::new();
+        for external_id in ["tw:pending", "tw:confirmed", "tw:dismissed"] {
+            let a = artifact(&c, external_id, vec![]);
+            ids.insert(external_id, a.internal_id);
+            upsert_normalized(&pool, a).await.unwrap();
+        }
+        set_review(&pool, ids["tw:confirmed"], ReviewStatus::Confirmed)
+            .await
+            .unwrap();
+        set_review(&pool, ids["tw:dismissed"], ReviewStatus::Dismissed)
+            .await
+            .unwrap();
+
+        // Matching terms, then nothing searchable (the fallback query).
+        for q in ["reasoning", "?"] {
+            let found = search_artifacts_fts(&pool, q, c.id, 10, &FtsQueryOptions::default())
+                .await
+                .unwrap();
+            let found: Vec<_> = found.iter().map(|a| a.external_id.as_str()).collect();
+            assert_eq!(found, ["tw:confirmed", "tw:pending"], "query {q:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn structured_provenance_round_trips() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+
+        let provenance = Provenance {
+            source_url: Some("https://x.com/i/web/status/1".into()),
+            author: Some("citydot".into()),
+            captured_at: Some("2025-03-01T12:30:00Z".parse().unwrap()),
+            platform: Some("twitter".into()),
+            html_path: None,
+            screenshot_path: None,
+        };
+        let mut a = artifact(&c, "tw:1", vec![]);
+        a.provenance = provenance.clone();
+        let id = a.internal_id.to_string();
+        upsert_normalized(&pool, a).await.unwrap();
+
+        let bundle = get_artifact_with_entities(&pool, &id).await.unwrap();
+        assert_eq!(bundle.artifact.provenance, provenance);
+        let found = search_artifacts_fts(&pool, "reasoning", c.id, 5, &FtsQueryOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(found[0].provenance, provenance);
+
+        // A re-normalization without provenance keeps what was stored.
+        upsert_normalized(&pool, artifact(&c, "tw:1", vec![]))
+            .await
+            .unwrap();
+        let bundle = get_artifact_with_entities(&pool, &id).await.unwrap();
+        assert_eq!(bundle.artifact.provenance, provenance);
+    }
+
+    #[tokio::test]
+    async fn version_1_rows_read_with_current_defaults() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+
+        // Written the way the first schema did: no provenance, no stance.
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO normalized_artifact
+               (internal_id, external_id, claim_relevance, reasoning, provenance_info, claim_id, schema_version)
+             VALUES (?1, '1850000000000000000', 1, 'old reasoning', 'tweet by @citydot', ?2, 1)",
+        )
+        .bind(&id)
+        .bind(c.id.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO entity (article_id, external_id, name, credibility)
+             VALUES (?1, '', 'City DOT', 'strong')",
+        )
+        .bind(&id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let old = get_artifact_with_entities(&pool, &id).await.unwrap();
+        assert_eq!(old.artifact.provenance_info, "tweet by @citydot");
+        let p = &old.artifact.provenance;
+        assert_eq!(p.platform.as_deref(), Some("twitter"));
+        assert_eq!(
+            p.source_url.as_deref(),
+            Some("https://x.com/i/web/status/1850000000000000000")
+        );
+        assert_eq!(p.author, None, "nothing to derive it from");
+        assert_eq!(old.entities[0].stance, "neutral");
+        let found = search_artifacts_fts(&pool, "reasoning", c.id, 5, &FtsQueryOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(&found[0].provenance, p);
+
+        // Current rows are taken as stored.
+        let current = artifact(&c, "1850000000000000001", vec![]);
+        let current_id = current.internal_id.to_string();
+        upsert_normalized(&pool, current).await.unwrap();
+        let stored = get_artifact_with_entities(&pool, &current_id)
+            .await
+            .unwrap();
+        assert_eq!(stored.artifact.provenance, Provenance::default());
+    }
+
+    #[tokio::test]
+    async fn captured_page_paths_survive_a_recapture_without_them() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+
+        let mut page = artifact(&c, "https://news.example/story", vec![]);
+        page.provenance.platform = Some("web".into());
+        page.provenance.html_path = Some("captures/story.html".into());
+        page.provenance.screenshot_path = Some("captures/story.png".into());
+        let expected = page.provenance.clone();
+        let id = page.internal_id.to_string();
+        upsert_normalized(&pool, page).await.unwrap();
+        upsert_normalized(&pool, artifact(&c, "https://news.example/story", vec![]))
+            .await
+            .unwrap();
+
+        let bundle = get_artifact_with_entities(&pool, &id).await.unwrap();
+        assert_eq!(bundle.artifact.provenance, expected);
+    }
+
+    #[tokio::test]
+    async fn retention_removes_only_artifacts_older_than_the_ttl() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let capture = std::env::temp_dir().join(format!("nowhere-capture-{}.html", Uuid::new_v4()));
+        std::fs::write(&capture, "<html></html>").unwrap();
+        let mut old = artifact(&c, "tw:old", vec![entity("Mayor", Stance::Supports)]);
+        old.provenance.html_path = Some(capture.to_string_lossy().into_owned());
+        let fresh = artifact(&c, "tw:fresh", vec![entity("Council", Stance::Refutes)]);
+        let old_id = old.internal_id;
+        for a in [old, fresh] {
+            upsert_normalized(&pool, a).await.unwrap();
+        }
+        sqlx::query(
+            "UPDATE normalized_artifact SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now', '-31 days') WHERE external_id = 'tw:old'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(purge_expired_artifacts(&pool, 30).await.unwrap(), 1);
+
+        let remaining: Vec<String> =
+            sqlx::query_scalar("SELECT external_id FROM normalized_artifact")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(remaining, ["tw:fresh"]);
+        let orphans: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM entity WHERE article_id = ?1")
+            .bind(old_id.to_string())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(orphans, 0);
+        assert!(!capture.exists());
+        assert_eq!(purge_expired_artifacts(&pool, 30).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn rebuilt_fts_finds_rows_written_around_the_triggers() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        upsert_normalized(&pool, artifact(&c, "tw:indexed", vec![]))
+            .await
+            .unwrap();
+        // A manual import that skipped the sync trigger.
+        sqlx::raw_sql("DROP TRIGGER trg_artifact_fts_ai")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO normalized_artifact (internal_id, external_id, claim_relevance, reasoning, claim_id) VALUES (?1, 'tw:imported', 1, 'ferry timetable', ?2)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(c.id.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+        let indexed = |q: &'static str| {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM fts_artifact WHERE fts_artifact MATCH ?1",
+            )
+            .bind(q)
+            .fetch_one(&pool)
+        };
+        assert_eq!(indexed("ferry").await.unwrap(), 0);
+
+        assert_eq!(rebuild_fts(&pool).await.unwrap(), 2);
+
+        assert_eq!(indexed("ferry").await.unwrap(), 1);
+        // An FTS hit, not the recent-rows fallback that would list both artifacts.
+        let found = search_artifacts_fts(&pool, "ferry", c.id, 10, &FtsQueryOptions::default())
+            .await
+            .unwrap();
+        let ids: Vec<_> = found.iter().map(|r| r.external_id.as_str()).collect();
+        assert_eq!(ids, ["tw:imported"]);
+    }
+
+    #[tokio::test]
+    async fn recorded_searches_round_trip_and_the_latest_wins() {
+        let (store, _pool) = test_store().await;
+        let bridge = claim("the bridge is closed");
+        let last = |claim: Uuid| {
+            let store = store.clone();
+            async move {
+                let (tx, rx) = oneshot::channel();
+                store
+                    .send(StoreMsg::LastSearch { claim, reply: tx })
+                    .await
+                    .ok()
+                    .unwrap();
+                rx.await.unwrap().unwrap()
+            }
+        };
+        assert_eq!(last(bridge.id).await, None);
+
+        let at = |s: &str| s.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        let first = RecordedSearch {
+            query: "bridge closed lang:en".into(),
+            date_from: at("2024-03-01T00:00:00Z"),
+            date_to: at("2024-03-31T23:59:59.250Z"),
+        };
+        let second = RecordedSearch {
+            query: "bridge closure springfield".into(),
+            ..first.clone()
+        };
+        // Writes are spawned; wait for each to land so the order is the send order.
+        for search in [first, second] {
+            store
+                .send(StoreMsg::RecordSearch {
+                    claim: bridge.id,
+                    search: search.clone(),
+                })
+                .await
+                .ok()
+                .unwrap();
+            let mut found = None;
+            for _ in 0..50 {
+                found = last(bridge.id).await;
+                if found.as_ref() == Some(&search) {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            assert_eq!(found, Some(search));
+        }
+        assert_eq!(last(Uuid::new_v4()).await, None);
+    }
+
+    #[tokio::test]
+    async fn tags_are_added_once_and_filter_claim_listings() {
+        let pool = test_pool().await;
+        let bridge = ClaimContext {
+            tags: vec!["transport".into()],
+            ..claim("the bridge is closed")
+        };
+        let ballot = claim("ballots were shredded");
+        let untagged = claim("the moon is cheese");
+        for c in [&bridge, &ballot, &untagged] {
+            insert_claim(&pool, c.clone()).await.unwrap();
+        }
+
+        assert_eq!(
+            tag_claim(&pool, bridge.id, "springfield").await.unwrap(),
+            ["transport", "springfield"]
+        );
+        assert_eq!(
+            tag_claim(&pool, bridge.id, "springfield").await.unwrap(),
+            ["transport", "springfield"]
+        );
+        tag_claim(&pool, ballot.id, "springfield").await.unwrap();
+        assert!(tag_claim(&pool, Uuid::new_v4(), "x").await.is_err());
+
+        let mut listed = list_claims_by_tag(&pool, "springfield", 10).await.unwrap();
+        listed.sort_by(|a, b| a.text.cmp(&b.text));
+        assert_eq!(
+            listed,
+            [
+                ClaimContext {
+                    tags: vec!["springfield".into()],
+                    ..ballot.clone()
+                },
+                ClaimContext {
+                    tags: vec!["transport".into(), "springfield".into()],
+                    ..bridge.clone()
+                },
+            ]
+        );
+        assert_eq!(
+            list_claims_by_tag(&pool, "transport", 10).await.unwrap(),
+            [ClaimContext {
+                tags: vec!["transport".into(), "springfield".into()],
+                ..bridge
+            }]
+        );
+        assert!(list_claims_by_tag(&pool, "spring", 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn source_breakdown_counts_platforms_and_credibility() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let cited = |name: &str, credibility: Credibility| Entity {
+            credibility,
+            ..entity(name, Stance::Supports)
+        };
+        let from = |platform: Option<&str>, id: &str, entities: Vec<Entity>| {
+            let mut a = artifact(&c, id, entities);
+            a.provenance.platform = platform.map(str::to_string);
+            a
+        };
+
+        for a in [
+            from(
+                Some("twitter"),
+                "tw:1",
+                vec![
+                    cited("Mayor", Credibility::Strong),
+                    cited("Bot", Credibility::Weak),
+                ],
+            ),
+            from(
+                Some("twitter"),
+                "tw:2",
+                vec![cited("Anon", Credibility::Unknown)],
+            ),
+            from(Some("twitter"), "tw:3", vec![]),
+            from(
+                Some("web"),
+                "https://news.example/a",
+                vec![cited("Paper", Credibility::Strong)],
+            ),
+            from(None, "legacy:1", vec![]),
+        ] {
+            upsert_normalized(&pool, a).await.unwrap();
+        }
+        let mut irrelevant = from(Some("web"), "https://news.example/b", vec![]);
+        irrelevant.claim_relevance = false;
+        upsert_normalized(&pool, irrelevant).await.unwrap();
+        let other = claim("another claim");
+        insert_claim(&pool, other.clone()).await.unwrap();
+        upsert_normalized(&pool, artifact(&other, "tw:other", vec![]))
+            .await
+            .unwrap();
+
+        let breakdown = source_breakdown(&pool, c.id).await.unwrap();
+        let row = |platform: &str, artifacts, strong, weak, unknown| PlatformSources {
+            platform: platform.into(),
+            artifacts,
+            strong,
+            weak,
+            unknown,
+        };
+        assert_eq!(
+            breakdown,
+            [
+                row("twitter", 3, 1, 1, 1),
+                row("unknown", 1, 0, 0, 0),
+                row("web", 1, 1, 0, 0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn tweet_and_linked_page_are_cross_linked() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+
+        let mut tweet = artifact(&c, "tw:1", vec![]);
+        tweet.canonical_url = nowhere_common::urls::canonical_url("https://news.example/story/");
+        let tweet_id = tweet.internal_id.to_string();
+        upsert_normalized(&pool, tweet).await.unwrap();
+
+        let mut page = artifact(&c, "web:news.example/story", vec![]);
+        page.provenance_info = "web".to_string();
+        page.canonical_url = nowhere_common::urls::canonical_url("https://news.example/story#top");
+        let page_id = page.internal_id.to_string();
+        upsert_normalized(&pool, page).await.unwrap();
+
+        let linked = list_linked_artifacts(&pool, &tweet_id).await.unwrap();
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].internal_id, page_id);
+        let linked = list_linked_artifacts(&pool, &page_id).await.unwrap();
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].internal_id, tweet_id);
+
+        // Re-ingesting the tweet keeps its row and does not duplicate the edge.
+        let mut again = artifact(&c, "tw:1", vec![]);
+        again.canonical_url = nowhere_common::urls::canonical_url("https://news.example/story");
+        upsert_normalized(&pool, again).await.unwrap();
+        let edges: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM graph_edge")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(edges, 1);
+        assert_eq!(
+            list_linked_artifacts(&pool, &tweet_id).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn currency_token_survives_sanitizing() {
+        let opts = FtsQueryOptions::default();
+        assert_eq!(
+            sanitize_fts_query("Did he sign for $500M?", &opts).as_deref(),
+            Some(r#""sign" "$500m""#)
+        );
+        // Stopwords alone leave nothing to match.
+        assert_eq!(sanitize_fts_query("what is the", &opts), None);
+    }
+
+    #[test]
+    fn quoted_phrase_becomes_an_fts_phrase() {
+        let opts = FtsQueryOptions::default();
+        assert_eq!(
+            sanitize_fts_query(r#""Jean-Luc Picard" of the Enterprise"#, &opts).as_deref(),
+            Some(r#""jean-luc picard" "enterprise""#)
+        );
+
+        let words_only = FtsQueryOptions {
+            keep_phrases: false,
+            stopwords: HashSet::new(),
+            ..FtsQueryOptions::default()
+        };
+        assert_eq!(
+            sanitize_fts_query(r#""Jean-Luc Picard" resigned"#, &words_only).as_deref(),
+            Some(r#""jean-luc" "picard" "resigned""#)
+        );
+    }
+
+    #[test]
+    fn long_input_keeps_only_the_leading_terms() {
+        let opts = FtsQueryOptions::default();
+        let words: Vec<String> = (0..100).map(|i| format!("word{i}")).collect();
+        let query = sanitize_fts_query(&words.join(" "), &opts).unwrap();
+        let terms: Vec<&str> = query.split(' ').collect();
+        assert_eq!(terms.len(), DEFAULT_FTS_MAX_TERMS);
+        assert_eq!(terms[0], r#""word0""#);
+        assert_eq!(terms[DEFAULT_FTS_MAX_TERMS - 1], r#""word19""#);
+
+        // A phrase across the cap keeps the words that fit.
+        let two = FtsQueryOptions {
+            max_terms: 2,
+            ..FtsQueryOptions::default()
+        };
+        assert_eq!(
+            sanitize_fts_query(r#"bridge "golden gate closed" today"#, &two).as_deref(),
+            Some(r#""bridge" "golden""#)
+        );
+    }
+
+    #[tokio::test]
+    async fn currency_search_matches_through_fts() {
+        let pool = test_pool().await;
+        let c = claim("a claim");
+        insert_claim(&pool, c.clone()).await.unwrap();
+
+        let mut deal = artifact(&c, "tw:deal", vec![]);
+        deal.reasoning = "Reports a $500M contract signed by Jean-Luc Picard".into();
+        upsert_normalized(&pool, deal).await.unwrap();
+        let mut other = artifact(&c, "tw:other", vec![]);
+        other.reasoning = "Unrelated commentary about the season".into();
+        upsert_normalized(&pool, other).await.unwrap();
+
+        let opts = FtsQueryOptions::default();
+        for query in ["$500M", r#""jean-luc picard""#] {
+            let rows = search_artifacts_fts(&pool, query, c.id, 10, &opts)
+                .await
+                .unwrap();
+            let ids: Vec<_> = rows.iter().map(|r| r.external_id.as_str()).collect();
+            assert_eq!(ids, vec!["tw:deal"], "query {query}");
+        }
+    }
+
+    #[tokio::test]
+    async fn imported_jsonl_artifacts_are_searchable() {
+        let pool = test_pool().await;
+        let c = claim("the bridge closed on Monday");
+        let path = std::env::temp_dir().join(format!("nowhere-import-{}.jsonl", Uuid::new_v4()));
+        let lines = [
+            r#"{"external_id":"tw:1","claim_relevance":true,"reasoning":"Reports the bridge closure","provenance_info":"twitter","entities":[{"name":"City DOT","credibility":"Strong","stance":"supports"}]}"#,
+            "",
+            r#"{"external_id":"web:news.example/bridge","claim_relevance":true,"reasoning":"Confirms the bridge closed Monday","provenance_info":"web"}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let counts = import_jsonl(&pool, &path, c.clone()).await.unwrap();
+        assert_eq!(
+            counts,
+            ImportCounts {
+                artifacts: 2,
+                entities: 1,
+                skipped: 0
+            }
+        );
+
+        let opts = FtsQueryOptions::default();
+        let mut ids: Vec<_> = search_artifacts_fts(&pool, "bridge", c.id, 10, &opts)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.external_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["tw:1", "web:news.example/bridge"]);
+
+        // Re-importing the same file updates in place.
+        let again = import_jsonl(&pool, &path, c.clone()).await.unwrap();
+        assert_eq!(again.artifacts, 2);
+        let artifacts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM normalized_artifact")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(artifacts, 2);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn claims_resolve_by_slug_prefix() {
+        let pool = test_pool().await;
+        // Two ids whose slugs differ only in the last character, and one elsewhere.
+        let base = slug::encode(Uuid::new_v4());
+        let twin = |last: &str| {
+            let id = slug::decode(&format!("{}{last}", &base[..SLUG_LEN - 1])).unwrap();
+            ClaimContext {
+                id,
+                ..claim("a claim")
+            }
+        };
+        let (a, b) = (twin("0"), twin("1"));
+        let mut c = claim("another claim");
+        while slug::encode(c.id).starts_with(&base[..1]) {
+            c.id = Uuid::new_v4();
+        }
+        for claim in [&a, &b, &c] {
+            insert_claim(&pool, claim.clone()).await.unwrap();
+        }
+
+        let ids = |slug: &str| {
+            let pool = pool.clone();
+            let slug = slug.to_string();
+            async move {
+                list_claims_by_slug(&pool, &slug, 10)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|c| c.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(ids(&slug::encode(a.id)).await, [a.id]);
+        assert_eq!(ids(&slug::encode(c.id)[..SHORT_LEN]).await, [c.id]);
+        assert_eq!(
+            ids(&base[..SLUG_LEN - 1]).await,
+            [a.id, b.id],
+            "a shared prefix is ambiguous"
+        );
+        assert!(ids(&base[..1]).await.len() >= 2);
+        assert!(ids("zz").await.is_empty(), "no UUID's slug starts with z");
+        assert!(ids("not-a-slug").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn claims_resolve_by_uuid_prefix() {
+        let pool = test_pool().await;
+        let (a, b) = (claim("a claim"), claim("another claim"));
+        for claim in [&a, &b] {
+            insert_claim(&pool, claim.clone()).await.unwrap();
+        }
+        let hex = a.id.to_string();
+        let shared = hex
+            .chars()
+            .zip(b.id.to_string().chars())
+            .take_while(|(x, y)| x == y)
+            .count();
+
+        let ids = |prefix: String| {
+            let pool = pool.clone();
+            async move {
+                list_claims_by_uuid_prefix(&pool, &prefix, 10)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|c| c.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(ids(hex[..shared + 1].to_string()).await, [a.id]);
+        assert_eq!(ids(a.id.simple().to_string()).await, [a.id]);
+        assert!(ids("not-hex".into()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn artifacts_resolve_by_slug_or_uuid_prefix() {
+        let pool = test_pool().await;
+        let c = claim("the bridge closed");
+        insert_claim(&pool, c.clone()).await.unwrap();
+        let (a, b) = (artifact(&c, "tw:1", vec![]), artifact(&c, "tw:2", vec![]));
+        let id = a.internal_id;
+        for artifact in [a, b] {
+            upsert_normalized(&pool, artifact).await.unwrap();
+        }
+
+        let ids = |prefix: String| {
+            let pool = pool.clone();
+            async move {
+                list_artifact_ids_by_prefix(&pool, &prefix, 5)
+                    .await
+                    .unwrap()
+            }
+        };
+        assert_eq!(ids(slug::short(id)).await, [id]);
+        assert_eq!(ids(slug::encode(id)).await, [id]);
+        assert_eq!(ids(id.to_string()[..13].to_string()).await, [id]);
+        assert!(ids("tw:1".into()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn claim_overlap_lists_shared_entities_and_sources() {
+        let pool = test_pool().await;
+        let (ferry, port) = (claim("the ferry sank"), claim("the port closed"));
+        for c in [&ferry, &port] {
+            insert_claim(&pool, c.clone()).await.unwrap();
+        }
+        let from = |c: &ClaimContext, id: &str, url: Option<&str>, names: &[&str]| {
+            let entities = names.iter().map(|n| entity(n, Stance::Neutral)).collect();
+            let mut a = artifact(c, id, entities);
+            a.canonical_url = url.and_then(nowhere_common::urls::canonical_url);
+            a
+        };
+        for a in [
+            from(
+                &ferry,
+                "tw:1",
+                Some("https://news.example/ferry"),
+                &["Coast Guard", "Mayor Ruiz"],
+            ),
+            from(&ferry, "tw:2", None, &["Harbor Master"]),
+            from(
+                &port,
+                "tw:3",
+                Some("https://news.example/ferry#update"),
+                &["coast guard"],
+            ),
+            from(
+                &port,
+                "tw:4",
+                Some("https://news.example/port"),
+                &["Mayor  Ruiz", "Dock Union"],
+            ),
+        ] {
+            upsert_normalized(&pool, a).await.unwrap();
+        }
+        // Irrelevant evidence does not count towards the overlap.
+        let mut irrelevant = from(&port, "tw:5", None, &["Harbor Master"]);
+        irrelevant.claim_relevance = false;
+        upsert_normalized(&pool, irrelevant).await.unwrap();
+
+        let overlap = claim_overlap(&pool, ferry.id, port.id).await.unwrap();
+        assert_eq!(overlap.entities, ["Coast Guard", "Mayor Ruiz"]);
+        assert_eq!(overlap.sources, ["https://news.example/ferry"]);
+        assert_eq!(
+            claim_overlap(&pool, port.id, ferry.id)
+                .await
+                .unwrap()
+                .entities,
+            overlap.entities,
+            "the overlap is symmetric"
+        );
+        let unrelated = claim("the bridge closed");
+        insert_claim(&pool, unrelated.clone()).await.unwrap();
+        assert_eq!(
+            claim_overlap(&pool, ferry.id, unrelated.id).await.unwrap(),
+            EvidenceOverlap::default()
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_queries_expire_after_max_age() {
+        let pool = test_pool().await;
+        let search = CachedSearchQuery {
+            query: "bridge closed".into(),
+            date_from: Some("2025-01-01T00:00:00Z".into()),
+            date_to: None,
+        };
+        cache_query(&pool, "k1", &search).await.unwrap();
+        let hour = Duration::from_secs(3600);
+        assert_eq!(cached_query(&pool, "k1", hour).await.unwrap(), Some(search));
+        assert_eq!(cached_query(&pool, "k2", hour).await.unwrap(), None);
+
+        let two_hours_ago = Utc::now() - chrono::Duration::hours(2);
+        sqlx::query("UPDATE query_cache SET created_at = ?1")
+            .bind(two_hours_ago)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(cached_query(&pool, "k1", hour).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn dead_letters_keep_the_raw_artifact_and_reply() {
+        let pool = test_pool().await;
+        let c = claim("the bridge closed");
+        let letter = DeadLetter {
+            external_id: "tw:bad".into(),
+            claim_id: c.id,
+            payload: serde_json::json!({ "text": "bridge is shut" }),
+            error: "expected value at line 1 column 1".into(),
+            model_output: "I'm sorry".into(),
+        };
+        dead_letter(&pool, &letter).await.unwrap();
+
+        let (external_id, payload, output): (String, String, String) = sqlx::query_as(
+            "SELECT external_id, payload, model_output FROM normalization_dead_letter
+             WHERE claim_id = ?1",
+        )
+        .bind(c.id.to_string())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(external_id, "tw:bad");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&payload).unwrap(),
+            letter.payload
+        );
+        assert_eq!(output, "I'm sorry");
+    }
+
+    #[tokio::test]
+    async fn test_store_serves_writes_and_searches_through_its_address() {
+        let (store, pool) = test_store().await;
+        let c = claim("the bridge closed");
+        store
+            .send(StoreMsg::InsertClaim(c.clone()))
+            .await
+            .ok()
+            .unwrap();
+        let (seen_tx, seen_rx) = oneshot::channel();
+        store
+            .send(StoreMsg::WatchArtifacts {
+                claim: c.id,
+                reply: seen_tx,
+            })
+            .await
+            .ok()
+            .unwrap();
+        store
+            .send(StoreMsg::UpsertArtifact(
+                Box::new(artifact(&c, "tw:bridge", Vec::new())),
+                None,
+            ))
+            .await
+            .ok()
+            .unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(2), seen_rx)
+            .await
+            .expect("upsert notified in time")
+            .unwrap();
+
+        let (reply, rx) = oneshot::channel();
+        store
+            .send(StoreMsg::SearchArtifacts {
+                claim: c.id,
+                query: "reasoning".into(),
+                limit: 10,
+                reply,
+            })
+            .await
+            .ok()
+            .unwrap();
+        let rows = rx.await.unwrap().unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].external_id, "tw:bridge");
+
+        let claims: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM claim WHERE id = ?")
+            .bind(c.id.to_string())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(claims, 1);
     }
 }
@@ -1,17 +1,30 @@
 //! Actor that orchestrates Twitter/X searches and forwards results to the LLM pipeline.
 //!
 //! It enforces rate limiting, normalizes temporal windows, and fans out fetched tweets
-//! as `RawArtifact` messages. Further documentation should outline pagination strategy
-//! and resilience plans for transient HTTP or auth failures.
+//! as `RawArtifact` messages. Searches follow `next_token` for up to `max_pages` pages and
+//! stop early once the command's cancellation token fires, the claim's budget runs out,
+//! the search's own deadline passes or the claim holds its capped number of artifacts.
+//! Further documentation should outline resilience plans for transient HTTP or auth
+//! failures.
 use crate::actor::{Actor, Addr, Context};
 use crate::llm::LlmActor;
-use crate::rate::{RateKey, RateLimiter, RateMsg};
-use crate::{ClaimContext, LlmMsg, RawArtifact, SearchCmd};
+use crate::rate::{acquire_rate_permit, Priority, RateKey, RateLimiter};
+use crate::store::ArtifactCap;
+use crate::{ClaimContext, LlmMsg, RawArtifact, SearchCmd, SearchOutcome};
 use anyhow::{anyhow, ensure, Result};
 use chrono::{DateTime, Utc};
-use nowhere_social::twitter::{types::SearchResponse, TwitterApi};
-use time::OffsetDateTime;
-use tokio::sync::oneshot;
+use nowhere_social::twitter::{
+    api_problem, recent_search_window,
+    types::{Includes, SearchResponse, Tweet},
+    TwitterApi,
+};
+use std::time::Duration;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// How long a full normalize mailbox may hold up one forwarded message before it is skipped.
+pub const DEFAULT_FORWARD_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct TwitterSearchActor {
     api: TwitterApi,
@@ -19,6 +32,11 @@ pub struct TwitterSearchActor {
     rate_limiter: Addr<RateLimiter>,
     out: Addr<LlmActor>,
     max_results: u32,
+    max_pages: u32,
+    normalize_batch: usize,
+    artifact_cap: Option<ArtifactCap>,
+    forward_timeout: Duration,
+    search_deadline: Option<Duration>,
 }
 
 impl TwitterSearchActor {
@@ -34,6 +52,11 @@ impl TwitterSearchActor {
             rate_limiter,
             out,
             max_results: 100,
+            max_pages: 1,
+            normalize_batch: 1,
+            artifact_cap: None,
+            forward_timeout: DEFAULT_FORWARD_TIMEOUT,
+            search_deadline: None,
         }
     }
 
@@ -52,6 +75,40 @@ impl TwitterSearchActor {
         self
     }
 
+    /// Follow `next_token` for up to `n` result pages per search (default 1).
+    pub fn with_max_pages(mut self, n: u32) -> Self {
+        self.max_pages = n.max(1);
+        self
+    }
+
+    /// Forward tweets to the LLM in groups of `n` (one prompt per group). `1` keeps the
+    /// per-tweet `NormalizeArtifact` flow.
+    pub fn with_normalize_batch(mut self, n: usize) -> Self {
+        self.normalize_batch = n.max(1);
+        self
+    }
+
+    /// Stop forwarding tweets for a claim once the store holds `cap.max` artifacts for it.
+    pub fn with_artifact_cap(mut self, cap: ArtifactCap) -> Self {
+        self.artifact_cap = Some(cap);
+        self
+    }
+
+    /// Skip a forwarded message once the normalize mailbox has been full for `timeout`
+    /// (default 30s), instead of stalling the rest of the page behind it.
+    pub fn with_forward_timeout(mut self, timeout: Duration) -> Self {
+        self.forward_timeout = timeout;
+        self
+    }
+
+    /// Give each search `total` across all its pages. Once it is spent the page in flight
+    /// is abandoned and the search reports `SearchOutcome::Partial` with what it forwarded.
+    /// Unset, only the per-request timeouts and `max_pages` bound a search.
+    pub fn with_search_deadline(mut self, total: Duration) -> Self {
+        self.search_deadline = Some(total);
+        self
+    }
+
     // FIXME: add unit tests for chrono->time conversion to ensure overflow and error branches behave as expected on boundary timestamps.
     fn chrono_to_offset(dt: DateTime<Utc>) -> Result<OffsetDateTime> {
         let nanos = dt
@@ -64,9 +121,26 @@ impl TwitterSearchActor {
     fn search_response_to_artifacts(
         &self,
         resp: SearchResponse,
-        claim: ClaimContext,
+        claim: &ClaimContext,
+        cancel: &CancellationToken,
     ) -> Result<Vec<RawArtifact>> {
-        let SearchResponse { data, .. } = resp;
+        let SearchResponse {
+            data,
+            errors,
+            includes,
+            ..
+        } = resp;
+
+        for err in errors.iter().flatten() {
+            tracing::warn!(
+                claim_id = %claim.id,
+                title = err.title.as_deref(),
+                detail = err.detail.as_deref(),
+                resource_type = err.resource_type.as_deref(),
+                resource_id = err.resource_id.as_deref(),
+                "twitter.search.partial_error"
+            );
+        }
 
         let mut artifacts = Vec::new();
         if let Some(tweets) = data {
@@ -74,13 +148,14 @@ impl TwitterSearchActor {
             for tw in tweets {
                 let tweet_id = tw.id.clone();
 
-                let payload = serde_json::to_value(&tw)?;
+                let payload = tweet_payload(&tw, includes.as_ref())?;
 
-                // FIXME: hydrate tweets with expansions (users, media, referenced tweets) to avoid follow-up fetches during normalization.
+                // FIXME: hydrate tweets with the user and media expansions too, to avoid follow-up fetches during normalization.
                 artifacts.push(RawArtifact {
                     external_id: tweet_id,
                     payload,
                     claim: claim.clone(),
+                    cancel: cancel.clone(),
                 });
             }
         }
@@ -89,6 +164,141 @@ impl TwitterSearchActor {
     }
 }
 
+/// `tw` as normalization sees it. Each of its `referenced_tweets` that the page's
+/// `includes` resolved carries that tweet's `text` and `author_id`, so a quote, retweet or
+/// reply arrives with what it refers to.
+fn tweet_payload(tw: &Tweet, includes: Option<&Includes>) -> Result<serde_json::Value> {
+    let mut payload = serde_json::to_value(tw)?;
+    let (Some(includes), Some(refs)) = (
+        includes,
+        payload
+            .get_mut("referenced_tweets")
+            .and_then(|r| r.as_array_mut()),
+    ) else {
+        return Ok(payload);
+    };
+    for r in refs {
+        let Some(referenced) = r["id"].as_str().and_then(|id| includes.tweet(id)) else {
+            continue;
+        };
+        r["text"] = referenced.text.clone().into();
+        if let Some(author) = &referenced.author_id {
+            r["author_id"] = author.clone().into();
+        }
+    }
+    Ok(payload)
+}
+
+/// The `[start, end]` window a search page was requested with.
+type Window = (OffsetDateTime, OffsetDateTime);
+
+impl TwitterSearchActor {
+    /// One page of results, with the window the API actually asked for: it picks its own
+    /// compliant window rather than the claim's dates.
+    async fn fetch_page(
+        &self,
+        query: &str,
+        date_from: OffsetDateTime,
+        date_to: OffsetDateTime,
+        next_token: Option<String>,
+    ) -> Result<(SearchResponse, Window)> {
+        acquire_rate_permit(&self.rate_limiter, &self.rate_key, Priority::Background).await?;
+        let window = recent_search_window(OffsetDateTime::now_utc());
+        // FIXME: implement retry/backoff for transient HTTP/429 errors instead of erroring out immediately.
+        let resp = self
+            .api
+            .recent_search_page(
+                query.to_string(),
+                Some(self.max_results),
+                Some(date_from),
+                Some(date_to),
+                next_token,
+            )
+            .await?;
+        Ok((resp, window))
+    }
+
+    /// Artifacts the claim may still collect, or `None` when uncapped. Tweets this search
+    /// forwarded may not be stored yet, so they count on top of what was stored before it.
+    async fn room(
+        &self,
+        claim: Uuid,
+        stored_before: usize,
+        forwarded: usize,
+    ) -> Result<Option<usize>> {
+        let Some(cap) = &self.artifact_cap else {
+            return Ok(None);
+        };
+        let used = cap.stored(claim).await?.max(stored_before + forwarded);
+        Ok(Some(cap.max.saturating_sub(used)))
+    }
+
+    /// Hand `artifacts` to the normalizer and return the ids it accepted. A message the
+    /// mailbox does not take within `forward_timeout` is logged and skipped, so one stall
+    /// does not discard the rest of the page; only a closed mailbox is an error.
+    async fn forward(&self, claim: Uuid, artifacts: Vec<RawArtifact>) -> Result<Vec<String>> {
+        let messages: Vec<LlmMsg> = if self.normalize_batch > 1 {
+            let mut artifacts = artifacts.into_iter().peekable();
+            let mut batches = Vec::new();
+            while artifacts.peek().is_some() {
+                let chunk = artifacts.by_ref().take(self.normalize_batch).collect();
+                batches.push(LlmMsg::NormalizeBatch(chunk));
+            }
+            batches
+        } else {
+            artifacts
+                .into_iter()
+                .map(LlmMsg::NormalizeArtifact)
+                .collect()
+        };
+
+        let mut delivered = Vec::new();
+        let mut skipped = 0;
+        for msg in messages {
+            let ids = artifact_ids(&msg);
+            if self
+                .out
+                .send_timeout(msg, self.forward_timeout)
+                .await
+                .is_ok()
+            {
+                delivered.extend(ids);
+            } else if self.out.is_closed() {
+                return Err(anyhow!(
+                    "normalize actor mailbox dropped (artifacts={})",
+                    ids.join(",")
+                ));
+            } else {
+                tracing::warn!(
+                    claim_id = %claim,
+                    artifacts = ?ids,
+                    timeout_ms = self.forward_timeout.as_millis() as u64,
+                    "twitter.search.forward_skipped"
+                );
+                skipped += ids.len();
+            }
+        }
+        if skipped > 0 {
+            tracing::warn!(
+                claim_id = %claim,
+                forwarded = delivered.len(),
+                skipped,
+                "twitter.search.partial_forward"
+            );
+        }
+        Ok(delivered)
+    }
+}
+
+/// External ids of the artifacts carried by a normalize message.
+fn artifact_ids(msg: &LlmMsg) -> Vec<String> {
+    match msg {
+        LlmMsg::NormalizeArtifact(artifact) => vec![artifact.external_id.clone()],
+        LlmMsg::NormalizeBatch(batch) => batch.iter().map(|a| a.external_id.clone()).collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[async_trait::async_trait]
 impl Actor for TwitterSearchActor {
     type Msg = SearchCmd;
@@ -101,7 +311,15 @@ impl Actor for TwitterSearchActor {
             date_from,
             date_to,
             claim,
+            cancel,
+            report,
+            budget,
         } = msg;
+        let report = |outcome: SearchOutcome| {
+            if let Some(tx) = &report {
+                let _ = tx.try_send(outcome);
+            }
+        };
 
         ensure!(
             date_to >= date_from,
@@ -109,49 +327,526 @@ impl Actor for TwitterSearchActor {
             date_to,
             date_from
         );
+        let date_from = Self::chrono_to_offset(date_from)?;
+        let date_to = Self::chrono_to_offset(date_to)?;
+
+        let stored_before = match &self.artifact_cap {
+            Some(cap) => cap.stored(claim.id).await?,
+            None => 0,
+        };
+        let deadline = self
+            .search_deadline
+            .map(|total| tokio::time::Instant::now() + total);
+        let deadline_passed = async {
+            match deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(deadline_passed);
+        let sent_query = self.api.search_query(query.clone());
+        let mut next_token = None;
+        let mut forwarded = Vec::new();
+        for page in 0..self.max_pages {
+            if budget.as_ref().is_some_and(|b| b.enforce()) {
+                tracing::info!(claim_id = %claim.id, page, "twitter.search.budget_exhausted");
+                return Ok(());
+            }
+            let room = self.room(claim.id, stored_before, forwarded.len()).await?;
+            if room == Some(0) {
+                tracing::info!(claim_id = %claim.id, page, "twitter.search.cap_reached");
+                report(SearchOutcome::CapReached(forwarded));
+                return Ok(());
+            }
+            let resp = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => {
+                    tracing::info!(claim_id = %claim.id, page, "twitter.search.cancelled");
+                    return Ok(());
+                }
+                _ = &mut deadline_passed => {
+                    tracing::info!(
+                        claim_id = %claim.id,
+                        page,
+                        forwarded = forwarded.len(),
+                        "twitter.search.deadline_reached"
+                    );
+                    report(SearchOutcome::Partial(forwarded));
+                    return Ok(());
+                }
+                resp = self.fetch_page(&query, date_from, date_to, next_token.take()) => resp,
+            };
+            // An API failure ends this search but not the worker; the next claim may succeed.
+            let (resp, (window_from, window_to)) = match resp {
+                Ok(page) => page,
+                Err(err) => {
+                    let problem = api_problem(&err);
+                    tracing::warn!(
+                        claim_id = %claim.id,
+                        page,
+                        error = %err,
+                        problem = problem.and_then(|p| p.kind_name()),
+                        parameter = problem.and_then(|p| p.parameter.as_deref()),
+                        "twitter.search.failed"
+                    );
+                    report(SearchOutcome::Failed(format!("{err:#}")));
+                    return Ok(());
+                }
+            };
+            next_token = resp.meta.as_ref().and_then(|m| m.next_token.clone());
+            // Enough to tell an over-narrow query from an empty window when nothing comes back.
+            let rfc3339 = |t: OffsetDateTime| t.format(&Rfc3339).unwrap_or_default();
+            tracing::info!(
+                claim_id = %claim.id,
+                page,
+                query = %sent_query,
+                date_from = %rfc3339(window_from),
+                date_to = %rfc3339(window_to),
+                result_count = ?resp.meta.as_ref().and_then(|m| m.result_count),
+                next_token = next_token.is_some(),
+                "twitter.search.outcome"
+            );
+
+            let mut artifacts = self.search_response_to_artifacts(resp, &claim, &cancel)?;
+            let capped = room.is_some_and(|room| artifacts.len() >= room);
+            if let Some(room) = room {
+                artifacts.truncate(room);
+            }
+            forwarded.extend(self.forward(claim.id, artifacts).await?);
+            if capped {
+                tracing::info!(claim_id = %claim.id, page, "twitter.search.cap_reached");
+                report(SearchOutcome::CapReached(forwarded));
+                return Ok(());
+            }
+
+            if next_token.is_none() {
+                break;
+            }
+        }
+
+        report(SearchOutcome::Done(forwarded));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::{probe, spawn_actor};
+    use crate::budget::ClaimBudget;
+    use crate::rate::MockClock;
+    use nowhere_common::testing::capture_events;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+
+    /// Minimal HTTP stub for `/2/tweets/search/recent`: every page returns one tweet and a
+    /// `next_token`, and `on_request` runs as each request arrives.
+    async fn search_stub(
+        on_request: impl Fn(usize) + Send + Sync + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        http_stub(move |page| {
+            on_request(page);
+            let body = serde_json::json!({
+                "data": [{ "id": format!("tw:{page}"), "text": "tweet" }],
+                "meta": { "next_token": format!("page-{}", page + 1) }
+            });
+            ("200 OK", body.to_string())
+        })
+        .await
+    }
 
-        let (permit_tx, permit_rx) = oneshot::channel();
-        self.rate_limiter
-            .send(RateMsg::Acquire {
-                key: self.rate_key.clone(),
-                cost: 1,
-                reply: permit_tx,
+    /// Answers the `n`th request (1-based) with `respond(n)`'s status line and JSON body.
+    async fn http_stub(
+        respond: impl Fn(usize) -> (&'static str, String) + Send + Sync + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        slow_http_stub(|_| Duration::ZERO, respond).await
+    }
+
+    /// Like [`http_stub`], holding the response to page `n` back for `delay(n)`.
+    async fn slow_http_stub(
+        delay: impl Fn(usize) -> Duration + Send + Sync + 'static,
+        respond: impl Fn(usize) -> (&'static str, String) + Send + Sync + 'static,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut sock, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = vec![0u8; 8192];
+                let _ = sock.read(&mut buf).await;
+                let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                let (status, body) = respond(n);
+                tokio::time::sleep(delay(n)).await;
+                let resp = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = sock.write_all(resp.as_bytes()).await;
+            }
+        });
+        (base, hits)
+    }
+
+    fn rate_limiter() -> Addr<RateLimiter> {
+        let handle = spawn_actor(RateLimiter::new(), 8);
+        handle
+            .addr
+            .try_send(crate::rate::RateMsg::Upsert {
+                key: RateKey("test".into()),
+                qps: 1000.0,
+                burst: 1000,
             })
+            .unwrap();
+        handle.addr
+    }
+
+    fn search_cmd(cancel: CancellationToken) -> SearchCmd {
+        let now = Utc::now();
+        SearchCmd {
+            query: "claim".into(),
+            date_from: now - chrono::Duration::days(1),
+            date_to: now,
+            claim: ClaimContext {
+                id: Uuid::new_v4(),
+                text: "claim".into(),
+                tags: Vec::new(),
+            },
+            cancel,
+            report: None,
+            budget: None,
+        }
+    }
+
+    fn spawn_search(base: &str) -> (Addr<TwitterSearchActor>, mpsc::Receiver<LlmMsg>) {
+        let (llm, normalized) = probe::<LlmActor>(16);
+        let api = TwitterApi::with_base_url("token".into(), base).unwrap();
+        let actor = TwitterSearchActor::new(rate_limiter(), RateKey("test".into()), llm, api)
+            .with_max_pages(3);
+        (spawn_actor(actor, 4).addr, normalized)
+    }
+
+    async fn recv_ids(rx: &mut mpsc::Receiver<LlmMsg>, n: usize) -> Vec<String> {
+        let mut ids = Vec::new();
+        while ids.len() < n {
+            let msg = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("artifact in time")
+                .expect("llm mailbox open");
+            if let LlmMsg::NormalizeArtifact(raw) = msg {
+                ids.push(raw.external_id);
+            }
+        }
+        ids
+    }
+
+    #[tokio::test]
+    async fn follows_next_token_up_to_max_pages() {
+        let (base, hits) = search_stub(|_| {}).await;
+        let (search, mut normalized) = spawn_search(&base);
+        search
+            .send(search_cmd(CancellationToken::new()))
             .await
-            .map_err(|_| anyhow!("rate limiter actor dropped"))?;
+            .ok()
+            .unwrap();
 
-        permit_rx
+        assert_eq!(recv_ids(&mut normalized, 3).await, ["tw:1", "tw:2", "tw:3"]);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn quote_tweets_carry_the_quoted_text() {
+        let (base, _) = http_stub(|_| {
+            let body = serde_json::json!({
+                "data": [{
+                    "id": "1850",
+                    "text": "Not what the mayor said last week",
+                    "referenced_tweets": [
+                        { "type": "quoted", "id": "1849" },
+                        { "type": "replied_to", "id": "1700" }
+                    ]
+                }],
+                "includes": { "tweets": [{
+                    "id": "1849",
+                    "text": "The bridge will reopen on Friday",
+                    "author_id": "7"
+                }] }
+            });
+            ("200 OK", body.to_string())
+        })
+        .await;
+        let (search, mut normalized) = spawn_search(&base);
+        search
+            .send(search_cmd(CancellationToken::new()))
             .await
-            .map_err(|_| anyhow!("failed to receive rate permit from limiter"))?;
+            .ok()
+            .unwrap();
 
-        let resp = self
-            // FIXME: implement retry/backoff for transient HTTP/429 errors instead of erroring out immediately.
-            .api
-            .simple_recent_search(
-                query,
-                Some(self.max_results),
-                Some(Self::chrono_to_offset(date_from)?),
-                Some(Self::chrono_to_offset(date_to)?),
-            )
-            // FIXME: paginate through `next_token` so long-running claims can gather more than one page of tweets.
-            .await?;
+        let msg = tokio::time::timeout(Duration::from_secs(5), normalized.recv())
+            .await
+            .expect("artifact in time")
+            .expect("llm mailbox open");
+        let LlmMsg::NormalizeArtifact(raw) = msg else {
+            panic!("expected NormalizeArtifact");
+        };
+        let refs = &raw.payload["referenced_tweets"];
+        assert_eq!(refs[0]["type"], "quoted");
+        assert_eq!(refs[0]["text"], "The bridge will reopen on Friday");
+        assert_eq!(refs[0]["author_id"], "7");
+        assert!(
+            refs[1].get("text").is_none(),
+            "unresolved references stay as they were"
+        );
+    }
 
-        for artifact in self.search_response_to_artifacts(resp, claim)? {
-            if let Err(msg) = self.out.send(LlmMsg::NormalizeArtifact(artifact)).await {
-                return Err(anyhow!(
-                    "normalize actor mailbox dropped (artifact={})",
-                    match msg {
-                        LlmMsg::NormalizeArtifact(raw_artifact) => {
-                            raw_artifact.external_id
-                        }
-                        _ => {
-                            String::new()
-                        }
-                    }
-                ));
+    #[tokio::test]
+    async fn cancelled_claim_stops_issuing_pages() {
+        let cancel = CancellationToken::new();
+        let trip = cancel.clone();
+        // Cancel while the first page is in flight, as `/cancel` would.
+        let (base, hits) = search_stub(move |page| {
+            if page == 1 {
+                trip.cancel();
             }
-        }
+        })
+        .await;
+        let (search, mut normalized) = spawn_search(&base);
+        search.send(search_cmd(cancel)).await.ok().unwrap();
+        // Commands run in order, so the next search's first page shows how many pages the
+        // cancelled one requested.
+        search
+            .send(search_cmd(CancellationToken::new()))
+            .await
+            .ok()
+            .unwrap();
 
-        Ok(())
+        assert_eq!(recv_ids(&mut normalized, 3).await, ["tw:2", "tw:3", "tw:4"]);
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn spent_budget_stops_issuing_pages() {
+        let clock = Arc::new(MockClock::new());
+        let cancel = CancellationToken::new();
+        let budget =
+            ClaimBudget::with_clock(cancel.clone(), Duration::from_secs(60), clock.clone());
+        // The budget elapses while the first page is in flight.
+        let (base, hits) = search_stub(move |page| {
+            if page == 1 {
+                clock.advance(Duration::from_secs(61));
+            }
+        })
+        .await;
+        let (search, mut normalized) = spawn_search(&base);
+        let mut cmd = search_cmd(cancel.clone());
+        cmd.budget = Some(budget);
+        search.send(cmd).await.ok().unwrap();
+        search
+            .send(search_cmd(CancellationToken::new()))
+            .await
+            .ok()
+            .unwrap();
+
+        // Page one is forwarded; the next search starts at page two, so no second page
+        // was requested for the spent claim.
+        assert_eq!(
+            recv_ids(&mut normalized, 4).await,
+            ["tw:1", "tw:2", "tw:3", "tw:4"]
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 4);
+        assert!(cancel.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn fan_out_halts_at_the_artifact_cap() {
+        let (base, hits) = search_stub(|_| {}).await;
+        let (store, pool) = crate::store::tests::test_store().await;
+        let (llm, mut normalized) = probe::<LlmActor>(16);
+        let api = TwitterApi::with_base_url("token".into(), &base).unwrap();
+        let search = TwitterSearchActor::new(rate_limiter(), RateKey("test".into()), llm, api)
+            .with_max_pages(3)
+            .with_artifact_cap(ArtifactCap::new(store, 3));
+        let search = spawn_actor(search, 4).addr;
+
+        let (tx, mut outcomes) = mpsc::channel(4);
+        let mut cmd = search_cmd(CancellationToken::new());
+        cmd.report = Some(tx);
+        // One artifact from an earlier search is already stored for the claim.
+        sqlx::query(
+            "INSERT INTO normalized_artifact (internal_id, external_id, claim_relevance, claim_id)
+             VALUES (?1, 'tw:earlier', 1, ?2)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(cmd.claim.id.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+        search.send(cmd).await.ok().unwrap();
+
+        let outcome = tokio::time::timeout(Duration::from_secs(5), outcomes.recv())
+            .await
+            .expect("outcome in time")
+            .expect("report channel open");
+        assert_eq!(
+            outcome,
+            SearchOutcome::CapReached(vec!["tw:1".into(), "tw:2".into()])
+        );
+        assert_eq!(recv_ids(&mut normalized, 2).await, ["tw:1", "tw:2"]);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+        assert!(normalized.try_recv().is_err(), "nothing past the cap");
+    }
+
+    #[tokio::test]
+    async fn spent_deadline_reports_the_pages_fetched_so_far() {
+        // The first two pages answer at once; the third never does before the deadline.
+        let hold_third = |page| match page {
+            3 => Duration::from_secs(3600),
+            _ => Duration::ZERO,
+        };
+        let (base, hits) = slow_http_stub(hold_third, |page| {
+            let body = serde_json::json!({
+                "data": [{ "id": format!("tw:{page}"), "text": "tweet" }],
+                "meta": { "next_token": format!("page-{}", page + 1) }
+            });
+            ("200 OK", body.to_string())
+        })
+        .await;
+        let (llm, mut normalized) = probe::<LlmActor>(16);
+        let api = TwitterApi::with_base_url("token".into(), &base).unwrap();
+        let search = TwitterSearchActor::new(rate_limiter(), RateKey("test".into()), llm, api)
+            .with_max_pages(5)
+            .with_search_deadline(Duration::from_secs(1));
+        let search = spawn_actor(search, 4).addr;
+
+        let (tx, mut outcomes) = mpsc::channel(4);
+        let mut cmd = search_cmd(CancellationToken::new());
+        cmd.report = Some(tx);
+        search.send(cmd).await.ok().unwrap();
+
+        let outcome = tokio::time::timeout(Duration::from_secs(5), outcomes.recv())
+            .await
+            .expect("outcome in time")
+            .expect("report channel open");
+        assert_eq!(
+            outcome,
+            SearchOutcome::Partial(vec!["tw:1".into(), "tw:2".into()])
+        );
+        assert_eq!(recv_ids(&mut normalized, 2).await, ["tw:1", "tw:2"]);
+        assert_eq!(hits.load(Ordering::SeqCst), 3, "no page after the deadline");
+    }
+
+    #[tokio::test]
+    async fn unauthorized_search_reports_failure_not_empty() {
+        let (base, hits) = http_stub(|_| {
+            let body = serde_json::json!({ "title": "Unauthorized", "status": 401 });
+            ("401 Unauthorized", body.to_string())
+        })
+        .await;
+        let (search, mut normalized) = spawn_search(&base);
+        let (tx, mut outcomes) = mpsc::channel(4);
+        let mut cmd = search_cmd(CancellationToken::new());
+        cmd.report = Some(tx.clone());
+        search.send(cmd).await.ok().unwrap();
+
+        let outcome = tokio::time::timeout(Duration::from_secs(5), outcomes.recv())
+            .await
+            .expect("outcome in time")
+            .expect("report channel open");
+        let SearchOutcome::Failed(reason) = outcome else {
+            panic!("expected a failure, got {outcome:?}");
+        };
+        assert!(reason.contains("401"), "reason: {reason}");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        assert!(normalized.try_recv().is_err(), "nothing forwarded");
+
+        // The worker survives the failure and keeps serving searches.
+        let mut cmd = search_cmd(CancellationToken::new());
+        cmd.report = Some(tx);
+        search.send(cmd).await.ok().unwrap();
+        let outcome = tokio::time::timeout(Duration::from_secs(5), outcomes.recv())
+            .await
+            .expect("outcome in time");
+        assert!(matches!(outcome, Some(SearchOutcome::Failed(_))));
+    }
+
+    #[tokio::test]
+    async fn empty_page_logs_the_query_and_window_it_ran_with() {
+        let captured = capture_events("twitter.search.outcome");
+
+        let (base, _) = http_stub(|_| {
+            let body = serde_json::json!({ "meta": { "result_count": 0 } });
+            ("200 OK", body.to_string())
+        })
+        .await;
+        let (search, mut normalized) = spawn_search(&base);
+        let (tx, mut outcomes) = mpsc::channel(4);
+        let mut cmd = search_cmd(CancellationToken::new());
+        cmd.report = Some(tx);
+        search.send(cmd).await.ok().unwrap();
+        let outcome = tokio::time::timeout(Duration::from_secs(5), outcomes.recv())
+            .await
+            .expect("outcome in time")
+            .expect("report channel open");
+        assert_eq!(outcome, SearchOutcome::Done(Vec::new()));
+        assert!(normalized.try_recv().is_err(), "nothing forwarded");
+
+        let seen = captured.events();
+        assert_eq!(seen.len(), 1, "{seen:?}");
+        let event = &seen[0];
+        let api = TwitterApi::with_base_url("token".into(), &base).unwrap();
+        assert_eq!(event["query"], api.search_query("claim".into()));
+        assert_eq!(event["result_count"], "Some(0)");
+        assert_eq!(event["next_token"], "false");
+        let from = OffsetDateTime::parse(&event["date_from"], &Rfc3339).unwrap();
+        let to = OffsetDateTime::parse(&event["date_to"], &Rfc3339).unwrap();
+        assert!(to - from > time::Duration::days(6), "{from} .. {to}");
+    }
+
+    #[tokio::test]
+    async fn a_stalled_forward_is_skipped_and_the_rest_of_the_page_flows() {
+        let (base, _) = http_stub(|_| {
+            let body = serde_json::json!({
+                "data": [
+                    { "id": "tw:1", "text": "tweet" },
+                    { "id": "tw:2", "text": "tweet" },
+                    { "id": "tw:3", "text": "tweet" }
+                ]
+            });
+            ("200 OK", body.to_string())
+        })
+        .await;
+        // Room for one message: tw:1 fills the mailbox, tw:2 times out behind it, and the
+        // normalizer drains in time for tw:3.
+        let (llm, mut normalized) = probe::<LlmActor>(1);
+        let api = TwitterApi::with_base_url("token".into(), &base).unwrap();
+        let search = TwitterSearchActor::new(rate_limiter(), RateKey("test".into()), llm, api)
+            .with_forward_timeout(Duration::from_millis(250));
+        let search = spawn_actor(search, 4);
+
+        let (tx, mut outcomes) = mpsc::channel(4);
+        let mut cmd = search_cmd(CancellationToken::new());
+        cmd.report = Some(tx);
+        search.addr.send(cmd).await.ok().unwrap();
+
+        // Start draining after tw:2 has given up but well before tw:3 would.
+        tokio::time::sleep(Duration::from_millis(375)).await;
+        assert_eq!(recv_ids(&mut normalized, 2).await, ["tw:1", "tw:3"]);
+        let outcome = tokio::time::timeout(Duration::from_secs(5), outcomes.recv())
+            .await
+            .expect("outcome in time")
+            .expect("report channel open");
+        assert_eq!(
+            outcome,
+            SearchOutcome::Done(vec!["tw:1".into(), "tw:3".into()])
+        );
+        assert!(!search.task.is_finished(), "worker keeps running");
     }
 }
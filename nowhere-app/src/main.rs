@@ -8,12 +8,24 @@ mod tether;
 #[tokio::main]
 async fn main() -> Result<()> {
     // 1) Load config (env wins)
-    let cfg: NowhereConfig = NowhereConfigLoader::new()
+    let mut cfg: NowhereConfig = NowhereConfigLoader::new()
         .with_file("nowhere.yaml")
         .load()?;
+    if std::env::args().skip(1).any(|arg| arg == "--no-animation") {
+        cfg.tui.get_or_insert_with(Default::default).animation = Some(false);
+    }
 
-    //FIXME: Need to set up logging from YAML config file
-    init_logging(LogConfig::default())?;
+    let mut log_config = LogConfig::default();
+    if let Some(logging) = cfg.logging.as_ref() {
+        log_config.targets = logging
+            .targets
+            .iter()
+            .map(|(target, level)| (target.clone(), level.clone()))
+            .collect();
+        log_config.max_files = logging.max_files;
+        log_config.max_age_days = logging.max_age_days;
+    }
+    init_logging(log_config)?;
 
     let mut tether = Tether::new();
     build_from_config(&mut tether, cfg).await?;
@@ -1,17 +1,45 @@
 use anyhow::Result;
+#[cfg(feature = "postgres")]
+use nowhere_actors::pg::{self, PgStoreActor};
 use nowhere_actors::{
+    ReadinessCheck, ReadinessReport,
     actor::{Addr, Reserved},
+    analysis::AnalysisActor,
     builder::Builder,
-    llm::{ChatLlmActor, LlmActor},
+    llm::{
+        ChatLlmActor, DEFAULT_QUERY_TEMPLATE, LlmActor, PARSE_FAILURE_SUMMARY_INTERVAL, PromptJson,
+        QueryPrompt, spawn_parse_failure_summary,
+    },
     rate::{RateKey, RateLimiter, RateMsg},
-    store::StoreActor,
+    store::{ArtifactCap, FtsQueryOptions, RETENTION_INTERVAL, StoreActor, spawn_retention},
     twitter::TwitterSearchActor,
 };
-use nowhere_config::{ActorDetails, LlmConfig, NowhereConfig};
-use nowhere_llm::{ollama::OllamaClient, openai::OpenAiClient, traits::LlmClient};
-use nowhere_tui::{TuiActor, spawn_tui_feeders};
+use nowhere_common::NowhereError;
+#[cfg(feature = "brave")]
+use nowhere_common::StealthLevel;
+use nowhere_config::{
+    ActorDetails, LlmConfig, LlmRole, NowhereConfig, QueryPromptConfig, TwitterFieldsConfig,
+};
+#[cfg(feature = "brave")]
+use nowhere_config::{BraveConfig, WebCaptureConfig};
+use nowhere_llm::{
+    ollama::{OllamaClient, StartupProbe},
+    openai::OpenAiClient,
+    single_flight::SingleFlight,
+    traits::LlmClient,
+};
+use nowhere_social::twitter::{SearchExclusions, TweetFields, TwitterApi};
+use nowhere_tui::{CrosstermEvents, DEFAULT_TICK_RATE, TuiActor, TuiMsg, spawn_tui_feeders_with};
+#[cfg(feature = "brave")]
+use nowhere_web::{
+    brave::client::{BraveApi, BraveSearchOptions},
+    browser::{CaptureOptions, FantocciniCapturer},
+    capture::WebCaptureActor,
+    search::{WebOut, WebSearchActor},
+};
 use sqlx::SqlitePool;
-use std::sync::Arc;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use tokio::sync::Semaphore;
 
 pub struct Tether {
     builder: Builder,
@@ -41,15 +69,324 @@ fn twitter_rate_key(spec_id: &str) -> RateKey {
 fn chat_llm_rate_key(spec_id: &str) -> RateKey {
     RateKey(format!("llm:chat:{spec_id}"))
 }
+fn analysis_llm_rate_key(spec_id: &str) -> RateKey {
+    RateKey(format!("llm:analysis:{spec_id}"))
+}
+
+fn twitter_fields(cfg: Option<&TwitterFieldsConfig>) -> TweetFields {
+    let mut fields = TweetFields::default();
+    let Some(cfg) = cfg else {
+        return fields;
+    };
+    if let Some(tweet) = &cfg.tweet {
+        fields = fields.with_tweet_fields(tweet.iter().cloned());
+    }
+    if let Some(user) = &cfg.user {
+        fields = fields.with_user_fields(user.iter().cloned());
+    }
+    if let Some(media) = &cfg.media {
+        fields = fields.with_media_fields(media.iter().cloned());
+    }
+    if let Some(expansions) = &cfg.expansions {
+        fields = fields.with_expansions(expansions.iter().cloned());
+    }
+    fields
+}
+
+/// A template without `{claim}` would search for the same thing on every claim.
+fn query_prompt_from(spec_id: &str, cfg: &QueryPromptConfig) -> Result<QueryPrompt> {
+    let template = cfg.template.as_deref().unwrap_or(DEFAULT_QUERY_TEMPLATE);
+    if !template.contains("{claim}") {
+        return Err(NowhereError::Config(format!(
+            "`query_prompt.template` of \"{spec_id}\" never mentions {{claim}}"
+        ))
+        .into());
+    }
+    Ok(QueryPrompt {
+        system: cfg.system.clone(),
+        template: template.to_string(),
+    })
+}
+
+/// The database `DATABASE_URL` points at, which decides the store backend.
+enum Db {
+    Sqlite(SqlitePool),
+    #[cfg(feature = "postgres")]
+    Postgres(sqlx::PgPool),
+}
 
-async fn make_pool_from_env() -> Result<SqlitePool> {
+fn is_postgres_url(url: &str) -> bool {
+    url.starts_with("postgres://") || url.starts_with("postgresql://")
+}
+
+async fn make_pool_from_env() -> Result<Db> {
     let url =
         std::env::var("DATABASE_URL").expect("DATABASE_URL not set (e.g. sqlite://nowhere.db)");
-    let pool = SqlitePool::connect(&url).await?;
-    Ok(pool)
+    if is_postgres_url(&url) {
+        #[cfg(feature = "postgres")]
+        return Ok(Db::Postgres(sqlx::PgPool::connect(&url).await?));
+        #[cfg(not(feature = "postgres"))]
+        return Err(NowhereError::Config(
+            "DATABASE_URL is a Postgres URL, but this build has no Postgres support \
+             (rebuild with `--features postgres`)"
+                .into(),
+        )
+        .into());
+    }
+    Ok(Db::Sqlite(SqlitePool::connect(&url).await?))
 }
 
-pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()> {
+/// Id of the LLM actor that takes every role no other LLM claims.
+const MAIN_LLM_ID: &str = "llm:main";
+
+/// Spec ids of the LLM actors that each consumer talks to.
+#[derive(Debug, PartialEq, Eq)]
+struct LlmRoutes {
+    normalizer: String,
+    chat: String,
+    query: String,
+}
+
+impl LlmRoutes {
+    /// Pick an LLM for each role: the first one that claims it in `roles`, else
+    /// `llm:main`, else the only LLM configured. The TUI and the Twitter workers need
+    /// all three, so say so plainly instead of panicking halfway through wiring.
+    fn resolve(cfg: &NowhereConfig) -> Result<Self> {
+        let llms: Vec<_> = cfg
+            .actors
+            .iter()
+            .filter(|a| a.enabled.unwrap_or(true))
+            .filter_map(|a| match &a.details {
+                ActorDetails::Llm { roles, .. } => Some((a.id.as_str(), roles.as_deref())),
+                _ => None,
+            })
+            .collect();
+        if llms.is_empty() {
+            return Err(NowhereError::Config(format!(
+                "no LLM configured: add an enabled `kind: llm` actor with id \"{MAIN_LLM_ID}\" to nowhere.yaml (see the OpenAI and Ollama examples there)"
+            ))
+            .into());
+        }
+        let pick = |role: LlmRole| -> Result<String> {
+            let claimed = llms
+                .iter()
+                .find(|(_, roles)| roles.is_some_and(|r| r.contains(&role)));
+            let fallback = || match &llms[..] {
+                [(only, _)] => Some(*only),
+                _ => llms.iter().map(|(id, _)| *id).find(|id| *id == MAIN_LLM_ID),
+            };
+            claimed
+                .map(|(id, _)| *id)
+                .or_else(fallback)
+                .map(str::to_string)
+                .ok_or_else(|| {
+                    let role = format!("{role:?}").to_lowercase();
+                    NowhereError::Config(format!(
+                        "no LLM takes the `{role}` role: add it to one actor's `roles`, or name one \"{MAIN_LLM_ID}\""
+                    ))
+                    .into()
+                })
+        };
+        Ok(Self {
+            normalizer: pick(LlmRole::Normalizer)?,
+            chat: pick(LlmRole::Chat)?,
+            query: pick(LlmRole::Query)?,
+        })
+    }
+}
+
+/// Name of the Brave web search worker, when one runs.
+const WEB_SEARCH_ID: &str = "web:search";
+/// Name of the page capture worker between web search and normalization, when one runs.
+#[cfg(feature = "brave")]
+const WEB_CAPTURE_ID: &str = "web:capture";
+
+/// Result count, freshness and verticals for web searches; a `freshness` or
+/// `result_filter` Brave doesn't know is a config error.
+#[cfg(feature = "brave")]
+fn brave_search_options(cfg: &BraveConfig) -> Result<BraveSearchOptions> {
+    let invalid = |field: &str, err: String| NowhereError::Config(format!("brave.{field}: {err}"));
+    Ok(BraveSearchOptions {
+        count: cfg.count,
+        freshness: cfg
+            .freshness
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err| invalid("freshness", err))?
+            .unwrap_or_default(),
+        result_filter: cfg
+            .result_filter
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err| invalid("result_filter", err))?,
+    })
+}
+
+/// The runtime settings page captures read (stealth, headless, browser sessions), from
+/// the `brave.capture` block.
+#[cfg(feature = "brave")]
+fn capture_settings(cfg: &WebCaptureConfig) -> Result<nowhere_common::NowhereConfig> {
+    let stealth = cfg.stealth.as_deref().map(str::to_ascii_lowercase);
+    let stealth_level = match stealth.as_deref() {
+        None | Some("balanced") => StealthLevel::Balanced,
+        Some("lightweight") => StealthLevel::Lightweight,
+        Some("maximum") => StealthLevel::Maximum,
+        Some(other) => {
+            return Err(NowhereError::Config(format!(
+                "brave.capture.stealth: unknown level '{other}' (expected lightweight|balanced|maximum)"
+            ))
+            .into());
+        }
+    };
+    let defaults = nowhere_common::NowhereConfig::default();
+    Ok(nowhere_common::NowhereConfig {
+        stealth_level,
+        headless: cfg.headless.unwrap_or(true),
+        browser_sessions: cfg.browser_sessions.unwrap_or(defaults.browser_sessions),
+        ..defaults
+    })
+}
+
+/// How long one readiness check may take before it counts as unhealthy.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(10);
+
+type HealthCheck = Pin<Box<dyn Future<Output = Result<bool>> + Send>>;
+
+/// Startup checks that each external dependency answers: the LLMs, Twitter, Brave, the
+/// database, and anything registered with [`Readiness::with_check`].
+pub struct Readiness {
+    checks: Vec<(String, HealthCheck)>,
+    timeout: Duration,
+}
+
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self {
+            checks: Vec::new(),
+            timeout: READINESS_TIMEOUT,
+        }
+    }
+
+    /// `check` resolves to `Ok(true)` when healthy, `Ok(false)` when the dependency
+    /// answered but refused (e.g. rejected credentials), `Err` when it could not be asked.
+    pub fn with_check<F>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Future<Output = Result<bool>> + Send + 'static,
+    {
+        self.checks.push((name.into(), Box::pin(check)));
+        self
+    }
+
+    pub fn with_llm(
+        self,
+        name: impl Into<String>,
+        client: Arc<dyn LlmClient + Send + Sync>,
+    ) -> Self {
+        self.with_check(name, async move { Ok(client.health_check().await?) })
+    }
+
+    pub fn with_twitter(self, name: impl Into<String>, api: TwitterApi) -> Self {
+        self.with_check(name, async move { api.health_check().await })
+    }
+
+    #[cfg(feature = "brave")]
+    pub fn with_brave(self, api: BraveApi) -> Self {
+        self.with_check("brave", async move { api.health_check().await })
+    }
+
+    pub fn with_db(self, pool: SqlitePool) -> Self {
+        self.with_check("db", async move {
+            sqlx::query("SELECT 1").execute(&pool).await?;
+            Ok(true)
+        })
+    }
+
+    /// Run every check concurrently and report them in registration order.
+    pub async fn probe_all(self) -> ReadinessReport {
+        let timeout = self.timeout;
+        let running: Vec<_> = self
+            .checks
+            .into_iter()
+            .map(|(name, check)| (name, tokio::spawn(tokio::time::timeout(timeout, check))))
+            .collect();
+        let mut checks = Vec::with_capacity(running.len());
+        for (name, handle) in running {
+            let (healthy, detail) = match handle.await {
+                Ok(Ok(Ok(true))) => (true, None),
+                Ok(Ok(Ok(false))) => (
+                    false,
+                    Some("reported unhealthy; check its credentials and endpoint".to_string()),
+                ),
+                Ok(Ok(Err(e))) => (false, Some(format!("{e:#}"))),
+                Ok(Err(_)) => (
+                    false,
+                    Some(format!("no answer within {}s", timeout.as_secs_f32())),
+                ),
+                Err(e) => (false, Some(format!("check failed: {e}"))),
+            };
+            checks.push(ReadinessCheck {
+                name,
+                healthy,
+                detail,
+            });
+        }
+        ReadinessReport { checks }
+    }
+}
+
+/// What the services hand to the TUI once they are running.
+struct Services {
+    routes: LlmRoutes,
+    store: Addr<StoreActor>,
+    readiness: Readiness,
+}
+
+/// The actors the TUI talks to, picked per role by [`LlmRoutes`].
+struct TuiDeps {
+    llm: Addr<LlmActor>,
+    chat_llm: Addr<ChatLlmActor>,
+    analysis: Addr<AnalysisActor>,
+    twitter: Addr<TwitterSearchActor>,
+    web_search: Option<Addr<TwitterSearchActor>>,
+}
+
+impl TuiDeps {
+    fn resolve(b: &Builder, routes: &LlmRoutes) -> Self {
+        Self {
+            llm: b.addr(&routes.query).expect("llm addr"),
+            chat_llm: b
+                .addr(&format!("{}#chat", routes.chat))
+                .expect("chat llm addr"),
+            analysis: b
+                .addr(&format!("{}#analysis", routes.chat))
+                .expect("analysis addr"),
+            // FIXME: fan-in messages from all Twitter workers instead of hard-coding #0 so higher concurrency actually reaches the TUI.
+            twitter: b.addr("twitter:ingest#0").expect("twitter addr"), // optional
+            web_search: b.addr(WEB_SEARCH_ID),
+        }
+    }
+}
+
+/// Phases 1 and 2: everything but the TUI, so tests can drive the wired actors headless.
+async fn start_services(t: &mut Tether, cfg: &NowhereConfig) -> Result<Services> {
+    let routes = LlmRoutes::resolve(cfg)?;
+    #[cfg(feature = "brave")]
+    let brave_options = cfg.brave.as_ref().map(brave_search_options).transpose()?;
+    #[cfg(feature = "brave")]
+    let capture = cfg
+        .brave
+        .as_ref()
+        .and_then(|brave| brave.capture.as_ref())
+        .map(|capture| capture_settings(capture).map(|settings| (capture, settings)))
+        .transpose()?;
     let b = t.builder_mut();
     let shutdown = b.shutdown_handle();
 
@@ -57,14 +394,15 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
     use std::collections::HashMap;
     let mut r_llm: HashMap<String, Reserved<LlmActor>> = HashMap::new();
     let mut r_chat_llm: HashMap<String, Reserved<ChatLlmActor>> = HashMap::new();
+    let mut r_analysis: HashMap<String, Reserved<AnalysisActor>> = HashMap::new();
     let mut r_tw: HashMap<String, Vec<Reserved<TwitterSearchActor>>> = HashMap::new();
+    #[cfg(feature = "brave")]
+    let mut llm_clients: HashMap<String, Arc<dyn LlmClient + Send + Sync>> = HashMap::new();
 
     // infra
     let r_rate = b.reserve::<RateLimiter>("rate:main", 1024);
     let r_store = b.reserve::<StoreActor>("store:main", 1024);
 
-    // ui (start last)
-    let r_tui = b.reserve::<TuiActor>("tui:main", 256);
     // let r_tui_store = b.reserve::<StoreActor>("store:tui", 1024);
 
     // app actors
@@ -76,6 +414,11 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
                 r_llm.insert(spec.id.clone(), b.reserve::<LlmActor>(&spec.id, 1024));
                 let chat_name = format!("{}#chat", spec.id);
                 r_chat_llm.insert(spec.id.clone(), b.reserve::<ChatLlmActor>(&chat_name, 1024));
+                let analysis_name = format!("{}#analysis", spec.id);
+                r_analysis.insert(
+                    spec.id.clone(),
+                    b.reserve::<AnalysisActor>(&analysis_name, 256),
+                );
             }
             ActorDetails::Twitter { .. } => {
                 let mut v = Vec::with_capacity(conc);
@@ -90,19 +433,83 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
 
     // -------- PHASE 2a: START INFRA FIRST --------
     // Start RateLimiter and Store so we can provision keys and wire outputs.
-    let rate = RateLimiter::new();
+    let mut rate = RateLimiter::new();
+    if let Some(path) = cfg.rate.as_ref().and_then(|r| r.state_path.as_deref()) {
+        rate = rate.with_state_file(path);
+    }
     b.start_reserved(r_rate, rate);
     // FIXME: surface database connection errors instead of panicking so the TUI can report configuration issues.
-    let pool = make_pool_from_env().await.unwrap();
-    let store = StoreActor::new(pool.clone());
-    // let tui_store = StoreActor::new(pool.clone());
-    b.start_reserved(r_store, store);
-    // b.start_reserved(r_tui_store, tui_store);
+    let db = make_pool_from_env().await.unwrap();
+    // Shared with the LLM actors so normalization backs off while every write slot is taken.
+    let write_concurrency = cfg
+        .store
+        .as_ref()
+        .and_then(|s| s.write_concurrency)
+        .unwrap_or(1)
+        .max(1);
+    let write_limit = Arc::new(Semaphore::new(write_concurrency));
+    let fts = cfg
+        .store
+        .as_ref()
+        .filter(|s| s.search_text_chars.is_some() || s.fts_max_terms.is_some())
+        .map(|s| {
+            let defaults = FtsQueryOptions::default();
+            FtsQueryOptions {
+                max_text_chars: match s.search_text_chars {
+                    Some(chars) => (chars > 0).then_some(chars),
+                    None => defaults.max_text_chars,
+                },
+                max_terms: s.fts_max_terms.unwrap_or(defaults.max_terms).max(1),
+                ..defaults
+            }
+        });
+    let ttl_days = cfg
+        .store
+        .as_ref()
+        .and_then(|s| s.artifact_ttl_days)
+        .filter(|d| *d > 0);
+    let mut readiness;
+    match db {
+        Db::Sqlite(pool) => {
+            let mut store = StoreActor::new(pool.clone()).with_write_limit(write_limit.clone());
+            if let Some(fts) = fts {
+                store = store.with_fts_options(fts);
+            }
+            readiness = Readiness::new().with_db(pool.clone());
+            // let tui_store = StoreActor::new(pool.clone());
+            b.start_reserved(r_store, store);
+            // b.start_reserved(r_tui_store, tui_store);
+            if let Some(days) = ttl_days {
+                spawn_retention(pool.clone(), days, RETENTION_INTERVAL, shutdown.subscribe());
+            }
+        }
+        #[cfg(feature = "postgres")]
+        Db::Postgres(pool) => {
+            let mut store = PgStoreActor::new(pool.clone()).with_write_limit(write_limit.clone());
+            if let Some(fts) = fts {
+                store = store.with_fts_options(fts);
+            }
+            if let Some(days) = ttl_days {
+                pg::spawn_retention(pool.clone(), days, RETENTION_INTERVAL, shutdown.subscribe());
+            }
+            readiness = Readiness::new().with_check("db", async move {
+                sqlx::query("SELECT 1").execute(&pool).await?;
+                Ok(true)
+            });
+            // Same mailbox, so `Addr<StoreActor>` below reaches the Postgres store.
+            b.start_reserved(r_store.into_actor::<PgStoreActor>(), store);
+        }
+    }
 
     // Resolve infra addrs
     let rate_addr: Addr<RateLimiter> = b.addr("rate:main").expect("rate addr");
     let store_addr: Addr<StoreActor> = b.addr("store:main").expect("store addr");
     // let tui_store_addr: Addr<StoreActor> = b.addr("store:tui").expect("tui_store addr");
+    let artifact_cap = cfg
+        .store
+        .as_ref()
+        .and_then(|s| s.max_artifacts_per_claim)
+        .map(|max| ArtifactCap::new(store_addr.clone(), max));
 
     // -------- PHASE 2b: PROVISION RATE LIMITS (policy lives here) --------
     // Example defaults — make these come from config if you want.
@@ -122,6 +529,11 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
                 qps: 1.0,
                 burst: 5,
             });
+            let _ = rate_addr.try_send(RateMsg::Upsert {
+                key: analysis_llm_rate_key(&spec.id),
+                qps: 1.0,
+                burst: 5,
+            });
         }
     }
     // Twitter limits (pooled per spec across workers)
@@ -140,51 +552,133 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
     // -------- PHASE 2c: START APP ACTORS (deps injected) --------
     for spec in cfg.actors.iter().filter(|a| a.enabled.unwrap_or(true)) {
         match &spec.details {
-            ActorDetails::Llm { config } => {
-                let client = build_llm_client(config).await?;
+            ActorDetails::Llm {
+                config,
+                min_relevance,
+                coalesce_requests,
+                search_window_days,
+                pretty_prompt_json,
+                min_context_artifacts,
+                roles: _,
+                query_prompt,
+                query_cache_hours,
+                share_chat_rate_limit,
+                answer_language,
+                dead_letter,
+            } => {
+                let mut client = build_llm_client(config).await?;
+                if coalesce_requests.unwrap_or(false) {
+                    client = Arc::new(SingleFlight::new(client));
+                }
+                readiness = readiness.with_llm(spec.id.clone(), client.clone());
+                #[cfg(feature = "brave")]
+                llm_clients.insert(spec.id.clone(), client.clone());
                 let key = llm_rate_key(&spec.id);
-                let chat_key = chat_llm_rate_key(&spec.id);
+                let prompt_json = if pretty_prompt_json.unwrap_or(false) {
+                    PromptJson::Pretty
+                } else {
+                    PromptJson::Compact
+                };
+                // A shared bucket still puts chat first: it acquires at interactive priority.
+                let chat_key = if share_chat_rate_limit.unwrap_or(false) {
+                    key.clone()
+                } else {
+                    chat_llm_rate_key(&spec.id)
+                };
 
                 let r = r_llm.remove(&spec.id).expect("reserved LlmActor");
-                let actor = LlmActor::new(
+                let mut actor = LlmActor::new(
                     rate_addr.clone(),
                     key.clone(),
                     store_addr.clone(),
                     client.clone(),
                 )
-                .with_rate_key(key.clone());
+                .with_rate_key(key.clone())
+                .with_write_limit(write_limit.clone())
+                .with_prompt_json(prompt_json)
+                .with_dead_letter(dead_letter.unwrap_or(false));
+                if let Some(min) = *min_relevance {
+                    actor = actor.with_min_relevance(min);
+                }
+                if let Some(days) = *search_window_days {
+                    actor = actor.with_search_window(chrono::Duration::days(days.into()));
+                }
+                if let Some(prompt) = query_prompt {
+                    actor = actor.with_query_prompt(query_prompt_from(&spec.id, prompt)?);
+                }
+                if let Some(hours) = query_cache_hours.filter(|h| *h > 0) {
+                    actor = actor.with_query_cache(Duration::from_secs(u64::from(hours) * 3600));
+                }
 
+                spawn_parse_failure_summary(
+                    spec.id.clone(),
+                    actor.parse_failures(),
+                    PARSE_FAILURE_SUMMARY_INTERVAL,
+                    shutdown.subscribe(),
+                );
                 b.start_reserved(r, actor);
 
                 if let Some(chat_reserved) = r_chat_llm.remove(&spec.id) {
-                    let chat_actor = ChatLlmActor::new(
+                    let mut chat_actor = ChatLlmActor::new(
                         rate_addr.clone(),
                         chat_key.clone(),
                         store_addr.clone(),
                         client.clone(),
                     )
-                    .with_rate_key(chat_key.clone());
+                    .with_rate_key(chat_key.clone())
+                    .with_prompt_json(prompt_json)
+                    .with_min_context_artifacts(min_context_artifacts.unwrap_or(0));
+                    if let Some(language) = answer_language {
+                        chat_actor = chat_actor.with_answer_language(language.clone());
+                    }
                     b.start_reserved(chat_reserved, chat_actor);
                 }
+
+                if let Some(analysis_reserved) = r_analysis.remove(&spec.id) {
+                    let analysis_actor = AnalysisActor::new(
+                        rate_addr.clone(),
+                        analysis_llm_rate_key(&spec.id),
+                        client.clone(),
+                    );
+                    b.start_reserved(analysis_reserved, analysis_actor);
+                }
             }
 
             ActorDetails::Twitter { config } => {
-                let llm_id = "llm:main".to_string();
                 let llm_addr: Addr<LlmActor> = b
-                    .addr(&llm_id)
-                    .unwrap_or_else(|| panic!("missing LLM dep '{llm_id}'"));
+                    .addr(&routes.normalizer)
+                    .unwrap_or_else(|| panic!("missing LLM dep '{}'", routes.normalizer));
 
                 let shared_key = twitter_rate_key(&spec.id); // pooled
                 // let per_worker_key = |idx| RateKey(format!("tw:search:{}#{}", spec.id, idx)); // alt
 
+                let fields = twitter_fields(config.fields.as_ref());
+                let exclusions = SearchExclusions {
+                    retweets: config.exclude_retweets.unwrap_or(false),
+                    replies: config.exclude_replies.unwrap_or(false),
+                };
+                readiness = readiness
+                    .with_twitter(spec.id.clone(), TwitterApi::new(config.auth_token.clone()));
+
                 if let Some(workers) = r_tw.remove(&spec.id) {
                     for r in workers.into_iter() {
-                        let actor = TwitterSearchActor::with_bearer(
+                        let api = TwitterApi::new(config.auth_token.clone())
+                            .with_fields(fields.clone())
+                            .with_exclusions(exclusions);
+                        let mut actor = TwitterSearchActor::new(
                             rate_addr.clone(),
                             shared_key.clone(), // or per_worker_key(idx)
                             llm_addr.clone(),
-                            config.auth_token.clone(),
-                        );
+                            api,
+                        )
+                        .with_normalize_batch(config.normalize_batch.unwrap_or(1))
+                        .with_max_pages(config.max_pages.unwrap_or(1));
+                        if let Some(cap) = &artifact_cap {
+                            actor = actor.with_artifact_cap(cap.clone());
+                        }
+                        if let Some(secs) = config.search_deadline_secs {
+                            actor = actor.with_search_deadline(Duration::from_secs(secs));
+                        }
                         b.start_reserved(r, actor);
                     }
                 }
@@ -192,18 +686,119 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
         }
     }
 
+    #[cfg(feature = "brave")]
+    if let (Some(brave), Some(options)) = (cfg.brave.as_ref(), brave_options) {
+        readiness = readiness.with_brave(BraveApi::new(brave.api_key.clone()));
+        let llm_addr: Addr<LlmActor> = b
+            .addr(&routes.normalizer)
+            .unwrap_or_else(|| panic!("missing LLM dep '{}'", routes.normalizer));
+        let out = match capture {
+            Some((capture, settings)) => {
+                let capturer =
+                    FantocciniCapturer::from_config(&settings).with_options(CaptureOptions {
+                        screenshot: true,
+                        ..CaptureOptions::default()
+                    });
+                let mut actor = WebCaptureActor::new(
+                    Arc::new(capturer),
+                    llm_clients[&routes.normalizer].clone(),
+                    llm_addr,
+                    &capture.archive_dir,
+                )
+                .with_config(&settings);
+                if let Some(cap) = &artifact_cap {
+                    actor = actor.with_artifact_cap(cap.clone());
+                }
+                let r = b.reserve::<WebCaptureActor>(WEB_CAPTURE_ID, 256);
+                let capture_addr = r.addr();
+                b.start_reserved(r, actor);
+                WebOut::Capture(capture_addr)
+            }
+            None => WebOut::Normalize(llm_addr),
+        };
+        let mut actor =
+            WebSearchActor::new(BraveApi::new(brave.api_key.clone()), out).with_options(options);
+        if let Some(cap) = &artifact_cap {
+            actor = actor.with_artifact_cap(cap.clone());
+        }
+        // Same mailbox type as a Twitter worker, so the TUI sends it each search too.
+        let r = b.reserve::<TwitterSearchActor>(WEB_SEARCH_ID, 256);
+        b.start_reserved(r.into_actor::<WebSearchActor>(), actor);
+    }
+    #[cfg(not(feature = "brave"))]
+    if cfg.brave.is_some() {
+        tracing::warn!("brave is configured but this build lacks the `brave` feature");
+    }
+
+    Ok(Services {
+        routes,
+        store: store_addr,
+        readiness,
+    })
+}
+
+pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()> {
+    let Services {
+        routes,
+        store: store_addr,
+        readiness,
+    } = start_services(t, &cfg).await?;
+    let b = t.builder_mut();
+    let shutdown = b.shutdown_handle();
+
     // -------- PHASE 3: START TUI LAST --------
     {
-        let llm_addr: Addr<LlmActor> = b.addr("llm:main").expect("llm addr");
-        let chat_llm_addr: Addr<ChatLlmActor> = b.addr("llm:main#chat").expect("chat llm addr");
-        // FIXME: fan-in messages from all Twitter workers instead of hard-coding #0 so higher concurrency actually reaches the TUI.
-        let tw0: Addr<TwitterSearchActor> = b.addr("twitter:ingest#0").expect("twitter addr"); // optional
-
-        let tui = TuiActor::new(llm_addr, chat_llm_addr, tw0, store_addr, shutdown.clone())?;
+        let r_tui = b.reserve::<TuiActor>("tui:main", 256);
+        let deps = TuiDeps::resolve(b, &routes);
+        let mut tui = TuiActor::new(
+            deps.llm,
+            deps.chat_llm,
+            deps.analysis,
+            deps.twitter,
+            store_addr,
+            shutdown.clone(),
+        )?;
+        if let Some(web) = deps.web_search {
+            tui = tui.with_web_search(web);
+        }
+        if let Some(max) = cfg.tui.as_ref().and_then(|t| t.max_claim_chars) {
+            tui = tui.with_max_claim_chars(max);
+        }
+        if let Some(secs) = cfg.tui.as_ref().and_then(|t| t.claim_budget_secs) {
+            tui = tui.with_claim_budget(Duration::from_secs(secs));
+        }
+        if let Some(fps) = cfg.tui.as_ref().and_then(|t| t.max_fps) {
+            tui = tui.with_max_fps(fps);
+        }
+        let tick_rate = cfg
+            .tui
+            .as_ref()
+            .and_then(|t| t.tick_ms)
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_TICK_RATE);
+        let animate = cfg.tui.as_ref().and_then(|t| t.animation).unwrap_or(true);
+        let id_slugs = cfg.tui.as_ref().and_then(|t| t.id_slugs).unwrap_or(true);
+        tui = tui
+            .with_tick_rate(tick_rate)
+            .with_animation(animate)
+            .with_id_slugs(id_slugs);
         b.start_reserved(r_tui, tui);
 
         let tui_addr: Addr<TuiActor> = b.addr("tui:main").unwrap();
-        spawn_tui_feeders(tui_addr, shutdown);
+        // Surface a bad token or an unreachable service now instead of on the first claim.
+        let tui = tui_addr.clone();
+        tokio::spawn(async move {
+            let report = readiness.probe_all().await;
+            for check in report.checks.iter().filter(|c| !c.healthy) {
+                tracing::warn!(
+                    check = %check.name,
+                    detail = check.detail.as_deref(),
+                    "readiness.unhealthy"
+                );
+            }
+            let _ = tui.send(TuiMsg::Readiness(report)).await;
+        });
+        spawn_tui_feeders_with(tui_addr, shutdown, CrosstermEvents, tick_rate);
     }
 
     Ok(())
@@ -211,18 +806,56 @@ pub async fn build_from_config(t: &mut Tether, cfg: NowhereConfig) -> Result<()>
 pub async fn build_llm_client(cfg: &LlmConfig) -> Result<Arc<dyn LlmClient + Send + Sync>> {
     match cfg {
         LlmConfig::Openai {
-            model, auth_token, ..
+            model,
+            auth_token,
+            system_prompt_override,
+            organization,
+            project,
+            request_timeout_secs,
+            ..
         } => {
             // FIXME: thread through configurable endpoint/temperature/max_tokens instead of relying on client defaults.
             // sync constructor
-            let client = OpenAiClient::new(auth_token.clone(), model.clone())?;
+            let mut client = OpenAiClient::new(auth_token.clone(), model.clone())?;
+            if let Some(prompt) = system_prompt_override {
+                client = client.with_system_prompt_override(prompt.clone());
+            }
+            if let Some(organization) = organization {
+                client = client.with_organization(organization.clone());
+            }
+            if let Some(project) = project {
+                client = client.with_project(project.clone());
+            }
+            if let Some(secs) = request_timeout_secs {
+                client = client.with_request_timeout(Duration::from_secs(*secs));
+            }
             Ok(Arc::new(client))
         }
         LlmConfig::Ollama {
-            model, endpoint, ..
+            model,
+            endpoint,
+            system_prompt_override,
+            startup_attempts,
+            startup_interval_ms,
+            startup_timeout_secs,
+            ..
         } => {
+            let defaults = StartupProbe::default();
+            let startup = StartupProbe {
+                attempts: startup_attempts.unwrap_or(defaults.attempts),
+                interval: startup_interval_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(defaults.interval),
+                timeout: startup_timeout_secs
+                    .map(Duration::from_secs)
+                    .unwrap_or(defaults.timeout),
+            };
             // FIXME: reuse a shared client per endpoint to avoid reconnecting for each actor instance.
-            let client = OllamaClient::new(endpoint.clone(), model.clone()).await?;
+            let mut client =
+                OllamaClient::connect(endpoint.clone(), model.clone(), startup).await?;
+            if let Some(prompt) = system_prompt_override {
+                client = client.with_system_prompt_override(prompt.clone());
+            }
             Ok(Arc::new(client))
         }
     }
@@ -244,14 +877,6 @@ mod db_env_tests {
             }
             Self { key, original }
         }
-        pub fn unset<K: Into<String>>(key: K) -> Self {
-            let key = key.into();
-            let original = std::env::var(&key).ok();
-            unsafe {
-                std::env::remove_var(&key);
-            }
-            Self { key, original }
-        }
     }
     impl Drop for EnvGuard {
         fn drop(&mut self) {
@@ -273,25 +898,49 @@ mod wiring_tests {
     use super::*;
     use nowhere_config::{ActorDetails, ActorSpec, LlmConfig, NowhereConfig};
 
+    fn llm_spec(id: &str, roles: Option<Vec<LlmRole>>) -> ActorSpec {
+        ActorSpec {
+            id: id.into(),
+            enabled: Some(true),
+            concurrency: Some(1),
+            details: ActorDetails::Llm {
+                config: LlmConfig::Openai {
+                    model: "gpt-4o-mini".into(),
+                    auth_token: "sk-test".into(),
+                    temperature: None,
+                    max_tokens: None,
+                    endpoint: "test".into(),
+                    system_prompt_override: None,
+                    organization: None,
+                    project: None,
+                    request_timeout_secs: None,
+                },
+                min_relevance: None,
+                coalesce_requests: None,
+                search_window_days: None,
+                pretty_prompt_json: None,
+                min_context_artifacts: None,
+                roles,
+                query_prompt: None,
+                query_cache_hours: None,
+                share_chat_rate_limit: None,
+                answer_language: None,
+                dead_letter: None,
+            },
+        }
+    }
+
     fn cfg_minimal() -> NowhereConfig {
         NowhereConfig {
             version: None,
+            tui: None,
+            store: None,
+            logging: None,
+            rate: None,
+            brave: None,
             // adjust if your struct has more fields
             actors: vec![
-                ActorSpec {
-                    id: "llm:main".into(),
-                    enabled: Some(true),
-                    concurrency: Some(1),
-                    details: ActorDetails::Llm {
-                        config: LlmConfig::Openai {
-                            model: "gpt-4o-mini".into(),
-                            auth_token: "sk-test".into(),
-                            temperature: None,
-                            max_tokens: None,
-                            endpoint: "test".into(),
-                        },
-                    },
-                },
+                llm_spec("llm:main", None),
                 ActorSpec {
                     id: "twitter:ingest".into(),
                     enabled: Some(true),
@@ -299,6 +948,12 @@ mod wiring_tests {
                     details: ActorDetails::Twitter {
                         config: nowhere_config::TwitterConfig {
                             auth_token: "bearer-test".into(), /* … */
+                            normalize_batch: None,
+                            max_pages: None,
+                            search_deadline_secs: None,
+                            exclude_retweets: None,
+                            exclude_replies: None,
+                            fields: None,
                         },
                     },
                 },
@@ -335,6 +990,10 @@ mod wiring_tests {
             b.addr::<ChatLlmActor>("llm:main#chat").is_some(),
             "chat llm actor"
         );
+        assert!(
+            b.addr::<AnalysisActor>("llm:main#analysis").is_some(),
+            "analysis actor"
+        );
 
         // Twitter workers pooled under the spec id with #i suffix
         assert!(b.addr::<TwitterSearchActor>("twitter:ingest#0").is_some());
@@ -343,4 +1002,258 @@ mod wiring_tests {
         // TUI started last
         assert!(b.addr::<nowhere_tui::TuiActor>("tui:main").is_some());
     }
+
+    #[tokio::test]
+    async fn readiness_reports_each_check_in_order() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let report = Readiness {
+            timeout: Duration::from_millis(100),
+            ..Readiness::new()
+        }
+        .with_db(pool)
+        .with_check("twitter:ingest", async { Ok(false) })
+        .with_check("llm:main", async {
+            Err(anyhow::anyhow!("connection refused"))
+        })
+        .with_check("brave", async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(true)
+        })
+        .probe_all()
+        .await;
+
+        let summary: Vec<_> = report
+            .checks
+            .iter()
+            .map(|c| (c.name.as_str(), c.healthy))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("db", true),
+                ("twitter:ingest", false),
+                ("llm:main", false),
+                ("brave", false),
+            ]
+        );
+        assert!(!report.is_ready());
+        assert_eq!(report.checks[0].detail, None);
+        assert_eq!(
+            report.checks[2].detail.as_deref(),
+            Some("connection refused")
+        );
+        assert!(
+            report.checks[3]
+                .detail
+                .as_deref()
+                .unwrap()
+                .starts_with("no answer")
+        );
+    }
+
+    #[test]
+    fn chat_role_routes_to_its_own_llm() {
+        let mut cfg = cfg_minimal();
+        cfg.actors.retain(|a| a.id != "llm:main");
+        cfg.actors.push(llm_spec(
+            "llm:cheap",
+            Some(vec![LlmRole::Normalizer, LlmRole::Query]),
+        ));
+        cfg.actors
+            .push(llm_spec("llm:strong", Some(vec![LlmRole::Chat])));
+
+        assert_eq!(
+            LlmRoutes::resolve(&cfg).unwrap(),
+            LlmRoutes {
+                normalizer: "llm:cheap".into(),
+                chat: "llm:strong".into(),
+                query: "llm:cheap".into(),
+            }
+        );
+    }
+
+    /// Ollama stub serving model `m` that answers every generate call with `answer`.
+    async fn ollama_stub(answer: &str) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "models": [{ "name": "m" }] })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "response": answer })),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn ollama_spec(id: &str, server: &wiremock::MockServer, roles: Vec<LlmRole>) -> ActorSpec {
+        let mut spec = llm_spec(id, Some(roles));
+        if let ActorDetails::Llm { config, .. } = &mut spec.details {
+            *config = LlmConfig::Ollama {
+                model: "m".into(),
+                endpoint: server.uri(),
+                temperature: None,
+                max_tokens: None,
+                system_prompt_override: None,
+                startup_attempts: Some(1),
+                startup_interval_ms: None,
+                startup_timeout_secs: None,
+            };
+        }
+        spec
+    }
+
+    #[tokio::test]
+    async fn chat_questions_reach_the_chat_role_llm() {
+        let _db = super::db_env_tests::EnvGuard::set("DATABASE_URL", "sqlite::memory:");
+        let cheap = ollama_stub("from the cheap model").await;
+        let strong = ollama_stub("from the strong model").await;
+        let mut cfg = cfg_minimal();
+        cfg.actors.retain(|a| a.id != "llm:main");
+        cfg.actors.push(ollama_spec(
+            "llm:cheap",
+            &cheap,
+            vec![LlmRole::Normalizer, LlmRole::Query],
+        ));
+        cfg.actors
+            .push(ollama_spec("llm:strong", &strong, vec![LlmRole::Chat]));
+
+        let mut t = Tether::new();
+        let services = start_services(&mut t, &cfg).await.expect("services start");
+        let deps = TuiDeps::resolve(t.builder_mut(), &services.routes);
+
+        let (reply, answer) = tokio::sync::oneshot::channel();
+        deps.chat_llm
+            .send(nowhere_actors::ChatCmd {
+                user_text: "Who ran the ferry?".into(),
+                k: 4,
+                reply,
+                claim: nowhere_actors::ClaimContext {
+                    id: uuid::Uuid::new_v4(),
+                    text: "The ferry ran on time.".into(),
+                    tags: Vec::new(),
+                },
+                language: None,
+            })
+            .await
+            .unwrap_or_else(|_| panic!("chat actor gone"));
+        let answer = tokio::time::timeout(Duration::from_secs(10), answer)
+            .await
+            .expect("chat answered")
+            .unwrap();
+
+        assert_eq!(answer.text, "from the strong model");
+        let generated = |requests: Vec<wiremock::Request>| {
+            requests
+                .iter()
+                .filter(|r| r.url.path() == "/api/generate")
+                .count()
+        };
+        assert_eq!(generated(strong.received_requests().await.unwrap()), 1);
+        assert_eq!(generated(cheap.received_requests().await.unwrap()), 0);
+    }
+
+    #[test]
+    fn a_single_llm_takes_every_role_whatever_its_id() {
+        let mut cfg = cfg_minimal();
+        cfg.actors[0] = llm_spec("llm:local", None);
+        let routes = LlmRoutes::resolve(&cfg).unwrap();
+        assert_eq!(routes.normalizer, "llm:local");
+        assert_eq!(routes.chat, "llm:local");
+        assert_eq!(routes.query, "llm:local");
+
+        // With two, an unclaimed role needs `llm:main` or an explicit owner.
+        cfg.actors
+            .push(llm_spec("llm:strong", Some(vec![LlmRole::Chat])));
+        let err = LlmRoutes::resolve(&cfg).unwrap_err();
+        assert!(err.to_string().contains("`normalizer`"), "{err}");
+    }
+
+    #[cfg(feature = "brave")]
+    #[test]
+    fn brave_search_options_come_from_config() {
+        let brave = BraveConfig {
+            api_key: "token".into(),
+            count: Some(30),
+            freshness: Some("pw".into()),
+            result_filter: Some("web,news".into()),
+            capture: None,
+        };
+        let options = brave_search_options(&brave).unwrap();
+        assert_eq!(options.count, Some(30));
+        assert_eq!(options.freshness, "pw".parse().unwrap());
+        assert_eq!(options.result_filter, Some("web,news".parse().unwrap()));
+
+        let stale = BraveConfig {
+            freshness: Some("fortnight".into()),
+            ..brave
+        };
+        let err = brave_search_options(&stale).unwrap_err();
+        assert!(err.to_string().contains("brave.freshness"), "{err}");
+    }
+
+    #[cfg(feature = "brave")]
+    #[test]
+    fn capture_block_sets_the_browser_settings() {
+        let capture = WebCaptureConfig {
+            archive_dir: "captures".into(),
+            browser_sessions: Some(4),
+            headless: None,
+            stealth: Some("Maximum".into()),
+        };
+        let settings = capture_settings(&capture).unwrap();
+        assert_eq!(settings.browser_sessions, 4);
+        assert!(settings.headless);
+        assert!(matches!(settings.stealth_level, StealthLevel::Maximum));
+
+        let unknown = WebCaptureConfig {
+            stealth: Some("ninja".into()),
+            ..capture
+        };
+        let err = capture_settings(&unknown).unwrap_err();
+        assert!(err.to_string().contains("brave.capture.stealth"), "{err}");
+    }
+
+    #[test]
+    fn query_template_must_mention_the_claim() {
+        let unset = query_prompt_from("llm:main", &QueryPromptConfig::default()).unwrap();
+        assert_eq!(unset, QueryPrompt::default());
+
+        let fixed = QueryPromptConfig {
+            system: None,
+            template: Some("Search for football news.".into()),
+        };
+        let err = query_prompt_from("llm:main", &fixed).unwrap_err();
+        assert!(err.to_string().contains("{claim}"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn missing_llm_actor_is_a_config_error() {
+        let mut t = Tether::new();
+        let mut cfg = cfg_minimal();
+        cfg.actors
+            .retain(|a| !matches!(a.details, ActorDetails::Llm { .. }));
+
+        let err = build_from_config(&mut t, cfg).await.unwrap_err();
+
+        assert!(
+            matches!(
+                err.downcast_ref::<NowhereError>(),
+                Some(NowhereError::Config(_))
+            ),
+            "{err:#}"
+        );
+        assert!(err.to_string().contains("no LLM configured"), "{err}");
+        assert!(t.builder_mut().addr::<StoreActor>("store:main").is_none());
+    }
 }
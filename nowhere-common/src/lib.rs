@@ -10,6 +10,9 @@
 //! - [`NowhereConfig`]: Top‑level runtime configuration
 //! - [`LlmConfig`]: Provider‑agnostic LLM configuration
 //! - [`observability`]: Centralised tracing/logging initialisation
+//! - [`urls`]: URL canonicalization used for cross-source dedup
+//! - [`slug`]: Short base62 spellings of UUIDs for the terminal
+//! - `testing` (feature `test-util`): Capturing tracing events in tests
 //! - [`NowhereError`] and [`Result`]: Shared error handling
 //! - Enums describing behavior such as [`StealthLevel`], [`ApprovalMode`],
 //!   and [`OutputFormat`]
@@ -29,6 +32,10 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 pub mod observability;
+pub mod slug;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod urls;
 
 /// Configuration for an LLM provider used by the platform.
 ///
@@ -45,6 +52,13 @@ pub enum LlmConfig {
     Gemini {
         api_key: String,
         model: String,
+        /// Harm category -> blocking threshold (e.g. `HARM_CATEGORY_DANGEROUS_CONTENT:
+        /// BLOCK_ONLY_HIGH`). Unlisted categories keep `BLOCK_MEDIUM_AND_ABOVE`.
+        #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+        safety_thresholds: std::collections::BTreeMap<String, String>,
+        /// Seconds before a generate request is abandoned. Defaults to 120.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_timeout_secs: Option<u64>,
     },
     #[cfg(feature = "openai")]
     OpenAi {
@@ -52,6 +66,9 @@ pub enum LlmConfig {
         model: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         base_url: Option<String>,
+        /// Seconds before a generate request is abandoned. Defaults to 120.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        request_timeout_secs: Option<u64>,
     },
     None,
 }
@@ -87,6 +104,7 @@ impl Default for LlmConfig {
 /// assert_eq!(cfg.max_concurrent_agents, 5);
 /// assert_eq!(cfg.default_timeout_secs, 60);
 /// assert!(!cfg.headless);
+/// assert_eq!(cfg.browser_sessions, 2);
 /// assert_eq!(cfg.max_rabbit_hole_depth, Some(3));
 /// assert!(matches!(cfg.stealth_level, StealthLevel::Balanced));
 /// assert!(matches!(cfg.approval_mode, ApprovalMode::Interactive));
@@ -114,6 +132,10 @@ pub struct NowhereConfig {
     pub stealth_level: StealthLevel,
     /// Whether to run browser automation without a visible window.
     pub headless: bool,
+    /// Browser sessions kept open and reused across page captures; also the number of
+    /// captures run at once. `0` opens a fresh session per capture instead.
+    #[serde(default = "default_browser_sessions")]
+    pub browser_sessions: usize,
     /// How potentially sensitive actions are approved.
     pub approval_mode: ApprovalMode,
     /// Preferred output format for rendered results.
@@ -131,6 +153,7 @@ impl Default for NowhereConfig {
             default_timeout_secs: 60,
             stealth_level: StealthLevel::Balanced,
             headless: false,
+            browser_sessions: default_browser_sessions(),
             approval_mode: ApprovalMode::Interactive,
             output_format: OutputFormat::Json,
             llm_config: LlmConfig::default(),
@@ -139,6 +162,10 @@ impl Default for NowhereConfig {
     }
 }
 
+fn default_browser_sessions() -> usize {
+    2
+}
+
 /// Browser automation stealth level.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StealthLevel {
@@ -9,7 +9,7 @@ use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
 use anyhow::Context;
-use chrono::Local;
+use chrono::{Local, NaiveDate, Utc};
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_appender::rolling;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
@@ -36,8 +36,14 @@ pub struct LogConfig {
     pub emit_stderr: bool,
     /// Preferred log encoding.
     pub format: LogFormat,
-    /// Default filter applied when `RUST_LOG` is unset.
+    /// Base filter. `RUST_LOG`, when set, is applied on top of it and of `targets`.
     pub default_filter: &'static str,
+    /// Per-target levels such as `("nowhere_http", "debug")` or `("sqlx", "warn")`.
+    pub targets: Vec<(String, String)>,
+    /// Keep at most this many daily files (including today's). `None` keeps any number.
+    pub max_files: Option<usize>,
+    /// Delete daily files older than this many days. `None` keeps them forever.
+    pub max_age_days: Option<u32>,
 }
 
 impl Default for LogConfig {
@@ -48,6 +54,9 @@ impl Default for LogConfig {
             emit_stderr: false,
             format: LogFormat::Text,
             default_filter: "info",
+            targets: Vec::new(),
+            max_files: None,
+            max_age_days: None,
         }
     }
 }
@@ -69,12 +78,33 @@ pub fn init_logging(config: LogConfig) -> anyhow::Result<PathBuf> {
     let today = Local::now().format("%Y-%m-%d").to_string();
     let full_path = resolved_dir.join(&today).join(&log_filename);
 
-    let appender = rolling::daily(resolved_dir, log_filename);
+    let pruned = prune_logs(
+        &resolved_dir,
+        &log_filename,
+        config.max_files,
+        config.max_age_days,
+        Utc::now().date_naive(),
+    )
+    .unwrap_or_default();
+
+    // The appender also prunes by count whenever it rolls over to a new day.
+    let mut builder = rolling::Builder::new()
+        .rotation(rolling::Rotation::DAILY)
+        .filename_prefix(&log_filename);
+    if let Some(max_files) = config.max_files {
+        builder = builder.max_log_files(max_files.max(1));
+    }
+    let appender = builder
+        .build(&resolved_dir)
+        .context("failed to create log appender")?;
     let (writer, guard) = tracing_appender::non_blocking(appender);
     let _ = LOG_GUARD.set(guard);
 
-    let env_filter =
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(config.default_filter));
+    let (env_filter, rejected) = compose_filter(
+        config.default_filter,
+        &config.targets,
+        std::env::var(EnvFilter::DEFAULT_ENV).ok().as_deref(),
+    );
 
     match (config.format, config.emit_stderr) {
         (LogFormat::Text, false) => {
@@ -109,10 +139,81 @@ pub fn init_logging(config: LogConfig) -> anyhow::Result<PathBuf> {
         }
     }
 
+    if !pruned.is_empty() {
+        tracing::info!(removed = pruned.len(), "logging.pruned_old_files");
+    }
+    for (directive, error) in rejected {
+        tracing::warn!(directive, error, "logging.directive_ignored");
+    }
+
     let _ = LOG_PATH.set(full_path.clone());
     Ok(full_path)
 }
 
+/// `default_filter`, then each `target=level` from `targets`, then the `RUST_LOG`
+/// directives, so later layers win for the same target. Malformed directives are skipped
+/// and returned with their parse errors, to be logged once the subscriber is up.
+fn compose_filter(
+    default_filter: &str,
+    targets: &[(String, String)],
+    rust_log: Option<&str>,
+) -> (EnvFilter, Vec<(String, String)>) {
+    let mut filter = EnvFilter::new(default_filter);
+    let directives = targets
+        .iter()
+        .map(|(target, level)| format!("{target}={level}"))
+        .chain(
+            rust_log
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+        );
+    let mut rejected = Vec::new();
+    for directive in directives {
+        match directive.parse() {
+            Ok(parsed) => filter = filter.add_directive(parsed),
+            Err(e) => rejected.push((directive, e.to_string())),
+        }
+    }
+    (filter, rejected)
+}
+
+/// Delete the daily files (`<file_name>.YYYY-MM-DD`) in `dir` that are older than
+/// `max_age_days` before `today`, then all but the newest `max_files`. Other files are
+/// left alone. Returns the removed paths.
+pub fn prune_logs(
+    dir: &Path,
+    file_name: &str,
+    max_files: Option<usize>,
+    max_age_days: Option<u32>,
+    today: NaiveDate,
+) -> std::io::Result<Vec<PathBuf>> {
+    let prefix = format!("{file_name}.");
+    let mut dated: Vec<(NaiveDate, PathBuf)> = std::fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let name = path.file_name()?.to_str()?;
+            let date = NaiveDate::parse_from_str(name.strip_prefix(&prefix)?, "%Y-%m-%d").ok()?;
+            Some((date, path))
+        })
+        .collect();
+    // Newest first, so the count limit keeps the most recent files.
+    dated.sort_by_key(|(date, _)| std::cmp::Reverse(*date));
+
+    let mut removed = Vec::new();
+    for (idx, (date, path)) in dated.into_iter().enumerate() {
+        let too_old = max_age_days.is_some_and(|days| (today - date).num_days() > i64::from(days));
+        let too_many = max_files.is_some_and(|max| idx >= max.max(1));
+        if too_old || too_many {
+            std::fs::remove_file(&path)?;
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
+
 fn resolve_log_dir(app_name: &str, explicit: Option<&Path>) -> PathBuf {
     if let Some(dir) = explicit {
         return expand_home(dir);
@@ -144,3 +245,130 @@ fn default_data_dir(app_name: &str) -> PathBuf {
         PathBuf::from(".").join(app_name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_overrides_raise_one_target_and_leave_the_rest_at_the_default() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Buf(Arc<Mutex<Vec<u8>>>);
+        impl std::io::Write for Buf {
+            fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(bytes);
+                Ok(bytes.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let (filter, rejected) = compose_filter(
+            "info",
+            &[
+                ("nowhere_http".into(), "debug".into()),
+                ("sqlx".into(), "warn".into()),
+            ],
+            None,
+        );
+        assert!(rejected.is_empty(), "{rejected:?}");
+        let buf = Buf::default();
+        let writer = buf.clone();
+        let subscriber = tracing_subscriber::registry().with(filter).with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(move || writer.clone()),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!(target: "nowhere_http", "http-debug");
+            tracing::debug!(target: "nowhere_actors", "actors-debug");
+            tracing::info!(target: "nowhere_actors", "actors-info");
+            tracing::info!(target: "sqlx", "sqlx-info");
+            tracing::warn!(target: "sqlx", "sqlx-warn");
+        });
+
+        let out = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        for shown in ["http-debug", "actors-info", "sqlx-warn"] {
+            assert!(out.contains(shown), "missing {shown}: {out}");
+        }
+        for hidden in ["actors-debug", "sqlx-info"] {
+            assert!(!out.contains(hidden), "leaked {hidden}: {out}");
+        }
+    }
+
+    #[test]
+    fn rust_log_is_layered_over_the_configured_targets() {
+        let (filter, rejected) = compose_filter(
+            "info",
+            &[("nowhere_http".into(), "debug".into())],
+            Some("nowhere_http=trace, bad==directive"),
+        );
+        let rendered = filter.to_string();
+        assert!(rendered.contains("nowhere_http=trace"), "{rendered}");
+        assert!(!rendered.contains("nowhere_http=debug"), "{rendered}");
+        let rejected: Vec<_> = rejected.iter().map(|(d, _)| d.as_str()).collect();
+        assert_eq!(rejected, ["bad==directive"]);
+    }
+
+    #[test]
+    fn pruning_drops_files_past_the_retention_window() {
+        let dir = std::env::temp_dir().join(format!("nowhere-logs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "nowhere.log.2026-10-16",
+            "nowhere.log.2026-10-15",
+            "nowhere.log.2026-10-10",
+            "nowhere.log.2026-09-01",
+            "nowhere.log.2025-12-31",
+            "other.log.2020-01-01",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.join(name), "x").unwrap();
+        }
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        // Retention is opt-in: the defaults delete nothing.
+        let defaults = LogConfig::default();
+        let kept = prune_logs(
+            &dir,
+            "nowhere.log",
+            defaults.max_files,
+            defaults.max_age_days,
+            today,
+        )
+        .unwrap();
+        assert!(kept.is_empty(), "{kept:?}");
+
+        let mut removed = prune_logs(&dir, "nowhere.log", None, Some(14), today).unwrap();
+        removed.sort();
+        assert_eq!(
+            removed,
+            [
+                dir.join("nowhere.log.2025-12-31"),
+                dir.join("nowhere.log.2026-09-01"),
+            ]
+        );
+
+        let removed = prune_logs(&dir, "nowhere.log", Some(2), None, today).unwrap();
+        assert_eq!(removed, [dir.join("nowhere.log.2026-10-10")]);
+
+        let mut left: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(
+            left,
+            [
+                "notes.txt",
+                "nowhere.log.2026-10-15",
+                "nowhere.log.2026-10-16",
+                "other.log.2020-01-01",
+            ]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+//! Short base62 spellings of UUIDs for typing and reading ids in the terminal.
+//!
+//! A slug is the UUID's 128 bits in base62, most significant digit first and padded to
+//! [`SLUG_LEN`] characters, so slugs sort like the UUIDs they encode. That makes a slug
+//! prefix a contiguous range of UUIDs ([`prefix_range`]), which the store can look up
+//! directly instead of scanning every id.
+use uuid::Uuid;
+
+/// Digits in ascending ASCII order, which keeps slug order equal to UUID order.
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Characters in a full slug; enough for any 128-bit value.
+pub const SLUG_LEN: usize = 22;
+
+/// Characters [`short`] shows, which is plenty to tell a store's claims apart.
+pub const SHORT_LEN: usize = 8;
+
+/// The full slug for `id`.
+pub fn encode(id: Uuid) -> String {
+    let mut n = id.as_u128();
+    let mut digits = [b'0'; SLUG_LEN];
+    for d in digits.iter_mut().rev() {
+        *d = ALPHABET[(n % 62) as usize];
+        n /= 62;
+    }
+    digits.iter().map(|&d| d as char).collect()
+}
+
+/// The first [`SHORT_LEN`] characters of `id`'s slug, for listings.
+pub fn short(id: Uuid) -> String {
+    let mut slug = encode(id);
+    slug.truncate(SHORT_LEN);
+    slug
+}
+
+/// The UUID a full slug encodes, or `None` if `slug` is not one.
+pub fn decode(slug: &str) -> Option<Uuid> {
+    if slug.len() != SLUG_LEN {
+        return None;
+    }
+    let (lo, hi) = prefix_range(slug)?;
+    (lo == hi).then_some(lo)
+}
+
+/// The smallest and largest UUIDs whose slugs start with `prefix`, or `None` when no
+/// UUID's slug can (empty, too long, or not base62).
+pub fn prefix_range(prefix: &str) -> Option<(Uuid, Uuid)> {
+    if prefix.is_empty() || prefix.len() > SLUG_LEN {
+        return None;
+    }
+    let (mut lo, mut hi) = (Some(0u128), Some(0u128));
+    for c in prefix.bytes() {
+        let d = digit(c)? as u128;
+        let step = |n: Option<u128>| n?.checked_mul(62)?.checked_add(d);
+        lo = step(lo);
+        hi = step(hi);
+    }
+    // Pad the low end with the smallest digit and the high end with the largest.
+    for _ in prefix.len()..SLUG_LEN {
+        lo = lo.and_then(|n| n.checked_mul(62));
+        hi = hi.and_then(|n| n.checked_mul(62)?.checked_add(61));
+    }
+    let lo = lo?;
+    Some((
+        Uuid::from_u128(lo),
+        Uuid::from_u128(hi.unwrap_or(u128::MAX)),
+    ))
+}
+
+/// The smallest and largest UUIDs whose hex spelling starts with `prefix`, hyphenated
+/// (`3f2a9c1e-4b`) or simple (`3f2a9c1e4b`), or `None` when `prefix` is not one.
+pub fn uuid_prefix_range(prefix: &str) -> Option<(Uuid, Uuid)> {
+    let hyphenated = prefix.contains('-');
+    let mut hex = String::with_capacity(32);
+    for (i, c) in prefix.chars().enumerate() {
+        match c {
+            '-' if [8, 13, 18, 23].contains(&i) => {}
+            c if c.is_ascii_hexdigit() && !(hyphenated && [8, 13, 18, 23].contains(&i)) => {
+                hex.push(c)
+            }
+            _ => return None,
+        }
+    }
+    if hex.is_empty() || hex.len() > 32 {
+        return None;
+    }
+    let pad = |fill: char| {
+        let padded: String = hex
+            .chars()
+            .chain(std::iter::repeat(fill))
+            .take(32)
+            .collect();
+        u128::from_str_radix(&padded, 16).ok().map(Uuid::from_u128)
+    };
+    Some((pad('0')?, pad('f')?))
+}
+
+fn digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'Z' => Some(c - b'A' + 10),
+        b'a'..=b'z' => Some(c - b'a' + 36),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugs_round_trip_and_keep_uuid_order() {
+        let mut ids: Vec<Uuid> = (0..200).map(|_| Uuid::new_v4()).collect();
+        ids.extend([Uuid::nil(), Uuid::max()]);
+        for id in &ids {
+            let slug = encode(*id);
+            assert_eq!(slug.len(), SLUG_LEN);
+            assert_eq!(decode(&slug), Some(*id), "{slug}");
+        }
+        assert_eq!(encode(Uuid::nil()), "0".repeat(SLUG_LEN));
+
+        let mut by_slug = ids.clone();
+        by_slug.sort_by_key(|id| encode(*id));
+        ids.sort();
+        assert_eq!(by_slug, ids);
+
+        assert_eq!(decode("not a slug"), None);
+        assert_eq!(decode(&"z".repeat(SLUG_LEN)), None, "past the largest UUID");
+    }
+
+    #[test]
+    fn prefix_range_covers_exactly_the_ids_with_that_prefix() {
+        let id = Uuid::new_v4();
+        let slug = encode(id);
+        for len in 1..=SLUG_LEN {
+            let (lo, hi) = prefix_range(&slug[..len]).unwrap();
+            assert!(lo <= id && id <= hi, "{len}");
+            assert!(encode(lo).starts_with(&slug[..len]));
+            assert!(encode(hi).starts_with(&slug[..len]));
+        }
+        assert_eq!(prefix_range(&slug), Some((id, id)));
+        assert_eq!(short(id), slug[..SHORT_LEN]);
+
+        // The largest UUID's slug starts with `7`, so nothing starts with `8` or above.
+        assert!(encode(Uuid::max()).starts_with('7'));
+        assert_eq!(
+            prefix_range("7").map(|(_, hi)| hi),
+            Some(Uuid::max()),
+            "clamped at the largest UUID"
+        );
+        assert_eq!(prefix_range("8"), None);
+        assert_eq!(prefix_range(""), None);
+        assert_eq!(prefix_range("ab-c"), None);
+    }
+
+    #[test]
+    fn uuid_prefix_range_accepts_hyphenated_and_simple_hex() {
+        let id = Uuid::new_v4();
+        let hyphenated = id.to_string();
+        let simple = id.simple().to_string();
+        for len in [1, 8, 9, 14, 36] {
+            let (lo, hi) = uuid_prefix_range(&hyphenated[..len]).unwrap();
+            assert!(lo <= id && id <= hi, "{len}");
+            assert!(lo.to_string().starts_with(&hyphenated[..len]));
+            assert!(hi.to_string().starts_with(&hyphenated[..len]));
+        }
+        assert_eq!(uuid_prefix_range(&hyphenated), Some((id, id)));
+        assert_eq!(uuid_prefix_range(&simple), Some((id, id)));
+        assert_eq!(
+            uuid_prefix_range(&simple[..10]),
+            uuid_prefix_range(&hyphenated[..11])
+        );
+
+        assert_eq!(uuid_prefix_range(""), None);
+        assert_eq!(uuid_prefix_range("3f2a-9c"), None, "hyphen out of place");
+        assert_eq!(uuid_prefix_range("3f2a9c1eX"), None);
+        assert_eq!(uuid_prefix_range(&format!("{simple}0")), None);
+    }
+}
@@ -0,0 +1,86 @@
+//! Test helpers shared across crates (with the `test-util` feature).
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::subscriber::DefaultGuard;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+
+/// One captured event: field name to its rendered value (`%`/`?` values as formatted,
+/// strings unquoted).
+pub type EventFields = HashMap<String, String>;
+
+/// Records the fields of every event named `name` on this thread until dropped.
+///
+/// An event matches when `name` is its explicit `name:`, its `target:` or its message.
+pub struct CapturedEvents {
+    seen: Arc<Mutex<Vec<EventFields>>>,
+    _guard: DefaultGuard,
+}
+
+pub fn capture_events(name: &'static str) -> CapturedEvents {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let layer = Capture {
+        name,
+        seen: seen.clone(),
+    };
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+    CapturedEvents { seen, _guard }
+}
+
+impl CapturedEvents {
+    /// Matching events so far, oldest first.
+    pub fn events(&self) -> Vec<EventFields> {
+        self.seen.lock().unwrap().clone()
+    }
+}
+
+struct Capture {
+    name: &'static str,
+    seen: Arc<Mutex<Vec<EventFields>>>,
+}
+
+struct Fields(EventFields);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().into(), format!("{value:?}"));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+}
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for Capture {
+    fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+        let mut fields = Fields(EventFields::new());
+        event.record(&mut fields);
+        let meta = event.metadata();
+        if meta.name() == self.name
+            || meta.target() == self.name
+            || fields.0.get("message").map(String::as_str) == Some(self.name)
+        {
+            self.seen.lock().unwrap().push(fields.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captures_events_by_name_target_or_message() {
+        let captured = capture_events("probe");
+        tracing::info!(name: "probe", count = 3, "by name");
+        tracing::info!(target: "probe", body = %"{}", "by target");
+        tracing::info!(label = "x", "probe");
+        tracing::info!("unrelated");
+
+        let events = captured.events();
+        assert_eq!(events.len(), 3, "{events:?}");
+        assert_eq!(events[0]["count"], "3");
+        assert_eq!(events[1]["body"], "{}");
+        assert_eq!(events[2]["label"], "x");
+    }
+}
@@ -0,0 +1,35 @@
+//! URL normalization shared by ingest sources.
+//!
+//! Twitter and web discovery can surface the same page (a tweet linking an article, and the
+//! article itself); keying both on [`url_key`] lets the store recognise them as one source.
+use url::Url;
+
+/// Simple normalization key for deduping (drop fragment, trim trailing slash).
+pub fn url_key(u: &Url) -> String {
+    let mut clone = u.clone();
+    clone.set_fragment(None);
+    clone.as_str().trim_end_matches('/').to_string()
+}
+
+/// Parse `raw` and return its [`url_key`], or `None` unless it is an http(s) URL.
+pub fn canonical_url(raw: &str) -> Option<String> {
+    let u = Url::parse(raw.trim()).ok()?;
+    match u.scheme() {
+        "http" | "https" => Some(url_key(&u)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_url_ignores_fragment_trailing_slash_and_host_case() {
+        let a = canonical_url("https://Example.com/story/#comments").unwrap();
+        let b = canonical_url("https://example.com/story").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(canonical_url("mailto:someone@example.com"), None);
+        assert_eq!(canonical_url("not a url"), None);
+    }
+}
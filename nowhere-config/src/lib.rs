@@ -13,6 +13,126 @@ const MAXIMUM_ENV_EXPANSION_DEPTH: usize = 8;
 pub struct NowhereConfig {
     pub version: Option<String>,
     pub actors: Vec<ActorSpec>,
+    #[serde(default)]
+    pub tui: Option<TuiConfig>,
+    #[serde(default)]
+    pub store: Option<StoreConfig>,
+    #[serde(default)]
+    pub logging: Option<LoggingConfig>,
+    #[serde(default)]
+    pub rate: Option<RateConfig>,
+    #[serde(default)]
+    pub brave: Option<BraveConfig>,
+}
+
+/// Brave Search. Apps built with the `brave` feature check the token at startup and search
+/// the web for each claim alongside Twitter.
+#[derive(Debug, Deserialize)]
+pub struct BraveConfig {
+    /// Subscription token, sent as `X-Subscription-Token`.
+    pub api_key: String,
+    /// Results per search, at most 20. Unset leaves Brave's default.
+    #[serde(default)]
+    pub count: Option<u32>,
+    /// How recent results must be: `pd`, `pw`, `pm` or `py`. Unset accepts any age.
+    #[serde(default)]
+    pub freshness: Option<String>,
+    /// Verticals to search, comma-separated from `web`, `news` and `videos`. Unset is web.
+    #[serde(default)]
+    pub result_filter: Option<String>,
+    /// Render and archive each hit's page before normalization. Unset skips capture.
+    #[serde(default)]
+    pub capture: Option<WebCaptureConfig>,
+}
+
+/// Browser capture of web hits: HTML and a screenshot per page, kept as evidence.
+#[derive(Debug, Deserialize)]
+pub struct WebCaptureConfig {
+    /// Directory the HTML and screenshots are written to.
+    pub archive_dir: String,
+    /// Browser sessions kept open and reused; also how many captures run at once. `0`
+    /// opens a fresh session per capture. Defaults to 2.
+    #[serde(default)]
+    pub browser_sessions: Option<usize>,
+    /// Run the browser without a window. Defaults to true.
+    #[serde(default)]
+    pub headless: Option<bool>,
+    /// Evasions applied to each page: `lightweight`, `balanced` or `maximum`. Defaults to
+    /// balanced.
+    #[serde(default)]
+    pub stealth: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct RateConfig {
+    /// File to save rate-limit bucket levels to on shutdown and restore them from on
+    /// startup, so a restart does not reset the limits. Unset keeps them in memory only.
+    #[serde(default)]
+    pub state_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct LoggingConfig {
+    /// Per-target levels, e.g. `nowhere_http: debug`. `RUST_LOG` still wins when set.
+    #[serde(default)]
+    pub targets: std::collections::BTreeMap<String, String>,
+    /// Keep at most this many daily log files.
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    /// Delete daily log files older than this many days. Unset (the default) keeps them.
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct StoreConfig {
+    /// Artifact writes allowed in flight at once; normalization waits for a free slot
+    /// before handing the store an upsert. Defaults to 1.
+    #[serde(default)]
+    pub write_concurrency: Option<usize>,
+    /// Stop collecting artifacts for a claim once this many are stored. Unset is unlimited.
+    #[serde(default)]
+    pub max_artifacts_per_claim: Option<usize>,
+    /// Delete artifacts (and their page captures) stored more than this many days ago,
+    /// checked hourly. Unset or `0` keeps everything.
+    #[serde(default)]
+    pub artifact_ttl_days: Option<u32>,
+    /// Characters of each artifact's reasoning and provenance that searches return, e.g.
+    /// to synthesis prompts. Longer gives the model more context per artifact and costs
+    /// more prompt tokens; `0` returns the full text. Defaults to 2000. Chat loads its
+    /// artifacts separately and always sees at most 2000 characters of each.
+    #[serde(default)]
+    pub search_text_chars: Option<usize>,
+    /// Most words a search's full-text query keeps from its input (a chat question, say);
+    /// longer input keeps its leading words. Defaults to 20.
+    #[serde(default)]
+    pub fts_max_terms: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TuiConfig {
+    /// Longest accepted claim, in characters; longer claims are truncated. Defaults to 2000.
+    #[serde(default)]
+    pub max_claim_chars: Option<usize>,
+    /// Seconds a claim may keep searching and normalizing before it stops. Defaults to 900.
+    #[serde(default)]
+    pub claim_budget_secs: Option<u64>,
+    /// Milliseconds between UI ticks, at least 1. Defaults to 80.
+    #[serde(default)]
+    pub tick_ms: Option<u64>,
+    /// Most redraws per second; changes arriving faster are drawn together. Redraws only
+    /// happen on ticks, so this only has an effect below `1000 / tick_ms` (12.5 at the
+    /// default tick). Defaults to 30.
+    #[serde(default)]
+    pub max_fps: Option<u32>,
+    /// `false` swaps the braille spinner for a static `...` and skips idle redraws
+    /// (also set by `--no-animation`). Defaults to true.
+    #[serde(default)]
+    pub animation: Option<bool>,
+    /// `false` lists claim ids as full UUIDs instead of short base62 slugs; commands take
+    /// either form. Defaults to true.
+    #[serde(default)]
+    pub id_slugs: Option<bool>,
 }
 
 /// Shared fields + the per-kind “details”
@@ -35,12 +155,111 @@ pub enum ActorDetails {
     Twitter { config: TwitterConfig },
 
     #[serde(rename = "llm")]
-    Llm { config: LlmConfig },
+    Llm {
+        config: LlmConfig,
+        /// Artifacts whose normalized `relevance_score` falls below this are not stored.
+        /// Between 0.0 and 1.0, like the score.
+        #[serde(default)]
+        min_relevance: Option<f64>,
+        /// Share one provider call between identical prompts that are in flight at the
+        /// same time (e.g. retweets of one tweet). Defaults to false.
+        #[serde(default)]
+        coalesce_requests: Option<bool>,
+        /// Days back to search when the model's dates are missing or invalid. Defaults to 7.
+        #[serde(default)]
+        search_window_days: Option<u32>,
+        /// Indent the JSON embedded in prompts, for reading them while debugging. Defaults
+        /// to false: compact JSON costs fewer tokens.
+        #[serde(default)]
+        pretty_prompt_json: Option<bool>,
+        /// Chat answers drawing on fewer artifacts than this get a "limited evidence"
+        /// caveat. Defaults to 0 (never).
+        #[serde(default)]
+        min_context_artifacts: Option<usize>,
+        /// Work this LLM takes on when several are configured. An unclaimed role falls
+        /// back to `llm:main`, or to the only LLM when there is just one.
+        #[serde(default)]
+        roles: Option<Vec<LlmRole>>,
+        /// Replaces the prompts used to turn a claim into a search query.
+        #[serde(default)]
+        query_prompt: Option<QueryPromptConfig>,
+        /// Reuse the search query built for identical claim text for this many hours
+        /// instead of asking the model again (`/research --fresh` rebuilds it). Defaults
+        /// to 0 (off).
+        #[serde(default)]
+        query_cache_hours: Option<u32>,
+        /// Draw chat requests from the normalizer's rate bucket, ahead of any queued
+        /// normalization, instead of giving chat a bucket of its own. Defaults to false.
+        #[serde(default)]
+        share_chat_rate_limit: Option<bool>,
+        /// Language chat answers are written in, e.g. `Spanish`; `/lang` overrides it for
+        /// a session. Unset leaves it to the model.
+        #[serde(default)]
+        answer_language: Option<String>,
+        /// Keep artifacts whose normalization reply can't be parsed, with the reply, in the
+        /// store's `normalization_dead_letter` table for reprocessing. Defaults to false.
+        #[serde(default)]
+        dead_letter: Option<bool>,
+    },
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct QueryPromptConfig {
+    /// System prompt; unset keeps the provider's OSINT analyst prompt.
+    #[serde(default)]
+    pub system: Option<String>,
+    /// User prompt; `{claim}` is replaced by the claim text. It must still ask for a JSON
+    /// object with `query`, `date_from` and `date_to`. Unset keeps the built-in directions.
+    #[serde(default)]
+    pub template: Option<String>,
+}
+
+/// What an LLM actor is used for; see `ActorDetails::Llm::roles`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmRole {
+    /// Normalizes the artifacts the Twitter workers fetch.
+    Normalizer,
+    /// Answers follow-up questions and runs the analysis helpers.
+    Chat,
+    /// Builds the search query for a new claim.
+    Query,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct TwitterConfig {
     pub auth_token: String,
+    /// Tweets per normalization prompt; unset or `1` normalizes one tweet per LLM call.
+    #[serde(default)]
+    pub normalize_batch: Option<usize>,
+    /// Result pages to follow per search via `next_token`; unset fetches a single page.
+    #[serde(default)]
+    pub max_pages: Option<u32>,
+    /// Seconds a search may spend across all its pages before it stops and reports what
+    /// it gathered as partial; unset leaves only the per-request timeouts.
+    #[serde(default)]
+    pub search_deadline_secs: Option<u64>,
+    /// Leave retweets out of searches (`-is:retweet`). Defaults to false.
+    #[serde(default)]
+    pub exclude_retweets: Option<bool>,
+    /// Leave replies out of searches (`-is:reply`). Defaults to false.
+    #[serde(default)]
+    pub exclude_replies: Option<bool>,
+    /// Overrides for the requested field/expansion lists; unset lists keep the defaults.
+    #[serde(default)]
+    pub fields: Option<TwitterFieldsConfig>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TwitterFieldsConfig {
+    #[serde(default)]
+    pub tweet: Option<Vec<String>>,
+    #[serde(default)]
+    pub user: Option<Vec<String>>,
+    #[serde(default)]
+    pub media: Option<Vec<String>>,
+    #[serde(default)]
+    pub expansions: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -55,6 +274,18 @@ pub enum LlmConfig {
         max_tokens: Option<u32>,
         #[serde(default = "default_openai_endpoint")]
         endpoint: String,
+        /// Replaces the built-in analyst system prompt.
+        #[serde(default)]
+        system_prompt_override: Option<String>,
+        /// Sent as `OpenAI-Organization`.
+        #[serde(default)]
+        organization: Option<String>,
+        /// Sent as `OpenAI-Project`.
+        #[serde(default)]
+        project: Option<String>,
+        /// Seconds before a generate request is abandoned. Defaults to 120.
+        #[serde(default)]
+        request_timeout_secs: Option<u64>,
     },
     Ollama {
         model: String,
@@ -64,6 +295,18 @@ pub enum LlmConfig {
         temperature: Option<f32>,
         #[serde(default)]
         max_tokens: Option<u32>,
+        /// Replaces the built-in analyst system prompt.
+        #[serde(default)]
+        system_prompt_override: Option<String>,
+        /// Probes of a server that is still starting before giving up. Defaults to 5.
+        #[serde(default)]
+        startup_attempts: Option<u32>,
+        /// Milliseconds before re-probing, doubling after each failure. Defaults to 500.
+        #[serde(default)]
+        startup_interval_ms: Option<u64>,
+        /// Longest the startup wait may take, in seconds. Defaults to 15.
+        #[serde(default)]
+        startup_timeout_secs: Option<u64>,
     },
 }
 
@@ -76,21 +319,19 @@ fn default_ollama_endpoint() -> String {
 
 fn expand_env_in_value(v: &mut Value) {
     match v {
-        Value::String(s) => {
-            if s.contains('$') {
-                let mut cur = std::mem::take(s);
-                for _ in 0..MAXIMUM_ENV_EXPANSION_DEPTH {
-                    let expanded = match shellexpand::env(&cur) {
-                        Ok(cow) => cow.into_owned(),
-                        Err(_) => cur.clone(),
-                    };
-                    if expanded == cur {
-                        break;
-                    }
-                    cur = expanded;
+        Value::String(s) if s.contains('$') => {
+            let mut cur = std::mem::take(s);
+            for _ in 0..MAXIMUM_ENV_EXPANSION_DEPTH {
+                let expanded = match shellexpand::env(&cur) {
+                    Ok(cow) => cow.into_owned(),
+                    Err(_) => cur.clone(),
+                };
+                if expanded == cur {
+                    break;
                 }
-                *s = cur;
+                cur = expanded;
             }
+            *s = cur;
         }
         Value::Array(arr) => arr.iter_mut().for_each(expand_env_in_value),
         Value::Object(obj) => obj.values_mut().for_each(expand_env_in_value),
@@ -205,6 +446,7 @@ impl NowhereConfigLoader {
     ///             endpoint,
     ///             ..
     ///         },
+    ///         ..
     ///     } => {
     ///         assert_eq!(model, "gpt-4o");
     ///         assert_eq!(auth_token, "injected-from-env");
@@ -226,16 +468,41 @@ impl NowhereConfigLoader {
         // Deserialize into your strongly-typed config
         let typed: NowhereConfig =
             serde_json::from_value(v).map_err(|e| config::ConfigError::Message(e.to_string()))?;
+        typed.validate()?;
 
         Ok(typed)
     }
 }
 
+impl NowhereConfig {
+    /// Reject values that parse but would misbehave at runtime.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.tui.as_ref().and_then(|t| t.tick_ms) == Some(0) {
+            return Err(ConfigError::Message(
+                "tui.tick_ms must be at least 1".into(),
+            ));
+        }
+        for actor in &self.actors {
+            if let ActorDetails::Llm {
+                min_relevance: Some(min),
+                ..
+            } = actor.details
+                && !(0.0..=1.0).contains(&min)
+            {
+                return Err(ConfigError::Message(format!(
+                    "{}: min_relevance must be between 0.0 and 1.0, got {min}",
+                    actor.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
-    use temp_env;
 
     #[test]
     fn expands_simple_string() {
@@ -296,6 +563,37 @@ mod tests {
         });
     }
 
+    #[test]
+    fn zero_tick_is_rejected_at_load() {
+        let err = NowhereConfigLoader::new()
+            .with_yaml_str("actors: []\ntui:\n  tick_ms: 0\n")
+            .load()
+            .unwrap_err();
+        assert!(err.to_string().contains("tick_ms"), "{err}");
+    }
+
+    #[test]
+    fn out_of_range_min_relevance_is_rejected_at_load() {
+        let yaml = |min: &str| {
+            format!(
+                "actors:\n  - kind: llm\n    id: \"llm:main\"\n    min_relevance: {min}\n    config:\n      provider: ollama\n      model: m\n"
+            )
+        };
+        for bad in ["1.5", "-0.1"] {
+            let err = NowhereConfigLoader::new()
+                .with_yaml_str(&yaml(bad))
+                .load()
+                .unwrap_err();
+            assert!(err.to_string().contains("min_relevance"), "{bad}: {err}");
+        }
+        for good in ["0.0", "0.6", "1.0"] {
+            NowhereConfigLoader::new()
+                .with_yaml_str(&yaml(good))
+                .load()
+                .unwrap_or_else(|err| panic!("{good}: {err}"));
+        }
+    }
+
     #[test]
     fn unknown_vars_are_left_as_is() {
         let mut v = json!("hi-${DOES_NOT_EXIST}");
@@ -9,6 +9,12 @@ use tokio::time::sleep;
 /// Produces human‑like delays and typing behavior to reduce automation signals.
 pub struct BehavioralEngine {}
 
+impl Default for BehavioralEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BehavioralEngine {
     pub fn new() -> Self {
         Self {}
@@ -1,16 +1,13 @@
 use crate::nowhere_browser::{
     behavioral::BehavioralEngine,
-    fingerprint::{FingerprintManager, UserAgentManager},
-    page::NowherePage,
+    fingerprint::UserAgentManager,
+    page::{NowherePage, SelectorCache},
     stealth::{build_stealth_arguments, StealthProfile},
 };
-use anyhow::{anyhow, Result};
-use async_trait::async_trait;
+use anyhow::Result;
 use fantoccini::{Client, ClientBuilder};
-use nowhere_common::StealthLevel;
 use serde_json::json;
 use std::collections::HashMap;
-use url::Url;
 use webdriver::capabilities::Capabilities;
 
 /// Thin wrapper around a `fantoccini` WebDriver client with stealth and
@@ -20,6 +17,7 @@ pub struct NowhereDriver {
     pub behavioral_engine: BehavioralEngine,
     pub user_agent_manager: UserAgentManager,
     pub stealth_profile: StealthProfile,
+    pub selector_cache: Option<SelectorCache>,
 }
 
 impl NowhereDriver {
@@ -30,9 +28,18 @@ impl NowhereDriver {
     /// FIXME(config): respect `NOWHERE_WEBDRIVER_URL` if set to support Gecko
     /// or remote endpoints, aligning docs with behavior.
     pub async fn new(headless: bool, stealth_profile: StealthProfile) -> Result<Self> {
+        Self::with_user_agents(headless, stealth_profile, UserAgentManager::new()).await
+    }
+
+    /// Like [`NowhereDriver::new`], but draws the session fingerprint from
+    /// `user_agent_manager` (a configured pool and/or session seed).
+    pub async fn with_user_agents(
+        headless: bool,
+        stealth_profile: StealthProfile,
+        mut user_agent_manager: UserAgentManager,
+    ) -> Result<Self> {
         let mut caps = Capabilities::new();
         let mut chrome_opts = HashMap::new();
-        let mut user_agent_manager = UserAgentManager::new();
         let user_agent_profile = user_agent_manager.get_session_profile(&stealth_profile);
 
         let args = build_stealth_arguments(&stealth_profile, user_agent_profile);
@@ -61,18 +68,39 @@ impl NowhereDriver {
             behavioral_engine,
             user_agent_manager,
             stealth_profile,
+            selector_cache: None,
         })
     }
 
+    /// Share learned selectors across every page this driver opens.
+    pub fn with_selector_cache(mut self, cache: SelectorCache) -> Self {
+        self.selector_cache = Some(cache);
+        self
+    }
+
     /// Navigate to `url` and return a [`NowherePage`] with stealth/fingerprint
     /// scripts applied.
     pub async fn goto(&mut self, url: &str) -> Result<NowherePage> {
+        let profile = self.stealth_profile.clone();
+        self.goto_with_profile(url, profile).await
+    }
+
+    /// Like [`goto`](Self::goto), but apply `profile`'s evasion scripts instead of the
+    /// driver's. Launch arguments stay those of the profile the driver was created with.
+    pub async fn goto_with_profile(
+        &mut self,
+        url: &str,
+        profile: StealthProfile,
+    ) -> Result<NowherePage> {
         let mut page = NowherePage::new(
             self.client.clone(),
-            self.stealth_profile.clone(),
+            profile,
             self.user_agent_manager.clone(),
             self.behavioral_engine.clone(),
         );
+        if let Some(cache) = &self.selector_cache {
+            page = page.with_selector_cache(cache.clone());
+        }
         // Navigate via NowherePage so stealth/fingerprint scripts are applied consistently
         page.goto(url).await?;
         Ok(page)
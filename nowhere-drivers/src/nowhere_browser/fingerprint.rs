@@ -14,11 +14,21 @@ pub struct UserAgentProfile {
 
 #[derive(Debug, Clone)]
 /// Maintains a small pool of plausible desktop fingerprint profiles.
+///
+/// Each session uses one profile. Without a seed it is picked at random; with
+/// [`UserAgentManager::with_session_seed`] the pick is reproducible.
 pub struct UserAgentManager {
     desktop_profiles: Vec<UserAgentProfile>,
+    session_seed: Option<u64>,
     current_session_profile: Option<UserAgentProfile>,
 }
 
+impl Default for UserAgentManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UserAgentManager {
     /// Create a new manager with built‑in desktop profiles.
     pub fn new() -> Self {
@@ -38,29 +48,133 @@ impl UserAgentManager {
                     languages: vec!["en-US".to_string(),"en".to_string()],
                     timezone: "America/Los_Angeles".to_string(),
                 },
+                UserAgentProfile {
+                    user_agent: "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36".to_string(),
+                    viewport: (1366, 768),
+                    platform: "Linux x86_64".to_string(),
+                    languages: vec!["en-GB".to_string(),"en".to_string()],
+                    timezone: "Europe/London".to_string(),
+                },
+                UserAgentProfile {
+                    user_agent: "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36 Edg/131.0.0.0".to_string(),
+                    viewport: (1536, 864),
+                    platform: "Win32".to_string(),
+                    languages: vec!["en-US".to_string(),"en".to_string()],
+                    timezone: "America/Chicago".to_string(),
+                },
             ],
+            session_seed: None,
             current_session_profile: None,
         }
     }
 
+    /// Replace the bundled pool (e.g. with profiles loaded from config). An empty
+    /// pool keeps the bundled profiles.
+    pub fn with_profiles(mut self, profiles: Vec<UserAgentProfile>) -> Self {
+        if !profiles.is_empty() {
+            self.desktop_profiles = profiles;
+            self.current_session_profile = None;
+        }
+        self
+    }
+
+    /// Pick the session profile deterministically: profile `seed % pool size`.
+    pub fn with_session_seed(mut self, seed: u64) -> Self {
+        self.session_seed = Some(seed);
+        self.current_session_profile = None;
+        self
+    }
+
     /// Get (or lazily select) the current session profile.
     pub fn get_session_profile(&mut self, _: &StealthProfile) -> &UserAgentProfile {
         if self.current_session_profile.is_none() {
-            let mut rng = rand::thread_rng();
-            let p = self.desktop_profiles.choose(&mut rng).unwrap().clone();
+            let p = match self.session_seed {
+                Some(seed) => {
+                    let idx = (seed % self.desktop_profiles.len() as u64) as usize;
+                    self.desktop_profiles[idx].clone()
+                }
+                None => {
+                    let mut rng = rand::thread_rng();
+                    self.desktop_profiles.choose(&mut rng).unwrap().clone()
+                }
+            };
             self.current_session_profile = Some(p);
         }
         self.current_session_profile.as_ref().unwrap()
     }
 }
 
+impl UserAgentProfile {
+    /// Script that makes `navigator.platform` report this profile's platform, so it
+    /// agrees with the `--user-agent` the browser was launched with.
+    pub fn platform_override_script(&self) -> String {
+        format!(
+            "Object.defineProperty(navigator, 'platform', {{ get: () => {} }});",
+            serde_json::Value::String(self.platform.clone())
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 /// Placeholder for more advanced, per‑session fingerprint controls.
 pub struct FingerprintManager {}
 
+impl Default for FingerprintManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FingerprintManager {
     /// Create a new fingerprint manager.
     pub fn new() -> Self {
         Self {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn different_seeds_pick_different_profiles() {
+        let mut a = UserAgentManager::new().with_session_seed(0);
+        let mut b = UserAgentManager::new().with_session_seed(2);
+        let pa = a.get_session_profile(&StealthProfile::Maximum).clone();
+        let pb = b.get_session_profile(&StealthProfile::Maximum).clone();
+        assert_ne!(pa.user_agent, pb.user_agent);
+        assert_ne!(pa.platform, pb.platform);
+
+        // Same seed, same profile; the pick is sticky for the session.
+        let mut again = UserAgentManager::new().with_session_seed(0);
+        let p = again.get_session_profile(&StealthProfile::Maximum);
+        assert_eq!(p.user_agent, pa.user_agent);
+
+        for p in [&pa, &pb] {
+            assert_eq!(
+                p.platform_override_script(),
+                format!(
+                    "Object.defineProperty(navigator, 'platform', {{ get: () => \"{}\" }});",
+                    p.platform
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn configured_pool_replaces_bundled_profiles() {
+        let custom = UserAgentProfile {
+            user_agent: "custom-agent".into(),
+            viewport: (800, 600),
+            platform: "Linux armv8l".into(),
+            languages: vec!["de-DE".into()],
+            timezone: "Europe/Berlin".into(),
+        };
+        let mut m = UserAgentManager::new()
+            .with_profiles(vec![custom])
+            .with_session_seed(7);
+        let p = m.get_session_profile(&StealthProfile::Balanced);
+        assert_eq!(p.user_agent, "custom-agent");
+        assert!(p.platform_override_script().contains("\"Linux armv8l\""));
+    }
+}
@@ -5,10 +5,61 @@ use crate::nowhere_browser::{
 };
 use anyhow::{anyhow, Result};
 use fantoccini::{elements::Element, Client, Locator};
+use lru::LruCache;
 use nowhere_llm::traits::LlmClient;
 use serde_json;
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
 use tracing::info;
 
+/// LLM-learned selectors keyed by site host and natural-language query, shared by the
+/// pages of one driver session.
+pub type SelectorCache = Arc<Mutex<LruCache<String, String>>>;
+
+/// Create a [`SelectorCache`] holding at most `capacity` selectors (minimum 1).
+pub fn selector_cache(capacity: usize) -> SelectorCache {
+    let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+    Arc::new(Mutex::new(LruCache::new(capacity)))
+}
+
+fn selector_cache_key(llm_query: &str, host: &str) -> String {
+    format!("{host}\n{llm_query}")
+}
+
+/// Runs JavaScript in the current document; a WebDriver [`Client`] outside tests.
+#[async_trait::async_trait]
+pub trait ScriptRunner: Send + Sync {
+    async fn run_script(&self, script: &str) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl ScriptRunner for Client {
+    async fn run_script(&self, script: &str) -> Result<()> {
+        self.execute(script, vec![]).await?;
+        Ok(())
+    }
+}
+
+/// Run the evasions `profile` calls for (see [`StealthScripts::for_profile`]), plus the
+/// session's platform override under [`StealthProfile::Maximum`].
+pub(crate) async fn apply_evasions(
+    runner: &dyn ScriptRunner,
+    profile: &StealthProfile,
+    fingerprint_manager: &mut UserAgentManager,
+) -> Result<()> {
+    for script in StealthScripts::for_profile(profile) {
+        runner.run_script(script).await?;
+    }
+    if let StealthProfile::Maximum = profile {
+        let script = fingerprint_manager
+            .get_session_profile(profile)
+            .platform_override_script();
+        runner.run_script(&script).await?;
+    }
+    Ok(())
+}
+
 /// High‑level page wrapper providing element queries and LLM‑assisted
 /// selector discovery.
 pub struct NowherePage {
@@ -16,6 +67,7 @@ pub struct NowherePage {
     pub(crate) stealth_profile: StealthProfile,
     pub(crate) fingerprint_manager: UserAgentManager,
     pub(crate) behavioral_engine: BehavioralEngine,
+    pub(crate) selector_cache: Option<SelectorCache>,
 }
 
 impl NowherePage {
@@ -31,60 +83,38 @@ impl NowherePage {
             stealth_profile,
             fingerprint_manager,
             behavioral_engine,
+            selector_cache: None,
         }
     }
 
+    /// Reuse LLM-derived selectors from `cache` for pages on the same host.
+    pub fn with_selector_cache(mut self, cache: SelectorCache) -> Self {
+        self.selector_cache = Some(cache);
+        self
+    }
+
+    /// Use `profile` for this page's later navigations, e.g. heavier evasions for a site
+    /// that fingerprints. Each [`goto`](Self::goto) applies the current profile's scripts.
+    pub fn set_stealth_profile(&mut self, profile: StealthProfile) {
+        self.stealth_profile = profile;
+    }
+
+    /// The profile the next [`goto`](Self::goto) applies.
+    pub fn stealth_profile(&self) -> &StealthProfile {
+        &self.stealth_profile
+    }
+
     /// Navigate to `url` and apply stealth/fingerprint scripts.
     pub async fn goto(&mut self, url: &str) -> Result<()> {
         self.behavioral_engine.random_delay(300, 1200).await;
         self.client.goto(url).await.map_err(anyhow::Error::from)?;
 
-        self.apply_stealth_and_fingerprint().await?;
-
-        Ok(())
-    }
-
-    /// Apply stealth scripts and basic fingerprinting adjustments.
-    async fn apply_stealth_and_fingerprint(&mut self) -> Result<()> {
-        self.client
-            .execute(StealthScripts::get_core_evasions(), vec![])
-            .await?;
-
-        match self.stealth_profile {
-            StealthProfile::Lightweight => {
-                // No additional scripts for the lightest profile
-            }
-
-            StealthProfile::Balanced => {
-                self.client
-                    .execute(StealthScripts::get_canvas_evasions(), vec![])
-                    .await?;
-            }
-
-            StealthProfile::Maximum => {
-                self.client
-                    .execute(StealthScripts::get_canvas_evasions(), vec![])
-                    .await?;
-                self.client
-                    .execute(StealthScripts::get_webgl_evasions(), vec![])
-                    .await?;
-
-                let p = &self
-                    .fingerprint_manager
-                    .get_session_profile(&self.stealth_profile);
-
-                self.client
-                    .execute(
-                        &format!(
-                            "Object.defineProperty(navigator, 'platform', {{ get: () => '{}' }});",
-                            p.platform
-                        ),
-                        vec![],
-                    )
-                    .await?;
-            }
-        }
-        Ok(())
+        apply_evasions(
+            &self.client,
+            &self.stealth_profile,
+            &mut self.fingerprint_manager,
+        )
+        .await
     }
 
     /// Return the full page HTML source.
@@ -150,14 +180,24 @@ impl NowherePage {
                     %selector,
                     "initial selector failed; requesting LLM rewrite"
                 );
-                let sel = self.get_selector_from_llm(llm_query, llm_client).await?;
-                info!(
-                    target: "browser.selector",
-                    selector = %sel,
-                    "LLM provided replacement selector"
-                );
-
-                self.find_elements(&sel).await
+                let cache = match &self.selector_cache {
+                    Some(cache) => {
+                        let url = self.get_url().await?;
+                        let host = url::Url::parse(&url)
+                            .ok()
+                            .and_then(|u| u.host_str().map(str::to_string))
+                            .unwrap_or_default();
+                        Some((cache, selector_cache_key(llm_query, &host)))
+                    }
+                    None => None,
+                };
+
+                find_with_learned_selector(
+                    cache.as_ref().map(|(c, k)| (*c, k.as_str())),
+                    |sel| async move { self.find_elements(&sel).await },
+                    || self.get_selector_from_llm(llm_query, llm_client),
+                )
+                .await
             }
         }
     }
@@ -210,6 +250,51 @@ impl NowherePage {
     }
 }
 
+/// Resolve elements through a learned selector: a cached one if it still matches,
+/// otherwise a fresh one from `ask_llm`, which is cached once it finds something.
+/// A cached selector that no longer matches is evicted.
+async fn find_with_learned_selector<T, F, FFut, A, AFut>(
+    cache: Option<(&SelectorCache, &str)>,
+    find: F,
+    ask_llm: A,
+) -> Result<Vec<T>>
+where
+    F: Fn(String) -> FFut,
+    FFut: Future<Output = Result<Vec<T>>>,
+    A: FnOnce() -> AFut,
+    AFut: Future<Output = Result<String>>,
+{
+    if let Some((cache, key)) = cache {
+        let cached = cache.lock().unwrap().get(key).cloned();
+        if let Some(sel) = cached {
+            match find(sel.clone()).await {
+                Ok(found) if !found.is_empty() => {
+                    info!(target: "browser.selector", selector = %sel, "reused cached selector");
+                    return Ok(found);
+                }
+                _ => {
+                    info!(target: "browser.selector", selector = %sel, "cached selector failed; evicting");
+                    cache.lock().unwrap().pop(key);
+                }
+            }
+        }
+    }
+
+    let sel = ask_llm().await?;
+    info!(
+        target: "browser.selector",
+        selector = %sel,
+        "LLM provided replacement selector"
+    );
+    let found = find(sel.clone()).await;
+    if let (Some((cache, key)), Ok(elements)) = (cache, &found) {
+        if !elements.is_empty() {
+            cache.lock().unwrap().put(key.to_string(), sel);
+        }
+    }
+    found
+}
+
 // =========================
 // NowhereElement Definition
 // =========================
@@ -270,3 +355,95 @@ impl NowhereElement {
         self.element.text().await.map_err(anyhow::Error::from)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Stand-in for a page where only `good` matches anything.
+    async fn fake_find(sel: String) -> Result<Vec<()>> {
+        Ok(if sel == "good" { vec![()] } else { Vec::new() })
+    }
+
+    #[tokio::test]
+    async fn same_query_and_host_asks_the_llm_once() {
+        let cache = selector_cache(8);
+        let key = selector_cache_key("article body", "news.example");
+        let llm_calls = AtomicUsize::new(0);
+        let ask = || async {
+            llm_calls.fetch_add(1, Ordering::SeqCst);
+            Ok("good".to_string())
+        };
+
+        for _ in 0..2 {
+            let found = find_with_learned_selector(Some((&cache, &key)), fake_find, ask)
+                .await
+                .unwrap();
+            assert_eq!(found.len(), 1);
+        }
+        assert_eq!(llm_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn stale_cached_selector_is_evicted_and_relearned() {
+        let cache = selector_cache(8);
+        let key = selector_cache_key("article body", "news.example");
+        cache.lock().unwrap().put(key.clone(), "stale".into());
+        let llm_calls = AtomicUsize::new(0);
+
+        let found = find_with_learned_selector(Some((&cache, &key)), fake_find, || async {
+            llm_calls.fetch_add(1, Ordering::SeqCst);
+            Ok("good".to_string())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(llm_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            cache.lock().unwrap().get(&key).map(String::as_str),
+            Some("good")
+        );
+    }
+
+    /// Records every script it is asked to run.
+    #[derive(Default)]
+    struct SpyRunner(Mutex<Vec<String>>);
+
+    #[async_trait::async_trait]
+    impl ScriptRunner for SpyRunner {
+        async fn run_script(&self, script: &str) -> Result<()> {
+            self.0.lock().unwrap().push(script.to_string());
+            Ok(())
+        }
+    }
+
+    /// Scripts one navigation under `profile` runs.
+    async fn evasions(profile: StealthProfile, fingerprints: &mut UserAgentManager) -> Vec<String> {
+        let spy = SpyRunner::default();
+        apply_evasions(&spy, &profile, fingerprints).await.unwrap();
+        spy.0.into_inner().unwrap()
+    }
+
+    #[tokio::test]
+    async fn canvas_and_webgl_evasions_follow_the_profile_of_each_navigation() {
+        let mut fingerprints = UserAgentManager::new().with_session_seed(0);
+        let has = |scripts: &[String], script: &str| scripts.iter().any(|s| s == script);
+        let (canvas, webgl) = (
+            StealthScripts::get_canvas_evasions(),
+            StealthScripts::get_webgl_evasions(),
+        );
+
+        // One session, switching profiles between navigations.
+        let light = evasions(StealthProfile::Lightweight, &mut fingerprints).await;
+        assert_eq!(light, [StealthScripts::get_core_evasions()]);
+        let heavy = evasions(StealthProfile::Maximum, &mut fingerprints).await;
+        assert!(has(&heavy, canvas) && has(&heavy, webgl), "{heavy:?}");
+        assert!(heavy.iter().any(|s| s.contains("'platform'")));
+        let light = evasions(StealthProfile::Lightweight, &mut fingerprints).await;
+        assert!(!has(&light, canvas) && !has(&light, webgl), "{light:?}");
+        let balanced = evasions(StealthProfile::Balanced, &mut fingerprints).await;
+        assert!(has(&balanced, canvas) && !has(&balanced, webgl));
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// Levels of stealth applied to the browser session.
 pub enum StealthProfile {
     Lightweight,
@@ -43,6 +43,19 @@ pub fn build_stealth_arguments(
 pub struct StealthScripts;
 
 impl StealthScripts {
+    /// Scripts applied after each navigation under `profile`, lightest first: the core
+    /// evasions always, canvas noise from `Balanced` up, and WebGL spoofing at `Maximum`.
+    pub fn for_profile(profile: &StealthProfile) -> Vec<&'static str> {
+        let mut scripts = vec![Self::get_core_evasions()];
+        if matches!(profile, StealthProfile::Balanced | StealthProfile::Maximum) {
+            scripts.push(Self::get_canvas_evasions());
+        }
+        if let StealthProfile::Maximum = profile {
+            scripts.push(Self::get_webgl_evasions());
+        }
+        scripts
+    }
+
     pub fn get_core_evasions() -> &'static str {
         r#"
             Object.defineProperty(navigator, 'webdriver', { get: () => undefined });
@@ -2,8 +2,10 @@
 //!
 //! - Request options: headers, `Auth`, query params, timeout, retries
 //! - Redacts sensitive query params and never logs secret values
-//! - Retries 429/5xx with exponential backoff and `Retry-After` support
-//! - Optional *raw* request/response logging via `NOWHERE_HTTP_RAW=1`
+//! - Retries 429/5xx with jittered exponential backoff above a configurable floor, and
+//!   `Retry-After` support
+//! - Optional *raw* request/response logging via `NOWHERE_HTTP_RAW=1`, with secrets in
+//!   JSON response bodies masked
 //!
 //! Example (no_run):
 //! ```rust
@@ -16,7 +18,7 @@
 //! ```
 //!
 //! Security: `Auth::Bearer` values are sanitized before use, and logs only
-//! ever include the auth kind (bearer/header/query/none), not the secret.
+//! ever include the auth kind (bearer/basic/header/query/none), not the secret.
 //!
 //! Observability: structured `tracing` events are emitted for request start,
 //! headers, body snippets (truncated), retries, final errors, and (optionally)
@@ -28,7 +30,7 @@ use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::env;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 use tokio::time::sleep;
 
@@ -39,7 +41,8 @@ use tokio::time::sleep;
 const RAW_ENV: &str = "NOWHERE_HTTP_RAW";
 const RAW_MAX_BODY: usize = 64 * 1024; // cap raw body logs (64 KiB)
 
-fn raw_enabled() -> bool {
+/// Whether `NOWHERE_HTTP_RAW` asks for raw request/response logging.
+pub fn raw_enabled() -> bool {
     matches!(
         env::var(RAW_ENV).as_deref(),
         Ok("1") | Ok("true") | Ok("yes")
@@ -56,7 +59,7 @@ fn make_curl(method: &Method, url: &Url, headers: &HeaderMap, body: Option<&[u8]
         let mut v = val.to_str().unwrap_or("").to_string();
         let lname = name.as_str().to_ascii_lowercase();
         if lname == "authorization" {
-            v = "Bearer <redacted>".into();
+            v = redacted_authorization(&v);
         }
         parts.push(format!(
             "-H '{}: {}'",
@@ -88,13 +91,22 @@ fn redact_headers(h: &HeaderMap) -> Vec<(String, String)> {
             let key = k.as_str().to_string();
             let mut val = v.to_str().unwrap_or("").to_string();
             if key.eq_ignore_ascii_case("authorization") {
-                val = "Bearer <redacted>".into();
+                val = redacted_authorization(&val);
             }
             (key, val)
         })
         .collect()
 }
 
+/// Mask the credentials of an `Authorization` value but keep its scheme, so logs still
+/// tell `Bearer` from `Basic`.
+fn redacted_authorization(value: &str) -> String {
+    match value.split_once(' ') {
+        Some((scheme, _)) if !scheme.is_empty() => format!("{scheme} <redacted>"),
+        _ => "<redacted>".into(),
+    }
+}
+
 // ==============================
 // Errors
 // ==============================
@@ -107,6 +119,8 @@ pub enum HttpError {
     Build(String),
     #[error("network error: {0}")]
     Network(String),
+    #[error("request timed out: {0}")]
+    Timeout(String),
     #[error("decode error: {0}, body_snippet: {1}")]
     Decode(String, String),
     #[error("server returned error {status}: {message}, request_id={request_id}")]
@@ -114,9 +128,58 @@ pub enum HttpError {
         status: StatusCode,
         message: String,
         request_id: String,
+        /// Structured error fields, when the body was a problem document.
+        problem: Option<Box<ApiProblem>>,
     },
 }
 
+/// Fields of a JSON error body in the Twitter v2 / RFC 7807 "problem" shape:
+/// top-level `title`/`detail`/`type`/`status`, plus the first entry of `errors`.
+/// Values on the first `errors` entry win over the top-level ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiProblem {
+    pub title: Option<String>,
+    pub detail: Option<String>,
+    /// The problem `type`, usually a URI such as
+    /// `https://api.twitter.com/2/problems/invalid-request`.
+    pub kind: Option<String>,
+    /// The request parameter the error is about, e.g. `query`.
+    pub parameter: Option<String>,
+    pub status: Option<u16>,
+}
+
+impl ApiProblem {
+    /// Last path segment of `kind`, e.g. `invalid-request` or `usage-capped`.
+    pub fn kind_name(&self) -> Option<&str> {
+        let kind = self.kind.as_deref()?;
+        kind.rsplit('/').next().filter(|s| !s.is_empty())
+    }
+}
+
+impl HttpError {
+    /// Whether the same request may succeed if sent again: transport failures,
+    /// timeouts, throttling (429) and server errors (5xx).
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            HttpError::Network(_) | HttpError::Timeout(_) => true,
+            HttpError::Api { status, .. } => is_retryable_status(*status),
+            HttpError::Url(_) | HttpError::Build(_) | HttpError::Decode(..) => false,
+        }
+    }
+
+    /// The structured error body of an `Api` error, if the server sent one.
+    pub fn problem(&self) -> Option<&ApiProblem> {
+        match self {
+            HttpError::Api { problem, .. } => problem.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
 // ==============================
 // Auth & Request Options
 // ==============================
@@ -136,6 +199,12 @@ pub enum HttpError {
 pub enum Auth<'a> {
     /// Authorization: Bearer <token>
     Bearer(&'a str),
+    /// Authorization: Basic <base64(username:password)>, for gateways and proxies that
+    /// want HTTP basic auth
+    Basic {
+        username: &'a str,
+        password: &'a str,
+    },
     /// Custom header (e.g., Brave: X-Subscription-Token)
     Header {
         name: HeaderName,
@@ -178,6 +247,9 @@ pub struct RequestOpts<'a> {
     pub query: Option<Vec<(&'a str, Cow<'a, str>)>>, // e.g. [("q", "term".into())]
     /// If true and `path` is an absolute URL, use it as-is (ignore base).
     pub allow_absolute: bool,
+    /// Sent under [`HttpClient::idempotency_header`] with every attempt of the call, so a
+    /// server that honours it can drop the duplicates a retried POST would otherwise create.
+    pub idempotency_key: Option<String>,
 }
 
 // ==============================
@@ -190,11 +262,36 @@ pub struct HttpClient {
     inner: Client,
     pub default_timeout: Duration,
     pub max_retries: usize,
+    /// Shortest wait before retrying a 429/5xx that carries no `Retry-After`.
+    pub min_backoff: Duration,
+    /// Response headers checked, in order, for the upstream request id.
+    pub request_id_headers: Vec<String>,
+    /// Resolve paths with a leading `/` under the base path too (see [`HttpClient::url_for`]).
+    pub preserve_base_path: bool,
+    /// Log raw requests and responses (target `http.raw`); defaults to [`raw_enabled`].
+    pub raw_logging: bool,
+    /// Mask secret-looking values (see [`redact_json_secrets`]) in JSON bodies before they
+    /// are raw-logged. On by default; non-JSON bodies are only truncated.
+    pub redact_raw_json: bool,
+    /// Request header carrying [`RequestOpts::idempotency_key`].
+    pub idempotency_header: HeaderName,
 }
 
+/// Request-id headers checked by default.
+pub const DEFAULT_REQUEST_ID_HEADERS: &[&str] = &["x-request-id", "x-correlation-id"];
+
+/// Default [`HttpClient::idempotency_header`].
+pub const DEFAULT_IDEMPOTENCY_HEADER: &str = "idempotency-key";
+
+/// Default [`HttpClient::min_backoff`].
+pub const DEFAULT_MIN_BACKOFF: Duration = Duration::from_millis(1100);
+
 impl HttpClient {
     /// Construct a client anchored to a base URL.
     ///
+    /// The base is stored with a trailing `/`, so relative paths land under its last
+    /// segment: `https://api.x.com/2` + `tweets/search` is `https://api.x.com/2/tweets/search`.
+    ///
     /// ```no_run
     /// use nowhere_http::{HttpClient, HttpError};
     /// use std::time::Duration;
@@ -205,7 +302,11 @@ impl HttpClient {
     /// # Ok::<(), HttpError>(())
     /// ```
     pub fn new(base: &str) -> Result<Self, HttpError> {
-        let base = Url::parse(base).map_err(|e| HttpError::Url(e.to_string()))?;
+        let mut base = Url::parse(base).map_err(|e| HttpError::Url(e.to_string()))?;
+        if !base.path().ends_with('/') {
+            let path = format!("{}/", base.path());
+            base.set_path(&path);
+        }
         let inner = Client::builder()
             .connect_timeout(Duration::from_secs(5))
             .build()
@@ -215,6 +316,15 @@ impl HttpClient {
             inner,
             default_timeout: Duration::from_secs(15),
             max_retries: 2,
+            min_backoff: DEFAULT_MIN_BACKOFF,
+            request_id_headers: DEFAULT_REQUEST_ID_HEADERS
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            preserve_base_path: false,
+            raw_logging: raw_enabled(),
+            redact_raw_json: true,
+            idempotency_header: HeaderName::from_static(DEFAULT_IDEMPOTENCY_HEADER),
         })
     }
 
@@ -248,6 +358,91 @@ impl HttpClient {
         self
     }
 
+    /// Override the backoff floor applied to every retryable status (default
+    /// [`DEFAULT_MIN_BACKOFF`]). A `Retry-After` header still takes precedence.
+    ///
+    /// ```no_run
+    /// use nowhere_http::{HttpClient, HttpError};
+    /// use std::time::Duration;
+    ///
+    /// let client = HttpClient::new("https://api.example.com")?
+    ///     .with_min_backoff(Duration::from_secs(3));
+    /// assert_eq!(client.min_backoff, Duration::from_secs(3));
+    /// # Ok::<(), HttpError>(())
+    /// ```
+    pub fn with_min_backoff(mut self, floor: Duration) -> Self {
+        self.min_backoff = floor;
+        self
+    }
+
+    /// Replace the ordered list of response headers read for the upstream request id
+    /// (defaults to [`DEFAULT_REQUEST_ID_HEADERS`]). The first one present wins.
+    ///
+    /// ```no_run
+    /// use nowhere_http::{HttpClient, HttpError};
+    ///
+    /// let client = HttpClient::new("https://api.example.com")?
+    ///     .with_request_id_headers(&["cf-ray", "x-amzn-requestid"]);
+    /// assert_eq!(client.request_id_headers, ["cf-ray", "x-amzn-requestid"]);
+    /// # Ok::<(), HttpError>(())
+    /// ```
+    pub fn with_request_id_headers(mut self, names: &[&str]) -> Self {
+        self.request_id_headers = names.iter().map(|h| h.to_ascii_lowercase()).collect();
+        self
+    }
+
+    /// Keep the base path for paths that start with `/`. By default such a path replaces
+    /// the base path, as in a browser: `https://api.x.com/2` + `/tweets` is
+    /// `https://api.x.com/tweets`. With this set it is `https://api.x.com/2/tweets`.
+    ///
+    /// ```
+    /// use nowhere_http::{HttpClient, HttpError};
+    ///
+    /// let client = HttpClient::new("https://api.x.com/2")?;
+    /// assert_eq!(client.url_for("/tweets")?.as_str(), "https://api.x.com/tweets");
+    ///
+    /// let client = client.with_preserved_base_path();
+    /// assert_eq!(client.url_for("/tweets")?.as_str(), "https://api.x.com/2/tweets");
+    /// # Ok::<(), HttpError>(())
+    /// ```
+    pub fn with_preserved_base_path(mut self) -> Self {
+        self.preserve_base_path = true;
+        self
+    }
+
+    /// Send [`RequestOpts::idempotency_key`] under `name` instead of
+    /// [`DEFAULT_IDEMPOTENCY_HEADER`], for servers that expect e.g. `X-Idempotency-Key`.
+    pub fn with_idempotency_header(mut self, name: HeaderName) -> Self {
+        self.idempotency_header = name;
+        self
+    }
+
+    /// Turn raw request/response logging on or off for this client, whatever
+    /// `NOWHERE_HTTP_RAW` says.
+    pub fn with_raw_logging(mut self, on: bool) -> Self {
+        self.raw_logging = on;
+        self
+    }
+
+    /// Log raw JSON response bodies verbatim instead of masking secrets in them. Only for
+    /// debugging against throwaway credentials.
+    pub fn without_raw_json_redaction(mut self) -> Self {
+        self.redact_raw_json = false;
+        self
+    }
+
+    /// The URL a request for `path` goes to.
+    pub fn url_for(&self, path: &str) -> Result<Url, HttpError> {
+        let path = if self.preserve_base_path {
+            path.trim_start_matches('/')
+        } else {
+            path
+        };
+        self.base
+            .join(path)
+            .map_err(|e| HttpError::Url(e.to_string()))
+    }
+
     // ==============================
     // Backward-compatible API
     // ==============================
@@ -319,22 +514,22 @@ impl HttpClient {
         T: DeserializeOwned,
     {
         // Resolve URL (allow absolute URL when requested).
-        let url = if opts.allow_absolute {
-            if let Ok(abs) = Url::parse(path) {
-                abs
-            } else {
-                self.base
-                    .join(path)
-                    .map_err(|e| HttpError::Url(e.to_string()))?
-            }
-        } else {
-            self.base
-                .join(path)
-                .map_err(|e| HttpError::Url(e.to_string()))?
+        let url = match Url::parse(path) {
+            Ok(abs) if opts.allow_absolute => abs,
+            _ => self.url_for(path)?,
         };
 
+        // Parsed once and sent unchanged with every attempt, so retries share the key.
+        let idempotency_key = opts
+            .idempotency_key
+            .as_deref()
+            .map(HeaderValue::from_str)
+            .transpose()
+            .map_err(|e| HttpError::Build(format!("invalid idempotency key: {e}")))?;
+
         let mut attempt = 0usize;
         let max_retries = opts.retries.unwrap_or(self.max_retries);
+        let started = std::time::Instant::now();
 
         loop {
             // ----- Build request -----
@@ -371,6 +566,9 @@ impl HttpClient {
             if let Some(hdrs) = &opts.headers {
                 rb = rb.headers(hdrs.clone());
             }
+            if let Some(key) = &idempotency_key {
+                rb = rb.header(self.idempotency_header.clone(), key.clone());
+            }
 
             // auth
             if let Some(auth) = &opts.auth {
@@ -379,6 +577,9 @@ impl HttpClient {
                         let tok = sanitize_api_key(tok)?;
                         rb = rb.bearer_auth(tok);
                     }
+                    Auth::Basic { username, password } => {
+                        rb = rb.basic_auth(username, Some(password));
+                    }
                     Auth::Header { name, value } => {
                         rb = rb.header(name, value);
                     }
@@ -397,6 +598,7 @@ impl HttpClient {
             // ----- Safe request logging (pre-send) -----
             let auth_kind = match &opts.auth {
                 Some(Auth::Bearer(_)) => "bearer",
+                Some(Auth::Basic { .. }) => "basic",
                 Some(Auth::Header { .. }) => "header",
                 Some(Auth::Query { .. }) => "query",
                 Some(Auth::None) | None => "none",
@@ -461,7 +663,7 @@ impl HttpClient {
             );
 
             // NEW: raw request line (curl) if enabled
-            if raw_enabled() {
+            if self.raw_logging {
                 // Merge only caller-provided headers (auth header will be redacted anyway)
                 let mut merged = HeaderMap::new();
                 if let Some(h) = &opts.headers {
@@ -469,6 +671,9 @@ impl HttpClient {
                         merged.append(k, v.clone());
                     }
                 }
+                if let Some(key) = &idempotency_key {
+                    merged.insert(self.idempotency_header.clone(), key.clone());
+                }
                 let curl = make_curl(&method, &url, &merged, request_body_bytes.as_deref());
                 tracing::debug!(target: "http.raw", %req_id, %curl, "request");
             }
@@ -501,6 +706,10 @@ impl HttpClient {
                         message=%message,
                         "http.network_error.send"
                     );
+                    log_final_error(&method, &url, None, "", attempt + 1, started, &message);
+                    if err.is_timeout() {
+                        return Err(HttpError::Timeout(message));
+                    }
                     return Err(HttpError::Network(message));
                 }
             };
@@ -532,16 +741,17 @@ impl HttpClient {
                         message=%message,
                         "http.network_error.body"
                     );
+                    log_final_error(&method, &url, None, "", attempt + 1, started, &message);
                     return Err(HttpError::Network(message));
                 }
             };
             let dur_ms = t0.elapsed().as_millis() as u64;
 
             // Response header diagnostics
-            let req_hdr_id = headers
-                .get("x-request-id")
-                .or_else(|| headers.get("x-correlation-id"))
-                .and_then(|v| v.to_str().ok())
+            let req_hdr_id = self
+                .request_id_headers
+                .iter()
+                .find_map(|name| headers.get(name.as_str())?.to_str().ok())
                 .unwrap_or("-");
 
             let limit = headers
@@ -567,14 +777,9 @@ impl HttpClient {
             );
 
             // NEW: raw response (headers + body)
-            if raw_enabled() {
+            if self.raw_logging {
                 let hdrs = redact_headers(&headers);
-                let mut body_snip = bytes.clone();
-                let truncated = body_snip.len() > RAW_MAX_BODY;
-                if truncated {
-                    body_snip.truncate(RAW_MAX_BODY);
-                }
-                let text = String::from_utf8_lossy(&body_snip);
+                let (text, truncated) = raw_body(&bytes, self.redact_raw_json);
                 tracing::info!(
                     target:"http.raw",
                     %req_id,
@@ -607,6 +812,19 @@ impl HttpClient {
                     );
                 }
 
+                // serde would report binary or mis-encoded bodies as a syntax error at some
+                // arbitrary column; name the real problem instead.
+                if let Err(e) = std::str::from_utf8(&bytes) {
+                    tracing::warn!(
+                        req_id=%req_id,
+                        valid_up_to=e.valid_up_to(),
+                        body_len=bytes.len(),
+                        body_snippet=%snippet,
+                        "http.response.invalid_utf8"
+                    );
+                    return Err(invalid_utf8(&e, snippet));
+                }
+
                 // FIXME(content-type): Validate content-type before JSON decode and/or
                 // provide non-JSON helpers (get_text/get_bytes).
                 return serde_json::from_slice::<T>(&bytes).map_err(|e| {
@@ -624,25 +842,16 @@ impl HttpClient {
 
             // ----- Non-success: maybe retry -----
             let message = extract_error_message_multi(&bytes);
+            let problem = extract_api_problem(&bytes).map(Box::new);
             let request_id = req_hdr_id.to_string();
 
-            let is_429 = status == StatusCode::TOO_MANY_REQUESTS;
-            let is_5xx = status.is_server_error();
-
-            if (is_429 || is_5xx) && attempt < max_retries {
+            if is_retryable_status(status) && attempt < max_retries {
                 attempt += 1;
-                // FIXME(retry-policy): Make policy pluggable with jitter and cap on total
-                // elapsed time; consider honoring Retry-After for 5xx as well.
-                let delay = if let Some(secs) = retry_after_delay_secs(&headers) {
-                    Duration::from_secs(secs)
-                } else {
-                    let exp = Duration::from_millis(200u64.saturating_mul(1 << (attempt - 1)));
-                    if is_429 {
-                        // default floor for 429 when no Retry-After is present
-                        exp.max(Duration::from_millis(1100))
-                    } else {
-                        exp
-                    }
+                // FIXME(retry-policy): Make policy pluggable with a cap on total elapsed
+                // time.
+                let delay = match retry_after_delay_secs(&headers) {
+                    Some(secs) => Duration::from_secs(secs),
+                    None => status_backoff(attempt, self.min_backoff),
                 };
                 tracing::warn!(
                     req_id=%req_id,
@@ -668,10 +877,20 @@ impl HttpClient {
                 body_snippet=%snippet,
                 "http.error"
             );
+            log_final_error(
+                &method,
+                &url,
+                Some(status),
+                &request_id,
+                attempt + 1,
+                started,
+                &message,
+            );
             return Err(HttpError::Api {
                 status,
                 message,
                 request_id,
+                problem,
             });
         }
     }
@@ -681,6 +900,30 @@ impl HttpClient {
 // Helpers
 // ==============================
 
+/// One event named `http.final_error` per request that gave up, with stable fields for
+/// alerting: `status` (absent for transport failures), `x_request_id`, `attempts`,
+/// `total_elapsed_ms` and `message`.
+fn log_final_error(
+    method: &Method,
+    url: &Url,
+    status: Option<StatusCode>,
+    request_id: &str,
+    attempts: usize,
+    started: std::time::Instant,
+    message: &str,
+) {
+    tracing::error!(
+        name: "http.final_error",
+        method = %method,
+        host_path = %format!("{}{}", url.domain().unwrap_or("-"), url.path()),
+        status = status.map(|s| s.as_u16()),
+        x_request_id = request_id,
+        attempts = attempts as u64,
+        total_elapsed_ms = started.elapsed().as_millis() as u64,
+        message = message,
+    );
+}
+
 // FIXME(dedup): Consolidate helper definitions and remove any duplicates.
 fn extract_error_message_multi(body: &[u8]) -> String {
     // OpenAI style: {"error":{"message":"..."}}
@@ -722,17 +965,17 @@ fn extract_error_message_multi(body: &[u8]) -> String {
     if let Ok(env) = serde_json::from_slice::<OpenAiEnv>(body) {
         return env.error.message;
     }
-    if let Ok(tw) = serde_json::from_slice::<TwErrors>(body) {
-        if let Some(first) = tw.errors.into_iter().next() {
-            if !first.message.is_empty() {
-                return first.message;
-            }
-            if !first.detail.is_empty() {
-                return first.detail;
-            }
-            if !first.title.is_empty() {
-                return first.title;
-            }
+    if let Ok(tw) = serde_json::from_slice::<TwErrors>(body)
+        && let Some(first) = tw.errors.into_iter().next()
+    {
+        if !first.message.is_empty() {
+            return first.message;
+        }
+        if !first.detail.is_empty() {
+            return first.detail;
+        }
+        if !first.title.is_empty() {
+            return first.title;
         }
     }
     if let Ok(m) = serde_json::from_slice::<Msg>(body) {
@@ -749,6 +992,48 @@ fn extract_error_message_multi(body: &[u8]) -> String {
     snip_body(body)
 }
 
+fn extract_api_problem(body: &[u8]) -> Option<ApiProblem> {
+    #[derive(Deserialize)]
+    struct Problem {
+        title: Option<String>,
+        detail: Option<String>,
+        #[serde(rename = "type")]
+        kind: Option<String>,
+        status: Option<u16>,
+        #[serde(default)]
+        errors: Vec<ProblemError>,
+    }
+    // `parameters` maps each offending parameter to its values: {"query": ["..."]}.
+    #[derive(Deserialize)]
+    struct ProblemError {
+        title: Option<String>,
+        detail: Option<String>,
+        message: Option<String>,
+        #[serde(rename = "type")]
+        kind: Option<String>,
+        parameter: Option<String>,
+        #[serde(default)]
+        parameters: serde_json::Map<String, serde_json::Value>,
+    }
+
+    let p: Problem = serde_json::from_slice(body).ok()?;
+    let first = p.errors.into_iter().next();
+    let problem = ApiProblem {
+        title: first.as_ref().and_then(|e| e.title.clone()).or(p.title),
+        detail: first
+            .as_ref()
+            .and_then(|e| e.detail.clone().or_else(|| e.message.clone()))
+            .or(p.detail),
+        kind: first.as_ref().and_then(|e| e.kind.clone()).or(p.kind),
+        parameter: first.and_then(|e| {
+            e.parameter
+                .or_else(|| e.parameters.into_iter().next().map(|(k, _)| k))
+        }),
+        status: p.status,
+    };
+    (problem != ApiProblem::default()).then_some(problem)
+}
+
 fn retry_after_delay_secs(h: &HeaderMap) -> Option<u64> {
     h.get(RETRY_AFTER)
         .and_then(|v| v.to_str().ok())?
@@ -756,6 +1041,79 @@ fn retry_after_delay_secs(h: &HeaderMap) -> Option<u64> {
         .ok()
 }
 
+/// The `Decode` error for a JSON body that is not UTF-8.
+fn invalid_utf8(e: &std::str::Utf8Error, snippet: String) -> HttpError {
+    HttpError::Decode(
+        format!(
+            "response body is not valid UTF-8 (first bad byte at offset {})",
+            e.valid_up_to()
+        ),
+        snippet,
+    )
+}
+
+/// JSON keys (compared lowercase, `-` read as `_`) whose values are masked in raw logs,
+/// besides any key ending in `_secret` or mentioning `password`.
+const SECRET_JSON_KEYS: &[&str] = &[
+    "access_token",
+    "refresh_token",
+    "id_token",
+    "session_token",
+    "bearer_token",
+    "token",
+    "api_key",
+    "apikey",
+    "authorization",
+    "secret",
+    "client_secret",
+];
+
+fn is_secret_json_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    SECRET_JSON_KEYS.contains(&key.as_str()) || key.ends_with("_secret") || key.contains("password")
+}
+
+/// Replace the value under every secret-looking key, at any depth, with `"<redacted>"`.
+/// Nulls stay null so an absent credential still reads as absent.
+pub fn redact_json_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if is_secret_json_key(key) && !v.is_null() {
+                    *v = serde_json::Value::String("<redacted>".into());
+                } else {
+                    redact_json_secrets(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_json_secrets),
+        _ => {}
+    }
+}
+
+/// A response body as raw-logged: JSON with secrets masked when `redact_json` is set,
+/// anything else as lossy text. Either way capped at [`RAW_MAX_BODY`] bytes; the flag says
+/// whether it was cut.
+fn raw_body(bytes: &[u8], redact_json: bool) -> (String, bool) {
+    let mut text = match serde_json::from_slice::<serde_json::Value>(bytes) {
+        Ok(mut json) if redact_json => {
+            redact_json_secrets(&mut json);
+            json.to_string()
+        }
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    };
+    let truncated = text.len() > RAW_MAX_BODY;
+    if truncated {
+        let mut cut = RAW_MAX_BODY;
+        while !text.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        text.truncate(cut);
+    }
+    (text, truncated)
+}
+
+/// Lossy on purpose: snippets only feed logs and error messages, never decoding.
 fn snip_body(body: &[u8]) -> String {
     let mut snip = String::from_utf8_lossy(body).to_string();
     if snip.len() > 500 {
@@ -793,36 +1151,403 @@ fn sanitize_api_key(raw: &str) -> Result<String, HttpError> {
     Ok(s)
 }
 
-fn redact_query(url: &Url) -> (String, Vec<(String, String)>) {
-    // Return "host + path" string and redacted query list for logging
-    let host_path = format!("{}{}", url.domain().unwrap_or("-"), url.path());
-    let redacted = url
-        .query_pairs()
-        .map(|(k, v)| {
-            let k = k.to_string();
-            let v = v.to_string();
-            let is_secret = matches!(
-                k.to_ascii_lowercase().as_str(),
-                "access_token"
-                    | "authorization"
-                    | "auth"
-                    | "key"
-                    | "api_key"
-                    | "token"
-                    | "secret"
-                    | "client_secret"
-                    | "bearer"
-            );
-            (k, if is_secret { "<redacted>".into() } else { v })
-        })
-        .collect::<Vec<_>>();
-    (host_path, redacted)
+/// Wait before retry `attempt` (1-based) of a retryable status: exponential from 200ms,
+/// never below `floor`, plus up to a tenth again as jitter so clients that failed together
+/// do not retry in lockstep.
+fn status_backoff(attempt: usize, floor: Duration) -> Duration {
+    let exp = Duration::from_millis(200u64.saturating_mul(1 << (attempt - 1).min(16)));
+    let base = exp.max(floor);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    base + base.mul_f64(f64::from(nanos % 1000) / 10_000.0)
 }
 
-fn content_len(headers: &HeaderMap, body_len: usize) -> usize {
-    headers
-        .get(reqwest::header::CONTENT_LENGTH)
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(body_len)
+#[cfg(test)]
+mod mock_server;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nowhere_common::testing::capture_events;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answers every connection with the same canned response and extra header line.
+    async fn stub(status: &'static str, header: &'static str) -> String {
+        stub_body(status, header, br#"{"error":"bad"}"#).await
+    }
+
+    /// Like [`stub`], with a body of arbitrary bytes.
+    async fn stub_body(status: &'static str, header: &'static str, body: &'static [u8]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 4096];
+                let _ = sock.read(&mut buf).await;
+                let head = format!(
+                    "HTTP/1.1 {status}\r\n{header}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = sock.write_all(head.as_bytes()).await;
+                let _ = sock.write_all(body).await;
+            }
+        });
+        base
+    }
+
+    #[test]
+    fn retryability_by_variant() {
+        let api = |code: u16| HttpError::Api {
+            status: StatusCode::from_u16(code).unwrap(),
+            message: String::new(),
+            request_id: String::new(),
+            problem: None,
+        };
+        assert!(HttpError::Network("reset".into()).is_retryable());
+        assert!(HttpError::Timeout("30s".into()).is_retryable());
+        assert!(api(429).is_retryable());
+        assert!(api(500).is_retryable());
+        assert!(api(503).is_retryable());
+        assert!(!api(400).is_retryable());
+        assert!(!api(401).is_retryable());
+        assert!(!api(404).is_retryable());
+        assert!(!HttpError::Url("nope".into()).is_retryable());
+        assert!(!HttpError::Build("bad header".into()).is_retryable());
+        assert!(!HttpError::Decode("eof".into(), String::new()).is_retryable());
+    }
+
+    #[test]
+    fn twitter_error_body_parses_into_a_problem() {
+        let body = serde_json::json!({
+            "errors": [{
+                "parameters": { "query": ["bridge closure OR ..."] },
+                "message": "There were errors processing your request: Query is too long"
+            }],
+            "title": "Invalid Request",
+            "detail": "One or more parameters to your request was invalid.",
+            "type": "https://api.twitter.com/2/problems/invalid-request"
+        })
+        .to_string();
+        let problem = extract_api_problem(body.as_bytes()).unwrap();
+        assert_eq!(
+            problem,
+            ApiProblem {
+                title: Some("Invalid Request".into()),
+                detail: Some("There were errors processing your request: Query is too long".into()),
+                kind: Some("https://api.twitter.com/2/problems/invalid-request".into()),
+                parameter: Some("query".into()),
+                status: None,
+            }
+        );
+        assert_eq!(problem.kind_name(), Some("invalid-request"));
+
+        let throttled = br#"{"title":"Too Many Requests","detail":"Too Many Requests","type":"about:blank","status":429}"#;
+        let problem = extract_api_problem(throttled).unwrap();
+        assert_eq!(problem.status, Some(429));
+        assert_eq!(problem.parameter, None);
+
+        assert_eq!(extract_api_problem(b"<html>bad gateway</html>"), None);
+        assert_eq!(extract_api_problem(br#"{"error":{"message":"x"}}"#), None);
+    }
+
+    #[test]
+    fn base_path_survives_relative_joins_and_optionally_leading_slashes() {
+        let url = |client: &HttpClient, path: &str| client.url_for(path).unwrap().to_string();
+        for base in ["https://api.x.com/2", "https://api.x.com/2/"] {
+            let client = HttpClient::new(base).unwrap();
+            assert_eq!(
+                url(&client, "tweets/search"),
+                "https://api.x.com/2/tweets/search"
+            );
+            assert_eq!(
+                url(&client, "/tweets/search"),
+                "https://api.x.com/tweets/search"
+            );
+
+            let client = client.with_preserved_base_path();
+            assert_eq!(
+                url(&client, "tweets/search"),
+                "https://api.x.com/2/tweets/search"
+            );
+            assert_eq!(
+                url(&client, "/tweets/search"),
+                "https://api.x.com/2/tweets/search"
+            );
+        }
+        let root = HttpClient::new("https://api.search.brave.com").unwrap();
+        assert_eq!(
+            url(&root, "res/v1/web/search"),
+            "https://api.search.brave.com/res/v1/web/search"
+        );
+    }
+
+    #[tokio::test]
+    async fn non_utf8_json_body_is_an_encoding_error() {
+        // Latin-1 "café" inside otherwise valid JSON.
+        let base = stub_body(
+            "200 OK",
+            "Cache-Control: no-store",
+            b"{\"name\":\"caf\xe9\"}",
+        )
+        .await;
+        let client = HttpClient::new(&base).unwrap();
+
+        let err = client
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        match err {
+            HttpError::Decode(msg, snippet) => {
+                assert!(msg.contains("not valid UTF-8"), "{msg}");
+                assert!(msg.contains("offset 12"), "{msg}");
+                assert!(snippet.starts_with("{\"name\":\"caf\u{FFFD}"), "{snippet}");
+            }
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_request_id_header_reaches_the_error() {
+        let base = stub("400 Bad Request", "CF-Ray: 8a1b2c3d-IAD").await;
+        let client = HttpClient::new(&base)
+            .unwrap()
+            .with_request_id_headers(&["x-amzn-requestid", "cf-ray"]);
+
+        let err = client
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        match err {
+            HttpError::Api { request_id, .. } => assert_eq!(request_id, "8a1b2c3d-IAD"),
+            other => panic!("expected an API error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn server_error_retry_waits_at_least_the_floor() {
+        let base = stub("503 Service Unavailable", "Cache-Control: no-store").await;
+        let client = HttpClient::new(&base)
+            .unwrap()
+            .with_retries(1)
+            .with_min_backoff(Duration::from_millis(400));
+
+        let started = std::time::Instant::now();
+        let err = client
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, HttpError::Api { status, .. } if status == StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert!(started.elapsed() >= Duration::from_millis(400));
+
+        for attempt in 1..=3 {
+            let delay = status_backoff(attempt, Duration::from_millis(400));
+            assert!(delay >= Duration::from_millis(400), "{delay:?}");
+            assert!(delay <= Duration::from_millis(880), "{delay:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_emit_one_structured_summary() {
+        let captured = capture_events("http.final_error");
+
+        let base = stub(
+            "503 Service Unavailable",
+            "Retry-After: 0\r\nX-Request-Id: req-42",
+        )
+        .await;
+        let client = HttpClient::new(&base).unwrap().with_retries(2);
+        let err = client
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HttpError::Api { .. }));
+
+        let seen = captured.events();
+        assert_eq!(seen.len(), 1, "{seen:?}");
+        let summary = &seen[0];
+        assert_eq!(summary["status"], "503");
+        assert_eq!(summary["x_request_id"], "req-42");
+        assert_eq!(summary["attempts"], "3");
+        assert_eq!(summary["message"], "bad");
+        assert!(summary["total_elapsed_ms"].parse::<u64>().is_ok());
+    }
+
+    #[tokio::test]
+    async fn raw_log_masks_secrets_in_json_bodies() {
+        let captured = capture_events("http.raw");
+
+        let base = stub_body(
+            "200 OK",
+            "X-Request-Id: req-1",
+            br#"{"access_token":"sk-live-123","token_type":"bearer","user":{"Refresh-Token":"rt-456","id":"7"}}"#,
+        )
+        .await;
+        let client = HttpClient::new(&base).unwrap().with_raw_logging(true);
+        let got: serde_json::Value = client
+            .get_json("oauth/token", RequestOpts::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            got["access_token"], "sk-live-123",
+            "callers still get the real body"
+        );
+
+        let seen: Vec<_> = captured
+            .events()
+            .into_iter()
+            .filter_map(|event| event.get("body").cloned())
+            .collect();
+        assert_eq!(seen.len(), 1, "{seen:?}");
+        let body = &seen[0];
+        assert!(
+            !body.contains("sk-live-123") && !body.contains("rt-456"),
+            "{body}"
+        );
+        assert!(body.contains(r#""access_token":"<redacted>""#), "{body}");
+        assert!(body.contains(r#""token_type":"bearer""#), "{body}");
+        assert!(body.contains(r#""id":"7""#), "{body}");
+    }
+
+    #[tokio::test]
+    async fn throttled_request_is_retried_once() {
+        let server = mock_server::too_many_then_ok().await;
+        let client = HttpClient::new(&server.uri()).unwrap();
+
+        let got: serde_json::Value = client
+            .get_json("v1/items", RequestOpts::default())
+            .await
+            .unwrap();
+        assert_eq!(got, mock_server::ok_body());
+        assert_eq!(mock_server::requests(&server).await, 2, "one retry");
+    }
+
+    #[tokio::test]
+    async fn server_error_is_retried_until_it_clears() {
+        let server = mock_server::server_error_then_ok().await;
+        let client = HttpClient::new(&server.uri()).unwrap();
+
+        let got: serde_json::Value = client
+            .get_json("v1/items", RequestOpts::default())
+            .await
+            .unwrap();
+        assert_eq!(got, mock_server::ok_body());
+        assert_eq!(mock_server::requests(&server).await, 2);
+
+        // Without retries the failure surfaces as is.
+        let server = mock_server::server_error_then_ok().await;
+        let err = HttpClient::new(&server.uri())
+            .unwrap()
+            .with_retries(0)
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, HttpError::Api { status, .. } if status == StatusCode::SERVICE_UNAVAILABLE)
+        );
+    }
+
+    #[tokio::test]
+    async fn retried_post_resends_the_same_idempotency_key() {
+        let server = mock_server::post_server_error_then_ok().await;
+        let client = HttpClient::new(&server.uri()).unwrap();
+
+        let opts = RequestOpts {
+            idempotency_key: Some("claim-7f3a".into()),
+            ..Default::default()
+        };
+        let got: serde_json::Value = client
+            .post_json_opts("v1/records", &serde_json::json!({ "n": 1 }), opts)
+            .await
+            .unwrap();
+        assert_eq!(got, mock_server::ok_body());
+
+        let keys: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.headers.get(DEFAULT_IDEMPOTENCY_HEADER).cloned())
+            .collect();
+        assert_eq!(keys.len(), 2, "one retry");
+        assert!(
+            keys.iter().all(|k| k.as_ref().unwrap() == "claim-7f3a"),
+            "{keys:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn slow_response_past_the_timeout_is_a_timeout() {
+        let server = mock_server::slow(Duration::from_secs(2)).await;
+        let client = HttpClient::new(&server.uri())
+            .unwrap()
+            .with_timeout(Duration::from_millis(100))
+            .with_retries(0);
+
+        let err = client
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, HttpError::Timeout(_)), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn invalid_json_is_a_decode_error_with_a_snippet() {
+        let server = mock_server::invalid_json().await;
+        let client = HttpClient::new(&server.uri()).unwrap();
+
+        let err = client
+            .get_json::<serde_json::Value>("v1/items", RequestOpts::default())
+            .await
+            .unwrap_err();
+        match err {
+            HttpError::Decode(_, snippet) => assert_eq!(snippet, r#"{"ok": tru"#),
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+        assert_eq!(mock_server::requests(&server).await, 1, "not retried");
+    }
+
+    #[tokio::test]
+    async fn basic_auth_sends_encoded_credentials_and_logs_only_the_scheme() {
+        let expected = "Basic YWxpY2U6czNjcmV0"; // alice:s3cret
+        let server = mock_server::requires_authorization(expected).await;
+        let client = HttpClient::new(&server.uri()).unwrap();
+
+        let opts = RequestOpts {
+            auth: Some(Auth::Basic {
+                username: "alice",
+                password: "s3cret",
+            }),
+            ..Default::default()
+        };
+        let got: serde_json::Value = client.get_json("v1/items", opts).await.unwrap();
+        assert_eq!(got, mock_server::ok_body());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            HeaderValue::from_static(expected),
+        );
+        assert_eq!(
+            redact_headers(&headers),
+            vec![("authorization".to_string(), "Basic <redacted>".to_string())]
+        );
+        let curl = make_curl(&Method::GET, &server.uri().parse().unwrap(), &headers, None);
+        assert!(!curl.contains("YWxpY2U6czNjcmV0"), "{curl}");
+    }
+
+    #[test]
+    fn raw_body_truncates_non_json_text() {
+        let (text, truncated) = raw_body(b"upstream timeout", true);
+        assert_eq!((text.as_str(), truncated), ("upstream timeout", false));
+
+        let long = "é".repeat(RAW_MAX_BODY);
+        let (text, truncated) = raw_body(long.as_bytes(), true);
+        assert!(truncated);
+        assert_eq!(text.len(), RAW_MAX_BODY);
+    }
 }
@@ -0,0 +1,93 @@
+//! `wiremock` fixtures for the failure modes retry and decode tests keep needing.
+//!
+//! Each fixture starts a fresh [`MockServer`] that answers `GET` (`POST` for the `post_`
+//! ones) on any path; point an [`HttpClient`](crate::HttpClient) at [`MockServer::uri`]
+//! and count attempts with [`requests`]. Failing responses carry `Retry-After: 0` so
+//! retries don't sleep.
+use std::time::Duration;
+use wiremock::matchers::{header, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Body every fixture's successful response returns.
+pub(crate) fn ok_body() -> serde_json::Value {
+    serde_json::json!({ "ok": true })
+}
+
+/// Answers the first request with `429 Too Many Requests`, then `200` with [`ok_body`].
+pub(crate) async fn too_many_then_ok() -> MockServer {
+    fail_once_then_ok("GET", 429).await
+}
+
+/// Answers the first request with `503 Service Unavailable`, then `200` with [`ok_body`].
+pub(crate) async fn server_error_then_ok() -> MockServer {
+    fail_once_then_ok("GET", 503).await
+}
+
+/// Like [`server_error_then_ok`], for `POST`.
+pub(crate) async fn post_server_error_then_ok() -> MockServer {
+    fail_once_then_ok("POST", 503).await
+}
+
+/// Answers every request with [`ok_body`], but only after `delay`.
+pub(crate) async fn slow(delay: Duration) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(ok_body())
+                .set_delay(delay),
+        )
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Answers every request with `200` and a body that claims to be JSON but isn't.
+pub(crate) async fn invalid_json() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(r#"{"ok": tru"#, "application/json"))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Answers requests carrying `Authorization: <authorization>` with [`ok_body`], and every
+/// other request with `401 Unauthorized`.
+pub(crate) async fn requires_authorization(authorization: &'static str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(header("authorization", authorization))
+        .respond_with(ResponseTemplate::new(200).set_body_json(ok_body()))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Requests `server` has received so far.
+pub(crate) async fn requests(server: &MockServer) -> usize {
+    server.received_requests().await.map_or(0, |r| r.len())
+}
+
+async fn fail_once_then_ok(verb: &str, status: u16) -> MockServer {
+    let server = MockServer::start().await;
+    // Mounted first, so it answers until its single use is spent.
+    Mock::given(method(verb))
+        .respond_with(
+            ResponseTemplate::new(status)
+                .insert_header("Retry-After", "0")
+                .set_body_json(serde_json::json!({ "error": "try again" })),
+        )
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method(verb))
+        .respond_with(ResponseTemplate::new(200).set_body_json(ok_body()))
+        .mount(&server)
+        .await;
+    server
+}
@@ -1,12 +1,31 @@
-use crate::traits::{LlmClient, LlmError, LlmResponse};
+use crate::traits::{LlmClient, LlmError, LlmResponse, DEFAULT_OSINT_SYSTEM_PROMPT};
+use crate::{DEFAULT_HEALTH_CHECK_TIMEOUT, DEFAULT_REQUEST_TIMEOUT};
 use async_trait::async_trait;
 use nowhere_common::Result;
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value as JsonValue};
 use std::time::Duration;
 
 const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta";
 
+/// Harm categories sent with every request.
+pub const SAFETY_CATEGORIES: &[&str] = &[
+    "HARM_CATEGORY_HARASSMENT",
+    "HARM_CATEGORY_HATE_SPEECH",
+    "HARM_CATEGORY_SEXUALLY_EXPLICIT",
+    "HARM_CATEGORY_DANGEROUS_CONTENT",
+];
+
+/// Thresholds the API accepts, loosest first.
+pub const SAFETY_THRESHOLDS: &[&str] = &[
+    "OFF",
+    "BLOCK_NONE",
+    "BLOCK_ONLY_HIGH",
+    "BLOCK_MEDIUM_AND_ABOVE",
+    "BLOCK_LOW_AND_ABOVE",
+];
+
+pub const DEFAULT_SAFETY_THRESHOLD: &str = "BLOCK_MEDIUM_AND_ABOVE";
+
 #[derive(Debug, Serialize)]
 struct GeminiRequest {
     contents: Vec<GeminiContent>,
@@ -45,7 +64,7 @@ struct GeminiGenerationConfig {
     top_k: Option<u32>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct GeminiSafetySetting {
     category: String,
     threshold: String,
@@ -53,20 +72,49 @@ struct GeminiSafetySetting {
 
 #[derive(Debug, Deserialize)]
 struct GeminiResponse {
+    // Absent when the prompt itself was blocked.
+    #[serde(default)]
     candidates: Vec<GeminiCandidate>,
+    #[serde(rename = "promptFeedback")]
+    prompt_feedback: Option<GeminiPromptFeedback>,
     #[serde(rename = "usageMetadata")]
     usage_metadata: Option<GeminiUsageMetadata>,
 }
 
+#[derive(Debug, Deserialize)]
+struct GeminiPromptFeedback {
+    #[serde(rename = "blockReason")]
+    block_reason: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct GeminiCandidate {
+    // A MAX_TOKENS finish can arrive with no content at all.
+    #[serde(default)]
     content: GeminiResponseContent,
     #[serde(rename = "finishReason")]
     finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Candidate finish reasons that mean the response was withheld by a filter.
+const BLOCKED_FINISH_REASONS: &[&str] = &[
+    "SAFETY",
+    "RECITATION",
+    "BLOCKLIST",
+    "PROHIBITED_CONTENT",
+    "SPII",
+];
+
+impl GeminiCandidate {
+    fn finish_reason(&self) -> (Option<String>, bool) {
+        let truncated = self.finish_reason.as_deref() == Some("MAX_TOKENS");
+        (self.finish_reason.clone(), truncated)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
 struct GeminiResponseContent {
+    #[serde(default)]
     parts: Vec<GeminiResponsePart>,
 }
 
@@ -77,29 +125,78 @@ struct GeminiResponsePart {
 
 #[derive(Debug, Deserialize)]
 struct GeminiUsageMetadata {
-    #[serde(rename = "promptTokenCount")]
-    prompt_token_count: Option<u32>,
-    #[serde(rename = "candidatesTokenCount")]
-    candidates_token_count: Option<u32>,
     #[serde(rename = "totalTokenCount")]
     total_token_count: Option<u32>,
 }
 
+impl GeminiResponse {
+    /// Pick the first candidate. Prompt blocks and filtered candidates become
+    /// `LlmError::Api` with the cause; a `MAX_TOKENS` finish is returned as truncated,
+    /// even when it carries no text.
+    fn into_llm_response(self, model: &str) -> Result<LlmResponse> {
+        if let Some(reason) = self.prompt_feedback.and_then(|f| f.block_reason) {
+            return Err(LlmError::Api(format!("Gemini blocked the prompt: {reason}")).into());
+        }
+        let Some(candidate) = self.candidates.into_iter().next() else {
+            return Err(nowhere_common::NowhereError::Agent(
+                "No candidates returned from Gemini".to_string(),
+            ));
+        };
+        if let Some(reason) = candidate
+            .finish_reason
+            .as_deref()
+            .filter(|r| BLOCKED_FINISH_REASONS.contains(r))
+        {
+            return Err(LlmError::Api(format!("Gemini blocked the response: {reason}")).into());
+        }
+
+        let (finish_reason, truncated) = candidate.finish_reason();
+        let text = match candidate.content.parts.into_iter().next() {
+            Some(part) => part.text,
+            None if truncated => String::new(),
+            None => {
+                return Err(nowhere_common::NowhereError::Agent(
+                    "No content parts in Gemini response".to_string(),
+                ));
+            }
+        };
+        let tokens_used = self.usage_metadata.and_then(|u| u.total_token_count);
+
+        Ok(LlmResponse {
+            text,
+            model: Some(model.to_string()),
+            tokens_used,
+            confidence: None,
+            finish_reason,
+            truncated,
+        })
+    }
+}
+
 /// Google Gemini API client.
 ///
 /// Requires a valid API key and internet access.
 pub struct GeminiClient {
     client: reqwest::Client,
+    base_url: String,
     api_key: String,
     model: String,
+    system_prompt_override: Option<String>,
+    safety_settings: Vec<GeminiSafetySetting>,
+    request_timeout: Duration,
+    health_check_timeout: Duration,
 }
 
 impl GeminiClient {
     /// Create a new client using the provided API key and model.
     pub fn new(api_key: String, model: String) -> Result<Self> {
+        Self::with_base_url(api_key, model, GEMINI_BASE_URL)
+    }
+
+    /// Point the client at a different API host (e.g. a local stub in tests).
+    pub fn with_base_url(api_key: String, model: String, base: &str) -> Result<Self> {
         let client = reqwest::Client::builder()
             .connect_timeout(Duration::from_secs(10))
-            .timeout(Duration::from_secs(60))
             .build()
             .map_err(|e| {
                 nowhere_common::NowhereError::Agent(format!("Failed to create HTTP client: {}", e))
@@ -107,43 +204,77 @@ impl GeminiClient {
 
         Ok(Self {
             client,
+            base_url: base.trim_end_matches('/').to_string(),
             api_key,
             model,
+            system_prompt_override: None,
+            safety_settings: Self::create_safety_settings(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            health_check_timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
         })
     }
 
+    /// How long a generate request may take before it is abandoned (default 120s).
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// How long `health_check` waits for the probe prompt (default 5s).
+    pub fn with_health_check_timeout(mut self, timeout: Duration) -> Self {
+        self.health_check_timeout = timeout;
+        self
+    }
+
+    /// Set the blocking threshold for one harm category (default
+    /// `BLOCK_MEDIUM_AND_ABOVE`). Fact-checking violent or political claims may need
+    /// `BLOCK_ONLY_HIGH` or `BLOCK_NONE`. Unknown categories or thresholds are a config error.
+    pub fn with_safety_threshold(mut self, category: &str, threshold: &str) -> Result<Self> {
+        if !SAFETY_CATEGORIES.contains(&category) {
+            return Err(LlmError::Config(format!(
+                "unknown Gemini harm category {category:?} (expected one of {SAFETY_CATEGORIES:?})"
+            ))
+            .into());
+        }
+        if !SAFETY_THRESHOLDS.contains(&threshold) {
+            return Err(LlmError::Config(format!(
+                "unknown Gemini safety threshold {threshold:?} (expected one of {SAFETY_THRESHOLDS:?})"
+            ))
+            .into());
+        }
+        for setting in &mut self.safety_settings {
+            if setting.category == category {
+                setting.threshold = threshold.to_string();
+            }
+        }
+        Ok(self)
+    }
+
+    /// Replace the built-in analyst system prompt used by actors and helper methods.
+    pub fn with_system_prompt_override(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt_override = Some(prompt.into());
+        self
+    }
+
     fn create_safety_settings() -> Vec<GeminiSafetySetting> {
-        vec![
-            GeminiSafetySetting {
-                category: "HARM_CATEGORY_HARASSMENT".to_string(),
-                threshold: "BLOCK_MEDIUM_AND_ABOVE".to_string(),
-            },
-            GeminiSafetySetting {
-                category: "HARM_CATEGORY_HATE_SPEECH".to_string(),
-                threshold: "BLOCK_MEDIUM_AND_ABOVE".to_string(),
-            },
-            GeminiSafetySetting {
-                category: "HARM_CATEGORY_SEXUALLY_EXPLICIT".to_string(),
-                threshold: "BLOCK_MEDIUM_AND_ABOVE".to_string(),
-            },
-            GeminiSafetySetting {
-                category: "HARM_CATEGORY_DANGEROUS_CONTENT".to_string(),
-                threshold: "BLOCK_MEDIUM_AND_ABOVE".to_string(),
-            },
-        ]
+        SAFETY_CATEGORIES
+            .iter()
+            .map(|category| GeminiSafetySetting {
+                category: category.to_string(),
+                threshold: DEFAULT_SAFETY_THRESHOLD.to_string(),
+            })
+            .collect()
     }
-}
 
-#[async_trait]
-impl LlmClient for GeminiClient {
-    async fn generate(
+    async fn request(
         &self,
         prompt: &str,
         system_prompt: Option<&str>,
         max_tokens: Option<u32>,
         temperature: Option<f32>,
-    ) -> Result<LlmResponse> {
-        let url = format!("{}/models/{}:generateContent", GEMINI_BASE_URL, self.model);
+        timeout: Duration,
+    ) -> Result<(LlmResponse, serde_json::Value)> {
+        let url = format!("{}/models/{}:generateContent", self.base_url, self.model);
 
         let generation_config = if max_tokens.is_some() || temperature.is_some() {
             Some(GeminiGenerationConfig {
@@ -170,7 +301,7 @@ impl LlmClient for GeminiClient {
                 }],
             }],
             generation_config,
-            safety_settings: Some(Self::create_safety_settings()),
+            safety_settings: Some(self.safety_settings.clone()),
             system_instruction,
         };
 
@@ -182,6 +313,7 @@ impl LlmClient for GeminiClient {
             .header("Content-Type", "application/json")
             .query(&[("key", &self.api_key)])
             .json(&request)
+            .timeout(timeout)
             .send()
             .await
             .map_err(|e| {
@@ -192,10 +324,12 @@ impl LlmClient for GeminiClient {
             let status = resp.status();
             let error_text = resp.text().await.unwrap_or_default();
 
+            // Quota exhaustion arrives as 429 with status RESOURCE_EXHAUSTED in the body.
             return Err(match status.as_u16() {
-                429 => nowhere_common::NowhereError::Agent("Rate limit exceeded".to_string()),
-                401 => nowhere_common::NowhereError::Agent("Invalid API key".to_string()),
-                403 => nowhere_common::NowhereError::Agent("API access forbidden".to_string()),
+                429 => LlmError::RateLimit.into(),
+                _ if error_text.contains("RESOURCE_EXHAUSTED") => LlmError::RateLimit.into(),
+                401 => LlmError::Config("Invalid API key".to_string()).into(),
+                403 => LlmError::Config("API access forbidden".to_string()).into(),
                 _ => nowhere_common::NowhereError::Agent(format!(
                     "Gemini API error ({}): {}",
                     status, error_text
@@ -203,51 +337,62 @@ impl LlmClient for GeminiClient {
             });
         }
 
-        let gemini_response: GeminiResponse = resp.json().await.map_err(|e| {
+        let parse_error = |e: &dyn std::fmt::Display| {
             nowhere_common::NowhereError::Agent(format!("Failed to parse Gemini response: {}", e))
-        })?;
-
-        if gemini_response.candidates.is_empty() {
-            return Err(nowhere_common::NowhereError::Agent(
-                "No candidates returned from Gemini".to_string(),
-            ));
-        }
-
-        let candidate = &gemini_response.candidates[0];
-
-        // Check for safety blocks
-        if let Some(finish_reason) = &candidate.finish_reason {
-            if finish_reason == "SAFETY" {
-                return Err(nowhere_common::NowhereError::Agent(
-                    "Content blocked by Gemini safety filters".to_string(),
-                ));
-            }
-        }
+        };
+        let raw: serde_json::Value = resp.json().await.map_err(|e| parse_error(&e))?;
+        let gemini_response = GeminiResponse::deserialize(&raw).map_err(|e| parse_error(&e))?;
 
-        if candidate.content.parts.is_empty() {
-            return Err(nowhere_common::NowhereError::Agent(
-                "No content parts in Gemini response".to_string(),
-            ));
-        }
+        Ok((gemini_response.into_llm_response(&self.model)?, raw))
+    }
+}
 
-        let text = candidate.content.parts[0].text.clone();
-        let tokens_used = gemini_response
-            .usage_metadata
-            .and_then(|u| u.total_token_count);
+#[async_trait]
+impl LlmClient for GeminiClient {
+    async fn generate(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<LlmResponse> {
+        self.generate_raw(prompt, system_prompt, max_tokens, temperature)
+            .await
+            .map(|(response, _)| response)
+    }
 
-        Ok(LlmResponse {
-            text,
-            model: Some(self.model.clone()),
-            tokens_used,
-            confidence: None,
-        })
+    async fn generate_raw(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<(LlmResponse, serde_json::Value)> {
+        self.request(
+            prompt,
+            system_prompt,
+            max_tokens,
+            temperature,
+            self.request_timeout,
+        )
+        .await
     }
 
     async fn health_check(&self) -> Result<bool> {
-        // Simple health check by trying to generate a minimal response
+        // Simple health check by trying to generate a minimal response, bounded by the
+        // short health-check timeout so a dead endpoint does not stall a status probe.
         let test_prompt = "Respond with just 'OK'";
 
-        match self.generate(test_prompt, None, Some(5), Some(0.1)).await {
+        match self
+            .request(
+                test_prompt,
+                None,
+                Some(5),
+                Some(0.1),
+                self.health_check_timeout,
+            )
+            .await
+        {
             Ok(_) => Ok(true),
             Err(e) => {
                 tracing::warn!("Gemini health check failed: {}", e);
@@ -259,4 +404,163 @@ impl LlmClient for GeminiClient {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    fn default_osint_system_prompt(&self) -> &str {
+        self.system_prompt_override
+            .as_deref()
+            .unwrap_or(DEFAULT_OSINT_SYSTEM_PROMPT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nowhere_common::NowhereError;
+
+    fn candidate(finish_reason: &str) -> GeminiCandidate {
+        serde_json::from_value(serde_json::json!({
+            "content": { "parts": [{ "text": "{\"claim_relevance\":" }] },
+            "finishReason": finish_reason,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn max_tokens_finish_reason_is_truncated() {
+        let (reason, truncated) = candidate("MAX_TOKENS").finish_reason();
+        assert_eq!(reason.as_deref(), Some("MAX_TOKENS"));
+        assert!(truncated);
+
+        let (reason, truncated) = candidate("STOP").finish_reason();
+        assert_eq!(reason.as_deref(), Some("STOP"));
+        assert!(!truncated);
+    }
+
+    fn response(body: serde_json::Value) -> Result<LlmResponse> {
+        serde_json::from_value::<GeminiResponse>(body)
+            .unwrap()
+            .into_llm_response("gemini-test")
+    }
+
+    #[test]
+    fn prompt_block_reports_the_reason() {
+        let err = response(serde_json::json!({
+            "promptFeedback": { "blockReason": "PROHIBITED_CONTENT" }
+        }))
+        .unwrap_err();
+        match LlmError::from_nowhere(&err) {
+            Some(LlmError::Api(msg)) => assert!(msg.contains("PROHIBITED_CONTENT"), "{msg}"),
+            other => panic!("expected an API error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn max_tokens_finish_without_text_is_truncated_not_an_error() {
+        let resp = response(serde_json::json!({
+            "candidates": [{ "content": { "role": "model" }, "finishReason": "MAX_TOKENS" }]
+        }))
+        .unwrap();
+        assert!(resp.truncated);
+        assert_eq!(resp.finish_reason.as_deref(), Some("MAX_TOKENS"));
+        assert!(resp.text.is_empty());
+    }
+
+    #[tokio::test]
+    async fn http_429_maps_to_rate_limit() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).set_body_json(serde_json::json!({
+                "error": { "code": 429, "status": "RESOURCE_EXHAUSTED" }
+            })))
+            .mount(&server)
+            .await;
+
+        let client =
+            GeminiClient::with_base_url("key".into(), "gemini-1.5-flash".into(), &server.uri())
+                .unwrap();
+        let err = client.generate("hi", None, None, None).await.unwrap_err();
+        assert!(matches!(
+            LlmError::from_nowhere(&err),
+            Some(LlmError::RateLimit)
+        ));
+    }
+
+    #[tokio::test]
+    async fn custom_safety_threshold_reaches_the_request() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "candidates": [{ "content": { "parts": [{ "text": "ok" }] }, "finishReason": "STOP" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client =
+            GeminiClient::with_base_url("key".into(), "gemini-1.5-flash".into(), &server.uri())
+                .unwrap()
+                .with_safety_threshold("HARM_CATEGORY_DANGEROUS_CONTENT", "BLOCK_ONLY_HIGH")
+                .unwrap();
+        client.generate("hi", None, None, None).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let threshold = |category: &str| {
+            body["safety_settings"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .find(|s| s["category"] == category)
+                .map(|s| s["threshold"].clone())
+        };
+        assert_eq!(
+            threshold("HARM_CATEGORY_DANGEROUS_CONTENT"),
+            Some("BLOCK_ONLY_HIGH".into())
+        );
+        assert_eq!(
+            threshold("HARM_CATEGORY_HARASSMENT"),
+            Some(DEFAULT_SAFETY_THRESHOLD.into())
+        );
+
+        let bad = GeminiClient::new("key".into(), "m".into())
+            .unwrap()
+            .with_safety_threshold("HARM_CATEGORY_HARASSMENT", "BLOCK_SOME");
+        assert!(matches!(bad, Err(NowhereError::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn request_timeout_is_applied_and_health_check_uses_the_shorter_one() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(Duration::from_millis(300))
+                    .set_body_json(serde_json::json!({
+                        "candidates": [{ "content": { "parts": [{ "text": "ok" }] }, "finishReason": "STOP" }]
+                    })),
+            )
+            .mount(&server)
+            .await;
+        let client = || {
+            GeminiClient::with_base_url("key".into(), "gemini-1.5-flash".into(), &server.uri())
+                .unwrap()
+        };
+
+        let patient = client()
+            .with_request_timeout(Duration::from_secs(5))
+            .with_health_check_timeout(Duration::from_millis(50));
+        patient.generate("hi", None, None, None).await.unwrap();
+        assert!(!patient.health_check().await.unwrap());
+
+        let impatient = client().with_request_timeout(Duration::from_millis(50));
+        assert!(impatient.generate("hi", None, None, None).await.is_err());
+    }
 }
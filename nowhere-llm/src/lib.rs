@@ -18,8 +18,11 @@
 //! # }
 //! ```
 pub mod gemini;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 pub mod ollama;
 pub mod openai;
+pub mod single_flight;
 pub mod traits;
 pub mod verifier;
 
@@ -28,6 +31,7 @@ use nowhere_common::{LlmConfig, NowhereError};
 use ollama::OllamaClient;
 use openai::OpenAiClient;
 use std::sync::Arc;
+use std::time::Duration;
 use traits::LlmClient;
 
 /// Default model recommendations for nowhere tasks
@@ -35,6 +39,11 @@ pub const DEFAULT_OLLAMA_MODEL: &str = "llama3.2:3b";
 pub const DEFAULT_GEMINI_MODEL: &str = "gemini-1.5-flash";
 pub const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
 
+/// Per-request timeout for hosted providers; long syntheses can run past a minute.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+/// Timeout for `health_check`, so a status probe against a dead endpoint fails fast.
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Ensure an LLM client is ready (e.g., downloading models if needed).
 pub async fn ensure_llm_ready(
     config: &LlmConfig,
@@ -46,8 +55,19 @@ pub async fn ensure_llm_ready(
             Ok(Arc::new(client))
         }
         #[cfg(feature = "gemini")]
-        LlmConfig::Gemini { api_key, model } => {
-            let client = GeminiClient::new(api_key.clone(), model.clone())?;
+        LlmConfig::Gemini {
+            api_key,
+            model,
+            safety_thresholds,
+            request_timeout_secs,
+        } => {
+            let mut client = GeminiClient::new(api_key.clone(), model.clone())?;
+            if let Some(secs) = request_timeout_secs {
+                client = client.with_request_timeout(Duration::from_secs(*secs));
+            }
+            for (category, threshold) in safety_thresholds {
+                client = client.with_safety_threshold(category, threshold)?;
+            }
             Ok(Arc::new(client))
         }
         LlmConfig::None => Err(NowhereError::Config("No LLM configured".to_string())),
@@ -56,10 +76,14 @@ pub async fn ensure_llm_ready(
             api_key,
             model,
             base_url: _,
+            request_timeout_secs,
         } => {
             // FIXME(config): honor `base_url` to support Azure/OpenAI-compatible
             // endpoints or gateways; thread through to OpenAiClient.
-            let client = OpenAiClient::new(api_key.clone(), model.clone())?;
+            let mut client = OpenAiClient::new(api_key.clone(), model.clone())?;
+            if let Some(secs) = request_timeout_secs {
+                client = client.with_request_timeout(Duration::from_secs(*secs));
+            }
             Ok(Arc::new(client))
         }
         #[allow(unreachable_patterns)]
@@ -0,0 +1,223 @@
+//! Scriptable [`LlmClient`] for tests, here and (with the `test-util` feature) downstream.
+//!
+//! ```
+//! use nowhere_llm::mock::MockLlmClient;
+//! use nowhere_llm::traits::{LlmClient, LlmError};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let client = MockLlmClient::new()
+//!     .on_prompt("Relevant?", "yes")
+//!     .reply("first")
+//!     .fail(LlmError::RateLimit);
+//!
+//! assert_eq!(client.generate("hello", None, None, None).await.unwrap().text, "first");
+//! assert!(client.generate("hello", None, None, None).await.is_err());
+//! assert_eq!(client.generate("... Relevant?", None, None, None).await.unwrap().text, "yes");
+//! assert_eq!(client.prompts(), ["hello", "hello", "... Relevant?"]);
+//! # }
+//! ```
+use crate::traits::{GenDefaults, LlmClient, LlmError, LlmResponse};
+use async_trait::async_trait;
+use nowhere_common::Result;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// One `generate` call as the mock saw it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    pub prompt: String,
+    pub system_prompt: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+}
+
+/// Answers `generate` from canned replies and records every call.
+///
+/// A prompt containing the needle of an [`on_prompt`](Self::on_prompt) rule gets that
+/// rule's reply (first match wins, rules are reusable). Any other prompt takes the next
+/// entry of the script built with [`reply`](Self::reply), [`respond`](Self::respond) and
+/// [`fail`](Self::fail); once the script runs dry, `generate` returns an `LlmError::Api`.
+pub struct MockLlmClient {
+    model: String,
+    gen_defaults: GenDefaults,
+    rules: Vec<(String, LlmResponse)>,
+    script: Mutex<VecDeque<Result<LlmResponse>>>,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl Default for MockLlmClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockLlmClient {
+    pub fn new() -> Self {
+        Self {
+            model: "mock".into(),
+            gen_defaults: GenDefaults::default(),
+            rules: Vec::new(),
+            script: Mutex::new(VecDeque::new()),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Name reported by `model_name` (default `"mock"`).
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+
+    /// Settings reported by `gen_defaults` (default [`GenDefaults::default`]).
+    pub fn with_gen_defaults(mut self, defaults: GenDefaults) -> Self {
+        self.gen_defaults = defaults;
+        self
+    }
+
+    /// Queue a completed reply with `text`.
+    pub fn reply(self, text: impl Into<String>) -> Self {
+        self.respond(completion(text))
+    }
+
+    /// Queue a completed reply for each of `texts`, in order.
+    pub fn replies<I>(self, texts: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        texts.into_iter().fold(self, Self::reply)
+    }
+
+    /// Queue a full response, e.g. one marked `truncated`.
+    pub fn respond(self, response: LlmResponse) -> Self {
+        self.push(Ok(response))
+    }
+
+    /// Queue a failure.
+    pub fn fail(self, err: LlmError) -> Self {
+        self.push(Err(err.into()))
+    }
+
+    /// Reply `text` to every prompt containing `needle`, ahead of the script.
+    pub fn on_prompt(mut self, needle: impl Into<String>, text: impl Into<String>) -> Self {
+        self.rules.push((needle.into(), completion(text)));
+        self
+    }
+
+    fn push(self, entry: Result<LlmResponse>) -> Self {
+        self.script.lock().unwrap().push_back(entry);
+        self
+    }
+
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    pub fn prompts(&self) -> Vec<String> {
+        self.calls().into_iter().map(|call| call.prompt).collect()
+    }
+
+    /// Scripted entries not yet consumed.
+    pub fn remaining(&self) -> usize {
+        self.script.lock().unwrap().len()
+    }
+}
+
+/// A finished (`"stop"`) response carrying `text`.
+pub fn completion(text: impl Into<String>) -> LlmResponse {
+    LlmResponse {
+        text: text.into(),
+        model: None,
+        tokens_used: None,
+        confidence: None,
+        finish_reason: Some("stop".into()),
+        truncated: false,
+    }
+}
+
+#[async_trait]
+impl LlmClient for MockLlmClient {
+    async fn generate(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<LlmResponse> {
+        self.calls.lock().unwrap().push(MockCall {
+            prompt: prompt.to_string(),
+            system_prompt: system_prompt.map(str::to_string),
+            max_tokens,
+            temperature,
+        });
+        if let Some((_, response)) = self
+            .rules
+            .iter()
+            .find(|(needle, _)| prompt.contains(needle))
+        {
+            return Ok(response.clone());
+        }
+        self.script.lock().unwrap().pop_front().unwrap_or_else(|| {
+            Err(LlmError::Api("MockLlmClient: no scripted reply left".into()).into())
+        })
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn model_name(&self) -> &str {
+        &self.model
+    }
+
+    fn gen_defaults(&self) -> GenDefaults {
+        self.gen_defaults
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_calls_and_replays_the_script_in_order() {
+        let client = MockLlmClient::new()
+            .reply("one")
+            .fail(LlmError::RateLimit)
+            .reply("two")
+            .on_prompt("ping", "pong");
+
+        let first = client
+            .generate("a", Some("sys"), Some(10), Some(0.2))
+            .await
+            .unwrap();
+        let second = client.generate("b", None, None, None).await.unwrap_err();
+        let pong = client.generate("ping?", None, None, None).await.unwrap();
+        let third = client.generate("c", None, None, None).await.unwrap();
+        let dry = client.generate("d", None, None, None).await.unwrap_err();
+
+        assert_eq!(first.text, "one");
+        assert!(matches!(
+            LlmError::from_nowhere(&second),
+            Some(LlmError::RateLimit)
+        ));
+        assert_eq!(pong.text, "pong");
+        assert_eq!(third.text, "two");
+        assert!(matches!(
+            LlmError::from_nowhere(&dry),
+            Some(LlmError::Api(_))
+        ));
+        assert_eq!(client.remaining(), 0);
+        assert_eq!(client.prompts(), ["a", "b", "ping?", "c", "d"]);
+        assert_eq!(
+            client.calls()[0],
+            MockCall {
+                prompt: "a".into(),
+                system_prompt: Some("sys".into()),
+                max_tokens: Some(10),
+                temperature: Some(0.2),
+            }
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::traits::{LlmClient, LlmResponse};
+use crate::traits::{LlmClient, LlmError, LlmResponse, DEFAULT_OSINT_SYSTEM_PROMPT};
 use async_trait::async_trait;
 use nowhere_common::{NowhereError, Result};
 use serde_json::{json, Value as JsonValue};
@@ -6,6 +6,28 @@ use std::time::Duration;
 
 const OLLAMA_CONNECTION_ERROR: &str = "No running Ollama server detected. Start it with: `ollama serve` (after installing). Install instructions: https://github.com/ollama/ollama";
 
+/// How [`OllamaClient::connect`] waits for a server that is still starting, e.g. one
+/// launched alongside the app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartupProbe {
+    /// Probes of `/api/tags` before giving up; at least one is always made.
+    pub attempts: u32,
+    /// Wait after the first failed probe; doubles after each further failure.
+    pub interval: Duration,
+    /// Longest the whole wait may take, probes included.
+    pub timeout: Duration,
+}
+
+impl Default for StartupProbe {
+    fn default() -> Self {
+        Self {
+            attempts: 5,
+            interval: Duration::from_millis(500),
+            timeout: Duration::from_secs(15),
+        }
+    }
+}
+
 /// Ollama client for local model inference.
 ///
 /// Expects a running Ollama server (see https://github.com/ollama/ollama).
@@ -13,11 +35,18 @@ pub struct OllamaClient {
     client: reqwest::Client,
     base_url: String,
     model: String,
+    system_prompt_override: Option<String>,
 }
 
 impl OllamaClient {
-    /// Create a new client and verify server/model availability.
+    /// Create a new client and verify server/model availability, waiting for the server
+    /// as [`StartupProbe::default`] allows.
     pub async fn new(base_url: String, model: String) -> Result<Self> {
+        Self::connect(base_url, model, StartupProbe::default()).await
+    }
+
+    /// Like [`new`](Self::new), but wait for the server as `startup` allows.
+    pub async fn connect(base_url: String, model: String, startup: StartupProbe) -> Result<Self> {
         let client = reqwest::Client::builder()
             .connect_timeout(Duration::from_secs(10))
             .build()
@@ -27,10 +56,11 @@ impl OllamaClient {
             client,
             base_url: base_url.trim_end_matches('/').to_string(),
             model,
+            system_prompt_override: None,
         };
 
         // Verify server is reachable
-        ollama_client.probe_server().await?;
+        ollama_client.wait_for_server(&startup).await?;
 
         // Ensure model is available
         ollama_client.ensure_model_available().await?;
@@ -38,6 +68,46 @@ impl OllamaClient {
         Ok(ollama_client)
     }
 
+    /// Replace the built-in analyst system prompt used by actors and helper methods.
+    pub fn with_system_prompt_override(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt_override = Some(prompt.into());
+        self
+    }
+
+    /// Probe until the server answers, backing off between attempts, within `startup`'s
+    /// attempt and time budget.
+    async fn wait_for_server(&self, startup: &StartupProbe) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + startup.timeout;
+        let attempts = startup.attempts.max(1);
+        let mut delay = startup.interval;
+        let mut attempt = 1;
+        loop {
+            let err = match tokio::time::timeout_at(deadline, self.probe_server()).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(err)) => err,
+                Err(_) => {
+                    return Err(NowhereError::Agent(format!(
+                        "{OLLAMA_CONNECTION_ERROR} (gave up after {:?})",
+                        startup.timeout
+                    )))
+                }
+            };
+            if attempt >= attempts || tokio::time::Instant::now() + delay >= deadline {
+                return Err(err);
+            }
+            tracing::info!(
+                base_url = %self.base_url,
+                attempt,
+                attempts,
+                retry_in_ms = delay.as_millis() as u64,
+                "ollama.probe.retry"
+            );
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2);
+            attempt += 1;
+        }
+    }
+
     async fn probe_server(&self) -> Result<()> {
         let url = format!("{}/api/tags", self.base_url);
         let resp = self
@@ -133,6 +203,18 @@ impl LlmClient for OllamaClient {
         max_tokens: Option<u32>,
         temperature: Option<f32>,
     ) -> Result<LlmResponse> {
+        self.generate_raw(prompt, system_prompt, max_tokens, temperature)
+            .await
+            .map(|(response, _)| response)
+    }
+
+    async fn generate_raw(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<(LlmResponse, JsonValue)> {
         let url = format!("{}/api/generate", self.base_url);
 
         let mut options = serde_json::Map::new();
@@ -156,19 +238,36 @@ impl LlmClient for OllamaClient {
             "stream": false,
             "options": options
         });
-        let resp = self
-            .client
-            .post(&url)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(|e| NowhereError::Agent(format!("Generate request failed: {}", e)))?;
+        let resp = match self.client.post(&url).json(&payload).send().await {
+            Ok(resp) => resp,
+            Err(e) if is_connection_reset(&e) => {
+                // The server dropped an idle pooled connection; reconnect and retry once.
+                tracing::warn!(error = %e, "ollama.generate.connection_reset");
+                self.probe_server().await?;
+                self.client
+                    .post(&url)
+                    .json(&payload)
+                    .send()
+                    .await
+                    .map_err(|e| NowhereError::Agent(format!("Generate request failed: {}", e)))?
+            }
+            Err(e) => {
+                return Err(NowhereError::Agent(format!(
+                    "Generate request failed: {}",
+                    e
+                )))
+            }
+        };
 
         if !resp.status().is_success() {
-            return Err(NowhereError::Agent(format!(
-                "Generate failed: HTTP {}",
-                resp.status()
-            )));
+            let status = resp.status().as_u16();
+            let detail = format!("Generate failed: HTTP {}", resp.status());
+            // A full request queue (OLLAMA_MAX_QUEUE) answers 503; treat it as throttling.
+            return Err(match status {
+                429 | 503 => LlmError::RateLimit.into(),
+                401 | 403 => LlmError::from_status(status, detail).into(),
+                _ => NowhereError::Agent(detail),
+            });
         }
 
         let val: JsonValue = resp
@@ -186,13 +285,17 @@ impl LlmClient for OllamaClient {
             .get("eval_count")
             .and_then(|c| c.as_u64())
             .map(|c| c as u32);
+        let (finish_reason, truncated) = done_reason(&val);
 
-        Ok(LlmResponse {
+        let response = LlmResponse {
             text,
             model: Some(self.model.clone()),
             tokens_used,
             confidence: None,
-        })
+            finish_reason,
+            truncated,
+        };
+        Ok((response, val))
     }
 
     async fn health_check(&self) -> Result<bool> {
@@ -202,4 +305,274 @@ impl LlmClient for OllamaClient {
     fn model_name(&self) -> &str {
         &self.model
     }
+
+    fn default_osint_system_prompt(&self) -> &str {
+        self.system_prompt_override
+            .as_deref()
+            .unwrap_or(DEFAULT_OSINT_SYSTEM_PROMPT)
+    }
+}
+
+/// Whether `err` means the server closed or reset the connection under the request,
+/// as opposed to refusing it, timing out or answering badly.
+fn is_connection_reset(err: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(err);
+    while let Some(e) = source {
+        if let Some(io) = e.downcast_ref::<std::io::Error>() {
+            if matches!(
+                io.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+        // hyper's `IncompleteMessage`, which carries no io error.
+        if e.to_string()
+            .contains("connection closed before message completed")
+        {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+/// Ollama reports `done_reason: "length"` when generation stopped at `num_predict`.
+fn done_reason(val: &JsonValue) -> (Option<String>, bool) {
+    let reason = val
+        .get("done_reason")
+        .and_then(|r| r.as_str())
+        .map(str::to_string);
+    let truncated = reason.as_deref() == Some("length");
+    (reason, truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn length_done_reason_is_truncated() {
+        let (reason, truncated) = done_reason(&json!({ "response": "{", "done_reason": "length" }));
+        assert_eq!(reason.as_deref(), Some("length"));
+        assert!(truncated);
+
+        let (reason, truncated) = done_reason(&json!({ "response": "{}", "done_reason": "stop" }));
+        assert_eq!(reason.as_deref(), Some("stop"));
+        assert!(!truncated);
+
+        assert_eq!(done_reason(&json!({ "response": "{}" })), (None, false));
+    }
+
+    /// Ollama stub whose tag list already holds model `m`.
+    async fn ollama_stub() -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "models": [{ "name": "m" }] })),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn startup_probe_retries_until_the_server_is_up() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        // Still starting: the first probe gets a 503, later ones the stub's tag list.
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "models": [{ "name": "m" }] })),
+            )
+            .mount(&server)
+            .await;
+        let startup = StartupProbe {
+            attempts: 3,
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_secs(5),
+        };
+
+        let client = OllamaClient::connect(server.uri(), "m".into(), startup).await;
+        assert!(client.is_ok(), "retried probe finds the server");
+        let probes = server.received_requests().await.unwrap();
+        // Two probes, then the model check.
+        assert_eq!(probes.len(), 3);
+
+        // A server that never comes up fails within the total timeout.
+        let down = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&down)
+            .await;
+        let startup = StartupProbe {
+            attempts: 1000,
+            interval: Duration::from_millis(10),
+            timeout: Duration::from_millis(200),
+        };
+        let started = std::time::Instant::now();
+        assert!(OllamaClient::connect(down.uri(), "m".into(), startup)
+            .await
+            .is_err());
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn system_prompt_override_reaches_analyze_relevance() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let server = ollama_stub().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "response": "yes" })))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::new(server.uri(), "m".into())
+            .await
+            .unwrap()
+            .with_system_prompt_override("You are a newsroom fact-checker.");
+        assert!(client.analyze_relevance("claim", "evidence").await.unwrap());
+
+        let requests = server.received_requests().await.unwrap();
+        let generate = requests
+            .iter()
+            .find(|r| r.url.path() == "/api/generate")
+            .expect("generate request");
+        let body: JsonValue = serde_json::from_slice(&generate.body).unwrap();
+        let prompt = body["prompt"].as_str().unwrap();
+        assert!(prompt.starts_with("You are a newsroom fact-checker.\n\nTask: Determine"));
+        assert!(!prompt.contains("expert (Open Source Intelligence) analyst"));
+    }
+
+    #[tokio::test]
+    async fn http_429_maps_to_rate_limit() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let server = ollama_stub().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(429))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::new(server.uri(), "m".into()).await.unwrap();
+        let err = client.generate("hi", None, None, None).await.unwrap_err();
+        assert!(matches!(
+            LlmError::from_nowhere(&err),
+            Some(LlmError::RateLimit)
+        ));
+    }
+
+    #[tokio::test]
+    async fn generate_retries_once_after_connection_reset() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::TcpListener;
+
+        // Hand-rolled server: wiremock cannot drop a connection mid-request.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let generates = Arc::new(AtomicUsize::new(0));
+        let seen = generates.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let seen = seen.clone();
+                tokio::spawn(async move {
+                    let mut request = Vec::new();
+                    let mut buf = [0u8; 4096];
+                    // Read until the headers and the announced body are in.
+                    loop {
+                        let n = socket.read(&mut buf).await.unwrap();
+                        if n == 0 {
+                            return;
+                        }
+                        request.extend_from_slice(&buf[..n]);
+                        let text = String::from_utf8_lossy(&request);
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text[..end]
+                                .lines()
+                                .find_map(|l| {
+                                    l.to_ascii_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|v| v.trim().parse::<usize>().unwrap())
+                                })
+                                .unwrap_or(0);
+                            if request.len() >= end + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+                    let body = if request.starts_with(b"GET /api/tags") {
+                        json!({ "models": [{ "name": "m" }] })
+                    } else if seen.fetch_add(1, Ordering::SeqCst) == 0 {
+                        return; // drop the connection without answering
+                    } else {
+                        json!({ "response": "recovered", "done_reason": "stop" })
+                    }
+                    .to_string();
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+
+        let client = OllamaClient::new(base_url, "m".into()).await.unwrap();
+        let response = client.generate("hi", None, None, None).await.unwrap();
+
+        assert_eq!(response.text, "recovered");
+        assert_eq!(generates.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn generate_raw_keeps_provider_fields() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let server = ollama_stub().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "model": "m",
+                "response": "{\"claim_relevance\": tru",
+                "done_reason": "stop",
+                "eval_count": 12,
+                "eval_duration": 345_000_000u64,
+                "context": [1, 2, 3]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::new(server.uri(), "m".into()).await.unwrap();
+        let (response, raw) = client.generate_raw("hi", None, None, None).await.unwrap();
+
+        assert_eq!(response.text, "{\"claim_relevance\": tru");
+        assert_eq!(response.tokens_used, Some(12));
+        assert_eq!(raw["eval_duration"], 345_000_000u64);
+        assert_eq!(raw["context"], json!([1, 2, 3]));
+    }
 }
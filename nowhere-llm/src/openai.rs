@@ -1,8 +1,11 @@
-use crate::traits::{LlmClient, LlmResponse};
+use crate::traits::{LlmClient, LlmError, LlmResponse, DEFAULT_OSINT_SYSTEM_PROMPT};
+use crate::{DEFAULT_HEALTH_CHECK_TIMEOUT, DEFAULT_REQUEST_TIMEOUT};
 use async_trait::async_trait;
 use nowhere_common::{NowhereError, Result};
-use nowhere_http::{HttpClient, HttpError};
+use nowhere_http::{Auth, HttpClient, HttpError, RequestOpts};
+use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 const OPENAI_API_BASE: &str = "https://api.openai.com/v1/";
 
@@ -10,6 +13,11 @@ pub struct OpenAiClient {
     client: HttpClient,
     api_key: String,
     model: String,
+    system_prompt_override: Option<String>,
+    organization: Option<String>,
+    project: Option<String>,
+    request_timeout: Duration,
+    health_check_timeout: Duration,
 }
 
 #[derive(Serialize)]
@@ -17,6 +25,8 @@ pub struct ResponsesApiRequest {
     model: String,
     input: String,
     instructions: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,6 +39,28 @@ pub struct ResponsesApiResponse {
     pub model: String,
     #[serde(default)]
     pub output: Vec<ResponseMessage>,
+    /// Set when `status` is `"incomplete"`.
+    #[serde(default)]
+    pub incomplete_details: Option<IncompleteDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IncompleteDetails {
+    pub reason: Option<String>,
+}
+
+impl ResponsesApiResponse {
+    /// The Responses API reports a stop reason only for incomplete responses; a
+    /// `max_output_tokens` reason (or chat-style `length`) means the output was cut off.
+    fn finish_reason(&self) -> (Option<String>, bool) {
+        let reason = self
+            .incomplete_details
+            .as_ref()
+            .and_then(|d| d.reason.clone())
+            .or_else(|| Some(self.status.clone()));
+        let truncated = matches!(reason.as_deref(), Some("max_output_tokens" | "length"));
+        (reason, truncated)
+    }
 }
 
 /// One element in the `output` array
@@ -54,48 +86,111 @@ pub struct ResponseContent {
 impl OpenAiClient {
     /// Create a new client for the given API key and model.
     ///
-    /// FIXME(timeout/retry): add per-request timeouts/backoff knobs and consider
-    /// integrating the `RateLimiter` actor at the call sites to avoid provider
-    /// throttling issues under load.
+    /// FIXME(retry): add backoff knobs and consider integrating the `RateLimiter`
+    /// actor at the call sites to avoid provider throttling issues under load.
     pub fn new(api_key: String, model: String) -> Result<Self> {
-        let client = HttpClient::new(OPENAI_API_BASE)
+        Self::with_base_url(api_key, model, OPENAI_API_BASE)
+    }
+
+    /// Point the client at an OpenAI-compatible endpoint (or a local stub in tests).
+    pub fn with_base_url(api_key: String, model: String, base: &str) -> Result<Self> {
+        let client = HttpClient::new(base)
             .map_err(|e| NowhereError::Agent(format!("HttpClient init failed: {e}")))?;
 
         Ok(Self {
             client,
             api_key,
             model,
+            system_prompt_override: None,
+            organization: None,
+            project: None,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            health_check_timeout: DEFAULT_HEALTH_CHECK_TIMEOUT,
         })
     }
-}
 
-#[async_trait]
-impl LlmClient for OpenAiClient {
-    async fn generate(
+    /// How long a generate request may take before it is abandoned (default 120s).
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// How long `health_check` waits for the probe prompt (default 5s, no retries).
+    pub fn with_health_check_timeout(mut self, timeout: Duration) -> Self {
+        self.health_check_timeout = timeout;
+        self
+    }
+
+    /// Replace the built-in analyst system prompt used by actors and helper methods.
+    pub fn with_system_prompt_override(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt_override = Some(prompt.into());
+        self
+    }
+
+    /// Send `OpenAI-Organization` with every request, for keys that belong to several orgs.
+    pub fn with_organization(mut self, organization: impl Into<String>) -> Self {
+        self.organization = Some(organization.into());
+        self
+    }
+
+    /// Send `OpenAI-Project` with every request, so usage is billed to that project.
+    pub fn with_project(mut self, project: impl Into<String>) -> Self {
+        self.project = Some(project.into());
+        self
+    }
+
+    fn scope_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("openai-organization", &self.organization),
+            ("openai-project", &self.project),
+        ] {
+            if let Some(value) = value {
+                let value = HeaderValue::from_str(value)
+                    .map_err(|e| LlmError::Config(format!("invalid {name} header value: {e}")))?;
+                headers.insert(name, value);
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Call the Responses API with an explicit timeout; `retries` overrides the
+    /// client's retry count.
+    async fn respond(
         &self,
         prompt: &str,
         system_prompt: Option<&str>,
-        max_tokens: Option<u32>,
-        temperature: Option<f32>,
-    ) -> Result<LlmResponse> {
-        tracing::debug!("==============OPENAI CLIENT GENERATE WAS CALLED================");
-
-        let instructions = match system_prompt {
-            Some(s) => s.to_string(),
-            None => "You are an objective, unbiased researcher.".to_string(),
+        max_output_tokens: Option<u32>,
+        timeout: Duration,
+        retries: Option<usize>,
+    ) -> Result<(LlmResponse, serde_json::Value)> {
+        let instructions = match (system_prompt, &self.system_prompt_override) {
+            (Some(s), _) => s.to_string(),
+            (None, Some(s)) => s.clone(),
+            (None, None) => "You are an objective, unbiased researcher.".to_string(),
         };
 
         let req = ResponsesApiRequest {
             model: self.model.clone(),
             input: prompt.to_string(),
             instructions,
+            max_output_tokens,
         };
 
-        let resp: ResponsesApiResponse = self
+        let opts = RequestOpts {
+            auth: Some(Auth::Bearer(&self.api_key)),
+            headers: Some(self.scope_headers()?),
+            timeout: Some(timeout),
+            retries,
+            ..Default::default()
+        };
+        let raw: serde_json::Value = self
             .client
-            .post_json("responses", Some(&self.api_key), &req)
+            .post_json_opts("responses", &req, opts)
             .await
             .map_err(http_to_nowhere)?;
+        let resp = ResponsesApiResponse::deserialize(&raw)
+            .map_err(|e| NowhereError::Agent(format!("Failed to parse OpenAI response: {e}")))?;
 
         let text = resp
             .output
@@ -104,26 +199,71 @@ impl LlmClient for OpenAiClient {
             .find(|c| c.kind == "output_text")
             .map(|c| c.text.clone())
             .unwrap_or_default();
+        let (finish_reason, truncated) = resp.finish_reason();
 
-        Ok(LlmResponse {
+        let response = LlmResponse {
             text,
             model: Some(resp.model),
             confidence: None,
             tokens_used: None,
-        })
+            finish_reason,
+            truncated,
+        };
+        Ok((response, raw))
+    }
+}
+
+#[async_trait]
+impl LlmClient for OpenAiClient {
+    async fn generate(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<LlmResponse> {
+        self.generate_raw(prompt, system_prompt, max_tokens, temperature)
+            .await
+            .map(|(response, _)| response)
+    }
+
+    async fn generate_raw(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        _temperature: Option<f32>,
+    ) -> Result<(LlmResponse, serde_json::Value)> {
+        tracing::debug!("==============OPENAI CLIENT GENERATE WAS CALLED================");
+        self.respond(
+            prompt,
+            system_prompt,
+            max_tokens,
+            self.request_timeout,
+            None,
+        )
+        .await
     }
 
     fn model_name(&self) -> &str {
         &self.model
     }
 
+    fn default_osint_system_prompt(&self) -> &str {
+        self.system_prompt_override
+            .as_deref()
+            .unwrap_or(DEFAULT_OSINT_SYSTEM_PROMPT)
+    }
+
     async fn health_check(&self) -> Result<bool> {
-        // Simple health check by trying to generate a minimal response
-        // FIXME(health): enforce a short timeout here to avoid lingering tasks
-        // during startup checks.
+        // Simple health check by trying to generate a minimal response, bounded by the
+        // short health-check timeout so a dead endpoint does not stall a status probe.
         let test_prompt = "Respond with just 'OK'";
 
-        match self.generate(test_prompt, None, Some(5), Some(0.1)).await {
+        match self
+            .respond(test_prompt, None, None, self.health_check_timeout, Some(0))
+            .await
+        {
             Ok(_) => Ok(true),
             Err(e) => {
                 tracing::warn!("OpenAi health check failed: {}", e);
@@ -134,5 +274,198 @@ impl LlmClient for OpenAiClient {
 }
 
 fn http_to_nowhere(e: HttpError) -> NowhereError {
-    NowhereError::Agent(format!("{e}"))
+    match &e {
+        HttpError::Api { status, .. } => {
+            LlmError::from_status(status.as_u16(), e.to_string()).into()
+        }
+        _ => NowhereError::Agent(format!("{e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: &str, incomplete: Option<&str>) -> ResponsesApiResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": "resp_1",
+            "object": "response",
+            "created_at": 0,
+            "status": status,
+            "model": "gpt-4o",
+            "output": [],
+            "incomplete_details": incomplete.map(|r| serde_json::json!({ "reason": r })),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn incomplete_max_output_tokens_is_truncated() {
+        let (reason, truncated) = response("incomplete", Some("max_output_tokens")).finish_reason();
+        assert_eq!(reason.as_deref(), Some("max_output_tokens"));
+        assert!(truncated);
+
+        let (reason, truncated) = response("completed", None).finish_reason();
+        assert_eq!(reason.as_deref(), Some("completed"));
+        assert!(!truncated);
+    }
+
+    #[tokio::test]
+    async fn http_429_maps_to_rate_limit() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("retry-after", "0")
+                    .set_body_json(serde_json::json!({ "error": { "message": "slow down" } })),
+            )
+            .mount(&server)
+            .await;
+
+        let client =
+            OpenAiClient::with_base_url("sk-test".into(), "gpt-4o".into(), &server.uri()).unwrap();
+        let err = client.generate("hi", None, None, None).await.unwrap_err();
+        assert!(matches!(
+            LlmError::from_nowhere(&err),
+            Some(LlmError::RateLimit)
+        ));
+    }
+
+    #[tokio::test]
+    async fn organization_and_project_headers_are_sent_only_when_configured() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "resp_1",
+                "object": "response",
+                "created_at": 0,
+                "status": "completed",
+                "model": "gpt-4o",
+                "output": [],
+            })))
+            .mount(&server)
+            .await;
+
+        let scoped = OpenAiClient::with_base_url("sk-test".into(), "gpt-4o".into(), &server.uri())
+            .unwrap()
+            .with_organization("org-123")
+            .with_project("proj_abc");
+        scoped.generate("hi", None, None, None).await.unwrap();
+        let plain =
+            OpenAiClient::with_base_url("sk-test".into(), "gpt-4o".into(), &server.uri()).unwrap();
+        plain.generate("hi", None, None, None).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let header = |i: usize, name: &str| {
+            requests[i]
+                .headers
+                .get(name)
+                .map(|v| v.to_str().unwrap().to_string())
+        };
+        assert_eq!(header(0, "openai-organization").as_deref(), Some("org-123"));
+        assert_eq!(header(0, "openai-project").as_deref(), Some("proj_abc"));
+        assert_eq!(header(1, "openai-organization"), None);
+        assert_eq!(header(1, "openai-project"), None);
+        assert_eq!(
+            header(1, "authorization").as_deref(),
+            Some("Bearer sk-test")
+        );
+    }
+
+    #[tokio::test]
+    async fn max_tokens_is_sent_as_max_output_tokens() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "resp_1",
+                "object": "response",
+                "created_at": 0,
+                "status": "completed",
+                "model": "gpt-4o",
+                "output": [],
+            })))
+            .mount(&server)
+            .await;
+
+        let client =
+            OpenAiClient::with_base_url("sk-test".into(), "gpt-4o".into(), &server.uri()).unwrap();
+        client.generate("hi", None, Some(600), None).await.unwrap();
+        client.generate("hi", None, Some(1200), None).await.unwrap();
+        client.generate("hi", None, None, None).await.unwrap();
+
+        let budgets: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.body_json::<serde_json::Value>().unwrap()["max_output_tokens"].clone())
+            .collect();
+        assert_eq!(
+            budgets,
+            vec![
+                serde_json::json!(600),
+                serde_json::json!(1200),
+                serde_json::Value::Null
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn request_timeout_is_applied_and_health_check_uses_the_shorter_one() {
+        use std::time::Duration;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/responses"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(Duration::from_millis(300))
+                    .set_body_json(serde_json::json!({
+                        "id": "resp_1",
+                        "object": "response",
+                        "created_at": 0,
+                        "status": "completed",
+                        "model": "gpt-4o",
+                        "output": [],
+                    })),
+            )
+            .mount(&server)
+            .await;
+        let client = || {
+            OpenAiClient::with_base_url("sk-test".into(), "gpt-4o".into(), &server.uri()).unwrap()
+        };
+
+        let patient = client()
+            .with_request_timeout(Duration::from_secs(5))
+            .with_health_check_timeout(Duration::from_millis(50));
+        patient.generate("hi", None, None, None).await.unwrap();
+        let before = server.received_requests().await.unwrap().len();
+        assert!(!patient.health_check().await.unwrap());
+        assert_eq!(
+            server.received_requests().await.unwrap().len(),
+            before + 1,
+            "health checks are not retried"
+        );
+
+        let impatient = client().with_request_timeout(Duration::from_millis(50));
+        let err = impatient
+            .generate("hi", None, None, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{err}");
+    }
 }
@@ -0,0 +1,348 @@
+//! Opt-in coalescing of identical in-flight `generate` calls.
+//!
+//! Retweets and quote chains often normalize to the same prompt at the same moment.
+//! [`SingleFlight`] wraps a client so that while one call for a given
+//! `(model, system prompt, prompt, max_tokens, temperature)` is running, identical calls
+//! wait for it and share its result instead of spending quota on a duplicate. Nothing is
+//! cached once the call finishes.
+use crate::traits::{GenDefaults, LlmClient, LlmResponse};
+use async_trait::async_trait;
+use nowhere_common::{NowhereError, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// What followers receive: the leader's response, or its error behind an `Arc` since
+/// `NowhereError` isn't `Clone`.
+#[derive(Clone)]
+enum Shared {
+    Ok(LlmResponse),
+    Err(Arc<NowhereError>),
+}
+
+/// A leader's driver error as its followers see it. [`LlmError::from_nowhere`] looks
+/// through it, so a follower retries and backs off exactly as the leader does.
+#[derive(Debug)]
+pub(crate) struct SharedError(pub(crate) Arc<NowhereError>);
+
+impl fmt::Display for SharedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &*self.0 {
+            NowhereError::Driver(inner) => write!(f, "{inner}"),
+            other => write!(f, "{other}"),
+        }
+    }
+}
+
+impl std::error::Error for SharedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &*self.0 {
+            NowhereError::Driver(inner) => inner.source(),
+            _ => None,
+        }
+    }
+}
+
+/// Rebuild the leader's error with the same variant for one follower.
+fn follower_error(err: &Arc<NowhereError>) -> NowhereError {
+    match &**err {
+        NowhereError::Agent(message) => NowhereError::Agent(message.clone()),
+        NowhereError::Driver(_) => NowhereError::Driver(SharedError(err.clone()).into()),
+        NowhereError::Config(message) => NowhereError::Config(message.clone()),
+        NowhereError::InvestigationNotFound(id) => NowhereError::InvestigationNotFound(*id),
+        NowhereError::Timeout => NowhereError::Timeout,
+    }
+}
+
+/// What makes two calls identical. Keys compare in full, so two different prompts can
+/// never share a response.
+#[derive(PartialEq, Eq, Hash)]
+struct CallKey {
+    model: String,
+    system_prompt: Option<String>,
+    prompt: String,
+    max_tokens: Option<u32>,
+    /// `f32` is not `Eq`; its bits are.
+    temperature: Option<u32>,
+}
+
+type InFlight = Arc<Mutex<HashMap<Arc<CallKey>, broadcast::Sender<Shared>>>>;
+
+pub struct SingleFlight {
+    inner: Arc<dyn LlmClient + Send + Sync>,
+    in_flight: InFlight,
+}
+
+impl SingleFlight {
+    pub fn new(inner: Arc<dyn LlmClient + Send + Sync>) -> Self {
+        Self {
+            inner,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn key(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Arc<CallKey> {
+        Arc::new(CallKey {
+            model: self.inner.model_name().to_string(),
+            system_prompt: system_prompt.map(str::to_string),
+            prompt: prompt.to_string(),
+            max_tokens,
+            temperature: temperature.map(f32::to_bits),
+        })
+    }
+}
+
+/// Unregisters the leader's call even if its future is dropped mid-flight; followers then
+/// see the channel close and make the call themselves.
+struct Registration {
+    in_flight: InFlight,
+    key: Arc<CallKey>,
+    finished: bool,
+}
+
+impl Registration {
+    /// Unregister and take the sender to answer followers with.
+    fn finish(mut self) -> Option<broadcast::Sender<Shared>> {
+        self.finished = true;
+        self.in_flight.lock().unwrap().remove(&self.key)
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.in_flight.lock().unwrap().remove(&self.key);
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for SingleFlight {
+    async fn generate(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<LlmResponse> {
+        let key = self.key(prompt, system_prompt, max_tokens, temperature);
+        let follower = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            match in_flight.get(&key) {
+                Some(leader) => Some(leader.subscribe()),
+                None => {
+                    in_flight.insert(key.clone(), broadcast::channel(1).0);
+                    None
+                }
+            }
+        };
+
+        if let Some(mut leader) = follower {
+            tracing::debug!(
+                model = self.inner.model_name(),
+                "llm.single_flight.coalesced"
+            );
+            match leader.recv().await {
+                Ok(Shared::Ok(response)) => return Ok(response),
+                Ok(Shared::Err(err)) => return Err(follower_error(&err)),
+                // The leader was cancelled before answering.
+                Err(_) => {
+                    return self
+                        .inner
+                        .generate(prompt, system_prompt, max_tokens, temperature)
+                        .await
+                }
+            }
+        }
+
+        let registration = Registration {
+            in_flight: self.in_flight.clone(),
+            key,
+            finished: false,
+        };
+        let result = self
+            .inner
+            .generate(prompt, system_prompt, max_tokens, temperature)
+            .await;
+        let sender = registration.finish();
+        match result {
+            Ok(response) => {
+                // No receivers just means nobody else asked.
+                if let Some(sender) = sender {
+                    let _ = sender.send(Shared::Ok(response.clone()));
+                }
+                Ok(response)
+            }
+            Err(err) => {
+                let err = Arc::new(err);
+                if let Some(sender) = sender {
+                    let _ = sender.send(Shared::Err(err.clone()));
+                }
+                // While a follower still holds the error, the leader rebuilds it the same way.
+                Err(Arc::try_unwrap(err).unwrap_or_else(|err| follower_error(&err)))
+            }
+        }
+    }
+
+    /// Debug calls bypass coalescing: each caller wants its own provider body.
+    async fn generate_raw(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<(LlmResponse, serde_json::Value)> {
+        self.inner
+            .generate_raw(prompt, system_prompt, max_tokens, temperature)
+            .await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    fn model_name(&self) -> &str {
+        self.inner.model_name()
+    }
+
+    fn default_osint_system_prompt(&self) -> &str {
+        self.inner.default_osint_system_prompt()
+    }
+
+    fn gen_defaults(&self) -> GenDefaults {
+        self.inner.gen_defaults()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::completion;
+    use crate::traits::LlmError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::sync::Notify;
+
+    /// Counts calls and holds each one until `release` is notified, then answers `reply`.
+    struct GatedClient {
+        calls: AtomicUsize,
+        release: Notify,
+        reply: fn(&str) -> Result<LlmResponse>,
+    }
+
+    #[async_trait]
+    impl LlmClient for GatedClient {
+        async fn generate(
+            &self,
+            prompt: &str,
+            _system_prompt: Option<&str>,
+            _max_tokens: Option<u32>,
+            _temperature: Option<f32>,
+        ) -> Result<LlmResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.release.notified().await;
+            (self.reply)(prompt)
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn model_name(&self) -> &str {
+            "gated"
+        }
+    }
+
+    /// Run two identical calls at once; returns both results and how many reached `reply`.
+    async fn call_twice(
+        reply: fn(&str) -> Result<LlmResponse>,
+    ) -> (Result<LlmResponse>, Result<LlmResponse>, usize) {
+        let inner = Arc::new(GatedClient {
+            calls: AtomicUsize::new(0),
+            release: Notify::new(),
+            reply,
+        });
+        let client = SingleFlight::new(inner.clone());
+
+        let call = || client.generate("same tweet", Some("sys"), Some(600), Some(0.2));
+        let release = async {
+            while inner.calls.load(Ordering::SeqCst) == 0 {
+                tokio::task::yield_now().await;
+            }
+            // Let the second call subscribe before the first one finishes.
+            tokio::task::yield_now().await;
+            inner.release.notify_waiters();
+        };
+        let (first, second, ()) = tokio::join!(call(), call(), release);
+        assert!(client.in_flight.lock().unwrap().is_empty());
+        (first, second, inner.calls.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_calls_reach_the_client_once() {
+        let (first, second, calls) =
+            call_twice(|prompt| Ok(completion(format!("re: {prompt}")))).await;
+
+        assert_eq!(calls, 1);
+        assert_eq!(first.unwrap().text, "re: same tweet");
+        assert_eq!(second.unwrap().text, "re: same tweet");
+    }
+
+    #[tokio::test]
+    async fn calls_differing_only_in_prompt_are_not_coalesced() {
+        let inner = Arc::new(GatedClient {
+            calls: AtomicUsize::new(0),
+            release: Notify::new(),
+            reply: |prompt| Ok(completion(format!("re: {prompt}"))),
+        });
+        let client = SingleFlight::new(inner.clone());
+
+        let release = async {
+            while inner.calls.load(Ordering::SeqCst) < 2 {
+                tokio::task::yield_now().await;
+            }
+            inner.release.notify_waiters();
+        };
+        let (first, second, ()) = tokio::join!(
+            client.generate("tweet a", Some("sys"), Some(600), Some(0.2)),
+            client.generate("tweet b", Some("sys"), Some(600), Some(0.2)),
+            release
+        );
+
+        assert_eq!(first.unwrap().text, "re: tweet a");
+        assert_eq!(second.unwrap().text, "re: tweet b");
+    }
+
+    #[tokio::test]
+    async fn followers_get_the_leaders_error_class() {
+        let (first, second, calls) =
+            call_twice(|_| Err(LlmError::Config("bad key".into()).into())).await;
+
+        assert_eq!(calls, 1);
+        for err in [first.unwrap_err(), second.unwrap_err()] {
+            assert!(
+                matches!(&err, NowhereError::Config(m) if m == "bad key"),
+                "{err}"
+            );
+        }
+
+        let (first, second, _) = call_twice(|_| Err(LlmError::RateLimit.into())).await;
+        for err in [first.unwrap_err(), second.unwrap_err()] {
+            assert!(
+                matches!(LlmError::from_nowhere(&err), Some(LlmError::RateLimit)),
+                "{err}"
+            );
+            assert_eq!(err.to_string(), "Driver error: Rate limit exceeded");
+        }
+
+        let (first, second, _) = call_twice(|_| Err(NowhereError::Timeout)).await;
+        assert!(matches!(first, Err(NowhereError::Timeout)));
+        assert!(matches!(second, Err(NowhereError::Timeout)));
+    }
+}
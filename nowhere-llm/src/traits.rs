@@ -1,13 +1,81 @@
+use crate::single_flight::SharedError;
 use async_trait::async_trait;
-use nowhere_common::Result;
+use nowhere_common::{NowhereError, Result};
 use serde::{Deserialize, Serialize};
 
+/// Built-in analyst persona prepended to the helper prompts below.
+pub const DEFAULT_OSINT_SYSTEM_PROMPT: &str = r#"You are an expert (Open Source Intelligence) analyst with extensive experience in digital investigations, social media analysis, and evidence evaluation.
+
+Your role:
+- Analyze evidence for relevance, credibility, and authenticity
+- Extract key factual information from various sources
+- Provide concise, accurate assessments
+- Focus on verifiable facts over speculation
+- Consider source reliability and potential biases
+
+Guidelines:
+- Be precise and factual in your responses
+- Use clear, professional language
+- Highlight important details that may be significant for investigation
+- Flag potential misinformation or unreliable sources
+- Prioritize actionable intelligence"#;
+
+/// Token budget and sampling temperature for one kind of helper call.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GenParams {
+    pub max_tokens: u32,
+    pub temperature: f32,
+}
+
+impl GenParams {
+    pub const fn new(max_tokens: u32, temperature: f32) -> Self {
+        Self {
+            max_tokens,
+            temperature,
+        }
+    }
+}
+
+/// What the [`LlmClient`] analysis helpers pass to `generate`, one entry per helper.
+/// `Default` is the built-in tuning; override [`LlmClient::gen_defaults`] to change it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GenDefaults {
+    pub relevance: GenParams,
+    pub key_info: GenParams,
+    pub credibility: GenParams,
+    pub synthesis: GenParams,
+    pub inconsistencies: GenParams,
+}
+
+impl Default for GenDefaults {
+    fn default() -> Self {
+        Self {
+            relevance: GenParams::new(10, 0.1),
+            key_info: GenParams::new(200, 0.3),
+            credibility: GenParams::new(20, 0.1),
+            synthesis: GenParams::new(500, 0.4),
+            inconsistencies: GenParams::new(400, 0.3),
+        }
+    }
+}
+
+/// Whether raw provider responses should be fetched and logged: `NOWHERE_HTTP_RAW=1`.
+pub fn raw_responses_enabled() -> bool {
+    nowhere_http::raw_enabled()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmResponse {
     pub text: String,
     pub model: Option<String>,
     pub tokens_used: Option<u32>,
     pub confidence: Option<f64>,
+    /// Provider's stop reason as reported (e.g. `"stop"`, `"MAX_TOKENS"`, `"length"`).
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// The output hit the token limit, so `text` may be cut off mid-JSON.
+    #[serde(default)]
+    pub truncated: bool,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -28,6 +96,51 @@ pub enum LlmError {
     Config(String),
 }
 
+impl LlmError {
+    /// Recover the provider error carried by a client's driver error, if any, including
+    /// one a coalesced call shared with its followers. Configuration errors arrive as
+    /// [`NowhereError::Config`] instead and yield `None`.
+    pub fn from_nowhere(err: &NowhereError) -> Option<&LlmError> {
+        match err {
+            NowhereError::Driver(inner) => inner.downcast_ref().or_else(|| {
+                inner
+                    .downcast_ref::<SharedError>()
+                    .and_then(|shared| Self::from_nowhere(&shared.0))
+            }),
+            _ => None,
+        }
+    }
+
+    /// Map an HTTP error status: 429 is throttling, 401/403 mean bad credentials.
+    pub fn from_status(status: u16, detail: impl Into<String>) -> Self {
+        match status {
+            429 => LlmError::RateLimit,
+            401 | 403 => LlmError::Config(detail.into()),
+            _ => LlmError::Api(detail.into()),
+        }
+    }
+
+    /// Whether retrying the same call may succeed: throttling and transport failures.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            LlmError::RateLimit | LlmError::Network(_) => true,
+            LlmError::Api(_) | LlmError::ModelNotAvailable(_) | LlmError::Config(_) => false,
+        }
+    }
+}
+
+/// Clients return `nowhere_common::Result`. Bad keys and settings become
+/// [`NowhereError::Config`]; every other `LlmError` rides inside a driver error so callers
+/// can tell throttling apart via [`LlmError::from_nowhere`].
+impl From<LlmError> for NowhereError {
+    fn from(err: LlmError) -> Self {
+        match err {
+            LlmError::Config(message) => NowhereError::Config(message),
+            err => NowhereError::Driver(err.into()),
+        }
+    }
+}
+
 #[async_trait]
 pub trait LlmClient: Send + Sync {
     /// Generate a response to the given prompt with optional system prompt
@@ -39,29 +152,39 @@ pub trait LlmClient: Send + Sync {
         temperature: Option<f32>,
     ) -> Result<LlmResponse>;
 
+    /// [`generate`](Self::generate), also returning the provider's parsed response body
+    /// for debugging (e.g. a normalization reply that fails to parse). Costs a copy of
+    /// the body, so callers should only use it when [`raw_responses_enabled`] is set.
+    /// The default serializes the normalized response, for clients without a body.
+    async fn generate_raw(
+        &self,
+        prompt: &str,
+        system_prompt: Option<&str>,
+        max_tokens: Option<u32>,
+        temperature: Option<f32>,
+    ) -> Result<(LlmResponse, serde_json::Value)> {
+        let response = self
+            .generate(prompt, system_prompt, max_tokens, temperature)
+            .await?;
+        let raw = serde_json::to_value(&response).unwrap_or_default();
+        Ok((response, raw))
+    }
+
     /// Check if the LLM service is available
     async fn health_check(&self) -> Result<bool>;
 
     /// Get the model name being used
     fn model_name(&self) -> &str;
 
-    /// Get the default system prompt for nowhere analysis
+    /// Get the default system prompt for nowhere analysis. Clients configured with a
+    /// `system_prompt_override` return that instead of [`DEFAULT_OSINT_SYSTEM_PROMPT`].
     fn default_osint_system_prompt(&self) -> &str {
-        r#"You are an expert (Open Source Intelligence) analyst with extensive experience in digital investigations, social media analysis, and evidence evaluation.
-
-Your role:
-- Analyze evidence for relevance, credibility, and authenticity
-- Extract key factual information from various sources
-- Provide concise, accurate assessments
-- Focus on verifiable facts over speculation
-- Consider source reliability and potential biases
+        DEFAULT_OSINT_SYSTEM_PROMPT
+    }
 
-Guidelines:
-- Be precise and factual in your responses
-- Use clear, professional language
-- Highlight important details that may be significant for investigation
-- Flag potential misinformation or unreliable sources
-- Prioritize actionable intelligence"#
+    /// Token and temperature settings the helpers below use; see [`GenDefaults`].
+    fn gen_defaults(&self) -> GenDefaults {
+        GenDefaults::default()
     }
 
     /// Analyze text relevance (specialized for nowhere)
@@ -77,8 +200,14 @@ Guidelines:
         );
 
         tracing::info!("Prompt: {}", prompt);
+        let params = self.gen_defaults().relevance;
         let response = self
-            .generate(&prompt, Some(&system_prompt), Some(10), Some(0.1))
+            .generate(
+                &prompt,
+                Some(&system_prompt),
+                Some(params.max_tokens),
+                Some(params.temperature),
+            )
             .await?;
         tracing::debug!("LLM response: {}", response.text);
 
@@ -97,8 +226,14 @@ Guidelines:
             context, text
         );
 
+        let params = self.gen_defaults().key_info;
         let response = self
-            .generate(&prompt, Some(&system_prompt), Some(200), Some(0.3))
+            .generate(
+                &prompt,
+                Some(&system_prompt),
+                Some(params.max_tokens),
+                Some(params.temperature),
+            )
             .await?;
 
         // Parse bullet points
@@ -140,14 +275,19 @@ Guidelines:
             format!("CONTENT: \"{}\"\n\nCredibility score:", content)
         };
 
+        let params = self.gen_defaults().credibility;
         let response = self
-            .generate(&prompt, Some(&system_prompt), Some(20), Some(0.1))
+            .generate(
+                &prompt,
+                Some(&system_prompt),
+                Some(params.max_tokens),
+                Some(params.temperature),
+            )
             .await?;
 
         // Parse the numerical score
         let score = response
             .text
-            .trim()
             .split_whitespace()
             .find_map(|word| word.parse::<f64>().ok())
             .unwrap_or(0.5); // Default to neutral if parsing fails
@@ -178,8 +318,14 @@ Guidelines:
             investigation_context, evidence_text
         );
 
+        let params = self.gen_defaults().synthesis;
         let response = self
-            .generate(&prompt, Some(&system_prompt), Some(500), Some(0.4))
+            .generate(
+                &prompt,
+                Some(&system_prompt),
+                Some(params.max_tokens),
+                Some(params.temperature),
+            )
             .await?;
         Ok(response.text)
     }
@@ -203,8 +349,14 @@ Guidelines:
             evidence_text
         );
 
+        let params = self.gen_defaults().inconsistencies;
         let response = self
-            .generate(&prompt, Some(&system_prompt), Some(400), Some(0.3))
+            .generate(
+                &prompt,
+                Some(&system_prompt),
+                Some(params.max_tokens),
+                Some(params.temperature),
+            )
             .await?;
 
         // Parse bullet points
@@ -233,3 +385,67 @@ Guidelines:
         Ok(inconsistencies)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockLlmClient;
+
+    #[test]
+    fn retryability_by_variant() {
+        let network = reqwest::Client::new().get("not a url").build().unwrap_err();
+        assert!(LlmError::Network(network).is_retryable());
+        assert!(LlmError::RateLimit.is_retryable());
+        assert!(!LlmError::Api("bad request".into()).is_retryable());
+        assert!(!LlmError::ModelNotAvailable("gpt-x".into()).is_retryable());
+        assert!(!LlmError::Config("missing key".into()).is_retryable());
+    }
+
+    #[test]
+    fn config_errors_keep_their_class_and_others_stay_driver_errors() {
+        let config = NowhereError::from(LlmError::Config("missing key".into()));
+        assert!(matches!(&config, NowhereError::Config(m) if m == "missing key"));
+
+        let throttled = NowhereError::from(LlmError::RateLimit);
+        assert!(matches!(throttled, NowhereError::Driver(_)));
+        assert!(matches!(
+            LlmError::from_nowhere(&throttled),
+            Some(LlmError::RateLimit)
+        ));
+    }
+
+    #[tokio::test]
+    async fn helpers_pass_overridden_gen_defaults_to_generate() {
+        let defaults = GenDefaults {
+            relevance: GenParams::new(32, 0.0),
+            synthesis: GenParams::new(2048, 0.7),
+            ..GenDefaults::default()
+        };
+        let client = MockLlmClient::new()
+            .with_gen_defaults(defaults)
+            .reply("yes")
+            .reply("summary")
+            .reply("0.8");
+
+        client.analyze_relevance("claim", "evidence").await.unwrap();
+        client
+            .synthesize_evidence(&["a".into()], "context")
+            .await
+            .unwrap();
+        client.analyze_credibility("content", None).await.unwrap();
+
+        let used: Vec<_> = client
+            .calls()
+            .into_iter()
+            .map(|call| (call.max_tokens, call.temperature))
+            .collect();
+        assert_eq!(
+            used,
+            [
+                (Some(32), Some(0.0)),
+                (Some(2048), Some(0.7)),
+                (Some(20), Some(0.1)),
+            ]
+        );
+    }
+}
@@ -129,6 +129,8 @@
 //     }
 //
 //     // Clamp to 1024 chars (Twitter v2 limit). Trim at last whitespace boundary.
+//     // FIXME: `TwitterApi` now trims with `nowhere_social::twitter::client::clamp_query`;
+//     // share that once this sanitizer is enabled instead of keeping a second copy.
 //     let mut out = q.trim().to_string();
 //     if out.len() > 1024 {
 //         if let Some(idx) = out
@@ -1,70 +1,208 @@
 //! Minimal wrapper around the Twitter/X search API with Nowhere defaults.
 //!
 //! Handles auth, request parameter shaping, and safe time windows before delegating to
-//! the shared HTTP client. Future documentation should cover pagination (`next_token`)
-//! handling once implemented.
+//! the shared HTTP client. Pagination is left to callers: pass the previous page's
+//! `meta.next_token` to [`TwitterApi::recent_search_page`] to fetch the next one.
 use crate::twitter::types::SearchResponse;
-use anyhow::Result;
-use nowhere_http::{Auth, HttpClient, RequestOpts};
+use anyhow::{Result, anyhow};
+pub use nowhere_http::ApiProblem;
+use nowhere_http::{Auth, HttpClient, HttpError, RequestOpts};
+use std::borrow::Cow;
 use time::{Duration, OffsetDateTime};
 
+/// Longest `query` that `/2/tweets/search/recent` accepts.
+pub const MAX_QUERY_LEN: usize = 1024;
+
+const DEFAULT_TWEET_FIELDS: &[&str] = &[
+    "created_at",
+    "lang",
+    "entities",
+    "conversation_id",
+    "public_metrics",
+    "possibly_sensitive",
+    "referenced_tweets",
+    "in_reply_to_user_id",
+    "attachments",
+    "author_id",
+];
+const DEFAULT_USER_FIELDS: &[&str] = &["username", "name", "verified"];
+const DEFAULT_MEDIA_FIELDS: &[&str] = &[
+    "type",
+    "url",
+    "preview_image_url",
+    "width",
+    "height",
+    "duration_ms",
+];
+const DEFAULT_EXPANSIONS: &[&str] = &[
+    "author_id",
+    "attachments.media_keys",
+    "referenced_tweets.id",
+];
+
+/// Which `tweet.fields`, `user.fields`, `media.fields` and `expansions` a search asks for.
+///
+/// The default covers what normalization reads, plus the author, media and
+/// referenced-tweet expansions that fill `Includes`. An empty list leaves that parameter off the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TweetFields {
+    pub tweet: Vec<String>,
+    pub user: Vec<String>,
+    pub media: Vec<String>,
+    pub expansions: Vec<String>,
+}
+
+impl Default for TweetFields {
+    fn default() -> Self {
+        let owned = |v: &[&str]| v.iter().map(|s| s.to_string()).collect();
+        Self {
+            tweet: owned(DEFAULT_TWEET_FIELDS),
+            user: owned(DEFAULT_USER_FIELDS),
+            media: owned(DEFAULT_MEDIA_FIELDS),
+            expansions: owned(DEFAULT_EXPANSIONS),
+        }
+    }
+}
+
+impl TweetFields {
+    pub fn with_tweet_fields<I: IntoIterator<Item = S>, S: Into<String>>(mut self, f: I) -> Self {
+        self.tweet = f.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_user_fields<I: IntoIterator<Item = S>, S: Into<String>>(mut self, f: I) -> Self {
+        self.user = f.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_media_fields<I: IntoIterator<Item = S>, S: Into<String>>(mut self, f: I) -> Self {
+        self.media = f.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_expansions<I: IntoIterator<Item = S>, S: Into<String>>(mut self, e: I) -> Self {
+        self.expansions = e.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn query_params(&self) -> Vec<(&'static str, Cow<'static, str>)> {
+        [
+            ("tweet.fields", &self.tweet),
+            ("user.fields", &self.user),
+            ("media.fields", &self.media),
+            ("expansions", &self.expansions),
+        ]
+        .into_iter()
+        .filter(|(_, values)| !values.is_empty())
+        .map(|(key, values)| (key, values.join(",").into()))
+        .collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct TwitterApi {
     http: HttpClient,
     bearer: String,
+    fields: TweetFields,
+    exclusions: SearchExclusions,
 }
 
 impl TwitterApi {
     pub fn new(bearer_token: String) -> Self {
-        let http = HttpClient::new("https://api.twitter.com").expect("twitter base url");
-        Self {
-            http,
+        Self::with_base_url(bearer_token, "https://api.twitter.com").expect("twitter base url")
+    }
+
+    /// Point the client at a different API host (e.g. a local stub in tests).
+    pub fn with_base_url(bearer_token: String, base: &str) -> Result<Self> {
+        Ok(Self {
+            http: HttpClient::new(base)?,
             bearer: bearer_token,
-        }
+            fields: TweetFields::default(),
+            exclusions: SearchExclusions::default(),
+        })
+    }
+
+    /// Request a different field/expansion set on every search.
+    pub fn with_fields(mut self, fields: TweetFields) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    /// Leave retweets and/or replies out of every search (both kept by default).
+    pub fn with_exclusions(mut self, exclusions: SearchExclusions) -> Self {
+        self.exclusions = exclusions;
+        self
     }
 
     pub async fn simple_recent_search(
+        &self,
+        query: String,
+        max_results: Option<u32>,
+        date_from: Option<OffsetDateTime>,
+        date_to: Option<OffsetDateTime>,
+    ) -> Result<SearchResponse> {
+        self.recent_search_page(query, max_results, date_from, date_to, None)
+            .await
+    }
+
+    /// Check the bearer token with one cheap tweet lookup, so a bad or expired token is
+    /// reported at startup rather than on the first search. (`/2/users/me` needs user
+    /// context, which app-only bearer tokens don't have.)
+    pub async fn verify_credentials(&self) -> Result<()> {
+        match self.probe().await {
+            Ok(_) => Ok(()),
+            Err(HttpError::Api {
+                status, message, ..
+            }) if status.as_u16() == 401 => Err(anyhow!(
+                "invalid Twitter credentials: the bearer token was rejected ({message})"
+            )),
+            Err(HttpError::Api {
+                status, message, ..
+            }) if status.as_u16() == 403 => Err(anyhow!(
+                "Twitter credentials lack API access for this app or tier ({message})"
+            )),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// The same lookup as [`TwitterApi::verify_credentials`], as a readiness signal:
+    /// `Ok(false)` when Twitter rejects the token (401/403), `Err` when it could not be
+    /// asked at all.
+    pub async fn health_check(&self) -> Result<bool> {
+        match self.probe().await {
+            Ok(_) => Ok(true),
+            Err(HttpError::Api { status, .. }) if matches!(status.as_u16(), 401 | 403) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn probe(&self) -> Result<serde_json::Value, HttpError> {
+        self.http
+            .get_json(
+                "2/tweets",
+                RequestOpts {
+                    auth: Some(Auth::Bearer(&self.bearer)),
+                    query: Some(vec![("ids", "20".into())]),
+                    retries: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await
+    }
+
+    /// One page of `/2/tweets/search/recent`; `next_token` continues a previous page.
+    pub async fn recent_search_page(
         &self,
         query: String,
         max_results: Option<u32>,
         _date_from: Option<OffsetDateTime>,
         _date_to: Option<OffsetDateTime>,
+        next_token: Option<String>,
     ) -> Result<SearchResponse> {
         let max_results = max_results.unwrap_or(100).clamp(10, 100);
-
-        // Twitter constraints for /2/tweets/search/recent
-        let now = OffsetDateTime::now_utc();
-        // Provide some slack so the request is safely >10s behind "now".
-        let latest_end = now - Duration::seconds(20);
-        let earliest_start = now - Duration::days(7);
-
-        // Derive a safe [start, end] window
-        // Twitter now enforces that callers supply a window that is fully within the
-        // last 7 days (and end <= now - 10s). We ignore caller-supplied dates so we
-        // always request a compliant window.
-        let start = earliest_start;
-        let end = latest_end;
-
-        let mut params: Vec<(&str, std::borrow::Cow<'_, str>)> = vec![
-        ("query", query.into()),
-        ("max_results", max_results.to_string().into()),
-        ("tweet.fields",
-         "created_at,lang,entities,conversation_id,public_metrics,possibly_sensitive,referenced_tweets,in_reply_to_user_id,attachments".into()),
-    ];
-
-        params.push((
-            "start_time",
-            start
-                .format(&time::format_description::well_known::Rfc3339)
-                .unwrap()
-                .into(),
-        ));
-        params.push((
-            "end_time",
-            end.format(&time::format_description::well_known::Rfc3339)
-                .unwrap()
-                .into(),
-        ));
+        let (start, end) = recent_search_window(OffsetDateTime::now_utc());
+        let query = self.search_query(query);
+        let params = search_params(&self.fields, query, max_results, start, end, next_token);
 
         let resp: SearchResponse = self
             .http
@@ -83,3 +221,332 @@ impl TwitterApi {
         Ok(resp)
     }
 }
+
+/// The `[start, end]` window [`TwitterApi::recent_search_page`] requests at `now`,
+/// whatever dates the caller passed.
+pub fn recent_search_window(now: OffsetDateTime) -> (OffsetDateTime, OffsetDateTime) {
+    // Twitter enforces that callers supply a window that is fully within the last 7 days
+    // (and end <= now - 10s). Caller-supplied dates are ignored so we always request a
+    // compliant window, with some slack so the end is safely >10s behind "now".
+    (now - Duration::days(7), now - Duration::seconds(20))
+}
+
+impl TwitterApi {
+    /// `query` with the exclusion operators appended, trimmed so the whole fits in
+    /// [`MAX_QUERY_LEN`]; the operators are kept over the tail of the query. A query with a
+    /// top-level `OR` is parenthesized first so the operators apply to every branch. This
+    /// is the query string a search for `query` actually sends.
+    pub fn search_query(&self, query: String) -> String {
+        let suffix = self.exclusions.suffix(&query);
+        let group = !suffix.is_empty() && has_top_level_or(&query);
+        let parens = if group { 2 } else { 0 };
+        let query = match clamp_query(&query, MAX_QUERY_LEN - suffix.len() - parens) {
+            Cow::Borrowed(kept) => kept.to_string(),
+            Cow::Owned(trimmed) => {
+                tracing::warn!(
+                    original_len = query.len(),
+                    trimmed_len = trimmed.len(),
+                    "twitter.search.query_trimmed"
+                );
+                trimmed
+            }
+        };
+        if group {
+            format!("({query}){suffix}")
+        } else {
+            query + &suffix
+        }
+    }
+}
+
+/// Whether `query` has an `OR` outside quotes and parentheses. Twitter gives `OR` lower
+/// precedence than the implicit AND, so operators appended after it bind to the last
+/// branch only.
+fn has_top_level_or(query: &str) -> bool {
+    let (mut in_quotes, mut depth) = (false, 0usize);
+    let mut word = String::new();
+    for ch in query.chars().chain([' ']) {
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth = depth.saturating_sub(1),
+            _ if ch.is_whitespace() => {
+                if word == "OR" {
+                    return true;
+                }
+                word.clear();
+                continue;
+            }
+            _ => {}
+        }
+        if !in_quotes && depth == 0 {
+            word.push(ch);
+        }
+    }
+    false
+}
+
+/// The Twitter error details behind a failed [`TwitterApi`] call, e.g. an
+/// `invalid-request` problem whose `parameter` is `query`.
+pub fn api_problem(err: &anyhow::Error) -> Option<&ApiProblem> {
+    err.downcast_ref::<HttpError>()?.problem()
+}
+
+/// Kinds of tweets to leave out of every search, via `-is:` operators on the query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchExclusions {
+    /// Append `-is:retweet`.
+    pub retweets: bool,
+    /// Append `-is:reply`.
+    pub replies: bool,
+}
+
+impl SearchExclusions {
+    /// The operators to append to `query`, each with a leading space; ones the query
+    /// already carries are skipped.
+    fn suffix(self, query: &str) -> String {
+        [(self.retweets, "-is:retweet"), (self.replies, "-is:reply")]
+            .into_iter()
+            .filter(|(on, op)| *on && !query.split_whitespace().any(|t| t == *op))
+            .map(|(_, op)| format!(" {op}"))
+            .collect()
+    }
+}
+
+/// Cut `query` to at most `max` bytes at the last whitespace that is outside quotes
+/// and parentheses, so the kept operators stay balanced. Falls back to any whitespace,
+/// then to a plain char boundary, when no such gap exists.
+pub fn clamp_query(query: &str, max: usize) -> Cow<'_, str> {
+    let query = query.trim();
+    if query.len() <= max {
+        return Cow::Borrowed(query);
+    }
+    let mut balanced_gap = None;
+    let mut any_gap = None;
+    let (mut in_quotes, mut depth) = (false, 0usize);
+    for (i, ch) in query.char_indices() {
+        if i > max {
+            break;
+        }
+        match ch {
+            '"' => in_quotes = !in_quotes,
+            '(' if !in_quotes => depth += 1,
+            ')' if !in_quotes => depth = depth.saturating_sub(1),
+            ch if ch.is_whitespace() => {
+                any_gap = Some(i);
+                if !in_quotes && depth == 0 {
+                    balanced_gap = Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    let cut = balanced_gap.or(any_gap).unwrap_or_else(|| {
+        (0..=max)
+            .rev()
+            .find(|&i| query.is_char_boundary(i))
+            .unwrap_or(0)
+    });
+    Cow::Owned(query[..cut].trim_end().to_string())
+}
+
+fn search_params(
+    fields: &TweetFields,
+    query: String,
+    max_results: u32,
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+    next_token: Option<String>,
+) -> Vec<(&'static str, Cow<'static, str>)> {
+    let rfc3339 = |t: OffsetDateTime| {
+        t.format(&time::format_description::well_known::Rfc3339)
+            .unwrap()
+    };
+
+    let mut params: Vec<(&'static str, Cow<'static, str>)> = vec![
+        ("query", query.into()),
+        ("max_results", max_results.to_string().into()),
+    ];
+    params.extend(fields.query_params());
+    params.push(("start_time", rfc3339(start).into()));
+    params.push(("end_time", rfc3339(end).into()));
+    if let Some(token) = next_token {
+        params.push(("next_token", token.into()));
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query_string(fields: &TweetFields) -> String {
+        let now = OffsetDateTime::now_utc();
+        let params = search_params(fields, "bridge closure".into(), 10, now, now, None);
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params.iter().map(|(k, v)| (*k, v.as_ref())))
+            .finish()
+    }
+
+    #[test]
+    fn configured_fields_reach_the_query_string() {
+        let fields = TweetFields::default()
+            .with_tweet_fields([
+                "created_at",
+                "context_annotations",
+                "geo",
+                "edit_history_tweet_ids",
+            ])
+            .with_expansions(["author_id", "geo.place_id"])
+            .with_media_fields(Vec::<String>::new());
+
+        let qs = query_string(&fields);
+        assert!(qs.contains(
+            "tweet.fields=created_at%2Ccontext_annotations%2Cgeo%2Cedit_history_tweet_ids"
+        ));
+        assert!(qs.contains("expansions=author_id%2Cgeo.place_id"));
+        assert!(qs.contains("user.fields=username%2Cname%2Cverified"));
+        assert!(
+            !qs.contains("media.fields"),
+            "empty lists are omitted: {qs}"
+        );
+    }
+
+    #[test]
+    fn over_long_queries_are_trimmed_at_a_word_boundary() {
+        let short = "\"bridge closure\" lang:en";
+        assert_eq!(clamp_query(short, MAX_QUERY_LEN), short);
+
+        let long = format!(
+            "(\"bridge closure\" OR detour) {}",
+            "springfield ".repeat(100)
+        );
+        let trimmed = clamp_query(&long, MAX_QUERY_LEN);
+        assert!(trimmed.len() <= MAX_QUERY_LEN, "{}", trimmed.len());
+        assert!(long.starts_with(trimmed.as_ref()));
+        assert!(trimmed.ends_with(" springfield"), "{trimmed}");
+
+        // The only gaps before the limit sit inside an open group: cut before it.
+        let grouped = format!("lang:en ({})", "a OR ".repeat(300));
+        assert_eq!(clamp_query(&grouped, MAX_QUERY_LEN), "lang:en");
+    }
+
+    #[tokio::test]
+    async fn rejected_token_reports_invalid_credentials() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/2/tweets"))
+            .and(header("authorization", "Bearer good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "id": "20", "text": "just setting up my twttr" }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/2/tweets"))
+            .respond_with(ResponseTemplate::new(401).set_body_json(serde_json::json!({
+                "title": "Unauthorized",
+                "detail": "Unauthorized",
+                "status": 401
+            })))
+            .mount(&server)
+            .await;
+
+        let good = TwitterApi::with_base_url("good".into(), &server.uri()).unwrap();
+        good.verify_credentials().await.unwrap();
+
+        let expired = TwitterApi::with_base_url("expired".into(), &server.uri()).unwrap();
+        let err = expired.verify_credentials().await.unwrap_err();
+        assert!(
+            err.to_string().starts_with("invalid Twitter credentials"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn health_check_maps_a_rejected_token_to_unhealthy() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/2/tweets"))
+            .and(header("authorization", "Bearer good"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{ "id": "20", "text": "just setting up my twttr" }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/2/tweets"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let good = TwitterApi::with_base_url("good".into(), &server.uri()).unwrap();
+        assert!(good.health_check().await.unwrap());
+        let expired = TwitterApi::with_base_url("expired".into(), &server.uri()).unwrap();
+        assert!(!expired.health_check().await.unwrap());
+
+        // Nothing listening is an error, not an unhealthy verdict.
+        let gone = TwitterApi::with_base_url("good".into(), "http://127.0.0.1:9").unwrap();
+        assert!(gone.health_check().await.is_err());
+    }
+
+    #[test]
+    fn exclusion_operators_are_appended_only_when_set() {
+        let api = TwitterApi::new("t".into());
+        let query = "\"bridge closure\" lang:en".to_string();
+        assert_eq!(api.search_query(query.clone()), query);
+
+        let api = api.with_exclusions(SearchExclusions {
+            retweets: true,
+            replies: true,
+        });
+        assert_eq!(
+            api.search_query(query.clone()),
+            "\"bridge closure\" lang:en -is:retweet -is:reply"
+        );
+        // An operator the model already wrote is not repeated.
+        assert_eq!(
+            api.search_query("bridge -is:retweet".into()),
+            "bridge -is:retweet -is:reply"
+        );
+
+        let only_replies = TwitterApi::new("t".into()).with_exclusions(SearchExclusions {
+            replies: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            only_replies.search_query(query.clone()),
+            "\"bridge closure\" lang:en -is:reply"
+        );
+
+        // A top-level OR is grouped so the operators cover both branches; one inside
+        // quotes or parentheses is left alone.
+        assert_eq!(
+            api.search_query("bridge closed OR \"bridge shut\" lang:en".into()),
+            "(bridge closed OR \"bridge shut\" lang:en) -is:retweet -is:reply"
+        );
+        assert_eq!(
+            api.search_query("(closed OR shut) bridge \"this OR that\"".into()),
+            "(closed OR shut) bridge \"this OR that\" -is:retweet -is:reply"
+        );
+        assert_eq!(
+            TwitterApi::new("t".into()).search_query("closed OR shut".into()),
+            "closed OR shut"
+        );
+
+        // Trimming an over-long query keeps the operators.
+        let long = format!("bridge {}", "springfield ".repeat(100));
+        let built = api.search_query(long);
+        assert!(built.len() <= MAX_QUERY_LEN, "{}", built.len());
+        assert!(
+            built.ends_with(" springfield -is:retweet -is:reply"),
+            "{built}"
+        );
+    }
+}
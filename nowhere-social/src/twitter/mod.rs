@@ -8,4 +8,6 @@ pub mod extract;
 pub mod types;
 
 // (optional) re-exports if you want `nowhere_social::twitter::TwitterApi` etc.
-pub use client::TwitterApi;
+pub use client::{
+    ApiProblem, SearchExclusions, TweetFields, TwitterApi, api_problem, recent_search_window,
+};
@@ -9,13 +9,47 @@ use serde::{Deserialize, Serialize};
 pub struct SearchResponse {
     pub data: Option<Vec<Tweet>>,
     pub includes: Option<Includes>,
-    pub meta: Option<Meta>,
+    pub meta: Option<SearchMeta>,
+    /// Per-item problems on an otherwise successful page, e.g. a deleted tweet named in
+    /// the expansions. The `data` that did resolve is still valid.
+    #[serde(default)]
+    pub errors: Option<Vec<TwitterPartialError>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct Meta {
+/// One entry of the `errors` array that can accompany `data` in a 200 response.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct TwitterPartialError {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// The problem `type`, e.g. `https://api.twitter.com/2/problems/resource-not-found`.
+    #[serde(default, rename = "type")]
+    pub kind: Option<String>,
+    /// The id or value the error is about.
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub resource_type: Option<String>,
+    #[serde(default)]
+    pub resource_id: Option<String>,
+    #[serde(default)]
+    pub parameter: Option<String>,
+}
+
+/// Pagination block of a search page.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct SearchMeta {
+    /// Pass back to fetch the next page; absent on the last one.
     #[serde(default)]
     pub next_token: Option<String>,
+    /// Tweets on this page.
+    #[serde(default)]
+    pub result_count: Option<u32>,
+    #[serde(default)]
+    pub newest_id: Option<String>,
+    #[serde(default)]
+    pub oldest_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -24,6 +58,17 @@ pub struct Includes {
     pub users: Option<Vec<User>>,
     #[serde(default)]
     pub media: Option<Vec<Media>>,
+    /// Tweets named in `referenced_tweets` (quoted, retweeted, replied to), with the
+    /// `referenced_tweets.id` expansion.
+    #[serde(default)]
+    pub tweets: Option<Vec<Tweet>>,
+}
+
+impl Includes {
+    /// The included tweet with this id, if the expansion resolved it.
+    pub fn tweet(&self, id: &str) -> Option<&Tweet> {
+        self.tweets.as_deref()?.iter().find(|t| t.id == id)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,3 +178,70 @@ pub struct MentionEntity {
 pub struct HashTag {
     pub tag: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_meta_deserializes_with_the_page() {
+        let resp: SearchResponse = serde_json::from_str(
+            r#"{
+                "data": [{ "id": "1850", "text": "bridge closed" }],
+                "meta": {
+                    "newest_id": "1850",
+                    "oldest_id": "1849",
+                    "result_count": 2,
+                    "next_token": "b26v89c19zqg8o3f"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            resp.meta,
+            Some(SearchMeta {
+                next_token: Some("b26v89c19zqg8o3f".into()),
+                result_count: Some(2),
+                newest_id: Some("1850".into()),
+                oldest_id: Some("1849".into()),
+            })
+        );
+
+        let last: SearchResponse =
+            serde_json::from_str(r#"{ "meta": { "result_count": 0 } }"#).unwrap();
+        let meta = last.meta.unwrap();
+        assert_eq!((meta.result_count, meta.next_token), (Some(0), None));
+    }
+
+    #[test]
+    fn partial_errors_deserialize_alongside_data() {
+        let resp: SearchResponse = serde_json::from_str(
+            r#"{
+                "data": [{ "id": "1850", "text": "bridge closed", "author_id": "7" }],
+                "includes": { "users": [{ "id": "7", "username": "roads" }] },
+                "errors": [{
+                    "value": "1849",
+                    "detail": "Could not find tweet with referenced_tweets.id: [1849].",
+                    "title": "Not Found Error",
+                    "resource_type": "tweet",
+                    "parameter": "referenced_tweets.id",
+                    "resource_id": "1849",
+                    "type": "https://api.twitter.com/2/problems/resource-not-found"
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let data = resp.data.unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].id, "1850");
+        let errors = resp.errors.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].resource_id.as_deref(), Some("1849"));
+        assert_eq!(errors[0].title.as_deref(), Some("Not Found Error"));
+        assert_eq!(
+            errors[0].kind.as_deref(),
+            Some("https://api.twitter.com/2/problems/resource-not-found")
+        );
+    }
+}
@@ -1,8 +1,24 @@
-#[derive(Debug, Clone)]
+use nowhere_actors::ReviewStatus;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Command {
-    Claim(Option<String>), // /claim <text> | /claim | /claim -
-    Help,                  // /help
-    Quit,                  // /quit or /exit
+    Claim(Option<String>),                  // /claim <text> | /claim | /claim -
+    Cancel,                                 // /cancel
+    Synthesize,                             // /synthesize
+    Sources,                                // /sources
+    Tag(Option<String>),                    // /tag <tag>
+    Claims(Option<String>),                 // /claims <tag>
+    Diff(Option<(String, String)>),         // /diff <id> <id>
+    Bundle(Option<String>, bool),           // /bundle <path> [--captures]
+    Review(Option<(String, ReviewStatus)>), // /review <id> confirm|dismiss|reset
+    Reindex,                                // /reindex
+    Research(Option<String>, bool),         // /research [days] [--fresh]
+    Query,                                  // /query
+    K(Option<String>),                      // /k [n]
+    Lang(Option<String>),                   // /lang [language] | /lang -
+    Palette(Option<String>),                // / [filter]
+    Help,                                   // /help
+    Quit,                                   // /quit or /exit
     Unknown(String),
 }
 
@@ -16,13 +32,372 @@ pub fn parse_command(input: &str) -> Command {
     let rest = parts.next().map(str::trim).filter(|s| !s.is_empty());
 
     match verb {
+        "/" => Command::Palette(rest.map(str::to_lowercase)),
         "/claim" => match rest {
             None => Command::Claim(None),
             Some("-") => Command::Claim(Some(String::new())),
             Some(text) => Command::Claim(Some(text.to_string())),
         },
+        "/cancel" => Command::Cancel,
+        "/synthesize" => Command::Synthesize,
+        "/sources" => Command::Sources,
+        "/tag" => Command::Tag(rest.map(str::to_lowercase)),
+        "/claims" => Command::Claims(rest.map(str::to_lowercase)),
+        "/diff" => {
+            let words: Vec<&str> = rest.unwrap_or_default().split_whitespace().collect();
+            Command::Diff(match words[..] {
+                [a, b] => Some((a.to_string(), b.to_string())),
+                _ => None,
+            })
+        }
+        "/bundle" => {
+            let mut words: Vec<&str> = rest.unwrap_or_default().split_whitespace().collect();
+            let captures = words.contains(&"--captures");
+            words.retain(|w| *w != "--captures");
+            Command::Bundle((!words.is_empty()).then(|| words.join(" ")), captures)
+        }
+        "/review" => {
+            let words: Vec<&str> = rest.unwrap_or_default().split_whitespace().collect();
+            let status = match words[..] {
+                [_, "confirm"] => Some(ReviewStatus::Confirmed),
+                [_, "dismiss"] => Some(ReviewStatus::Dismissed),
+                [_, "reset"] => Some(ReviewStatus::Pending),
+                _ => None,
+            };
+            // Accept the id as chat cites it, e.g. `[A:<id>]`.
+            Command::Review(status.map(|status| {
+                let id = words[0].trim_start_matches('[').trim_end_matches(']');
+                (id.trim_start_matches("A:").to_string(), status)
+            }))
+        }
+        "/reindex" => Command::Reindex,
+        "/research" => {
+            let mut words: Vec<&str> = rest.unwrap_or_default().split_whitespace().collect();
+            let fresh = words.contains(&"--fresh");
+            words.retain(|w| *w != "--fresh");
+            Command::Research((!words.is_empty()).then(|| words.join(" ")), fresh)
+        }
+        "/query" => Command::Query,
+        "/k" => Command::K(rest.map(str::to_string)),
+        "/lang" => Command::Lang(rest.map(str::to_string)),
         "/help" => Command::Help,
         "/quit" | "/exit" => Command::Quit,
         _ => Command::Unknown(trimmed.to_string()),
     }
 }
+
+/// One row of `/help` and the command palette.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommandHelp {
+    pub usage: &'static str,
+    pub summary: &'static str,
+}
+
+impl CommandHelp {
+    /// The `/verb` part of `usage`.
+    pub fn verb(&self) -> &'static str {
+        self.usage.split_whitespace().next().unwrap_or(self.usage)
+    }
+}
+
+const fn help(usage: &'static str, summary: &'static str) -> CommandHelp {
+    CommandHelp { usage, summary }
+}
+
+/// Every command, in `/help` order.
+pub const COMMANDS: &[CommandHelp] = &[
+    help("/claim <text>", "set the active claim"),
+    help("/claim", "show the active claim"),
+    help("/claim -", "clear the active claim"),
+    help("/cancel", "stop the active claim's searches"),
+    help("/synthesize", "summarize the active claim's evidence"),
+    help("/sources", "count the evidence by platform and credibility"),
+    help("/tag <tag>", "label the active claim"),
+    help("/claims <tag>", "list the claims labelled <tag>"),
+    help(
+        "/diff <id> <id>",
+        "show the entities and sources two claims share",
+    ),
+    help(
+        "/bundle <path>",
+        "save the active claim's evidence as one JSON file; --captures embeds pages",
+    ),
+    help(
+        "/review <id> confirm|dismiss",
+        "mark a cited artifact relevant or a false positive; reset undoes it",
+    ),
+    help("/reindex", "rebuild the full-text search index"),
+    help(
+        "/research [days]",
+        "re-run the active claim's search; --fresh rebuilds the query",
+    ),
+    help("/query", "show the query and dates of the last search"),
+    help("/k [n]", "show or set how many artifacts chat retrieves"),
+    help(
+        "/lang [language]",
+        "show or set the language chat answers in; - restores the default",
+    ),
+    help(
+        "/ [filter]",
+        "list the commands matching [filter] (or press Ctrl-P)",
+    ),
+    help("/help", "list every command"),
+    help("/quit", "exit"),
+];
+
+/// Commands whose verb matches `query` (with or without the leading `/`): verbs that
+/// start with it first, then verbs containing its letters in order, e.g. `rsh` for
+/// `/research`. An empty query matches everything.
+pub fn filter_commands(query: &str) -> Vec<&'static CommandHelp> {
+    let query = query.trim().trim_start_matches('/').to_lowercase();
+    let (mut prefixed, mut fuzzy) = (Vec::new(), Vec::new());
+    for cmd in COMMANDS {
+        let verb = cmd.verb().trim_start_matches('/');
+        if verb.starts_with(&query) {
+            prefixed.push(cmd);
+        } else if is_subsequence(&query, verb) {
+            fuzzy.push(cmd);
+        }
+    }
+    prefixed.append(&mut fuzzy);
+    prefixed
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut rest = haystack.chars();
+    needle.chars().all(|c| rest.any(|h| h == c))
+}
+
+/// Claim text longer than this is cut before it reaches the query-builder prompt.
+pub const DEFAULT_MAX_CLAIM_CHARS: usize = 2000;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClaimText {
+    Accepted(String),
+    Truncated { text: String, original_chars: usize },
+    Empty,
+}
+
+/// Trim `raw` and cap it at `max_chars` characters.
+///
+/// Zero-width and control characters count as whitespace here, so pasted invisible text
+/// cannot create an empty claim.
+pub fn validate_claim(raw: &str, max_chars: usize) -> ClaimText {
+    let is_blank = |c: char| c.is_whitespace() || c.is_control() || is_zero_width(c);
+    let text = raw.trim_matches(is_blank);
+    if text.is_empty() {
+        return ClaimText::Empty;
+    }
+
+    let original_chars = text.chars().count();
+    if original_chars <= max_chars {
+        return ClaimText::Accepted(text.to_string());
+    }
+    let cut: String = text.chars().take(max_chars).collect();
+    ClaimText::Truncated {
+        text: cut.trim_end_matches(is_blank).to_string(),
+        original_chars,
+    }
+}
+
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn over_long_claim_is_truncated() {
+        let long = "é".repeat(DEFAULT_MAX_CLAIM_CHARS + 50);
+        let Command::Claim(Some(text)) = parse_command(&format!("/claim {long}")) else {
+            panic!("expected a claim");
+        };
+        match validate_claim(&text, DEFAULT_MAX_CLAIM_CHARS) {
+            ClaimText::Truncated {
+                text,
+                original_chars,
+            } => {
+                assert_eq!(text.chars().count(), DEFAULT_MAX_CLAIM_CHARS);
+                assert_eq!(original_chars, DEFAULT_MAX_CLAIM_CHARS + 50);
+            }
+            other => panic!("expected truncation, got {other:?}"),
+        }
+        assert_eq!(
+            validate_claim("  short claim  ", DEFAULT_MAX_CLAIM_CHARS),
+            ClaimText::Accepted("short claim".into())
+        );
+    }
+
+    #[test]
+    fn whitespace_only_claim_is_rejected() {
+        assert_eq!(validate_claim(" \t\n ", 10), ClaimText::Empty);
+        assert_eq!(validate_claim("\u{200B}\u{FEFF} ", 10), ClaimText::Empty);
+        // `/claim` with only blanks after it never becomes a claim either.
+        assert_eq!(parse_command("/claim    "), Command::Claim(None));
+    }
+
+    #[test]
+    fn synthesize_parses() {
+        assert_eq!(parse_command("/synthesize"), Command::Synthesize);
+        assert_eq!(parse_command("  /synthesize  "), Command::Synthesize);
+        assert_eq!(
+            parse_command("/synth"),
+            Command::Unknown("/synth".to_string())
+        );
+    }
+
+    #[test]
+    fn sources_parses() {
+        assert_eq!(parse_command("/sources"), Command::Sources);
+        assert_eq!(parse_command("/sources extra"), Command::Sources);
+    }
+
+    #[test]
+    fn tag_and_claims_take_a_lowercased_tag() {
+        assert_eq!(
+            parse_command("/tag  Election-2024 "),
+            Command::Tag(Some("election-2024".into()))
+        );
+        assert_eq!(parse_command("/tag"), Command::Tag(None));
+        assert_eq!(
+            parse_command("/claims Springfield"),
+            Command::Claims(Some("springfield".into()))
+        );
+        assert_eq!(parse_command("/claims "), Command::Claims(None));
+    }
+
+    #[test]
+    fn diff_takes_two_claim_ids() {
+        let diff = |a: &str, b: &str| Command::Diff(Some((a.into(), b.into())));
+        assert_eq!(parse_command("/diff 4fQ2x 1bZ9k "), diff("4fQ2x", "1bZ9k"));
+        let id = "0b6c3f5e-8a3d-4d4e-9a63-2f1d6f0f6c11";
+        assert_eq!(
+            parse_command(&format!("/diff {id}  4fQ2x")),
+            diff(id, "4fQ2x")
+        );
+        assert_eq!(parse_command("/diff"), Command::Diff(None));
+        assert_eq!(parse_command("/diff 4fQ2x"), Command::Diff(None));
+        assert_eq!(parse_command("/diff a b c"), Command::Diff(None));
+    }
+
+    #[test]
+    fn bundle_takes_a_path_and_captures_flag() {
+        let bundle =
+            |path: Option<&str>, captures| Command::Bundle(path.map(str::to_string), captures);
+        assert_eq!(
+            parse_command("/bundle ferry.json"),
+            bundle(Some("ferry.json"), false)
+        );
+        assert_eq!(
+            parse_command("/bundle --captures out/ferry.json"),
+            bundle(Some("out/ferry.json"), true)
+        );
+        assert_eq!(parse_command("/bundle"), bundle(None, false));
+        assert_eq!(parse_command("/bundle --captures"), bundle(None, true));
+    }
+
+    #[test]
+    fn review_takes_an_artifact_id_and_a_verdict() {
+        let id = "0b6c3f5e-8a3d-4d4e-9a63-2f1d6f0f6c11";
+        let review = |status| Command::Review(Some((id.to_string(), status)));
+        assert_eq!(
+            parse_command(&format!("/review {id} confirm")),
+            review(ReviewStatus::Confirmed)
+        );
+        assert_eq!(
+            parse_command(&format!("/review [A:{id}] dismiss")),
+            review(ReviewStatus::Dismissed)
+        );
+        assert_eq!(
+            parse_command(&format!("/review {id} reset")),
+            review(ReviewStatus::Pending)
+        );
+        assert_eq!(parse_command("/review"), Command::Review(None));
+        assert_eq!(
+            parse_command(&format!("/review {id}")),
+            Command::Review(None)
+        );
+        assert_eq!(
+            parse_command(&format!("/review {id} maybe")),
+            Command::Review(None)
+        );
+    }
+
+    #[test]
+    fn reindex_parses() {
+        assert_eq!(parse_command("/reindex"), Command::Reindex);
+    }
+
+    #[test]
+    fn research_takes_an_optional_window_and_fresh_flag() {
+        let research =
+            |days: Option<&str>, fresh| Command::Research(days.map(str::to_string), fresh);
+        assert_eq!(parse_command("/research"), research(None, false));
+        assert_eq!(parse_command("/research  3 "), research(Some("3"), false));
+        assert_eq!(parse_command("/research --fresh"), research(None, true));
+        assert_eq!(
+            parse_command("/research --fresh 3"),
+            research(Some("3"), true)
+        );
+        assert_eq!(parse_command("/query"), Command::Query);
+    }
+
+    #[test]
+    fn k_takes_an_optional_count() {
+        assert_eq!(parse_command("/k"), Command::K(None));
+        assert_eq!(parse_command("/k 50 "), Command::K(Some("50".into())));
+    }
+
+    #[test]
+    fn lang_takes_an_optional_language() {
+        assert_eq!(parse_command("/lang"), Command::Lang(None));
+        assert_eq!(
+            parse_command("/lang Brazilian Portuguese "),
+            Command::Lang(Some("Brazilian Portuguese".into()))
+        );
+        assert_eq!(parse_command("/lang -"), Command::Lang(Some("-".into())));
+    }
+
+    #[test]
+    fn palette_filters_prefixes_first_then_fuzzy_matches() {
+        assert_eq!(parse_command("/"), Command::Palette(None));
+        assert_eq!(parse_command("/ Cl"), Command::Palette(Some("cl".into())));
+
+        assert_eq!(filter_commands("").len(), COMMANDS.len());
+        assert_eq!(filter_commands("/"), filter_commands(""));
+
+        let verbs = |q: &str| {
+            filter_commands(q)
+                .iter()
+                .map(|c| c.usage)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            verbs("/cla"),
+            ["/claim <text>", "/claim", "/claim -", "/claims <tag>"]
+        );
+        assert_eq!(
+            verbs("RE"),
+            [
+                "/review <id> confirm|dismiss",
+                "/reindex",
+                "/research [days]",
+                "/sources"
+            ]
+        );
+        assert_eq!(verbs("rsch"), ["/research [days]"]);
+        // Prefix matches lead even when a fuzzy match comes earlier in `/help` order.
+        assert_eq!(
+            verbs("s"),
+            [
+                "/synthesize",
+                "/sources",
+                "/claims <tag>",
+                "/research [days]"
+            ]
+        );
+        assert!(verbs("zz").is_empty());
+    }
+}
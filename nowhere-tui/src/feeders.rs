@@ -1,32 +1,82 @@
+//! Background tasks that feed the TUI actor: terminal input events and render ticks.
+//!
+//! Input is read from an [`EventSource`] on a blocking thread and forwarded as
+//! `TuiMsg::InputEvent`; ticks are sent every `tick_rate`. Both stop on shutdown.
 use crate::tui::{TuiActor, TuiMsg};
-use nowhere_actors::actor::Addr;
+use crossterm::event::Event as CtEvent;
+use nowhere_actors::actor::{Actor, Addr};
 use nowhere_actors::system::ShutdownHandle;
+use std::io;
 use std::time::Duration;
 use tokio::{self, time};
 
+/// How often the tick feeder wakes the TUI.
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_millis(80);
+
+/// Blocking source of terminal events for the input feeder.
+pub trait EventSource: Send + 'static {
+    /// Wait for the next event. `Ok(None)` means the source is exhausted.
+    fn read(&mut self) -> io::Result<Option<CtEvent>>;
+}
+
+/// The real terminal, via `crossterm::event::read`.
+pub struct CrosstermEvents;
+
+impl EventSource for CrosstermEvents {
+    fn read(&mut self) -> io::Result<Option<CtEvent>> {
+        crossterm::event::read().map(Some)
+    }
+}
+
+/// Scripted events, e.g. from a test; ends when every sender is dropped.
+impl EventSource for std::sync::mpsc::Receiver<CtEvent> {
+    fn read(&mut self) -> io::Result<Option<CtEvent>> {
+        Ok(self.recv().ok())
+    }
+}
+
 pub fn spawn_tui_feeders(tui: Addr<TuiActor>, shutdown: ShutdownHandle) {
+    spawn_tui_feeders_with(tui, shutdown, CrosstermEvents, DEFAULT_TICK_RATE);
+}
+
+/// [`spawn_tui_feeders`] with an explicit event source and tick rate.
+pub fn spawn_tui_feeders_with<A, S>(
+    tui: Addr<A>,
+    shutdown: ShutdownHandle,
+    events: S,
+    tick_rate: Duration,
+) where
+    A: Actor<Msg = TuiMsg>,
+    S: EventSource,
+{
     let tui_in = tui.clone();
     let mut shutdown_input = shutdown.subscribe();
     tokio::spawn(async move {
+        let mut events = Some(events);
         loop {
+            let Some(mut source) = events.take() else {
+                break;
+            };
             tokio::select! {
-                res = shutdown_input.recv() => {
-                    if res.is_err() {
-                        break;
-                    }
-                    break;
-                }
+                _ = shutdown_input.recv() => break,
                 // FIXME: reuse a dedicated blocking thread instead of spawning a task per keypress to reduce allocator pressure.
-                ev = tokio::task::spawn_blocking(|| crossterm::event::read()) => {
+                read = tokio::task::spawn_blocking(move || {
+                    let ev = source.read();
+                    (source, ev)
+                }) => {
+                    let Ok((source, ev)) = read else {
+                        break;
+                    };
                     match ev {
-                        Ok(Ok(e)) => {
+                        Ok(Some(e)) => {
                             let _ = tui_in.send(TuiMsg::InputEvent(e)).await;
                         }
-                        Ok(Err(e)) => {
+                        Ok(None) => break,
+                        Err(e) => {
                             let _ = tui_in.send(TuiMsg::OpError(format!("input: {e}"))).await;
                         }
-                        Err(_) => break,
                     }
+                    events = Some(source);
                 }
             }
         }
@@ -35,15 +85,11 @@ pub fn spawn_tui_feeders(tui: Addr<TuiActor>, shutdown: ShutdownHandle) {
     let tui_tick = tui.clone();
     let mut shutdown_tick = shutdown.subscribe();
     tokio::spawn(async move {
-        let mut interval = time::interval(Duration::from_millis(80));
+        // `interval` panics on a zero period.
+        let mut interval = time::interval(tick_rate.max(Duration::from_millis(1)));
         loop {
             tokio::select! {
-                res = shutdown_tick.recv() => {
-                    if res.is_err() {
-                        break;
-                    }
-                    break;
-                }
+                _ = shutdown_tick.recv() => break,
                 _ = interval.tick() => {
                     let _ = tui_tick.try_send(TuiMsg::Tick);
                 }
@@ -51,3 +97,67 @@ pub fn spawn_tui_feeders(tui: Addr<TuiActor>, shutdown: ShutdownHandle) {
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::tests::test_tui;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use nowhere_actors::actor::spawn_actor;
+    use nowhere_actors::system::ActorSystem;
+    use tokio::sync::oneshot;
+
+    fn key(code: KeyCode) -> CtEvent {
+        CtEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    async fn transcript<B>(tui: &Addr<TuiActor<B>>) -> Vec<String>
+    where
+        B: ratatui::backend::Backend + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        tui.send(TuiMsg::Transcript(tx)).await.ok().unwrap();
+        rx.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn help_typed_through_the_feeder_reaches_the_transcript() {
+        let system = ActorSystem::new();
+        let shutdown = system.shutdown_handle();
+        let tui = test_tui(shutdown.clone());
+        let tui = spawn_actor(tui, 64).addr;
+
+        let (keys, events) = std::sync::mpsc::channel();
+        spawn_tui_feeders_with(
+            tui.clone(),
+            shutdown.clone(),
+            events,
+            Duration::from_millis(5),
+        );
+        for ch in "/help".chars() {
+            keys.send(key(KeyCode::Char(ch))).unwrap();
+        }
+        keys.send(key(KeyCode::Enter)).unwrap();
+
+        let lines = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let lines = transcript(&tui).await;
+                if lines.iter().any(|l| l == "Commands:") {
+                    return lines;
+                }
+                time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("help rendered in time");
+
+        for expected in [
+            "  /claim <text>   set the active claim",
+            "  /cancel         stop the active claim's searches",
+            "  /quit           exit",
+        ] {
+            assert!(lines.iter().any(|l| l == expected), "missing {expected:?}");
+        }
+        shutdown.signal();
+    }
+}
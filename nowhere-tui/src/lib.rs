@@ -10,5 +10,7 @@ mod transcript;
 mod tui;
 mod view;
 
-pub use feeders::spawn_tui_feeders;
+pub use feeders::{
+    CrosstermEvents, DEFAULT_TICK_RATE, EventSource, spawn_tui_feeders, spawn_tui_feeders_with,
+};
 pub use tui::{TuiActor, TuiMsg};
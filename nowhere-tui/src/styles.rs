@@ -44,6 +44,10 @@ pub fn twitter_header() -> Style {
         .add_modifier(Modifier::BOLD)
 }
 
+pub fn warning() -> Style {
+    Style::default().fg(Color::Yellow)
+}
+
 pub fn error() -> Style {
     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
 }
@@ -1,5 +1,9 @@
 use crate::{
-    command::{Command, parse_command},
+    command::{
+        COMMANDS, ClaimText, Command, CommandHelp, DEFAULT_MAX_CLAIM_CHARS, filter_commands,
+        parse_command, validate_claim,
+    },
+    feeders::DEFAULT_TICK_RATE,
     styles,
     transcript::TranscriptLine,
     view::{self, ViewSnap},
@@ -12,64 +16,157 @@ use crossterm::{
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use nowhere_actors::{
-    ArtifactRow, BuiltSearchQuery, ChatCmd, ChatResponse, ClaimContext, LlmMsg, SearchCmd,
+    ArtifactRow, BuiltSearchQuery, ChatCmd, ChatResponse, ClaimContext, EvidenceOverlap, LlmMsg,
+    PlatformSources, ReadinessReport, RecordedSearch, ReviewStatus, SearchCmd, SearchOutcome,
     StoreMsg,
     actor::{Actor, Addr, Context},
+    analysis::{AnalysisActor, ClaimSynthesis, synthesize_claim},
+    budget::ClaimBudget,
+    bundle,
     llm::{ChatLlmActor, LlmActor},
     store::StoreActor,
     system::ShutdownHandle,
     twitter::TwitterSearchActor,
 };
-use ratatui::{Terminal, backend::CrosstermBackend, style::Style};
+use nowhere_common::slug;
+use ratatui::{
+    Terminal,
+    backend::{Backend, CrosstermBackend},
+    style::Style,
+};
 use std::{
+    collections::HashSet,
     io::{self, Stdout},
+    path::PathBuf,
     time::{Duration, Instant},
 };
-use tokio::{sync::oneshot, task::JoinHandle};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
+/// How long a claim may keep searching and normalizing before it is stopped.
+const DEFAULT_CLAIM_BUDGET: Duration = Duration::from_secs(15 * 60);
+
+/// Redraws per second until `with_max_fps` changes it; above the tick rate it has no effect.
+const DEFAULT_MAX_FPS: u32 = 30;
+
+/// Artifacts a chat question retrieves until `/k` changes it.
+const DEFAULT_CHAT_K: i64 = 25;
+/// Largest `/k`; beyond this the context no longer fits a chat prompt usefully.
+const MAX_CHAT_K: i64 = 200;
+
 const BRAILLE_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+/// Busy indicator when animation is off; plain ASCII for terminals without the glyphs.
+const STATIC_SPINNER: &str = "...";
+
+/// One piece of background work the spinner waits on. Results name the operation they
+/// finish, so each one ends exactly once however many paths report it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpId(u64);
 
 pub enum TuiMsg {
     InputEvent(CtEvent),
     Tick,
     Submit(String),
-    SearchQueryBuilt(BuiltSearchQuery),
-    LlmDone(String),
-    ChatDone(ChatResponse),
-    TwitterDone(Vec<String>),
-    ArtifactsCheckDone(std::result::Result<bool, String>),
+    SearchQueryBuilt(OpId, BuiltSearchQuery),
+    /// The LLM dropped a search-query request.
+    SearchQueryFailed(OpId, String),
+    LlmDone(OpId, String),
+    ChatDone(OpId, ChatResponse),
+    SynthesisDone(OpId, Option<ClaimSynthesis>),
+    SourcesDone(OpId, Vec<PlatformSources>),
+    /// The active claim's last recorded search, for `/query`.
+    LastSearch(OpId, Option<RecordedSearch>),
+    /// The claim's tags after a `/tag`.
+    Tagged(OpId, Uuid, Vec<String>),
+    ClaimsListed(OpId, String, Vec<ClaimContext>),
+    /// What the two claims given to `/diff` have in common.
+    OverlapFound(OpId, EvidenceOverlap),
+    /// A `/bundle` was written to this path with this many artifacts.
+    BundleWritten(OpId, PathBuf, usize),
+    /// An artifact's review status after a `/review`.
+    Reviewed(OpId, Uuid, ReviewStatus),
+    /// Rows in the rebuilt full-text index.
+    Reindexed(OpId, usize),
+    TwitterDone(OpId, Vec<String>),
+    TwitterFailed(OpId, String),
+    /// The search stopped at the claim's artifact cap after forwarding these ids.
+    CollectionCapReached(OpId, Vec<String>),
+    /// The search ran out of time with pages left after forwarding these ids.
+    SearchPartial(OpId, Vec<String>),
+    BudgetReached(Uuid),
+    ArtifactsCheckDone(OpId, std::result::Result<bool, String>),
     ArtifactsUpdated(Uuid),
+    /// Startup check of the LLMs, Twitter, the database, ...
+    Readiness(ReadinessReport),
+    /// An operation failed; shown as an error.
+    OpFailed(OpId, String),
+    /// An error outside any operation, e.g. from the input feeder.
     OpError(String),
     ScrollUp,
     ScrollDown,
     Shutdown,
+    /// Test hook: reply with the transcript text.
+    #[cfg(test)]
+    Transcript(oneshot::Sender<Vec<String>>),
+    /// Test hook: reply with the number of unfinished operations.
+    #[cfg(test)]
+    Busy(oneshot::Sender<usize>),
 }
 
-pub struct TuiActor {
+/// The TUI actor. `B` is the ratatui backend: the crossterm terminal in the app, a
+/// `TestBackend` in tests.
+pub struct TuiActor<B: Backend = CrosstermBackend<Stdout>> {
     claim: Option<ClaimContext>,
+    // cancels the active claim's search/normalization work
+    claim_cancel: Option<CancellationToken>,
+    // wall-clock budget for the active claim; cancels `claim_cancel` when spent
+    claim_budget: Option<ClaimBudget>,
+    // a search round for the active claim is in flight
+    searching: bool,
+    // `/research <days>`: window for the next search round instead of the LLM's dates
+    search_window: Option<chrono::Duration>,
 
     // deps
     llm: Addr<LlmActor>,
     chat_llm: Addr<ChatLlmActor>,
+    analysis: Addr<AnalysisActor>,
     // FIXME: allow the UI to select from multiple Twitter workers instead of assuming a single dedicated actor.
     twitter: Addr<TwitterSearchActor>,
+    // another `SearchCmd` worker each search also goes to (tether's web search)
+    web_search: Option<Addr<TwitterSearchActor>>,
     store: Addr<StoreActor>,
 
     // terminal
-    term: Terminal<CrosstermBackend<Stdout>>,
+    term: Terminal<B>,
     tick_rate: Duration,
     last_tick: Instant,
+    // shortest gap between two redraws; changes in between wait for a later tick
+    frame_budget: Duration,
+    // animated spinner + periodic redraws; off means redraw only on change
+    animate: bool,
+    // list claim ids as base62 slugs rather than UUIDs
+    id_slugs: bool,
 
     // ui state
+    max_claim_chars: usize,
+    claim_budget_limit: Duration,
+    // artifacts each chat question retrieves; `/k`
+    chat_k: i64,
+    // language chat answers are written in, over the configured one; `/lang`
+    answer_language: Option<String>,
     input: String,
     input_cursor: usize,
     lines: Vec<TranscriptLine>, // transcript buffer
     scroll: usize,              // from bottom
     dirty: bool,
 
-    // busy/spinner
-    busy: u32,
+    // busy/spinner: operations started and not yet finished
+    busy: HashSet<OpId>,
+    next_op: u64,
     spin_idx: usize,
 
     // artifact watch task
@@ -81,10 +178,12 @@ pub struct TuiActor {
 }
 
 impl TuiActor {
+    /// Take over the terminal (raw mode, alternate screen) and build the actor on it.
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         llm: Addr<LlmActor>,
         chat_llm: Addr<ChatLlmActor>,
+        analysis: Addr<AnalysisActor>,
         twitter: Addr<TwitterSearchActor>,
         store: Addr<StoreActor>,
         shutdown: ShutdownHandle,
@@ -93,18 +192,47 @@ impl TuiActor {
         enable_raw_mode()?;
         execute!(stdout, EnterAlternateScreen)?;
         let backend = CrosstermBackend::new(stdout);
-        let mut term = Terminal::new(backend)?;
+        let term = Terminal::new(backend)?;
+        Self::with_terminal(term, llm, chat_llm, analysis, twitter, store, shutdown)
+    }
+}
+
+impl<B: Backend + Send + 'static> TuiActor<B> {
+    /// Build the actor on an existing terminal; [`TuiActor::new`] for the real one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_terminal(
+        mut term: Terminal<B>,
+        llm: Addr<LlmActor>,
+        chat_llm: Addr<ChatLlmActor>,
+        analysis: Addr<AnalysisActor>,
+        twitter: Addr<TwitterSearchActor>,
+        store: Addr<StoreActor>,
+        shutdown: ShutdownHandle,
+    ) -> Result<Self> {
         term.clear()?;
 
         Ok(Self {
             claim: None,
+            claim_cancel: None,
+            claim_budget: None,
+            searching: false,
+            search_window: None,
             llm,
             chat_llm,
+            analysis,
             twitter,
+            web_search: None,
             store,
             term,
-            tick_rate: Duration::from_millis(80),
+            tick_rate: DEFAULT_TICK_RATE,
             last_tick: Instant::now(),
+            frame_budget: Duration::from_secs(1) / DEFAULT_MAX_FPS,
+            animate: true,
+            id_slugs: true,
+            max_claim_chars: DEFAULT_MAX_CLAIM_CHARS,
+            claim_budget_limit: DEFAULT_CLAIM_BUDGET,
+            chat_k: DEFAULT_CHAT_K,
+            answer_language: None,
             input: String::new(),
             input_cursor: 0,
             lines: vec![TranscriptLine::new(
@@ -113,7 +241,8 @@ impl TuiActor {
             )],
             scroll: 0,
             dirty: true,
-            busy: 0,
+            busy: HashSet::new(),
+            next_op: 0,
             spin_idx: 0,
             artifact_watch: None,
             artifact_watch_armed: false,
@@ -121,6 +250,57 @@ impl TuiActor {
         })
     }
 
+    /// Send each search to `web` as well as to Twitter. Any actor taking `SearchCmd` fits
+    /// behind the mailbox type (see `Reserved::into_actor`); its outcome is not awaited,
+    /// so the claim's progress still follows the Twitter search.
+    pub fn with_web_search(mut self, web: Addr<TwitterSearchActor>) -> Self {
+        self.web_search = Some(web);
+        self
+    }
+
+    /// Cap claim text at `n` characters (default 2000); longer claims are truncated.
+    pub fn with_max_claim_chars(mut self, n: usize) -> Self {
+        self.max_claim_chars = n.max(1);
+        self
+    }
+
+    /// Redraw at least this often while animating (default 80ms). Match the feeders' tick.
+    pub fn with_tick_rate(mut self, rate: Duration) -> Self {
+        self.tick_rate = rate;
+        self
+    }
+
+    /// Redraw at most `fps` times a second (default 30). Changes arriving faster are
+    /// painted together on the next tick past the budget, so bursts of streamed output
+    /// or scrolling don't repaint the whole screen for every event. Frames are only
+    /// drawn on ticks, so this only lowers the rate when `fps` is below one per tick
+    /// (12.5 at the default 80ms); above that the tick rate is the limit.
+    pub fn with_max_fps(mut self, fps: u32) -> Self {
+        self.frame_budget = Duration::from_secs(1) / fps.max(1);
+        self
+    }
+
+    /// With `false`, show a static `...` instead of the braille spinner and only redraw
+    /// when something changed. Meant for slow links and limited terminals.
+    pub fn with_animation(mut self, on: bool) -> Self {
+        self.animate = on;
+        self
+    }
+
+    /// With `false`, list claim ids as full UUIDs instead of short base62 slugs (see
+    /// `nowhere_common::slug`). Commands take either form.
+    pub fn with_id_slugs(mut self, on: bool) -> Self {
+        self.id_slugs = on;
+        self
+    }
+
+    /// Stop searching and normalizing for a claim once it has run this long (default 15
+    /// minutes).
+    pub fn with_claim_budget(mut self, budget: Duration) -> Self {
+        self.claim_budget_limit = budget;
+        self
+    }
+
     fn cursor_left(&mut self) {
         if self.input_cursor == 0 {
             return;
@@ -181,14 +361,50 @@ impl TuiActor {
     }
 
     pub fn set_claim(&mut self, ctx: ClaimContext) {
+        self.cancel_claim_work();
         self.claim = Some(ctx);
+        let cancel = CancellationToken::new();
+        self.claim_budget = Some(ClaimBudget::new(cancel.clone(), self.claim_budget_limit));
+        self.claim_cancel = Some(cancel);
     }
 
     pub fn clear_claim(&mut self) {
         self.cancel_artifact_watch();
+        self.cancel_claim_work();
         self.claim = None;
     }
 
+    fn cancel_claim_work(&mut self) {
+        if let Some(token) = self.claim_cancel.take() {
+            token.cancel();
+        }
+        self.claim_budget = None;
+        self.searching = false;
+        self.search_window = None;
+    }
+
+    /// Report to `me` when the active claim's budget runs out.
+    fn watch_claim_budget(&self, claim_id: Uuid, me: Addr<Self>) {
+        let Some(budget) = self.claim_budget.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            if budget.run_out().await {
+                let _ = me.send(TuiMsg::BudgetReached(claim_id)).await;
+            }
+        });
+    }
+
+    /// Token for the active claim, if it has not been cancelled.
+    fn live_claim_token(&self, claim_id: Uuid) -> Option<CancellationToken> {
+        match (&self.claim, &self.claim_cancel) {
+            (Some(claim), Some(token)) if claim.id == claim_id && !token.is_cancelled() => {
+                Some(token.clone())
+            }
+            _ => None,
+        }
+    }
+
     fn cancel_artifact_watch(&mut self) {
         if let Some(handle) = self.artifact_watch.take() {
             handle.abort();
@@ -196,7 +412,7 @@ impl TuiActor {
         self.artifact_watch_armed = false;
     }
 
-    fn subscribe_artifact_updates(&mut self, claim: &ClaimContext, me: Addr<TuiActor>) {
+    fn subscribe_artifact_updates(&mut self, claim: &ClaimContext, me: Addr<Self>) {
         self.cancel_artifact_watch();
         let store = self.store.clone();
         let claim_id = claim.id;
@@ -238,9 +454,27 @@ impl TuiActor {
         self.push(String::new());
     }
 
+    fn render_twitter_results(&mut self, ids: &[String]) {
+        self.push_styled(
+            format!("← [Twitter] {} result(s)", ids.len()),
+            styles::twitter_header(),
+        );
+        if ids.is_empty() {
+            self.push_styled("  (no tweets matched the search)", styles::dim());
+            return;
+        }
+        self.push_styled("  Top results:", styles::label());
+        for t in ids.iter().take(5) {
+            self.push_styled(format!("    • {t}"), styles::value());
+        }
+        if ids.len() > 5 {
+            self.push_styled(format!("    • … {} more", ids.len() - 5), styles::dim());
+        }
+    }
+
     fn render_chat(&mut self, resp: ChatResponse) {
         self.push_styled("← [Nowhere]", styles::llm_header());
-        for line in resp.text.lines() {
+        for line in cite_by_slug(&resp.text).lines() {
             self.push_styled(format!("  {line}"), styles::llm_text());
         }
 
@@ -272,25 +506,182 @@ impl TuiActor {
         self.push_blank();
     }
 
-    fn spinner(&self) -> &'static str {
-        if self.busy > 0 {
-            BRAILLE_FRAMES[self.spin_idx % BRAILLE_FRAMES.len()]
+    fn render_synthesis(&mut self, synthesis: Option<ClaimSynthesis>) {
+        let Some(synthesis) = synthesis else {
+            self.push_styled(
+                "No stored evidence for this claim yet; nothing to synthesize.",
+                styles::dim(),
+            );
+            self.push_blank();
+            return;
+        };
+
+        self.push_styled(
+            format!(
+                "← [Synthesis] from {} artifact(s)",
+                synthesis.artifact_count
+            ),
+            styles::llm_header(),
+        );
+        for line in synthesis.summary.lines() {
+            self.push_styled(format!("  {line}"), styles::llm_text());
+        }
+        if synthesis.red_flags.is_empty() {
+            self.push_styled("  Red flags: (none)", styles::dim());
         } else {
-            " "
+            self.push_styled("  Red flags:", styles::label());
+            for flag in synthesis.red_flags {
+                self.push_styled(format!("    • {flag}"), styles::warning());
+            }
         }
+        self.push_blank();
     }
 
-    fn set_busy(&mut self, on: bool) {
-        if on {
-            self.busy = self.busy.saturating_add(1)
-        } else {
-            self.busy = self.busy.saturating_sub(1)
+    fn render_sources(&mut self, rows: &[PlatformSources]) {
+        if rows.is_empty() {
+            self.push_styled(
+                "No relevant artifacts stored for this claim yet.",
+                styles::dim(),
+            );
+            self.push_blank();
+            return;
         }
+        let total: usize = rows.iter().map(|r| r.artifacts).sum();
+        self.push_styled(
+            format!("Sources: {total} relevant artifact(s)"),
+            styles::label(),
+        );
+        for r in rows {
+            self.push_styled(
+                format!(
+                    "  {:<10} {:>4} artifact(s)   entities: {} strong, {} weak, {} unknown",
+                    r.platform, r.artifacts, r.strong, r.weak, r.unknown
+                ),
+                styles::value(),
+            );
+        }
+        self.push_blank();
+    }
+
+    fn render_last_search(&mut self, search: Option<RecordedSearch>) {
+        let Some(search) = search else {
+            self.push_styled("No search recorded for this claim yet.", styles::dim());
+            self.push_blank();
+            return;
+        };
+        let day = |d: chrono::DateTime<chrono::Utc>| d.format("%Y-%m-%d %H:%M UTC").to_string();
+        self.push_styled("Last search:", styles::label());
+        self.push_styled(format!("  query: {}", search.query), styles::value());
+        self.push_styled(
+            format!("  from:  {}", day(search.date_from)),
+            styles::value(),
+        );
+        self.push_styled(format!("  to:    {}", day(search.date_to)), styles::value());
+        self.push_blank();
+    }
+
+    fn render_readiness(&mut self, report: &ReadinessReport) {
+        if report.is_ready() {
+            let names: Vec<&str> = report.checks.iter().map(|c| c.name.as_str()).collect();
+            self.push_styled(format!("✓ Ready: {}", names.join(", ")), styles::system());
+            self.push_blank();
+            return;
+        }
+        self.push_styled("Readiness:", styles::label());
+        for check in &report.checks {
+            match (&check.detail, check.healthy) {
+                (_, true) => self.push_styled(format!("  ✓ {}", check.name), styles::value()),
+                (Some(detail), false) => {
+                    self.push_styled(format!("  × {}: {detail}", check.name), styles::error())
+                }
+                (None, false) => self.push_styled(format!("  × {}", check.name), styles::error()),
+            }
+        }
+        self.push_blank();
+    }
+
+    fn render_overlap(&mut self, overlap: &EvidenceOverlap) {
+        if overlap.entities.is_empty() && overlap.sources.is_empty() {
+            self.push_styled(
+                "The two claims share no entities or sources.",
+                styles::dim(),
+            );
+            self.push_blank();
+            return;
+        }
+        self.push_styled("Shared by both claims:", styles::label());
+        self.push_styled(
+            format!("  entities ({}):", overlap.entities.len()),
+            styles::label(),
+        );
+        for name in &overlap.entities {
+            self.push_styled(format!("    {name}"), styles::value());
+        }
+        self.push_styled(
+            format!("  sources ({}):", overlap.sources.len()),
+            styles::label(),
+        );
+        for url in &overlap.sources {
+            self.push_styled(format!("    {url}"), styles::value());
+        }
+        self.push_blank();
+    }
+
+    fn render_claims(&mut self, tag: &str, claims: &[ClaimContext]) {
+        if claims.is_empty() {
+            self.push_styled(format!("No claims tagged `{tag}`."), styles::dim());
+            self.push_blank();
+            return;
+        }
+        self.push_styled(format!("Claims tagged `{tag}`:"), styles::label());
+        for c in claims {
+            let id = if self.id_slugs {
+                slug::short(c.id)
+            } else {
+                c.id.to_string()
+            };
+            self.push_styled(format!("  {id}  {}", c.text), styles::value());
+            self.push_styled(format_tags(&c.tags), styles::dim());
+        }
+        self.push_blank();
+    }
+
+    fn spinner(&self) -> &'static str {
+        match (!self.busy.is_empty(), self.animate) {
+            (false, _) => " ",
+            (true, false) => STATIC_SPINNER,
+            (true, true) => BRAILLE_FRAMES[self.spin_idx % BRAILLE_FRAMES.len()],
+        }
+    }
+
+    /// Whether a tick should repaint: on change, or periodically while animating.
+    fn needs_redraw(&self) -> bool {
+        self.dirty || (self.animate && self.last_tick.elapsed() >= self.tick_rate)
+    }
+
+    /// Whether the frame budget since the last redraw has been spent.
+    fn frame_due(&self) -> bool {
+        self.last_tick.elapsed() >= self.frame_budget
+    }
+
+    /// Start an operation; the spinner runs until every started one has ended.
+    fn begin_op(&mut self) -> OpId {
+        self.next_op += 1;
+        let op = OpId(self.next_op);
+        self.busy.insert(op);
         self.dirty = true;
+        op
+    }
+
+    /// End `op`. Ending it again, or after `/cancel` dropped it, changes nothing.
+    fn end_op(&mut self, op: OpId) {
+        if self.busy.remove(&op) {
+            self.dirty = true;
+        }
     }
 
     fn step_spinner(&mut self) {
-        if self.busy > 0 {
+        if self.animate && !self.busy.is_empty() {
             self.spin_idx = (self.spin_idx + 1) % BRAILLE_FRAMES.len();
             self.dirty = true;
         }
@@ -300,11 +691,12 @@ impl TuiActor {
         let snap = ViewSnap::new(
             self.input.clone(),
             self.input_cursor,
-            self.lines.clone(),
+            &self.lines,
             self.scroll,
-            self.busy,
+            self.busy.len(),
             self.spinner(),
-        );
+        )
+        .with_palette(palette_for(&self.input));
 
         view::draw(&mut self.term, &snap)
     }
@@ -313,6 +705,12 @@ impl TuiActor {
         match (key.code, key.modifiers) {
             (KeyCode::Char('c'), KeyModifiers::CONTROL)
             | (KeyCode::Char('q'), KeyModifiers::CONTROL) => return Some(TuiMsg::Shutdown),
+            (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                // Open the palette: an empty `/` lists every command.
+                self.input = "/".into();
+                self.input_cursor = 1;
+                self.dirty = true;
+            }
             (KeyCode::PageUp, _) => {
                 self.scroll = self.scroll.saturating_add(5);
                 self.dirty = true;
@@ -373,7 +771,7 @@ impl TuiActor {
         None
     }
 
-    fn route_submit(&mut self, line: String, me: Addr<TuiActor>) {
+    fn route_submit(&mut self, line: String, me: Addr<Self>) {
         let s = line.trim().to_string();
         if s.is_empty() {
             return;
@@ -391,22 +789,23 @@ impl TuiActor {
                 self.push_styled(format!("  {line}"), styles::user_text());
             }
             self.push_blank();
-            self.set_busy(true);
+            let op = self.begin_op();
             let (tx, rx) = oneshot::channel::<ChatResponse>();
             let _ = self.chat_llm.try_send(ChatCmd {
                 user_text: s.clone(),
-                k: 25,
+                k: self.chat_k,
                 claim,
                 reply: tx,
+                language: self.answer_language.clone(),
             });
             let me2 = me.clone();
             tokio::spawn(async move {
                 match rx.await {
                     Ok(resp) => {
-                        let _ = me2.send(TuiMsg::ChatDone(resp)).await;
+                        let _ = me2.send(TuiMsg::ChatDone(op, resp)).await;
                     }
                     Err(e) => {
-                        let _ = me2.send(TuiMsg::OpError(format!("chat: {e}"))).await;
+                        let _ = me2.send(TuiMsg::OpFailed(op, format!("chat: {e}"))).await;
                     }
                 }
             });
@@ -419,11 +818,11 @@ impl TuiActor {
         );
     }
 
-    fn check_for_artifacts(&mut self, claim: &ClaimContext, me: Addr<TuiActor>, announce: bool) {
+    fn check_for_artifacts(&mut self, claim: &ClaimContext, me: Addr<Self>, announce: bool) {
         if announce {
             self.push_styled("collecting artifacts", styles::system());
         }
-        self.set_busy(true);
+        let op = self.begin_op();
 
         let store = self.store.clone();
         let me2 = me;
@@ -446,7 +845,28 @@ impl TuiActor {
                 Err(_) => Err("store mailbox dropped".into()),
             };
 
-            let _ = me2.send(TuiMsg::ArtifactsCheckDone(result)).await;
+            let _ = me2.send(TuiMsg::ArtifactsCheckDone(op, result)).await;
+        });
+    }
+
+    /// Ask the LLM for the claim's search query; the reply continues in `SearchQueryBuilt`.
+    /// `fresh` skips any query cached for the same claim text.
+    fn start_search(&mut self, claim: ClaimContext, fresh: bool, me: Addr<Self>) {
+        let op = self.begin_op();
+        self.searching = true;
+        let (tx, rx) = oneshot::channel::<BuiltSearchQuery>();
+        let _ = self.llm.try_send(LlmMsg::BuildSearchQuery {
+            claim,
+            fresh,
+            reply: tx,
+        });
+
+        tokio::spawn(async move {
+            let msg = match rx.await {
+                Ok(response) => TuiMsg::SearchQueryBuilt(op, response),
+                Err(e) => TuiMsg::SearchQueryFailed(op, format!("llm: {e}")),
+            };
+            let _ = me.send(msg).await;
         });
     }
 
@@ -454,23 +874,326 @@ impl TuiActor {
         self.claim.as_ref().map(|c| c.text.clone())
     }
 
-    fn handle_command(&mut self, cmd: Command, me: Addr<TuiActor>) {
+    fn handle_command(&mut self, cmd: Command, me: Addr<Self>) {
         match cmd {
             Command::Quit => {
                 let _ = me.try_send(TuiMsg::Shutdown);
             }
             Command::Help => {
                 self.push_styled("Commands:", styles::label());
-                self.push_styled("  /claim <text>   set the active claim", styles::value());
-                self.push_styled("  /claim          show the active claim", styles::value());
-                self.push_styled("  /claim -        clear the active claim", styles::value());
-                self.push_styled("  /quit           exit", styles::value());
+                for cmd in COMMANDS {
+                    self.push_styled(
+                        format!("  {:<15} {}", cmd.usage, cmd.summary),
+                        styles::value(),
+                    );
+                }
                 self.push_blank();
             }
+            Command::Palette(filter) => {
+                let filter = filter.unwrap_or_default();
+                let matches = filter_commands(&filter);
+                if matches.is_empty() {
+                    self.push_styled(format!("No command matches `{filter}`."), styles::dim());
+                } else {
+                    for cmd in matches {
+                        self.push_styled(
+                            format!("  {:<15} {}", cmd.usage, cmd.summary),
+                            styles::value(),
+                        );
+                    }
+                }
+                self.push_blank();
+            }
+            Command::Cancel => {
+                let running = self
+                    .claim_cancel
+                    .as_ref()
+                    .is_some_and(|token| !token.is_cancelled());
+                if running {
+                    self.cancel_claim_work();
+                    self.cancel_artifact_watch();
+                    // Whatever the dropped work still reports ends nothing.
+                    self.busy.clear();
+                    self.dirty = true;
+                    self.push_styled(
+                        "✓ Cancelled background work for the active claim.",
+                        styles::system(),
+                    );
+                } else {
+                    self.push_styled("Nothing to cancel.", styles::dim());
+                }
+                self.push_blank();
+            }
+            Command::Synthesize => {
+                let Some(claim) = self.claim.clone() else {
+                    self.push_styled(
+                        "× No claim selected. Use `/claim <text>` first.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+
+                self.push_styled("synthesizing evidence", styles::system());
+                let op = self.begin_op();
+                let store = self.store.clone();
+                let analysis = self.analysis.clone();
+                tokio::spawn(async move {
+                    // FIXME: make the synthesis depth configurable alongside the chat retrieval depth.
+                    let msg = match synthesize_claim(&store, &analysis, &claim, 20).await {
+                        Ok(synthesis) => TuiMsg::SynthesisDone(op, synthesis),
+                        Err(e) => TuiMsg::OpFailed(op, format!("synthesis: {e}")),
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Sources => {
+                let Some(claim) = self.claim.as_ref().map(|c| c.id) else {
+                    self.push_styled(
+                        "× No claim selected. Use `/claim <text>` first.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = oneshot::channel();
+                    let msg = match store.send(StoreMsg::SourceBreakdown { claim, reply }).await {
+                        Err(_) => TuiMsg::OpFailed(op, "sources: store mailbox dropped".into()),
+                        Ok(()) => match rx.await {
+                            Ok(Ok(rows)) => TuiMsg::SourcesDone(op, rows),
+                            Ok(Err(e)) => TuiMsg::OpFailed(op, format!("sources: {e}")),
+                            Err(e) => TuiMsg::OpFailed(op, format!("sources: {e}")),
+                        },
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Query => {
+                let Some(claim) = self.claim.as_ref().map(|c| c.id) else {
+                    self.push_styled(
+                        "× No claim selected. Use `/claim <text>` first.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = oneshot::channel();
+                    let msg = match store.send(StoreMsg::LastSearch { claim, reply }).await {
+                        Err(_) => TuiMsg::OpFailed(op, "query: store mailbox dropped".into()),
+                        Ok(()) => match rx.await {
+                            Ok(Ok(search)) => TuiMsg::LastSearch(op, search),
+                            Ok(Err(e)) => TuiMsg::OpFailed(op, format!("query: {e}")),
+                            Err(e) => TuiMsg::OpFailed(op, format!("query: {e}")),
+                        },
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Tag(tag) => {
+                let Some(claim) = self.claim.as_ref().map(|c| c.id) else {
+                    self.push_styled(
+                        "× No claim selected. Use `/claim <text>` first.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+                let Some(tag) = tag.filter(|t| !t.contains(char::is_whitespace)) else {
+                    self.push_styled("× Usage: `/tag <tag>` (one word).", styles::error());
+                    self.push_blank();
+                    return;
+                };
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = oneshot::channel();
+                    let msg = match store.send(StoreMsg::TagClaim { claim, tag, reply }).await {
+                        Err(_) => TuiMsg::OpFailed(op, "tag: store mailbox dropped".into()),
+                        Ok(()) => match rx.await {
+                            Ok(Ok(tags)) => TuiMsg::Tagged(op, claim, tags),
+                            Ok(Err(e)) => TuiMsg::OpFailed(op, format!("tag: {e}")),
+                            Err(e) => TuiMsg::OpFailed(op, format!("tag: {e}")),
+                        },
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Claims(tag) => {
+                let Some(tag) = tag else {
+                    self.push_styled("× Usage: `/claims <tag>`.", styles::error());
+                    self.push_blank();
+                    return;
+                };
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = oneshot::channel();
+                    let msg = StoreMsg::ListClaimsByTag {
+                        tag: tag.clone(),
+                        limit: 50,
+                        reply,
+                    };
+                    let msg = match store.send(msg).await {
+                        Err(_) => TuiMsg::OpFailed(op, "claims: store mailbox dropped".into()),
+                        Ok(()) => match rx.await {
+                            Ok(Ok(claims)) => TuiMsg::ClaimsListed(op, tag, claims),
+                            Ok(Err(e)) => TuiMsg::OpFailed(op, format!("claims: {e}")),
+                            Err(e) => TuiMsg::OpFailed(op, format!("claims: {e}")),
+                        },
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Diff(ids) => {
+                let Some((a, b)) = ids else {
+                    self.push_styled(
+                        "× Usage: `/diff <id> <id>` with two claim ids or id prefixes (see `/claims`).",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let overlap = async {
+                        let a = resolve_claim_id(&store, &a).await?;
+                        let b = resolve_claim_id(&store, &b).await?;
+                        if a == b {
+                            return Err("both ids name the same claim".to_string());
+                        }
+                        let (reply, rx) = oneshot::channel();
+                        store
+                            .send(StoreMsg::ClaimOverlap { a, b, reply })
+                            .await
+                            .map_err(|_| "store mailbox dropped".to_string())?;
+                        rx.await
+                            .map_err(|e| e.to_string())?
+                            .map_err(|e| e.to_string())
+                    };
+                    let msg = match overlap.await {
+                        Ok(overlap) => TuiMsg::OverlapFound(op, overlap),
+                        Err(e) => TuiMsg::OpFailed(op, format!("diff: {e}")),
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Review(review) => {
+                let Some((id, status)) = review else {
+                    self.push_styled(
+                        "× Usage: `/review <id> confirm|dismiss` with an artifact id from a citation; `reset` undoes it.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+                if Uuid::parse_str(&id).is_err()
+                    && slug::prefix_range(&id).is_none()
+                    && slug::uuid_prefix_range(&id).is_none()
+                {
+                    self.push_styled(
+                        format!("× `{id}` is not an artifact id (see the `[A:<id>]` citations)."),
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                }
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let reviewed = async {
+                        let artifact = resolve_artifact_id(&store, &id).await?;
+                        let (reply, rx) = oneshot::channel();
+                        let msg = StoreMsg::SetReview {
+                            artifact,
+                            status,
+                            reply,
+                        };
+                        store
+                            .send(msg)
+                            .await
+                            .map_err(|_| "store mailbox dropped".to_string())?;
+                        rx.await
+                            .map_err(|e| e.to_string())?
+                            .map_err(|e| e.to_string())?;
+                        Ok::<_, String>(artifact)
+                    };
+                    let msg = match reviewed.await {
+                        Ok(artifact) => TuiMsg::Reviewed(op, artifact, status),
+                        Err(e) => TuiMsg::OpFailed(op, format!("review: {e}")),
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Bundle(path, captures) => {
+                let Some(claim) = self.claim.clone() else {
+                    self.push_styled(
+                        "× No claim selected. Use `/claim <text>` first.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+                let Some(path) = path.map(PathBuf::from) else {
+                    self.push_styled(
+                        "× Usage: `/bundle <path>`, with `--captures` to embed archived pages.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let written = async {
+                        let bundle = bundle::assemble(&store, claim, captures).await?;
+                        bundle::write(&bundle, &path).await?;
+                        anyhow::Ok(bundle.artifacts.len())
+                    };
+                    let msg = match written.await {
+                        Ok(artifacts) => TuiMsg::BundleWritten(op, path, artifacts),
+                        Err(e) => TuiMsg::OpFailed(op, format!("bundle: {e:#}")),
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
+            Command::Reindex => {
+                self.push_styled("rebuilding search index", styles::system());
+                let op = self.begin_op();
+                let store = self.store.clone();
+                tokio::spawn(async move {
+                    let (reply, rx) = oneshot::channel();
+                    let msg = match store.send(StoreMsg::RebuildFts { reply }).await {
+                        Err(_) => TuiMsg::OpFailed(op, "reindex: store mailbox dropped".into()),
+                        Ok(()) => match rx.await {
+                            Ok(Ok(rows)) => TuiMsg::Reindexed(op, rows),
+                            Ok(Err(e)) => TuiMsg::OpFailed(op, format!("reindex: {e}")),
+                            Err(e) => TuiMsg::OpFailed(op, format!("reindex: {e}")),
+                        },
+                    };
+                    let _ = me.send(msg).await;
+                });
+            }
             Command::Claim(None) => {
                 if let Some(text) = self.active_claim_text() {
                     self.push_styled("Active claim:", styles::label());
                     self.push_styled(format!("  {text}"), styles::value());
+                    if let Some(tags) = self.claim.as_ref().map(|c| format_tags(&c.tags)) {
+                        self.push_styled(tags, styles::dim());
+                    }
                 } else {
                     self.push_styled("No active claim. Use `/claim <text>`.", styles::dim());
                 }
@@ -484,11 +1207,35 @@ impl TuiActor {
                     return;
                 }
 
+                let text = match validate_claim(&text, self.max_claim_chars) {
+                    ClaimText::Accepted(text) => text,
+                    ClaimText::Truncated {
+                        text,
+                        original_chars,
+                    } => {
+                        self.push_styled(
+                            format!(
+                                "⚠ Claim truncated to {} of {original_chars} characters.",
+                                self.max_claim_chars
+                            ),
+                            styles::warning(),
+                        );
+                        text
+                    }
+                    ClaimText::Empty => {
+                        self.push_styled("× Claim text is empty.", styles::error());
+                        self.push_blank();
+                        return;
+                    }
+                };
+
                 let claim = ClaimContext {
                     id: Uuid::new_v4(),
                     text: text.clone(),
+                    tags: Vec::new(),
                 };
                 self.set_claim(claim.clone());
+                self.watch_claim_budget(claim.id, me.clone());
 
                 let _ = self.store.try_send(StoreMsg::InsertClaim(claim.clone()));
                 self.push_styled("→ [Claim]", styles::user_header());
@@ -498,24 +1245,99 @@ impl TuiActor {
                 self.check_for_artifacts(&claim, me.clone(), true);
                 self.subscribe_artifact_updates(&claim, me.clone());
 
-                self.set_busy(true);
-                let (tx, rx) = oneshot::channel::<BuiltSearchQuery>();
-                let _ = self.llm.try_send(LlmMsg::BuildSearchQuery {
-                    claim: claim.clone(),
-                    reply: tx,
-                });
+                self.start_search(claim, false, me);
+            }
+            Command::Research(days, fresh) => {
+                let Some(claim) = self.claim.clone() else {
+                    self.push_styled(
+                        "× No claim selected. Use `/claim <text>` first.",
+                        styles::error(),
+                    );
+                    self.push_blank();
+                    return;
+                };
+                let window = match days.as_deref().map(str::parse::<u32>) {
+                    None => None,
+                    Some(Ok(days)) if days > 0 => Some(chrono::Duration::days(i64::from(days))),
+                    Some(_) => {
+                        self.push_styled("× Usage: `/research [days] [--fresh]`.", styles::error());
+                        self.push_blank();
+                        return;
+                    }
+                };
+                if self.searching {
+                    self.push_styled("A search for this claim is already running.", styles::dim());
+                    self.push_blank();
+                    return;
+                }
 
-                let me2 = me.clone();
-                tokio::spawn(async move {
-                    match rx.await {
-                        Ok(response) => {
-                            let _ = me2.send(TuiMsg::SearchQueryBuilt(response)).await;
-                        }
-                        Err(e) => {
-                            let _ = me2.send(TuiMsg::OpError(format!("llm: {e}"))).await;
-                        }
+                // After `/cancel` or a spent budget the old token is dead; start a fresh one.
+                if self.live_claim_token(claim.id).is_none() {
+                    let cancel = CancellationToken::new();
+                    self.claim_budget =
+                        Some(ClaimBudget::new(cancel.clone(), self.claim_budget_limit));
+                    self.claim_cancel = Some(cancel);
+                    self.watch_claim_budget(claim.id, me.clone());
+                }
+
+                self.search_window = window;
+                self.push_styled(
+                    if fresh {
+                        "re-running the search with a new query"
+                    } else {
+                        "re-running the search"
+                    },
+                    styles::system(),
+                );
+                self.start_search(claim, fresh, me);
+            }
+            Command::K(None) => {
+                self.push_styled(
+                    format!("Chat retrieves up to {} artifact(s).", self.chat_k),
+                    styles::system(),
+                );
+                self.push_blank();
+            }
+            Command::K(Some(n)) => {
+                match n.parse::<i64>() {
+                    Ok(k) if (1..=MAX_CHAT_K).contains(&k) => {
+                        self.chat_k = k;
+                        self.push_styled(
+                            format!("✓ Chat now retrieves up to {k} artifact(s)."),
+                            styles::system(),
+                        );
                     }
-                });
+                    _ => self.push_styled(
+                        format!("× Usage: `/k [n]` with n from 1 to {MAX_CHAT_K}."),
+                        styles::error(),
+                    ),
+                }
+                self.push_blank();
+            }
+            Command::Lang(None) => {
+                let current = match &self.answer_language {
+                    Some(language) => format!("Chat answers in {language}."),
+                    None => "Chat answers in the configured language, or the model's choice."
+                        .to_string(),
+                };
+                self.push_styled(current, styles::system());
+                self.push_blank();
+            }
+            Command::Lang(Some(language)) => {
+                if language == "-" {
+                    self.answer_language = None;
+                    self.push_styled(
+                        "✓ Chat answers in the configured language again.",
+                        styles::system(),
+                    );
+                } else {
+                    self.push_styled(
+                        format!("✓ Chat now answers in {language}."),
+                        styles::system(),
+                    );
+                    self.answer_language = Some(language);
+                }
+                self.push_blank();
             }
             Command::Unknown(s) => {
                 self.push_styled(format!("× Unknown command: {s}"), styles::error());
@@ -526,8 +1348,25 @@ impl TuiActor {
     }
 }
 
+/// Commands to suggest while `input` is still a bare `/verb`; none once arguments start.
+fn palette_for(input: &str) -> Vec<&'static CommandHelp> {
+    if !input.starts_with('/') || input.contains(char::is_whitespace) {
+        return Vec::new();
+    }
+    filter_commands(input)
+}
+
+/// `  tags: a, b`, or `  no tags`, indented under a claim line.
+fn format_tags(tags: &[String]) -> String {
+    if tags.is_empty() {
+        "  no tags".to_string()
+    } else {
+        format!("  tags: {}", tags.join(", "))
+    }
+}
+
 #[async_trait]
-impl Actor for TuiActor {
+impl<B: Backend + Send + 'static> Actor for TuiActor<B> {
     type Msg = TuiMsg;
 
     async fn handle(&mut self, msg: Self::Msg, ctx: &mut Context<Self>) -> Result<()> {
@@ -538,51 +1377,212 @@ impl Actor for TuiActor {
                 {
                     let _ = ctx.addr().try_send(next);
                 }
+                if let CtEvent::Resize(..) = ev {
+                    self.dirty = true;
+                }
             }
             TuiMsg::Submit(line) => self.route_submit(line, ctx.addr()),
-            TuiMsg::SearchQueryBuilt(built_search_query) => {
+            TuiMsg::SearchQueryBuilt(op, mut built_search_query) => {
+                // The claim may have been cancelled or replaced while the query was built.
+                let Some(cancel) = self.live_claim_token(built_search_query.claim.id) else {
+                    self.end_op(op);
+                    return Ok(());
+                };
+                if let Some(window) = self.search_window.take() {
+                    built_search_query.date_to = chrono::Utc::now();
+                    built_search_query.date_from = built_search_query.date_to - window;
+                }
+                // Waits for room in the store's mailbox rather than losing the record.
+                let store = self.store.clone();
+                let record = StoreMsg::RecordSearch {
+                    claim: built_search_query.claim.id,
+                    search: RecordedSearch {
+                        query: built_search_query.query.clone(),
+                        date_from: built_search_query.date_from,
+                        date_to: built_search_query.date_to,
+                    },
+                };
+                tokio::spawn(async move {
+                    let _ = store.send(record).await;
+                });
+                let sources = if self.web_search.is_some() {
+                    "Twitter and the web"
+                } else {
+                    "Twitter"
+                };
+                let searching = match &built_search_query.rationale {
+                    Some(why) => format!(
+                        "Searching {sources} for: {} — because {why}",
+                        built_search_query.query
+                    ),
+                    None => format!("Searching {sources} for: {}", built_search_query.query),
+                };
+                self.push_styled(searching, styles::system());
+                let (report, mut outcome) = mpsc::channel(1);
+                let me = ctx.addr();
+                let cancelled = cancel.clone();
+                tokio::spawn(async move {
+                    let msg = match outcome.recv().await {
+                        Some(SearchOutcome::Done(ids)) => TuiMsg::TwitterDone(op, ids),
+                        Some(SearchOutcome::Failed(reason)) => TuiMsg::TwitterFailed(op, reason),
+                        Some(SearchOutcome::CapReached(ids)) => {
+                            TuiMsg::CollectionCapReached(op, ids)
+                        }
+                        Some(SearchOutcome::Partial(ids)) => TuiMsg::SearchPartial(op, ids),
+                        // Cancelled searches report nothing; `/cancel` already ended the op.
+                        None if cancelled.is_cancelled() => return,
+                        None => TuiMsg::TwitterFailed(
+                            op,
+                            "the search worker stopped before reporting".into(),
+                        ),
+                    };
+                    let _ = me.send(msg).await;
+                });
+                let search = SearchCmd {
+                    query: built_search_query.query,
+                    date_from: built_search_query.date_from,
+                    date_to: built_search_query.date_to,
+                    claim: built_search_query.claim,
+                    cancel,
+                    report: None,
+                    budget: self.claim_budget.clone(),
+                };
+                if let Some(web) = &self.web_search {
+                    let _ = web.send(search.clone()).await;
+                }
                 let _ = self
                     .twitter
                     .send(SearchCmd {
-                        query: built_search_query.query,
-                        date_from: built_search_query.date_from,
-                        date_to: built_search_query.date_to,
-                        claim: built_search_query.claim,
+                        report: Some(report),
+                        ..search
                     })
                     .await;
             }
-            TuiMsg::LlmDone(text) => {
+            TuiMsg::SearchQueryFailed(op, e) => {
+                self.searching = false;
+                self.search_window = None;
+                self.push_styled(format!("× Error: {e}"), styles::error());
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::LlmDone(op, text) => {
                 self.push_styled("← [Nowhere]", styles::llm_header());
                 for line in text.lines() {
                     self.push_styled(format!("  {line}"), styles::llm_text());
                 }
                 self.push_blank();
-                self.set_busy(false);
+                self.end_op(op);
             }
-            TuiMsg::ChatDone(resp) => {
+            TuiMsg::ChatDone(op, resp) => {
                 self.render_chat(resp);
-                self.set_busy(false);
+                self.end_op(op);
+            }
+            TuiMsg::SynthesisDone(op, synthesis) => {
+                self.render_synthesis(synthesis);
+                self.end_op(op);
             }
-            TuiMsg::TwitterDone(v) => {
+            TuiMsg::SourcesDone(op, rows) => {
+                self.render_sources(&rows);
+                self.end_op(op);
+            }
+            TuiMsg::LastSearch(op, search) => {
+                self.render_last_search(search);
+                self.end_op(op);
+            }
+            TuiMsg::Tagged(op, claim_id, tags) => {
                 self.push_styled(
-                    format!("← [Twitter] {} result(s)", v.len()),
-                    styles::twitter_header(),
+                    format!("✓ Tagged claim. {}", format_tags(&tags).trim_start()),
+                    styles::system(),
                 );
-                if v.is_empty() {
-                    self.push_styled("  (no tweets yet)", styles::dim());
-                } else {
-                    self.push_styled("  Top results:", styles::label());
-                    for t in v.clone().into_iter().take(5) {
-                        self.push_styled(format!("    • {t}"), styles::value());
-                    }
-                    if v.len() > 5 {
-                        self.push_styled(format!("    • … {} more", v.len() - 5), styles::dim());
-                    }
+                self.push_blank();
+                if let Some(claim) = self.claim.as_mut().filter(|c| c.id == claim_id) {
+                    claim.tags = tags;
                 }
+                self.end_op(op);
+            }
+            TuiMsg::Reviewed(op, artifact, status) => {
+                let verdict = match status {
+                    ReviewStatus::Confirmed => "confirmed; chat will prefer it",
+                    ReviewStatus::Dismissed => "dismissed; chat will leave it out",
+                    ReviewStatus::Pending => "back to unreviewed",
+                };
+                self.push_styled(
+                    format!("✓ Artifact {artifact} {verdict}."),
+                    styles::system(),
+                );
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::BundleWritten(op, path, artifacts) => {
+                self.push_styled(
+                    format!(
+                        "✓ Bundle written to {} ({artifacts} artifact(s)).",
+                        path.display()
+                    ),
+                    styles::system(),
+                );
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::Reindexed(op, rows) => {
+                self.push_styled(
+                    format!("✓ Search index rebuilt: {rows} artifact(s) indexed."),
+                    styles::system(),
+                );
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::ClaimsListed(op, tag, claims) => {
+                self.render_claims(&tag, &claims);
+                self.end_op(op);
+            }
+            TuiMsg::OverlapFound(op, overlap) => {
+                self.render_overlap(&overlap);
+                self.end_op(op);
+            }
+            TuiMsg::TwitterDone(op, v) => {
+                self.searching = false;
+                self.render_twitter_results(&v);
                 self.push_blank();
-                self.set_busy(false);
+                self.end_op(op);
             }
-            TuiMsg::ArtifactsCheckDone(result) => {
+            TuiMsg::CollectionCapReached(op, v) => {
+                self.searching = false;
+                self.render_twitter_results(&v);
+                self.push_styled(
+                    "⚠ Collection cap reached; no more artifacts will be gathered for this claim.",
+                    styles::warning(),
+                );
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::SearchPartial(op, v) => {
+                self.searching = false;
+                self.render_twitter_results(&v);
+                self.push_styled(
+                    "⚠ Search time budget ran out; these are the results of the pages fetched so far.",
+                    styles::warning(),
+                );
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::TwitterFailed(op, reason) => {
+                self.searching = false;
+                self.push_styled("× [Twitter] search failed", styles::error());
+                self.push_styled(format!("  {reason}"), styles::error());
+                self.push_blank();
+                self.end_op(op);
+            }
+            TuiMsg::BudgetReached(claim_id) => {
+                if self.claim.as_ref().is_some_and(|c| c.id == claim_id) {
+                    self.push_styled("⚠ Investigation time budget reached.", styles::warning());
+                    self.push_blank();
+                    self.searching = false;
+                    self.busy.clear();
+                    self.dirty = true;
+                }
+            }
+            TuiMsg::ArtifactsCheckDone(op, result) => {
                 match result {
                     Ok(true) => {
                         self.push_styled(
@@ -593,11 +1593,11 @@ impl Actor for TuiActor {
                     }
                     Ok(false) => {
                         self.push_styled("No artifacts found yet.", styles::dim());
-                        if let Some(claim) = self.claim.clone() {
-                            if !self.artifact_watch_armed {
-                                let addr = ctx.addr();
-                                self.subscribe_artifact_updates(&claim, addr);
-                            }
+                        if let Some(claim) = self.claim.clone()
+                            && !self.artifact_watch_armed
+                        {
+                            let addr = ctx.addr();
+                            self.subscribe_artifact_updates(&claim, addr);
                         }
                     }
                     Err(e) => {
@@ -605,35 +1605,40 @@ impl Actor for TuiActor {
                             format!("× Error checking artifacts: {e}"),
                             styles::error(),
                         );
-                        if let Some(claim) = self.claim.clone() {
-                            if !self.artifact_watch_armed {
-                                let addr = ctx.addr();
-                                self.subscribe_artifact_updates(&claim, addr);
-                            }
+                        if let Some(claim) = self.claim.clone()
+                            && !self.artifact_watch_armed
+                        {
+                            let addr = ctx.addr();
+                            self.subscribe_artifact_updates(&claim, addr);
                         }
                     }
                 }
                 self.push_blank();
-                self.set_busy(false);
+                self.end_op(op);
             }
             TuiMsg::ArtifactsUpdated(claim_id) => {
-                if let Some(claim) = self.claim.clone() {
-                    if claim.id == claim_id {
-                        self.artifact_watch = None;
-                        self.artifact_watch_armed = false;
-                        let addr = ctx.addr();
-                        self.check_for_artifacts(&claim, addr.clone(), false);
-                    }
+                if let Some(claim) = self.claim.clone()
+                    && claim.id == claim_id
+                {
+                    self.artifact_watch = None;
+                    self.artifact_watch_armed = false;
+                    let addr = ctx.addr();
+                    self.check_for_artifacts(&claim, addr.clone(), false);
                 }
             }
+            TuiMsg::Readiness(report) => self.render_readiness(&report),
+            TuiMsg::OpFailed(op, e) => {
+                self.push_styled(format!("× Error: {e}"), styles::error());
+                self.push_blank();
+                self.end_op(op);
+            }
             TuiMsg::OpError(e) => {
                 self.push_styled(format!("× Error: {e}"), styles::error());
                 self.push_blank();
-                self.set_busy(false);
             }
             TuiMsg::Tick => {
                 self.step_spinner();
-                if self.dirty || self.last_tick.elapsed() >= self.tick_rate {
+                if self.needs_redraw() && self.frame_due() {
                     self.draw()?;
                     self.last_tick = Instant::now();
                     self.dirty = false;
@@ -653,8 +1658,844 @@ impl Actor for TuiActor {
                 self.shutdown.signal();
                 ctx.stop();
             }
+            #[cfg(test)]
+            TuiMsg::Transcript(reply) => {
+                let _ = reply.send(self.lines.iter().map(|l| l.text.clone()).collect());
+            }
+            #[cfg(test)]
+            TuiMsg::Busy(reply) => {
+                let _ = reply.send(self.busy.len());
+            }
         }
 
         Ok(())
     }
 }
+
+/// The claim `id` names: a full UUID, or the slug (or a slug prefix) of one stored claim.
+/// Input that could also be the start of a UUID (`3f2a9c1e`, `3f2a9c1e-4b`) matches claims
+/// either way.
+async fn resolve_claim_id(store: &Addr<StoreActor>, id: &str) -> std::result::Result<Uuid, String> {
+    if let Ok(id) = Uuid::parse_str(id) {
+        return Ok(id);
+    }
+    let mut claims = list_claims(store, |reply| StoreMsg::ListClaimsBySlug {
+        slug: id.to_string(),
+        limit: 5,
+        reply,
+    })
+    .await?;
+    if slug::uuid_prefix_range(id).is_some() {
+        let by_uuid = list_claims(store, |reply| StoreMsg::ListClaimsByUuidPrefix {
+            prefix: id.to_string(),
+            limit: 5,
+            reply,
+        })
+        .await?;
+        claims.extend(by_uuid);
+        claims.sort_by_key(|c| c.id);
+        claims.dedup_by_key(|c| c.id);
+    }
+    match &claims[..] {
+        [] => Err(format!("no claim id starts with `{id}`")),
+        [claim] => Ok(claim.id),
+        several => {
+            let slugs: Vec<String> = several.iter().map(|c| slug::encode(c.id)).collect();
+            Err(format!(
+                "`{id}` matches several claims ({}); type more of the id",
+                slugs.join(", ")
+            ))
+        }
+    }
+}
+
+/// The artifact `id` names: a full UUID, or the slug (or a slug or UUID prefix) of one
+/// stored artifact, as chat citations show them.
+async fn resolve_artifact_id(
+    store: &Addr<StoreActor>,
+    id: &str,
+) -> std::result::Result<Uuid, String> {
+    if let Ok(id) = Uuid::parse_str(id) {
+        return Ok(id);
+    }
+    let (reply, rx) = oneshot::channel();
+    let msg = StoreMsg::ListArtifactIdsByPrefix {
+        prefix: id.to_string(),
+        limit: 5,
+        reply,
+    };
+    store
+        .send(msg)
+        .await
+        .map_err(|_| "store mailbox dropped".to_string())?;
+    let ids = rx
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    match &ids[..] {
+        [] => Err(format!("no artifact id starts with `{id}`")),
+        [artifact] => Ok(*artifact),
+        several => {
+            let slugs: Vec<String> = several.iter().map(|a| slug::encode(*a)).collect();
+            Err(format!(
+                "`{id}` matches several artifacts ({}); type more of the id",
+                slugs.join(", ")
+            ))
+        }
+    }
+}
+
+/// `text` with each `[A:<uuid>]` citation shortened to the artifact's short slug, which
+/// `/review` takes as well.
+fn cite_by_slug(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[A:") {
+        let (cited, after) = rest.split_at(start + 3);
+        out.push_str(cited);
+        rest = after;
+        if let Some(end) = rest.find(']')
+            && let Ok(id) = Uuid::parse_str(&rest[..end])
+        {
+            out.push_str(&slug::short(id));
+            rest = &rest[end..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+async fn list_claims(
+    store: &Addr<StoreActor>,
+    msg: impl FnOnce(oneshot::Sender<Result<Vec<ClaimContext>>>) -> StoreMsg,
+) -> std::result::Result<Vec<ClaimContext>, String> {
+    let (reply, rx) = oneshot::channel();
+    store
+        .send(msg(reply))
+        .await
+        .map_err(|_| "store mailbox dropped".to_string())?;
+    rx.await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use nowhere_actors::actor::{probe, spawn_actor, spawn_actor_reserved};
+    use nowhere_actors::system::ActorSystem;
+    use ratatui::backend::TestBackend;
+
+    /// A TUI on an 80x24 `TestBackend`. Its dependencies are unstarted mailboxes, so
+    /// anything it sends them is dropped.
+    pub(crate) fn test_tui(shutdown: ShutdownHandle) -> TuiActor<TestBackend> {
+        TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            spawn_actor_reserved("store", 8).addr(),
+            shutdown,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn without_animation_the_spinner_is_ascii_and_idle_ticks_skip_redraws() {
+        let system = ActorSystem::new();
+        let mut tui = test_tui(system.shutdown_handle())
+            .with_animation(false)
+            .with_tick_rate(Duration::ZERO);
+
+        let op = tui.begin_op();
+        tui.step_spinner();
+        assert_eq!(tui.spinner(), "...");
+        tui.end_op(op);
+
+        tui.dirty = false;
+        assert!(!tui.needs_redraw(), "idle tick must not force a redraw");
+        tui.dirty = true;
+        assert!(tui.needs_redraw());
+
+        let mut animated = test_tui(system.shutdown_handle()).with_tick_rate(Duration::ZERO);
+        animated.dirty = false;
+        assert!(animated.needs_redraw(), "animation keeps periodic redraws");
+        animated.begin_op();
+        assert!(BRAILLE_FRAMES.contains(&animated.spinner()));
+    }
+
+    #[test]
+    fn changes_within_the_frame_budget_wait_for_a_later_tick() {
+        let system = ActorSystem::new();
+        let mut tui = test_tui(system.shutdown_handle()).with_max_fps(1);
+
+        tui.last_tick = Instant::now();
+        tui.dirty = true;
+        assert!(
+            tui.needs_redraw() && !tui.frame_due(),
+            "budget not spent yet"
+        );
+        tui.last_tick = Instant::now() - Duration::from_secs(1);
+        assert!(tui.frame_due());
+    }
+
+    async fn transcript(tui: &Addr<TuiActor<TestBackend>>) -> Vec<String> {
+        let (tx, rx) = oneshot::channel();
+        tui.send(TuiMsg::Transcript(tx)).await.ok().unwrap();
+        rx.await.unwrap()
+    }
+
+    /// Answer the next `BuildSearchQuery` and return the `SearchCmd` it leads to.
+    async fn next_search(
+        llm: &mut mpsc::Receiver<LlmMsg>,
+        twitter: &mut mpsc::Receiver<SearchCmd>,
+    ) -> SearchCmd {
+        let Some(LlmMsg::BuildSearchQuery { claim, reply, .. }) = llm.recv().await else {
+            panic!("expected BuildSearchQuery");
+        };
+        let now = chrono::Utc::now();
+        let _ = reply.send(BuiltSearchQuery {
+            query: "turnout".into(),
+            date_from: now - chrono::Duration::days(30),
+            date_to: now,
+            rationale: None,
+            claim,
+        });
+        twitter.recv().await.expect("search dispatched")
+    }
+
+    #[tokio::test]
+    async fn search_is_recorded_even_when_the_store_mailbox_is_full() {
+        let system = ActorSystem::new();
+        let (llm, mut llm_rx) = probe::<LlmActor>(8);
+        let (twitter, mut twitter_rx) = probe::<TwitterSearchActor>(8);
+        // Room for one message, which the claim's own insert takes.
+        let (store, mut store_rx) = probe::<StoreActor>(1);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            llm,
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            twitter,
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        tui.send(TuiMsg::Submit("/claim turnout was 80%".into()))
+            .await
+            .ok()
+            .unwrap();
+        let search = next_search(&mut llm_rx, &mut twitter_rx).await;
+
+        let recorded = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                match store_rx.recv().await.expect("store mailbox open") {
+                    StoreMsg::RecordSearch { claim, search } => return (claim, search),
+                    _ => continue,
+                }
+            }
+        })
+        .await
+        .expect("search recorded");
+        assert_eq!(recorded.0, search.claim.id);
+        assert_eq!(recorded.1.query, "turnout");
+    }
+
+    #[tokio::test]
+    async fn searches_also_go_to_the_web_worker_when_one_is_wired() {
+        let system = ActorSystem::new();
+        let (llm, mut llm_rx) = probe::<LlmActor>(8);
+        let (twitter, mut twitter_rx) = probe::<TwitterSearchActor>(8);
+        let (web, mut web_rx) = probe::<TwitterSearchActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            llm,
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            twitter,
+            spawn_actor_reserved("store", 8).addr(),
+            system.shutdown_handle(),
+        )
+        .unwrap()
+        .with_web_search(web);
+        let tui = spawn_actor(tui, 64).addr;
+
+        tui.send(TuiMsg::Submit("/claim turnout was 80%".into()))
+            .await
+            .ok()
+            .unwrap();
+        let search = next_search(&mut llm_rx, &mut twitter_rx).await;
+
+        let web_search = web_rx.recv().await.expect("web search dispatched");
+        assert_eq!(web_search.query, search.query);
+        assert_eq!(web_search.claim.id, search.claim.id);
+        assert!(web_search.report.is_none(), "only Twitter reports back");
+        assert!(search.report.is_some());
+        let lines = transcript(&tui).await;
+        assert!(
+            lines
+                .iter()
+                .any(|l| l == "Searching Twitter and the web for: turnout"),
+            "{lines:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn research_dispatches_a_second_search_round_for_the_active_claim() {
+        let system = ActorSystem::new();
+        let (llm, mut llm_rx) = probe::<LlmActor>(8);
+        let (twitter, mut twitter_rx) = probe::<TwitterSearchActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            llm,
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            twitter,
+            spawn_actor_reserved("store", 8).addr(),
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        tui.send(TuiMsg::Submit("/claim turnout was 80%".into()))
+            .await
+            .ok()
+            .unwrap();
+        let first = next_search(&mut llm_rx, &mut twitter_rx).await;
+        first
+            .report
+            .unwrap()
+            .send(SearchOutcome::Done(vec![]))
+            .await
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while !transcript(&tui)
+                .await
+                .iter()
+                .any(|l| l == "← [Twitter] 0 result(s)")
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("first round finished");
+
+        tui.send(TuiMsg::Submit("/research 7".into()))
+            .await
+            .ok()
+            .unwrap();
+        let second = next_search(&mut llm_rx, &mut twitter_rx).await;
+        assert_eq!(second.claim.id, first.claim.id);
+        assert_eq!(second.date_to - second.date_from, chrono::Duration::days(7));
+
+        // A second `/research` while that round runs is refused.
+        tui.send(TuiMsg::Submit("/research".into()))
+            .await
+            .ok()
+            .unwrap();
+        let lines = transcript(&tui).await;
+        assert!(lines.iter().any(|l| l.contains("already running")));
+        assert!(
+            llm_rx.try_recv().is_err(),
+            "no duplicate query was requested"
+        );
+    }
+
+    #[tokio::test]
+    async fn a_search_dropped_by_its_worker_ends_the_round() {
+        let system = ActorSystem::new();
+        let (llm, mut llm_rx) = probe::<LlmActor>(8);
+        let (twitter, mut twitter_rx) = probe::<TwitterSearchActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            llm,
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            twitter,
+            spawn_actor_reserved("store", 8).addr(),
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        tui.send(TuiMsg::Submit("/claim turnout was 80%".into()))
+            .await
+            .ok()
+            .unwrap();
+        // The worker goes away without reporting, dropping the command.
+        drop(next_search(&mut llm_rx, &mut twitter_rx).await);
+        wait_busy(&tui, 0).await;
+        let lines = transcript(&tui).await;
+        assert!(
+            lines.iter().any(|l| l == "× [Twitter] search failed"),
+            "{lines:?}"
+        );
+
+        tui.send(TuiMsg::Submit("/research".into()))
+            .await
+            .ok()
+            .unwrap();
+        let again = next_search(&mut llm_rx, &mut twitter_rx).await;
+        assert_eq!(again.query, "turnout", "a new round is allowed");
+    }
+
+    #[tokio::test]
+    async fn query_rationale_from_the_model_reaches_the_transcript() {
+        use nowhere_actors::rate::{RateKey, RateLimiter, RateMsg};
+        use nowhere_llm::mock::MockLlmClient;
+        use std::sync::Arc;
+
+        let system = ActorSystem::new();
+        let rate_limiter = spawn_actor(RateLimiter::new(), 8).addr;
+        rate_limiter
+            .send(RateMsg::Upsert {
+                key: RateKey("llm".into()),
+                qps: 1000.0,
+                burst: 1000,
+            })
+            .await
+            .ok()
+            .unwrap();
+        let client = MockLlmClient::new().reply(
+            r#"{"query": "\"turnout\" 80%", "date_from": null, "date_to": null,
+                "rationale": "official turnout figures get quoted verbatim"}"#,
+        );
+        let llm = LlmActor::new(
+            rate_limiter,
+            RateKey("llm".into()),
+            spawn_actor_reserved("store", 8).addr(),
+            Arc::new(client),
+        );
+        let (twitter, mut twitter_rx) = probe::<TwitterSearchActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor(llm, 8).addr,
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            twitter,
+            spawn_actor_reserved("store", 8).addr(),
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        tui.send(TuiMsg::Submit("/claim turnout was 80%".into()))
+            .await
+            .ok()
+            .unwrap();
+        let search = tokio::time::timeout(Duration::from_secs(2), twitter_rx.recv())
+            .await
+            .expect("search dispatched in time")
+            .expect("search dispatched");
+        assert_eq!(search.query, "\"turnout\" 80%");
+
+        let lines = transcript(&tui).await;
+        assert!(
+            lines.iter().any(|l| l
+                == "Searching Twitter for: \"turnout\" 80% — because official turnout figures get quoted verbatim"),
+            "{lines:?}"
+        );
+    }
+
+    async fn busy(tui: &Addr<TuiActor<TestBackend>>) -> usize {
+        let (tx, rx) = oneshot::channel();
+        tui.send(TuiMsg::Busy(tx)).await.ok().unwrap();
+        rx.await.unwrap()
+    }
+
+    async fn wait_busy(tui: &Addr<TuiActor<TestBackend>>, n: usize) {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while busy(tui).await != n {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("busy never settled at {n}"));
+    }
+
+    #[tokio::test]
+    async fn bundle_writes_the_active_claims_evidence_to_the_given_path() {
+        let system = ActorSystem::new();
+        let (store, mut store_rx) = probe::<StoreActor>(8);
+        let mut tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the ferry sank".into(),
+            tags: Vec::new(),
+        };
+        tui.set_claim(claim.clone());
+        let tui = spawn_actor(tui, 64).addr;
+        let path = std::env::temp_dir().join(format!("nowhere-bundle-{}.json", claim.id));
+
+        tui.send(TuiMsg::Submit(format!("/bundle {}", path.display())))
+            .await
+            .ok()
+            .unwrap();
+        let Some(StoreMsg::LastSearch { reply, .. }) = store_rx.recv().await else {
+            panic!("expected LastSearch");
+        };
+        reply.send(Ok(None)).unwrap();
+        let Some(StoreMsg::ClaimArtifacts { claim: id, reply }) = store_rx.recv().await else {
+            panic!("expected ClaimArtifacts");
+        };
+        assert_eq!(id, claim.id);
+        reply.send(Ok(Vec::new())).unwrap();
+        wait_busy(&tui, 0).await;
+
+        let written: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written["schema"], bundle::BUNDLE_SCHEMA);
+        assert_eq!(written["claim"]["id"], claim.id.to_string());
+        let lines = transcript(&tui).await;
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with("✓ Bundle written to") && l.ends_with("(0 artifact(s)).")),
+            "{lines:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn long_claims_are_truncated_and_blank_ones_rejected() {
+        let system = ActorSystem::new();
+        let (llm, mut llm_rx) = probe::<LlmActor>(8);
+        let (store, mut store_rx) = probe::<StoreActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            llm,
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap()
+        .with_max_claim_chars(10);
+        let tui = spawn_actor(tui, 64).addr;
+
+        // Zero-width spaces survive the command parser's trim but are still blank.
+        tui.send(TuiMsg::Submit("/claim \u{200b} \u{200b}".into()))
+            .await
+            .ok()
+            .unwrap();
+        let lines = transcript(&tui).await;
+        assert!(
+            lines.iter().any(|l| l == "× Claim text is empty."),
+            "{lines:?}"
+        );
+        assert!(store_rx.try_recv().is_err(), "a blank claim is not stored");
+
+        let long = "the bridge over the river is closed";
+        tui.send(TuiMsg::Submit(format!("/claim {long}")))
+            .await
+            .ok()
+            .unwrap();
+        let Some(StoreMsg::InsertClaim(claim)) = store_rx.recv().await else {
+            panic!("expected InsertClaim");
+        };
+        assert_eq!(claim.text, "the bridge");
+        let Some(LlmMsg::BuildSearchQuery { claim, .. }) = llm_rx.recv().await else {
+            panic!("expected BuildSearchQuery");
+        };
+        assert_eq!(claim.text, "the bridge");
+        let lines = transcript(&tui).await;
+        let warning = format!(
+            "⚠ Claim truncated to 10 of {} characters.",
+            long.chars().count()
+        );
+        assert!(lines.contains(&warning), "{lines:?}");
+        assert!(lines.iter().any(|l| l == "  the bridge"), "{lines:?}");
+    }
+
+    #[tokio::test]
+    async fn review_sends_the_cited_artifacts_verdict_to_the_store() {
+        let system = ActorSystem::new();
+        let (store, mut store_rx) = probe::<StoreActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        tui.send(TuiMsg::Submit("/review tw:1 dismiss".into()))
+            .await
+            .ok()
+            .unwrap();
+        let artifact = Uuid::new_v4();
+        tui.send(TuiMsg::Submit(format!("/review [A:{artifact}] dismiss")))
+            .await
+            .ok()
+            .unwrap();
+        let Some(StoreMsg::SetReview {
+            artifact: id,
+            status,
+            reply,
+        }) = store_rx.recv().await
+        else {
+            panic!("expected SetReview");
+        };
+        assert_eq!((id, status), (artifact, ReviewStatus::Dismissed));
+        reply.send(Ok(())).unwrap();
+        wait_busy(&tui, 0).await;
+
+        // The short slug chat cites resolves through the store.
+        let cited = cite_by_slug(&format!("Closed [A:{artifact}]."));
+        assert_eq!(cited, format!("Closed [A:{}].", slug::short(artifact)));
+        let citation = cited.trim_start_matches("Closed ").trim_end_matches('.');
+        tui.send(TuiMsg::Submit(format!("/review {citation} confirm")))
+            .await
+            .ok()
+            .unwrap();
+        let Some(StoreMsg::ListArtifactIdsByPrefix { prefix, reply, .. }) = store_rx.recv().await
+        else {
+            panic!("expected ListArtifactIdsByPrefix");
+        };
+        assert_eq!(prefix, slug::short(artifact));
+        reply.send(Ok(vec![artifact])).unwrap();
+        let Some(StoreMsg::SetReview {
+            artifact: id,
+            status,
+            reply,
+        }) = store_rx.recv().await
+        else {
+            panic!("expected SetReview");
+        };
+        assert_eq!((id, status), (artifact, ReviewStatus::Confirmed));
+        reply.send(Ok(())).unwrap();
+        wait_busy(&tui, 0).await;
+
+        let lines = transcript(&tui).await;
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.contains("`tw:1` is not an artifact id")),
+            "{lines:?}"
+        );
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with(&format!("✓ Artifact {artifact} dismissed"))),
+            "{lines:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn diff_resolves_slug_prefixes_and_reports_ambiguous_ones() {
+        let system = ActorSystem::new();
+        let (store, mut store_rx) = probe::<StoreActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+        let claim = |text: &str| ClaimContext {
+            id: Uuid::new_v4(),
+            text: text.into(),
+            tags: Vec::new(),
+        };
+        let (mut ferry, port) = (claim("the ferry sank"), claim("the port closed"));
+        // A slug that also reads as hex would be looked up as a UUID prefix too.
+        while slug::uuid_prefix_range(&slug::short(ferry.id)).is_some() {
+            ferry.id = Uuid::new_v4();
+        }
+
+        let cmd = format!("/diff {} {}", slug::short(ferry.id), port.id);
+        tui.send(TuiMsg::Submit(cmd)).await.ok().unwrap();
+        let Some(StoreMsg::ListClaimsBySlug { slug, reply, .. }) = store_rx.recv().await else {
+            panic!("expected ListClaimsBySlug");
+        };
+        assert_eq!(slug, slug::short(ferry.id));
+        reply.send(Ok(vec![ferry.clone()])).unwrap();
+        // A full UUID needs no lookup.
+        let Some(StoreMsg::ClaimOverlap { a, b, reply }) = store_rx.recv().await else {
+            panic!("expected ClaimOverlap");
+        };
+        assert_eq!((a, b), (ferry.id, port.id));
+        let overlap = EvidenceOverlap {
+            entities: vec!["Coast Guard".into()],
+            sources: Vec::new(),
+        };
+        reply.send(Ok(overlap)).unwrap();
+        wait_busy(&tui, 0).await;
+
+        tui.send(TuiMsg::Submit("/diff 4f 1b".into()))
+            .await
+            .ok()
+            .unwrap();
+        let Some(StoreMsg::ListClaimsBySlug { reply, .. }) = store_rx.recv().await else {
+            panic!("expected ListClaimsBySlug");
+        };
+        reply.send(Ok(vec![ferry.clone(), port.clone()])).unwrap();
+        // `4f` is also hex, so it is looked up as the start of a UUID as well.
+        let Some(StoreMsg::ListClaimsByUuidPrefix { reply, .. }) = store_rx.recv().await else {
+            panic!("expected ListClaimsByUuidPrefix");
+        };
+        reply.send(Ok(vec![ferry.clone()])).unwrap();
+        wait_busy(&tui, 0).await;
+
+        let lines = transcript(&tui).await;
+        assert!(lines.iter().any(|l| l.trim() == "Coast Guard"), "{lines:?}");
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with("× Error: diff: `4f` matches several claims")),
+            "{lines:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn diff_resolves_uuid_prefixes() {
+        let system = ActorSystem::new();
+        let (store, mut store_rx) = probe::<StoreActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+        let (ferry, port) = (Uuid::new_v4(), Uuid::new_v4());
+        let prefix = ferry.to_string()[..13].to_string();
+
+        tui.send(TuiMsg::Submit(format!("/diff {prefix} {port}")))
+            .await
+            .ok()
+            .unwrap();
+        let Some(StoreMsg::ListClaimsBySlug { reply, .. }) = store_rx.recv().await else {
+            panic!("expected ListClaimsBySlug");
+        };
+        reply.send(Ok(Vec::new())).unwrap();
+        let Some(StoreMsg::ListClaimsByUuidPrefix {
+            prefix: asked,
+            reply,
+            ..
+        }) = store_rx.recv().await
+        else {
+            panic!("expected ListClaimsByUuidPrefix");
+        };
+        assert_eq!(asked, prefix);
+        reply
+            .send(Ok(vec![ClaimContext {
+                id: ferry,
+                text: "the ferry sank".into(),
+                tags: Vec::new(),
+            }]))
+            .unwrap();
+        let Some(StoreMsg::ClaimOverlap { a, b, .. }) = store_rx.recv().await else {
+            panic!("expected ClaimOverlap");
+        };
+        assert_eq!((a, b), (ferry, port));
+    }
+
+    #[tokio::test]
+    async fn overlapping_operations_each_end_once_even_when_one_fails() {
+        let system = ActorSystem::new();
+        let (store, mut store_rx) = probe::<StoreActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            spawn_actor_reserved("chat", 8).addr(),
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            store,
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        for cmd in ["/reindex", "/claims urgent"] {
+            tui.send(TuiMsg::Submit(cmd.into())).await.ok().unwrap();
+        }
+        let Some(StoreMsg::RebuildFts { reply: reindex }) = store_rx.recv().await else {
+            panic!("expected RebuildFts");
+        };
+        let Some(StoreMsg::ListClaimsByTag { reply: claims, .. }) = store_rx.recv().await else {
+            panic!("expected ListClaimsByTag");
+        };
+        assert_eq!(busy(&tui).await, 2);
+
+        // An error outside any operation used to count as one finishing.
+        tui.send(TuiMsg::OpError("input: device gone".into()))
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!(busy(&tui).await, 2);
+
+        reindex.send(Err(anyhow::anyhow!("disk full"))).unwrap();
+        wait_busy(&tui, 1).await;
+        claims.send(Ok(Vec::new())).unwrap();
+        wait_busy(&tui, 0).await;
+
+        let lines = transcript(&tui).await;
+        assert!(lines.iter().any(|l| l == "× Error: reindex: disk full"));
+    }
+
+    #[tokio::test]
+    async fn k_sets_the_retrieval_depth_of_the_next_chat() {
+        let system = ActorSystem::new();
+        let (chat, mut chat_rx) = probe::<ChatLlmActor>(8);
+        let tui = TuiActor::with_terminal(
+            Terminal::new(TestBackend::new(80, 24)).unwrap(),
+            spawn_actor_reserved("llm", 8).addr(),
+            chat,
+            spawn_actor_reserved("analysis", 8).addr(),
+            spawn_actor_reserved("twitter", 8).addr(),
+            spawn_actor_reserved("store", 8).addr(),
+            system.shutdown_handle(),
+        )
+        .unwrap();
+        let tui = spawn_actor(tui, 64).addr;
+
+        for line in [
+            "/claim turnout was 80%",
+            "/k 0",
+            "/k 10",
+            "/k",
+            "who counted?",
+        ] {
+            tui.send(TuiMsg::Submit(line.into())).await.ok().unwrap();
+        }
+        let cmd = tokio::time::timeout(Duration::from_secs(2), chat_rx.recv())
+            .await
+            .unwrap()
+            .expect("chat dispatched");
+        assert_eq!(cmd.k, 10);
+
+        let lines = transcript(&tui).await;
+        assert!(lines.iter().any(|l| l.starts_with("× Usage: `/k [n]`")));
+        assert!(
+            lines
+                .iter()
+                .any(|l| l == "Chat retrieves up to 10 artifact(s).")
+        );
+    }
+}
@@ -1,32 +1,36 @@
-use crate::transcript::TranscriptLine;
+use crate::{command::CommandHelp, transcript::TranscriptLine};
 use anyhow::Result;
 use ratatui::{
     Terminal,
-    backend::CrosstermBackend,
+    backend::Backend,
     layout::{Constraint, Direction, Layout, Position},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
-use std::io::Stdout;
 use textwrap::wrap;
 
-pub struct ViewSnap {
+/// What one frame shows. The transcript is borrowed, and only the entries the window
+/// reaches are wrapped.
+pub struct ViewSnap<'a> {
     pub input: String,
     pub input_cursor: usize,
-    pub lines: Vec<TranscriptLine>,
+    pub lines: &'a [TranscriptLine],
     pub scroll: usize,
-    pub busy: u32,
+    /// Operations still running.
+    pub busy: usize,
     pub spinner: &'static str,
+    /// Commands matching a `/verb` being typed, shown above the input box.
+    pub palette: Vec<&'static CommandHelp>,
 }
 
-impl ViewSnap {
+impl<'a> ViewSnap<'a> {
     pub fn new(
         input: String,
         input_cursor: usize,
-        lines: Vec<TranscriptLine>,
+        lines: &'a [TranscriptLine],
         scroll: usize,
-        busy: u32,
+        busy: usize,
         spinner: &'static str,
     ) -> Self {
         Self {
@@ -36,11 +40,17 @@ impl ViewSnap {
             scroll,
             busy,
             spinner,
+            palette: Vec::new(),
         }
     }
+
+    pub fn with_palette(mut self, palette: Vec<&'static CommandHelp>) -> Self {
+        self.palette = palette;
+        self
+    }
 }
 
-pub fn draw(term: &mut Terminal<CrosstermBackend<Stdout>>, snap: &ViewSnap) -> Result<()> {
+pub fn draw<B: Backend>(term: &mut Terminal<B>, snap: &ViewSnap<'_>) -> Result<()> {
     term.draw(|frame| {
         let area = frame.area();
 
@@ -67,23 +77,45 @@ pub fn draw(term: &mut Terminal<CrosstermBackend<Stdout>>, snap: &ViewSnap) -> R
         // Transcript window
         let visible_h = layout[1].height.saturating_sub(2) as usize;
         let content_width = layout[1].width.saturating_sub(2) as usize;
-        let wrapped = wrap_transcript(&snap.lines, content_width);
-        let total = wrapped.len();
-        let start = total.saturating_sub(visible_h + snap.scroll);
-        let end = total.saturating_sub(snap.scroll);
-
-        let items: Vec<ListItem> = wrapped[start..end]
-            .iter()
-            .map(|(text, style)| {
-                let line = Line::from(Span::styled(text.clone(), style.clone()));
-                ListItem::new(line)
-            })
+        let rows = visible_rows(snap.lines, content_width, visible_h, snap.scroll);
+
+        let items: Vec<ListItem> = rows
+            .into_iter()
+            .map(|(text, style)| ListItem::new(Line::from(Span::styled(text, style))))
             .collect();
 
         let body =
             List::new(items).block(Block::default().borders(Borders::ALL).title(" Transcript "));
         frame.render_widget(body, layout[1]);
 
+        // Command palette, drawn over the bottom of the transcript
+        if !snap.palette.is_empty() {
+            let area = layout[1];
+            let height = (snap.palette.len() as u16 + 2).min(area.height);
+            let popup = ratatui::layout::Rect {
+                y: area.y + area.height - height,
+                height,
+                ..area
+            };
+            let items: Vec<ListItem> = snap
+                .palette
+                .iter()
+                .map(|cmd| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled(
+                            format!("{:<17}", cmd.usage),
+                            Style::default().fg(Color::Cyan),
+                        ),
+                        Span::raw(cmd.summary),
+                    ]))
+                })
+                .collect();
+            let list =
+                List::new(items).block(Block::default().borders(Borders::ALL).title(" Commands "));
+            frame.render_widget(Clear, popup);
+            frame.render_widget(list, popup);
+        }
+
         // Input box
         let input_box = Paragraph::new(snap.input.clone())
             .block(Block::default().borders(Borders::ALL).title(" Input "));
@@ -123,31 +155,74 @@ fn visual_caret_col(input: &str, cursor: usize) -> u16 {
     UnicodeWidthStr::width(&input[..cursor]) as u16
 }
 
-fn wrap_transcript(lines: &[TranscriptLine], width: usize) -> Vec<(String, Style)> {
+/// The wrapped rows a `height`-row window shows when scrolled `scroll` rows up from the
+/// bottom. Entries are wrapped from the bottom up, only until the window is full, so a
+/// frame costs what the window and scroll offset need rather than the whole transcript.
+fn visible_rows(
+    lines: &[TranscriptLine],
+    width: usize,
+    height: usize,
+    scroll: usize,
+) -> Vec<(String, Style)> {
+    let wanted = height + scroll;
+    let mut first = lines.len();
+    // Bottom row first until the end.
+    let mut rows = Vec::with_capacity(wanted);
+    while first > 0 && rows.len() < wanted {
+        first -= 1;
+        rows.extend(wrap_entry(&lines[first], width).into_iter().rev());
+    }
+    rows.reverse();
+    let end = rows.len().saturating_sub(scroll);
+    rows.truncate(end);
+    rows.drain(..end.saturating_sub(height));
+    rows
+}
+
+fn wrap_entry(entry: &TranscriptLine, width: usize) -> Vec<(String, Style)> {
     let effective_width = width.max(1);
-    let mut out = Vec::new();
+    let style = entry.style;
+    if entry.text.is_empty() {
+        return vec![(String::new(), style)];
+    }
 
-    for entry in lines {
-        let style = entry.style;
-        if entry.text.is_empty() {
+    let mut out = Vec::new();
+    for raw_line in entry.text.split('\n') {
+        if raw_line.is_empty() {
             out.push((String::new(), style));
             continue;
         }
 
-        for raw_line in entry.text.split('\n') {
-            if raw_line.is_empty() {
-                out.push((String::new(), style));
-                continue;
-            }
-
-            let segments = wrap(raw_line, effective_width);
-            if segments.is_empty() {
-                out.push((String::new(), style));
-            } else {
-                out.extend(segments.into_iter().map(|seg| (seg.into_owned(), style)));
-            }
+        let segments = wrap(raw_line, effective_width);
+        if segments.is_empty() {
+            out.push((String::new(), style));
+        } else {
+            out.extend(segments.into_iter().map(|seg| (seg.into_owned(), style)));
         }
     }
-
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_entries_in_view_are_wrapped() {
+        let lines: Vec<_> = (0..10_000)
+            .map(|i| TranscriptLine::new(format!("entry {i} with words to wrap"), Style::default()))
+            .collect();
+        // Every entry wraps to two rows at this width.
+        let (width, height, scroll) = (16, 10, 7);
+
+        let rows = visible_rows(&lines, width, height, scroll);
+
+        let everything: Vec<_> = lines.iter().flat_map(|l| wrap_entry(l, width)).collect();
+        let end = everything.len() - scroll;
+        assert_eq!(rows, everything[end - height..end]);
+
+        // Scrolled past the top, the window shows what is left.
+        let rows = visible_rows(&lines[..3], width, height, 4);
+        assert_eq!(rows.len(), 2);
+    }
+}
@@ -0,0 +1,70 @@
+//! Brave hits as `RawArtifact`s, the shape `LlmActor` normalizes and the store keeps.
+//!
+//! The payload carries the page's `url`, so normalization reads it as a `web` source: the
+//! canonical URL links it to tweets about the same page, and provenance points at it.
+use super::types::BraveHit;
+use nowhere_actors::{ClaimContext, RawArtifact};
+use nowhere_common::urls::canonical_url;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+/// Marker stored under `source` in every payload built here.
+pub const WEB_SOURCE: &str = "web";
+
+/// Wrap `hit` for normalization. `external_id` is the hit's canonical URL (or the URL as
+/// returned when it isn't http(s)), so the same page found twice upserts one row.
+pub fn hit_to_artifact(
+    hit: &BraveHit,
+    claim: &ClaimContext,
+    cancel: &CancellationToken,
+) -> RawArtifact {
+    let external_id = canonical_url(&hit.url).unwrap_or_else(|| hit.url.clone());
+    RawArtifact {
+        payload: json!({
+            "source": WEB_SOURCE,
+            "url": external_id,
+            "title": hit.title,
+            "description": hit.description,
+            "rank": hit.rank,
+        }),
+        external_id,
+        claim: claim.clone(),
+        cancel: cancel.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn hit_becomes_a_web_artifact_keyed_by_canonical_url() {
+        let hit = BraveHit {
+            rank: 3,
+            title: "Bridge closed for repairs".into(),
+            url: "https://News.example/bridge/#comments".into(),
+            description: Some("The bridge closed on Monday.".into()),
+        };
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the bridge is closed".into(),
+            tags: Vec::new(),
+        };
+
+        let artifact = hit_to_artifact(&hit, &claim, &CancellationToken::new());
+
+        assert_eq!(artifact.external_id, "https://news.example/bridge");
+        assert_eq!(artifact.claim, claim);
+        assert_eq!(
+            artifact.payload,
+            json!({
+                "source": "web",
+                "url": "https://news.example/bridge",
+                "title": "Bridge closed for repairs",
+                "description": "The bridge closed on Monday.",
+                "rank": 3,
+            })
+        );
+    }
+}
@@ -1,579 +1,593 @@
-// use super::types::{
-//     BraveHit, MixedKind, NewsResult, SearchResult, VideoResult, WebSearchApiResponse,
-//     WebSearchRequest, map_freshness,
-// };
-// use anyhow::{Context, Result};
-// use nowhere_actors::{Addr, RateKey, RateLimiter, RateMsg};
-// use nowhere_data::ingest::{AnyStream, DiscoveryItem, WebSearchProgram, WebSource};
-// use nowhere_http::{Auth, HttpClient, RequestOpts};
-// use reqwest::header::{HeaderName, HeaderValue};
-// use std::borrow::Cow;
-// use std::collections::HashSet;
-// use std::time::Instant;
-// use tokio::sync::oneshot;
-// use url::Url;
-//
-// /// Minimal client for Brave Search API (web vertical).
-// #[derive(Clone)]
-// pub struct BraveApi {
-//     http: HttpClient,
-//     token: String,
-// }
-//
-// impl BraveApi {
-//     pub fn new(subscription_token: String) -> Self {
-//         let http = HttpClient::new("https://api.search.brave.com").expect("valid base");
-//         Self {
-//             http,
-//             token: subscription_token,
-//         }
-//     }
-//
-//     pub fn simple_search(
-//         &self,
-//         query: String,
-//         rate: Addr<RateLimiter>,
-//         rate_key: RateKey,
-//     ) -> AnyStream<DiscoveryItem> {
-//         let client = self.clone();
-//         Box::pin(async_stream::try_stream! {
-//           let mut page_idx = 0u32;
-//           while page_idx < 1 {
-//               // --- Rate gate per page ---
-//               let started = std::time::Instant::now();
-//               let (tx, rx) = oneshot::channel();
-//               let _ = rate.send(RateMsg::Acquire {
-//                   key: rate_key.clone(),
-//                   cost: 1,
-//                   reply: tx,
-//               }).await;
-//               tracing::trace!(target:"rate", key=%rate_key.0, "waiting for brave token");
-//               let _ = rx.await;
-//               tracing::trace!(target:"rate", key=%rate_key.0, waited_ms=?started.elapsed().as_millis(), "acquired brave token");
-//
-//               let resp = client.simple_query_search(query.clone()).await?;
-//
-//               // Collect URLs in Brave's display order if mixed.main is present, else fallback
-//               let hits = collect_brave_hits(&resp);
-//               tracing::info!(
-//                   target: "web.brave",
-//                   query = %query,
-//                   hit_count = hits.len(),
-//                   "brave.simple_search.page"
-//               );
-//
-//               for hit in hits {
-//                   yield DiscoveryItem::Web {
-//                       url: Url::parse(&hit.url).ok(),
-//                       source: WebSource::Brave,
-//                       description: hit.description,
-//                       title: hit.title,
-//                   };
-//               }
-//               page_idx += 1;
-//              }
-//
-//         })
-//     }
-//     /// Stream of `DiscoveryItem::WebUrl` honoring `max_results`.
-//     /// We page in fixed steps (20), respecting freshness and optional site filters.
-//     // pub fn search_stream(
-//     //     &self,
-//     //     program: WebSearchProgram,
-//     //     rate: Addr<RateLimiter>,
-//     //     rate_key: RateKey,
-//     // ) -> AnyStream<DiscoveryItem> {
-//     //     let client = self.clone();
-//     //
-//     //     Box::pin(async_stream::try_stream! {
-//     //                 // Build concrete query: base query + optional (site:A OR site:B)
-//     //                 let mut base_q = String::new();
-//     //                 if let Some(q) = program.queries.first() {
-//     //                     base_q.push_str(q.trim());
-//     //                 }
-//     //                 if !program.sites.is_empty() {
-//     //                     let sites_expr = program.sites
-//     //                         .iter()
-//     //                         .filter(|s| !s.trim().is_empty())
-//     //                         .map(|s| format!("site:{}", s.trim()))
-//     //                         .collect::<Vec<_>>()
-//     //                         .join(" OR ");
-//     //                     if !sites_expr.is_empty() {
-//     //                         if !base_q.is_empty() { base_q.push(' '); }
-//     //                         base_q.push_str(&format!("({})", sites_expr));
-//     //                     }
-//     //                 }
-//     //
-//     //                 let mut remaining = program.max_results as i32;
-//     //                 let mut page: u32 = 0;
-//     //                 const BRAVE_PAGE_SIZE: u32 = 20;
-//     //                 const BRAVE_MAX_PAGE: u32 = 9;
-//     //                 let mut seen: HashSet<String> = HashSet::new();
-//     //
-//     //                 while remaining > 0 && page <= BRAVE_MAX_PAGE {
-//     //                     let count = BRAVE_PAGE_SIZE.min(remaining as u32);
-//     //
-//     //                     let req = WebSearchRequest {
-//     //                         query: base_q.clone(),
-//     //                         country: None,
-//     //                         search_lang: None,
-//     //                         count: Some(count),
-//     //                         offset: Some(page.saturating_mul(BRAVE_PAGE_SIZE)),
-//     //                         freshness: map_freshness(&program.freshness),
-//     //                         safesearch: Some("moderate"),
-//     //                         // Default behavior: only web. To include others:
-//     //                         // result_filter: Some("web,news,videos".to_string()),
-//     //                         result_filter: None,
-//     //                         extra_snippets: None,
-//     //                         spellcheck_off: None,
-//     //                         goggles_id: None,
-//     //                     };
-//     //
-//     //                     let (tx, rx) = oneshot::channel();
-//     //                     let _ = rate
-//     //                          .send(RateMsg::Acquire {
-//     //                              key: rate_key.clone(),
-//     //                              cost: 1,
-//     //                              reply: tx,
-//     //                          })
-//     //                     .await;
-//     //                     let _ = rx.await;
-//     //
-//     //
-//     //                     let resp = client.search_page(&req).await?;
-//     //
-//     //                     // Decide which verticals to include
-//     //                     let (want_web, want_news, want_videos) = allowed_verticals_from_filter(req.result_filter.as_deref());
-//     //
-//     //                     // Collect URLs in Brave's display order if mixed.main is present, else fallback
-//     //                     let mut urls = collect_urls_in_display_order(&resp, want_web, want_news, want_videos);
-//     //
-//     //                     // Dedupe across pages and emit
-//     //                     urls.retain(|u| seen.insert(url_key(u)));
-//     //
-//     //                     if urls.is_empty() {
-//     //                         // nothing new on this page; try next page
-//     //                         if count == 0 { break; }
-//     //                     }
-//     //
-//     //                     for url in urls {
-//     //     //                    yield DiscoveryItem::WebUrl { url, source: WebSource::Brave };
-//     //                         remaining -= 1;
-//     //                         if remaining <= 0 { break; }
-//     //                     }
-//     //                     page += 1;
-//     //                     if count == 0 { break; }
-//     //                 }
-//     //             })
-//     // }
-//     //
-//     pub async fn simple_query_search(&self, query: String) -> Result<WebSearchApiResponse> {
-//         let params = vec![("q", query.clone().into())];
-//         let query_snippet = if query.len() > 160 {
-//             format!("{}…", &query[..160])
-//         } else {
-//             query.clone()
-//         };
-//         let started = Instant::now();
-//         tracing::info!(
-//             target: "web.brave",
-//             query = %query_snippet,
-//             "brave.simple_query.start"
-//         );
-//
-//         let resp: WebSearchApiResponse = match self
-//             .http
-//             .get_json(
-//                 "res/v1/web/search",
-//                 RequestOpts {
-//                     auth: Some(Auth::Header {
-//                         name: HeaderName::from_static("x-subscription-token"),
-//                         value: HeaderValue::from_str(&self.token)
-//                             .map_err(|e| nowhere_http::HttpError::Build(e.to_string()))?,
-//                     }),
-//                     query: Some(params),
-//                     retries: Some(0),
-//                     ..Default::default()
-//                 },
-//             )
-//             .await
-//         {
-//             Ok(resp) => {
-//                 tracing::info!(
-//                     target: "web.brave",
-//                     query = %query_snippet,
-//                     elapsed_ms = started.elapsed().as_millis() as u64,
-//                     "brave.simple_query.success"
-//                 );
-//                 resp
-//             }
-//             Err(e) => {
-//                 tracing::warn!(
-//                     target: "web.brave",
-//                     query = %query_snippet,
-//                     elapsed_ms = started.elapsed().as_millis() as u64,
-//                     error = %e,
-//                     "brave.simple_query.error"
-//                 );
-//                 return Err(anyhow::Error::new(e)).context("brave search request failed");
-//             }
-//         };
-//         tracing::debug!(?resp, "full web search response");
-//         Ok(resp)
-//     }
-//
-//     /// Single-page call using `WebSearchRequest`, returning your `WebSearchApiResponse`.
-//     pub async fn search_page(&self, req: &WebSearchRequest) -> Result<WebSearchApiResponse> {
-//         let mut params: Vec<(&str, Cow<'_, str>)> = Vec::with_capacity(12);
-//
-//         // Required
-//         params.push(("q", req.query.clone().into()));
-//
-//         // Optional numeric
-//         if let Some(v) = req.count {
-//             params.push(("count", v.to_string().into()));
-//         }
-//         if let Some(v) = req.offset {
-//             params.push(("offset", v.to_string().into()));
-//         }
-//
-//         // Optional strings
-//         if let Some(ref v) = req.country {
-//             if !v.is_empty() {
-//                 params.push(("country", v.clone().into()));
-//             }
-//         }
-//         if let Some(ref v) = req.search_lang {
-//             if !v.is_empty() {
-//                 params.push(("search_lang", v.clone().into()));
-//             }
-//         }
-//         if let Some(v) = req.freshness {
-//             params.push(("freshness", v.into()));
-//         }
-//         if let Some(v) = req.safesearch {
-//             params.push(("safesearch", v.into()));
-//         }
-//         if let Some(ref v) = req.result_filter {
-//             if !v.is_empty() {
-//                 params.push(("result_filter", v.clone().into()));
-//             }
-//         }
-//         if let Some(ref v) = req.goggles_id {
-//             if !v.is_empty() {
-//                 params.push(("goggles_id", v.clone().into()));
-//             }
-//         }
-//
-//         // Optional bools
-//         if let Some(v) = req.extra_snippets {
-//             params.push(("extra_snippets", (if v { "true" } else { "false" }).into()));
-//         }
-//         if let Some(v) = req.spellcheck_off {
-//             params.push(("spellcheck_off", (if v { "true" } else { "false" }).into()));
-//         }
-//
-//         // Default to "moderate" if caller did not set safesearch
-//         if !params.iter().any(|(k, _)| *k == "safesearch") {
-//             params.push(("safesearch", "moderate".into()));
-//         }
-//
-//         let resp: WebSearchApiResponse = self
-//             .http
-//             .get_json(
-//                 "res/v1/web/search",
-//                 RequestOpts {
-//                     auth: Some(Auth::Header {
-//                         name: HeaderName::from_static("x-subscription-token"),
-//                         value: HeaderValue::from_str(&self.token)
-//                             .map_err(|e| nowhere_http::HttpError::Build(e.to_string()))?,
-//                     }),
-//                     query: Some(params),
-//                     retries: Some(0),
-//                     ..Default::default()
-//                 },
-//             )
-//             .await
-//             .map_err(|e| anyhow::anyhow!(e.to_string()))
-//             .context("brave search request failed")?;
-//         tracing::info!(?resp, "full web search response");
-//         Ok(resp)
-//     }
-// }
-//
-// // Default to web-only; allow "web,news,videos" CSV to widen scope
-// fn allowed_verticals_from_filter(filter: Option<&str>) -> (bool, bool, bool) {
-//     match filter {
-//         None => (true, false, false), // default: web only
-//         Some(s) => {
-//             let mut web = false;
-//             let mut news = false;
-//             let mut videos = false;
-//             for part in s.split(',').map(|p| p.trim().to_ascii_lowercase()) {
-//                 match part.as_str() {
-//                     "web" | "search" => web = true,
-//                     "news" => news = true,
-//                     "videos" | "video" => videos = true,
-//                     "_all" | "all" => {
-//                         web = true;
-//                         news = true;
-//                         videos = true;
-//                     }
-//                     _ => {}
-//                 }
-//             }
-//             (web, news, videos)
-//         }
-//     }
-// }
-//
-// fn collect_brave_hits(resp: &WebSearchApiResponse) -> Vec<BraveHit> {
-//     let mut out = Vec::new();
-//
-//     let web: Option<&Vec<SearchResult>> = resp.web.as_ref().map(|w| &w.results);
-//     let news: Option<&Vec<NewsResult>> = resp.news.as_ref().map(|n| &n.results);
-//     let videos: Option<&Vec<VideoResult>> = resp.videos.as_ref().map(|v| &v.results);
-//
-//     if let Some(mixed) = resp.mixed.as_ref() {
-//         for slot in &mixed.main {
-//             let take_all = slot.all.unwrap_or(false);
-//             match &slot.kind {
-//                 MixedKind::Web => {
-//                     if take_all {
-//                         if let Some(vec) = web {
-//                             for it in vec {
-//                                 convert_and_push_search_result(&mut out, it);
-//                             }
-//                         }
-//                     } else if let Some(vec) = web
-//                         && let Some(it) = vec.get(slot.index)
-//                     {
-//                         convert_and_push_search_result(&mut out, it);
-//                     }
-//                 }
-//                 MixedKind::News => {
-//                     if take_all {
-//                         if let Some(vec) = news {
-//                             for it in vec {
-//                                 if let Some(u) = Url::parse(&it.url).ok()
-//                                     && let t = &it.title
-//                                     && let Some(d) = it.description.as_deref()
-//                                 {
-//                                     out.push(BraveHit {
-//                                         rank: (out.len() + 1) as u32,
-//                                         title: t.to_string(),
-//                                         url: u.to_string(),
-//                                         description: Some(d.to_string()),
-//                                     });
-//                                 }
-//                             }
-//                         }
-//                     } else if let Some(vec) = news
-//                         && let Some(it) = vec.get(slot.index)
-//                         && let Ok(u) = Url::parse(it.url.as_str())
-//                         && let t = &it.title
-//                         && let Some(d) = &it.description
-//                     {
-//                         out.push(BraveHit {
-//                             rank: (out.len() + 1) as u32,
-//                             title: t.to_string(),
-//                             url: u.to_string(),
-//                             description: Some(d.to_string()),
-//                         });
-//                     }
-//                 }
-//                 MixedKind::Videos => {
-//                     if take_all {
-//                         if let Some(vec) = videos {
-//                             for it in vec {
-//                                 if let Some(u) = Url::parse(&it.url).ok()
-//                                     && let t = &it.title
-//                                     && let Some(d) = it.description.as_deref()
-//                                 {
-//                                     out.push(BraveHit {
-//                                         rank: (out.len() + 1) as u32,
-//                                         title: t.to_string(),
-//                                         url: u.to_string(),
-//                                         description: Some(d.to_string()),
-//                                     });
-//                                 }
-//                             }
-//                         }
-//                     } else if let Some(vec) = news
-//                         && let Some(it) = vec.get(slot.index)
-//                         && let Ok(u) = Url::parse(it.url.as_str())
-//                         && let t = &it.title
-//                         && let Some(d) = &it.description
-//                     {
-//                         out.push(BraveHit {
-//                             rank: (out.len() + 1) as u32,
-//                             title: t.to_string(),
-//                             url: u.to_string(),
-//                             description: Some(d.to_string()),
-//                         });
-//                     }
-//                 }
-//                 _ => {}
-//             }
-//         }
-//     }
-//
-//     out
-// }
-//
-// fn collect_urls_in_display_order(
-//     resp: &WebSearchApiResponse,
-//     want_web: bool,
-//     want_news: bool,
-//     want_videos: bool,
-// ) -> Vec<Url> {
-//     let mut out = Vec::new();
-//
-//     // Borrow the inner Vecs (don't move them out of resp)
-//     let web: Option<&Vec<SearchResult>> = resp.web.as_ref().map(|w| &w.results);
-//     let news: Option<&Vec<NewsResult>> = resp.news.as_ref().map(|n| &n.results);
-//     let videos: Option<&Vec<VideoResult>> = resp.videos.as_ref().map(|v| &v.results);
-//
-//     // 1) Preferred: use mixed.main ordering when present
-//     if let Some(mixed) = resp.mixed.as_ref() {
-//         for slot in &mixed.main {
-//             let take_all = slot.all.unwrap_or(false);
-//             match &slot.kind {
-//                 MixedKind::Web if want_web => {
-//                     if take_all {
-//                         if let Some(vec) = web {
-//                             for it in vec {
-//                                 push_web_result_urls(&mut out, it);
-//                             }
-//                         }
-//                     } else if let Some(vec) = web
-//                         && let Some(it) = vec.get(slot.index)
-//                     {
-//                         push_web_result_urls(&mut out, it);
-//                     }
-//                 }
-//                 MixedKind::News if want_news => {
-//                     if take_all {
-//                         if let Some(vec) = news {
-//                             for it in vec {
-//                                 if let Ok(u) = Url::parse(it.url.as_str()) {
-//                                     out.push(u);
-//                                 }
-//                             }
-//                         }
-//                     } else if let Some(vec) = news
-//                         && let Some(it) = vec.get(slot.index)
-//                         && let Ok(u) = Url::parse(it.url.as_str())
-//                     {
-//                         out.push(u);
-//                     }
-//                 }
-//                 MixedKind::Videos if want_videos => {
-//                     if take_all {
-//                         if let Some(vec) = videos {
-//                             for it in vec {
-//                                 if let Ok(u) = Url::parse(it.url.as_str()) {
-//                                     out.push(u);
-//                                 }
-//                             }
-//                         }
-//                     } else if let Some(vec) = videos
-//                         && let Some(it) = vec.get(slot.index)
-//                         && let Ok(u) = Url::parse(it.url.as_str())
-//                     {
-//                         out.push(u);
-//                     }
-//                 }
-//                 _ => {}
-//             }
-//         }
-//     }
-//
-//     // 2) Fallback: if mixed was absent or yielded nothing, append verticals in order
-//     if out.is_empty() {
-//         if want_web {
-//             if let Some(vec) = web {
-//                 for it in vec {
-//                     push_web_result_urls(&mut out, it);
-//                 }
-//             }
-//         }
-//         if want_news {
-//             if let Some(vec) = news {
-//                 for it in vec {
-//                     if let Ok(u) = Url::parse(it.url.as_str()) {
-//                         out.push(u);
-//                     }
-//                 }
-//             }
-//         }
-//         if want_videos {
-//             if let Some(vec) = videos {
-//                 for it in vec {
-//                     if let Ok(u) = Url::parse(it.url.as_str()) {
-//                         out.push(u);
-//                     }
-//                 }
-//             }
-//         }
-//     }
-//
-//     out
-// }
-//
-// fn convert_and_push_search_result(out: &mut Vec<BraveHit>, it: &SearchResult) {
-//     if let Some(u) = it.url.as_deref().and_then(|s| Url::parse(s).ok())
-//         && let Some(t) = it.title.as_deref()
-//         && let Some(d) = it.description.as_deref()
-//     {
-//         out.push(BraveHit {
-//             rank: (out.len() + 1) as u32,
-//             title: t.to_string(),
-//             url: u.to_string(),
-//             description: Some(d.to_string()),
-//         });
-//         return;
-//     }
-//
-//     if let Some(cluster) = it.cluster.as_ref() {
-//         for item in cluster {
-//             if let Ok(u) = Url::parse(item.url.as_str())
-//                 && let t = &item.title
-//                 && let Some(d) = item.description.as_deref()
-//             {
-//                 out.push(BraveHit {
-//                     rank: (out.len() + 1) as u32,
-//                     title: t.to_string(),
-//                     url: u.to_string(),
-//                     description: Some(d.to_string()),
-//                 });
-//             }
-//         }
-//     }
-// }
-//
-// /// Web results can be a plain result with `url: Option<String>`
-// /// OR a "cluster" with multiple `ResultItem`s. Handle both.
-// ///
-// /// - If `it.url` is present, push that.
-// /// - Else, expand `cluster` and push each item's URL.
-// ///
-// /// Parsing failures are silently ignored (keeps this utility focused).
-// fn push_web_result_urls(out: &mut Vec<Url>, it: &SearchResult) {
-//     if let Some(u) = it.url.as_deref().and_then(|s| Url::parse(s).ok()) {
-//         out.push(u);
-//         return;
-//     }
-//     if let Some(cluster) = it.cluster.as_ref() {
-//         for item in cluster {
-//             if let Ok(u) = Url::parse(item.url.as_str()) {
-//                 out.push(u);
-//             }
-//         }
-//     }
-// } // Simple normalization key for deduping (drop fragment, trim trailing slash)
-// fn url_key(u: &Url) -> String {
-//     let mut clone = u.clone();
-//     clone.set_fragment(None);
-//     clone.as_str().trim_end_matches('/').to_string()
-// }
+use super::types::{
+    BraveHit, Freshness, MixedKind, NewsResult, SearchResult, Verticals, VideoResult,
+    WebSearchApiResponse, WebSearchRequest, map_freshness, map_verticals,
+};
+use anyhow::{Context, Result};
+use nowhere_http::{Auth, HttpClient, HttpError, RequestOpts};
+use reqwest::header::{HeaderName, HeaderValue};
+use std::borrow::Cow;
+use std::time::Instant;
+use url::Url;
+
+/// Most results Brave returns per page; larger `count`s are clamped to it.
+pub const MAX_COUNT: u32 = 20;
+
+/// Characters of a query kept in log lines.
+const QUERY_LOG_CHARS: usize = 160;
+
+/// Caller-tunable knobs for [`BraveApi::search`].
+#[derive(Debug, Clone, Default)]
+pub struct BraveSearchOptions {
+    /// Results per page, clamped to `1..=MAX_COUNT`.
+    pub count: Option<u32>,
+    pub freshness: Freshness,
+    /// `None` leaves Brave's default (web only).
+    pub result_filter: Option<Verticals>,
+}
+
+impl BraveSearchOptions {
+    fn to_request(&self, query: &str) -> WebSearchRequest {
+        WebSearchRequest {
+            query: query.to_string(),
+            country: None,
+            search_lang: None,
+            count: self.count,
+            offset: None,
+            freshness: map_freshness(&self.freshness),
+            safesearch: None,
+            result_filter: self.result_filter.and_then(map_verticals),
+            extra_snippets: None,
+            spellcheck_off: None,
+            goggles_id: None,
+        }
+    }
+}
+
+/// Minimal client for Brave Search API (web vertical).
+#[derive(Clone)]
+pub struct BraveApi {
+    http: HttpClient,
+    token: String,
+}
+
+impl BraveApi {
+    pub fn new(subscription_token: String) -> Self {
+        Self::with_base_url(subscription_token, "https://api.search.brave.com").expect("valid base")
+    }
+
+    /// Point the client at a different API host (e.g. a local stub in tests).
+    pub fn with_base_url(subscription_token: String, base: &str) -> Result<Self> {
+        Ok(Self {
+            http: HttpClient::new(base)?,
+            token: subscription_token,
+        })
+    }
+
+    /// One single-result search, as a readiness signal: `Ok(false)` when Brave rejects the
+    /// subscription token (401/403, or 422 `SUBSCRIPTION_TOKEN_INVALID`), `Err` when it
+    /// could not be asked at all.
+    pub async fn health_check(&self) -> Result<bool> {
+        let res: Result<serde_json::Value, HttpError> = self
+            .http
+            .get_json(
+                "res/v1/web/search",
+                RequestOpts {
+                    auth: Some(self.auth()?),
+                    query: Some(vec![("q", "health".into()), ("count", "1".into())]),
+                    retries: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await;
+        match res {
+            Ok(_) => Ok(true),
+            Err(HttpError::Api { status, .. }) if matches!(status.as_u16(), 401 | 403 | 422) => {
+                Ok(false)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn auth(&self) -> Result<Auth<'static>, HttpError> {
+        Ok(Auth::Header {
+            name: HeaderName::from_static("x-subscription-token"),
+            value: HeaderValue::from_str(&self.token)
+                .map_err(|e| HttpError::Build(e.to_string()))?,
+        })
+    }
+
+    pub async fn simple_query_search(&self, query: String) -> Result<WebSearchApiResponse> {
+        let params = vec![("q", query.clone().into())];
+        let query_snippet = query_snippet(&query);
+        let started = Instant::now();
+        tracing::info!(
+            target: "web.brave",
+            query = %query_snippet,
+            "brave.simple_query.start"
+        );
+
+        let resp: WebSearchApiResponse = match self
+            .http
+            .get_json(
+                "res/v1/web/search",
+                RequestOpts {
+                    auth: Some(self.auth()?),
+                    query: Some(params),
+                    retries: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await
+        {
+            Ok(resp) => {
+                tracing::info!(
+                    target: "web.brave",
+                    query = %query_snippet,
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "brave.simple_query.success"
+                );
+                resp
+            }
+            Err(e) => {
+                tracing::warn!(
+                    target: "web.brave",
+                    query = %query_snippet,
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    error = %e,
+                    "brave.simple_query.error"
+                );
+                return Err(anyhow::Error::new(e)).context("brave search request failed");
+            }
+        };
+        log_response_summary(&resp);
+        Ok(resp)
+    }
+
+    /// Single-page search honoring `count`, `freshness`, and `result_filter`.
+    pub async fn search(
+        &self,
+        query: &str,
+        opts: &BraveSearchOptions,
+    ) -> Result<WebSearchApiResponse> {
+        self.search_page(&opts.to_request(query)).await
+    }
+
+    /// Single-page call using `WebSearchRequest`, returning your `WebSearchApiResponse`.
+    pub async fn search_page(&self, req: &WebSearchRequest) -> Result<WebSearchApiResponse> {
+        let params = search_params(req);
+
+        let resp: WebSearchApiResponse = self
+            .http
+            .get_json(
+                "res/v1/web/search",
+                RequestOpts {
+                    auth: Some(self.auth()?),
+                    query: Some(params),
+                    retries: Some(0),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!(e.to_string()))
+            .context("brave search request failed")?;
+        log_response_summary(&resp);
+        Ok(resp)
+    }
+}
+
+/// `query` cut to `QUERY_LOG_CHARS` characters for logging, with `…` when cut.
+fn query_snippet(query: &str) -> String {
+    let mut chars = query.chars();
+    let snippet: String = chars.by_ref().take(QUERY_LOG_CHARS).collect();
+    if chars.next().is_some() {
+        format!("{snippet}…")
+    } else {
+        snippet
+    }
+}
+
+/// Result counts per vertical, instead of the whole response.
+fn log_response_summary(resp: &WebSearchApiResponse) {
+    tracing::debug!(
+        target: "web.brave",
+        web = resp.web.as_ref().map_or(0, |w| w.results.len()),
+        news = resp.news.as_ref().map_or(0, |n| n.results.len()),
+        videos = resp.videos.as_ref().map_or(0, |v| v.results.len()),
+        "brave.search.response"
+    );
+}
+
+/// Translate a `WebSearchRequest` into Brave query parameters.
+fn search_params(req: &WebSearchRequest) -> Vec<(&'static str, Cow<'_, str>)> {
+    let mut params: Vec<(&str, Cow<'_, str>)> = Vec::with_capacity(12);
+
+    // Required
+    params.push(("q", req.query.as_str().into()));
+
+    // Optional numeric
+    if let Some(v) = req.count {
+        params.push(("count", v.clamp(1, MAX_COUNT).to_string().into()));
+    }
+    if let Some(v) = req.offset {
+        params.push(("offset", v.to_string().into()));
+    }
+
+    // Optional strings
+    if let Some(ref v) = req.country
+        && !v.is_empty()
+    {
+        params.push(("country", v.as_str().into()));
+    }
+    if let Some(ref v) = req.search_lang
+        && !v.is_empty()
+    {
+        params.push(("search_lang", v.as_str().into()));
+    }
+    if let Some(v) = req.freshness {
+        params.push(("freshness", v.into()));
+    }
+    if let Some(v) = req.safesearch {
+        params.push(("safesearch", v.into()));
+    }
+    if let Some(ref v) = req.result_filter
+        && !v.is_empty()
+    {
+        params.push(("result_filter", v.as_str().into()));
+    }
+    if let Some(ref v) = req.goggles_id
+        && !v.is_empty()
+    {
+        params.push(("goggles_id", v.as_str().into()));
+    }
+
+    // Optional bools
+    if let Some(v) = req.extra_snippets {
+        params.push(("extra_snippets", (if v { "true" } else { "false" }).into()));
+    }
+    if let Some(v) = req.spellcheck_off {
+        params.push(("spellcheck_off", (if v { "true" } else { "false" }).into()));
+    }
+
+    // Default to "moderate" if caller did not set safesearch
+    if !params.iter().any(|(k, _)| *k == "safesearch") {
+        params.push(("safesearch", "moderate".into()));
+    }
+    params
+}
+
+/// Flatten a response into ranked hits, following Brave's `mixed.main` display order.
+pub fn collect_brave_hits(resp: &WebSearchApiResponse) -> Vec<BraveHit> {
+    let mut out = Vec::new();
+
+    let web: Option<&Vec<SearchResult>> = resp.web.as_ref().map(|w| &w.results);
+    let news: Option<&Vec<NewsResult>> = resp.news.as_ref().map(|n| &n.results);
+    let videos: Option<&Vec<VideoResult>> = resp.videos.as_ref().map(|v| &v.results);
+
+    if let Some(mixed) = resp.mixed.as_ref() {
+        for slot in &mixed.main {
+            let take_all = slot.all.unwrap_or(false);
+            match &slot.kind {
+                MixedKind::Web => {
+                    if take_all {
+                        if let Some(vec) = web {
+                            for it in vec {
+                                convert_and_push_search_result(&mut out, it);
+                            }
+                        }
+                    } else if let Some(vec) = web
+                        && let Some(it) = vec.get(slot.index)
+                    {
+                        convert_and_push_search_result(&mut out, it);
+                    }
+                }
+                MixedKind::News => {
+                    if take_all {
+                        if let Some(vec) = news {
+                            for it in vec {
+                                if let Some(u) = Url::parse(&it.url).ok()
+                                    && let t = &it.title
+                                    && let Some(d) = it.description.as_deref()
+                                {
+                                    out.push(BraveHit {
+                                        rank: (out.len() + 1) as u32,
+                                        title: t.to_string(),
+                                        url: u.to_string(),
+                                        description: Some(d.to_string()),
+                                    });
+                                }
+                            }
+                        }
+                    } else if let Some(vec) = news
+                        && let Some(it) = vec.get(slot.index)
+                        && let Ok(u) = Url::parse(it.url.as_str())
+                        && let t = &it.title
+                        && let Some(d) = &it.description
+                    {
+                        out.push(BraveHit {
+                            rank: (out.len() + 1) as u32,
+                            title: t.to_string(),
+                            url: u.to_string(),
+                            description: Some(d.to_string()),
+                        });
+                    }
+                }
+                MixedKind::Videos => {
+                    if take_all {
+                        if let Some(vec) = videos {
+                            for it in vec {
+                                if let Some(u) = Url::parse(&it.url).ok()
+                                    && let t = &it.title
+                                    && let Some(d) = it.description.as_deref()
+                                {
+                                    out.push(BraveHit {
+                                        rank: (out.len() + 1) as u32,
+                                        title: t.to_string(),
+                                        url: u.to_string(),
+                                        description: Some(d.to_string()),
+                                    });
+                                }
+                            }
+                        }
+                    } else if let Some(vec) = videos
+                        && let Some(it) = vec.get(slot.index)
+                        && let Ok(u) = Url::parse(it.url.as_str())
+                        && let t = &it.title
+                        && let Some(d) = &it.description
+                    {
+                        out.push(BraveHit {
+                            rank: (out.len() + 1) as u32,
+                            title: t.to_string(),
+                            url: u.to_string(),
+                            description: Some(d.to_string()),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    out
+}
+
+/// Result URLs restricted to `verticals`, in display order (falls back to vertical order).
+pub fn collect_urls_in_display_order(
+    resp: &WebSearchApiResponse,
+    verticals: Verticals,
+) -> Vec<Url> {
+    let Verticals {
+        web: want_web,
+        news: want_news,
+        videos: want_videos,
+    } = verticals;
+    let mut out = Vec::new();
+
+    // Borrow the inner Vecs (don't move them out of resp)
+    let web: Option<&Vec<SearchResult>> = resp.web.as_ref().map(|w| &w.results);
+    let news: Option<&Vec<NewsResult>> = resp.news.as_ref().map(|n| &n.results);
+    let videos: Option<&Vec<VideoResult>> = resp.videos.as_ref().map(|v| &v.results);
+
+    // 1) Preferred: use mixed.main ordering when present
+    if let Some(mixed) = resp.mixed.as_ref() {
+        for slot in &mixed.main {
+            let take_all = slot.all.unwrap_or(false);
+            match &slot.kind {
+                MixedKind::Web if want_web => {
+                    if take_all {
+                        if let Some(vec) = web {
+                            for it in vec {
+                                push_web_result_urls(&mut out, it);
+                            }
+                        }
+                    } else if let Some(vec) = web
+                        && let Some(it) = vec.get(slot.index)
+                    {
+                        push_web_result_urls(&mut out, it);
+                    }
+                }
+                MixedKind::News if want_news => {
+                    if take_all {
+                        if let Some(vec) = news {
+                            for it in vec {
+                                if let Ok(u) = Url::parse(it.url.as_str()) {
+                                    out.push(u);
+                                }
+                            }
+                        }
+                    } else if let Some(vec) = news
+                        && let Some(it) = vec.get(slot.index)
+                        && let Ok(u) = Url::parse(it.url.as_str())
+                    {
+                        out.push(u);
+                    }
+                }
+                MixedKind::Videos if want_videos => {
+                    if take_all {
+                        if let Some(vec) = videos {
+                            for it in vec {
+                                if let Ok(u) = Url::parse(it.url.as_str()) {
+                                    out.push(u);
+                                }
+                            }
+                        }
+                    } else if let Some(vec) = videos
+                        && let Some(it) = vec.get(slot.index)
+                        && let Ok(u) = Url::parse(it.url.as_str())
+                    {
+                        out.push(u);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // 2) Fallback: if mixed was absent or yielded nothing, append verticals in order
+    if out.is_empty() {
+        if want_web && let Some(vec) = web {
+            for it in vec {
+                push_web_result_urls(&mut out, it);
+            }
+        }
+        if want_news && let Some(vec) = news {
+            for it in vec {
+                if let Ok(u) = Url::parse(it.url.as_str()) {
+                    out.push(u);
+                }
+            }
+        }
+        if want_videos && let Some(vec) = videos {
+            for it in vec {
+                if let Ok(u) = Url::parse(it.url.as_str()) {
+                    out.push(u);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn convert_and_push_search_result(out: &mut Vec<BraveHit>, it: &SearchResult) {
+    if let Some(u) = it.url.as_deref().and_then(|s| Url::parse(s).ok())
+        && let Some(t) = it.title.as_deref()
+        && let Some(d) = it.description.as_deref()
+    {
+        out.push(BraveHit {
+            rank: (out.len() + 1) as u32,
+            title: t.to_string(),
+            url: u.to_string(),
+            description: Some(d.to_string()),
+        });
+        return;
+    }
+
+    if let Some(cluster) = it.cluster.as_ref() {
+        for item in cluster {
+            if let Ok(u) = Url::parse(item.url.as_str())
+                && let t = &item.title
+                && let Some(d) = item.description.as_deref()
+            {
+                out.push(BraveHit {
+                    rank: (out.len() + 1) as u32,
+                    title: t.to_string(),
+                    url: u.to_string(),
+                    description: Some(d.to_string()),
+                });
+            }
+        }
+    }
+}
+
+/// Web results can be a plain result with `url: Option<String>`
+/// OR a "cluster" with multiple `ResultItem`s. Handle both.
+///
+/// - If `it.url` is present, push that.
+/// - Else, expand `cluster` and push each item's URL.
+///
+/// Parsing failures are silently ignored (keeps this utility focused).
+fn push_web_result_urls(out: &mut Vec<Url>, it: &SearchResult) {
+    if let Some(u) = it.url.as_deref().and_then(|s| Url::parse(s).ok()) {
+        out.push(u);
+        return;
+    }
+    if let Some(cluster) = it.cluster.as_ref() {
+        for item in cluster {
+            if let Ok(u) = Url::parse(item.url.as_str()) {
+                out.push(u);
+            }
+        }
+    }
+}
+
+pub use nowhere_common::urls::url_key;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn param<'a>(params: &'a [(&'static str, Cow<'_, str>)], key: &str) -> Option<&'a str> {
+        params
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_ref())
+    }
+
+    #[test]
+    fn search_options_map_to_query_params() {
+        let opts = BraveSearchOptions {
+            count: Some(30),
+            freshness: "pw".parse().unwrap(),
+            result_filter: Some("web,news".parse().unwrap()),
+        };
+        let req = opts.to_request("lab leak");
+        let params = search_params(&req);
+
+        assert_eq!(param(&params, "q"), Some("lab leak"));
+        assert_eq!(
+            param(&params, "count"),
+            Some("20"),
+            "clamped to Brave's cap"
+        );
+        assert_eq!(param(&params, "freshness"), Some("pw"));
+        assert_eq!(param(&params, "result_filter"), Some("web,news"));
+        assert_eq!(param(&params, "safesearch"), Some("moderate"));
+    }
+
+    #[test]
+    fn zero_count_is_raised_to_one() {
+        let opts = BraveSearchOptions {
+            count: Some(0),
+            ..Default::default()
+        };
+        let req = opts.to_request("q");
+        let params = search_params(&req);
+        assert_eq!(param(&params, "count"), Some("1"));
+    }
+
+    #[test]
+    fn long_non_ascii_queries_are_cut_on_character_boundaries() {
+        // Two-byte characters put byte 160 in the middle of one.
+        let query = "é".repeat(200);
+        let snippet = query_snippet(&query);
+        assert_eq!(snippet.chars().count(), QUERY_LOG_CHARS + 1);
+        assert!(snippet.ends_with('…'));
+        assert_eq!(query_snippet("ferry sank"), "ferry sank");
+    }
+
+    #[test]
+    fn default_options_leave_brave_defaults() {
+        let req = BraveSearchOptions::default().to_request("q");
+        let params = search_params(&req);
+
+        assert_eq!(param(&params, "count"), None);
+        assert_eq!(param(&params, "freshness"), None);
+        assert_eq!(param(&params, "result_filter"), None);
+    }
+
+    #[tokio::test]
+    async fn health_check_maps_a_rejected_token_to_unhealthy() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/res/v1/web/search"))
+            .and(header("x-subscription-token", "good"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "type": "search" })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/res/v1/web/search"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let good = BraveApi::with_base_url("good".into(), &server.uri()).unwrap();
+        assert!(good.health_check().await.unwrap());
+        let expired = BraveApi::with_base_url("expired".into(), &server.uri()).unwrap();
+        assert!(!expired.health_check().await.unwrap());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod artifact;
 pub mod client;
 pub mod types;
@@ -1,9 +1,12 @@
 use anyhow::{Result, anyhow};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
+use nowhere_common::NowhereConfig;
 use nowhere_drivers::nowhere_browser::driver::NowhereDriver;
 use nowhere_drivers::nowhere_browser::stealth::StealthProfile;
 use nowhere_llm::traits::LlmClient;
-use regex::Regex;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -25,165 +28,555 @@ pub trait BrowserCapturer: Send + Sync {
     ) -> Result<PageCapture>;
 }
 
-/// Concrete capturer backed by your fantoccini-based driver.
-pub struct FantocciniCapturer;
+/// Limits for one page capture.
+#[derive(Debug, Clone)]
+pub struct CaptureOptions {
+    /// Longest a single navigate-and-read attempt may take.
+    pub nav_timeout: Duration,
+    /// Further attempts after the first one fails or times out.
+    pub retries: usize,
+    /// Also grab a PNG of the rendered page. A failed screenshot is logged, not fatal.
+    pub screenshot: bool,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            nav_timeout: Duration::from_secs(30),
+            retries: 1,
+            screenshot: false,
+        }
+    }
+}
+
+/// The browser session operations a capture needs; `NowhereDriver` in production.
+#[async_trait::async_trait]
+pub trait PageDriver: Send + Sized {
+    async fn goto(&mut self, url: &str) -> Result<()>;
+
+    /// Apply `profile`'s evasions from the next navigation on, without reopening the
+    /// session. Drivers without per-page evasions ignore it.
+    fn set_stealth_profile(&mut self, _profile: StealthProfile) {}
+
+    /// Source of the current page.
+    async fn get_content(&mut self) -> Result<String>;
+
+    /// PNG of the current viewport.
+    async fn screenshot(&mut self) -> Result<Vec<u8>>;
+
+    /// Whether an idle session can still take commands; checked before a pooled session
+    /// is reused.
+    async fn is_healthy(&mut self) -> bool {
+        true
+    }
+
+    async fn close(self) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+impl PageDriver for NowhereDriver {
+    async fn goto(&mut self, url: &str) -> Result<()> {
+        NowhereDriver::goto(self, url).await.map(|_| ())
+    }
+
+    fn set_stealth_profile(&mut self, profile: StealthProfile) {
+        self.stealth_profile = profile;
+    }
+
+    async fn get_content(&mut self) -> Result<String> {
+        Ok(self.client.source().await?)
+    }
+
+    async fn screenshot(&mut self) -> Result<Vec<u8>> {
+        Ok(self.client.screenshot().await?)
+    }
+
+    async fn is_healthy(&mut self) -> bool {
+        self.client.current_url().await.is_ok()
+    }
+
+    async fn close(self) -> Result<()> {
+        NowhereDriver::close(self).await
+    }
+}
+
+/// Opens a fresh [`PageDriver`] session for each capture.
+#[async_trait::async_trait]
+pub trait PageDriverFactory: Send + Sync {
+    type Driver: PageDriver;
+
+    async fn open(&self, headless: bool, profile: StealthProfile) -> Result<Self::Driver>;
+}
+
+/// Sessions on the local WebDriver service via [`NowhereDriver::new`].
+#[derive(Debug, Clone, Default)]
+pub struct NowhereDrivers;
+
+#[async_trait::async_trait]
+impl PageDriverFactory for NowhereDrivers {
+    type Driver = NowhereDriver;
+
+    async fn open(&self, headless: bool, profile: StealthProfile) -> Result<NowhereDriver> {
+        NowhereDriver::new(headless, profile).await
+    }
+}
+
+/// A session checked out of a [`DriverPool`]; hand it back with [`DriverPool::checkin`].
+pub struct PooledDriver<D> {
+    pub driver: D,
+    headless: bool,
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+struct IdleDriver<D> {
+    driver: D,
+    headless: bool,
+}
+
+/// Bounded set of reusable browser sessions.
+///
+/// At most `size` sessions are open at once. A checkout reuses a healthy idle session
+/// opened with the same headless flag, switched to the requested stealth profile (see
+/// [`PageDriver::set_stealth_profile`]), or opens a new one, and waits while
+/// all `size` are checked out. A session handed back as unhealthy is closed, so the next
+/// checkout opens a replacement. With `size == 0` nothing is kept: every checkout opens a
+/// fresh session and checkin closes it.
+pub struct DriverPool<F: PageDriverFactory> {
+    drivers: F,
+    size: usize,
+    slots: Option<Arc<Semaphore>>,
+    idle: Mutex<Vec<IdleDriver<F::Driver>>>,
+}
+
+impl<F: PageDriverFactory> DriverPool<F> {
+    pub fn new(drivers: F, size: usize) -> Self {
+        Self {
+            drivers,
+            size,
+            slots: (size > 0).then(|| Arc::new(Semaphore::new(size))),
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Take a session for one capture.
+    pub async fn checkout(
+        &self,
+        headless: bool,
+        profile: StealthProfile,
+    ) -> Result<PooledDriver<F::Driver>> {
+        let slot = match &self.slots {
+            Some(slots) => Some(slots.clone().acquire_owned().await?),
+            None => None,
+        };
+        loop {
+            let reusable = {
+                let mut idle = self.idle.lock().unwrap();
+                let found = idle.iter().position(|s| s.headless == headless);
+                found.map(|i| idle.swap_remove(i))
+            };
+            let Some(mut session) = reusable else { break };
+            if session.driver.is_healthy().await {
+                tracing::debug!(size = self.size, "browser.pool.reused");
+                session.driver.set_stealth_profile(profile);
+                return Ok(PooledDriver {
+                    driver: session.driver,
+                    headless,
+                    _slot: slot,
+                });
+            }
+            tracing::info!("browser.pool.unhealthy_session_dropped");
+            let _ = session.driver.close().await;
+        }
+
+        // Idle sessions in the other headless mode still count toward `size`; retire one to
+        // make room for the new session.
+        let stale = self.idle.lock().unwrap().pop();
+        if let Some(stale) = stale {
+            let _ = stale.driver.close().await;
+        }
+        let driver = self.drivers.open(headless, profile).await?;
+        tracing::debug!(size = self.size, "browser.pool.opened");
+        Ok(PooledDriver {
+            driver,
+            headless,
+            _slot: slot,
+        })
+    }
+
+    /// Return a session after a capture. Pass `healthy: false` when the capture failed, so
+    /// the session is closed instead of being handed to the next capture.
+    pub async fn checkin(&self, session: PooledDriver<F::Driver>, healthy: bool) {
+        // The slot is released at the end of this call, once the session is idle or closed.
+        let PooledDriver {
+            driver,
+            headless,
+            _slot,
+        } = session;
+        if healthy && self.slots.is_some() {
+            self.idle
+                .lock()
+                .unwrap()
+                .push(IdleDriver { driver, headless });
+        } else if let Err(err) = driver.close().await {
+            tracing::debug!(error = %err, "browser.pool.close_failed");
+        }
+    }
+
+    /// Close every idle session, e.g. on shutdown.
+    pub async fn close_idle(&self) {
+        let idle = std::mem::take(&mut *self.idle.lock().unwrap());
+        for session in idle {
+            let _ = session.driver.close().await;
+        }
+    }
+}
+
+impl<F: PageDriverFactory> std::fmt::Debug for DriverPool<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DriverPool")
+            .field("size", &self.size)
+            .field(
+                "idle",
+                &self.idle.lock().map(|idle| idle.len()).unwrap_or(0),
+            )
+            .finish()
+    }
+}
+
+/// Navigate to `url` and read the page, with up to `1 + options.retries` attempts, each
+/// bounded by `options.nav_timeout`.
+pub async fn fetch_html_with_retries<D: PageDriver>(
+    driver: &mut D,
+    url: &Url,
+    options: &CaptureOptions,
+) -> Result<String> {
+    let attempts = options.retries + 1;
+    let mut last_err = anyhow!("no capture attempt made for {url}");
+    for attempt in 1..=attempts {
+        let fetch = async {
+            driver.goto(url.as_str()).await?;
+            driver.get_content().await
+        };
+        match tokio::time::timeout(options.nav_timeout, fetch).await {
+            Ok(Ok(html)) => return Ok(html),
+            Ok(Err(err)) => last_err = err,
+            Err(_) => {
+                last_err = anyhow!(
+                    "navigation to {url} timed out after {:?}",
+                    options.nav_timeout
+                )
+            }
+        }
+        tracing::warn!(%url, attempt, attempts, error = %last_err, "browser.capture.attempt_failed");
+    }
+    Err(last_err.context(format!(
+        "capturing {url} failed after {attempts} attempt(s)"
+    )))
+}
+
+/// Capturer that drives a real browser session per page; the session source is
+/// pluggable so tests can inject a fake [`PageDriver`].
+#[derive(Debug, Clone)]
+pub struct FantocciniCapturer<F: PageDriverFactory = NowhereDrivers> {
+    drivers: Arc<DriverPool<F>>,
+    options: CaptureOptions,
+}
+
+impl FantocciniCapturer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reuse up to `cfg.browser_sessions` sessions on the local WebDriver service.
+    pub fn from_config(cfg: &NowhereConfig) -> Self {
+        Self::with_pool(DriverPool::new(NowhereDrivers, cfg.browser_sessions))
+    }
+}
+
+impl Default for FantocciniCapturer {
+    fn default() -> Self {
+        Self::with_drivers(NowhereDrivers)
+    }
+}
+
+impl<F: PageDriverFactory> FantocciniCapturer<F> {
+    /// Open a fresh session from `drivers` for each capture instead of using the local
+    /// WebDriver service.
+    pub fn with_drivers(drivers: F) -> Self {
+        Self::with_pool(DriverPool::new(drivers, 0))
+    }
+
+    /// Check sessions out of `pool`, reusing them across captures.
+    pub fn with_pool(pool: DriverPool<F>) -> Self {
+        Self {
+            drivers: Arc::new(pool),
+            options: CaptureOptions::default(),
+        }
+    }
+
+    /// Override the navigation timeout, retry budget and screenshot flag.
+    pub fn with_options(mut self, options: CaptureOptions) -> Self {
+        self.options = options;
+        self
+    }
+}
 
 #[async_trait::async_trait]
-impl BrowserCapturer for FantocciniCapturer {
+impl<F: PageDriverFactory> BrowserCapturer for FantocciniCapturer<F> {
     async fn capture(
         &self,
         url: &Url,
         headless: bool,
         profile: StealthProfile,
-        llm_client: &dyn LlmClient,
+        _llm_client: &dyn LlmClient,
     ) -> Result<PageCapture> {
-        let mut driver = NowhereDriver::new(headless, profile).await?;
-        let page = driver.goto(url.as_str()).await?;
-        let html = page.get_content().await?;
-
-        // let system_prompt = PUBDATE_FINDER_SYSTEM_PROMPT;
-        // let user_prompt = build_pubdate_finder_html_prompt(&html);
-
-        // let resp = llm_client
-        //     .generate(&user_prompt, Some(system_prompt), None, Some(0.2))
-        //     .await
-        //     .map_err(|e| anyhow!(format!("LLM error: {e}")))?;
-        //
-        // let text = resp.text.trim();
-        // let json = extract_json_block(text).unwrap_or_else(|| text.to_string());
-        //
-        // // Parse the object first, then pull the string
-        // let published_at = parse_pubdate_json(&json)
-        //     .map_err(|e| anyhow!("Failed to parse datetime for publication date: {e}: {json}"))?;
-        //
-        // Always attempt to close the driver before returning
-        let result = Ok(PageCapture {
+        let mut session = self.drivers.checkout(headless, profile).await?;
+        let html = fetch_html_with_retries(&mut session.driver, url, &self.options).await;
+        let screenshot_png = match &html {
+            Ok(_) if self.options.screenshot => match session.driver.screenshot().await {
+                Ok(png) => Some(png),
+                Err(err) => {
+                    tracing::warn!(%url, error = %err, "browser.capture.screenshot_failed");
+                    None
+                }
+            },
+            _ => None,
+        };
+        // Always hand the session back; one that failed the page is closed, not reused.
+        self.drivers.checkin(session, html.is_ok()).await;
+        let html = html?;
+
+        Ok(PageCapture {
             url: url.clone(),
             html,
-            screenshot_png: None,
+            screenshot_png,
             published_at: None,
-        });
-        let _ = driver.close().await;
-        result
-    }
-}
-
-const PUBDATE_FINDER_SYSTEM_PROMPT: &str = r#"
-You are an expert HTML analyzer. Your goal is to find any publication date within the provided HTML.
-Return only strict JSON as instructed by the user prompt.
-"#;
-
-fn build_pubdate_finder_html_prompt(html_string: &str) -> String {
-    // Be explicit: published_at must be an RFC3339 string or null.
-    format!(
-        r#"
-Return STRICT JSON ONLY, matching exactly this shape:
-
-{{
-  "published_at": "<RFC3339 timestamp string>" | null
-}}
-
-Rules:
-- If a clear publication date exists (e.g., meta tags like datePublished, article:published_time, time tags, etc.), output it as an RFC3339 string (e.g., "2025-08-15T15:14:04+00:00").
-- If you cannot find a trustworthy publication date, set "published_at" to null.
-- Do not include any other properties or text.
-
-HTML:
-{html_string}
-"#,
-        html_string = html_string
-    )
-}
-
-/// Try to extract a ```json ... ``` fenced block; fall back to raw.
-fn extract_json_block(text: &str) -> Option<String> {
-    let re_fence = Regex::new("(?s)```json\\s*(\\{.*?\\})\\s*```").ok()?;
-    if let Some(caps) = re_fence.captures(text) {
-        return Some(caps.get(1)?.as_str().to_string());
-    }
-    let re_plain = Regex::new("(?s)(\\{.*\\})").ok()?;
-    re_plain
-        .captures(text)
-        .and_then(|c| c.get(1).map(|m| m.as_str().to_string()))
-}
-
-/// Parse {"published_at":"..."} (and a few key aliases) into Option<DateTime<Utc>>.
-fn parse_pubdate_json(json: &str) -> Result<Option<DateTime<Utc>>> {
-    let v: serde_json::Value = serde_json::from_str(json)?;
-
-    // Primary and common alias keys we might accept
-    let candidates = &[
-        "published_at",
-        "date_published",
-        "datePublished",
-        "article_published_time",
-        "article:published_time",
-        "publishedAt",
-    ];
-
-    // Find the first present key as a string
-    let mut s_opt: Option<String> = None;
-    for k in candidates {
-        if let Some(val) = v.get(*k) {
-            if val.is_null() {
-                s_opt = None;
-                break;
-            }
-            if let Some(s) = val.as_str() {
-                s_opt = Some(s.to_string());
-                break;
-            }
-            // if it's nested like {"published_at":{"value":"..."}} try common subkey
-            if let Some(obj) = val.as_object() {
-                for sub in ["value", "timestamp", "time"] {
-                    if let Some(serde_json::Value::String(s)) = obj.get(sub) {
-                        s_opt = Some(s.to_string());
-                        break;
-                    }
-                }
-                if s_opt.is_some() {
-                    break;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nowhere_llm::mock::MockLlmClient;
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    enum Step {
+        Hang,
+        Fail,
+        Html(&'static str),
+    }
+
+    #[derive(Default)]
+    struct Log {
+        attempts: usize,
+        screenshots: usize,
+        closed: bool,
+    }
+
+    /// Plays `steps` in order, one per navigation, and records what was asked of it.
+    struct FakeDriver {
+        steps: VecDeque<Step>,
+        html: Option<&'static str>,
+        log: Arc<Mutex<Log>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PageDriver for FakeDriver {
+        async fn goto(&mut self, _url: &str) -> Result<()> {
+            self.log.lock().unwrap().attempts += 1;
+            match self.steps.pop_front() {
+                Some(Step::Hang) => std::future::pending().await,
+                Some(Step::Html(html)) => {
+                    self.html = Some(html);
+                    Ok(())
                 }
+                Some(Step::Fail) | None => Err(anyhow!("net::ERR_CONNECTION_RESET")),
             }
         }
+
+        async fn get_content(&mut self) -> Result<String> {
+            Ok(self.html.unwrap_or_default().to_string())
+        }
+
+        async fn screenshot(&mut self) -> Result<Vec<u8>> {
+            self.log.lock().unwrap().screenshots += 1;
+            Ok(b"\x89PNG".to_vec())
+        }
+
+        async fn close(self) -> Result<()> {
+            self.log.lock().unwrap().closed = true;
+            Ok(())
+        }
     }
 
-    // Also support the exact JSON being just {"published_at": "..."} and nothing else
-    if s_opt.is_none() {
-        if let Some(s) = v
-            .get("published_at")
-            .and_then(|x| x.as_str())
-            .map(|s| s.to_string())
-        {
-            s_opt = Some(s);
+    /// Hands out one prepared `FakeDriver`.
+    struct FakeDrivers(Mutex<Option<FakeDriver>>);
+
+    #[async_trait::async_trait]
+    impl PageDriverFactory for FakeDrivers {
+        type Driver = FakeDriver;
+
+        async fn open(&self, _headless: bool, _profile: StealthProfile) -> Result<FakeDriver> {
+            self.0
+                .lock()
+                .unwrap()
+                .take()
+                .ok_or_else(|| anyhow!("driver already used"))
         }
     }
 
-    let s = match s_opt {
-        None => return Ok(None),
-        Some(s) => s.trim().to_string(),
-    };
-    if s.is_empty() {
-        return Ok(None);
+    fn capturer(
+        steps: Vec<Step>,
+        retries: usize,
+    ) -> (FantocciniCapturer<FakeDrivers>, Arc<Mutex<Log>>) {
+        let log = Arc::new(Mutex::new(Log::default()));
+        let driver = FakeDriver {
+            steps: steps.into(),
+            html: None,
+            log: log.clone(),
+        };
+        let capturer = FantocciniCapturer::with_drivers(FakeDrivers(Mutex::new(Some(driver))))
+            .with_options(CaptureOptions {
+                nav_timeout: Duration::from_millis(20),
+                retries,
+                screenshot: false,
+            });
+        (capturer, log)
+    }
+
+    async fn capture(capturer: &FantocciniCapturer<FakeDrivers>) -> Result<PageCapture> {
+        let url = Url::parse("https://example.com/story").unwrap();
+        capturer
+            .capture(
+                &url,
+                true,
+                StealthProfile::Lightweight,
+                &MockLlmClient::new(),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn capture_returns_the_page_html_and_closes_the_driver() {
+        let (capturer, log) = capturer(vec![Step::Html("<html>story</html>")], 0);
+        let page = capture(&capturer).await.unwrap();
+        assert_eq!(page.html, "<html>story</html>");
+        assert_eq!(page.screenshot_png, None);
+        let log = log.lock().unwrap();
+        assert_eq!((log.attempts, log.screenshots, log.closed), (1, 0, true));
     }
 
-    // Try RFC3339 first (handles offsets like +00:00)
-    if let Ok(dt) = DateTime::parse_from_rfc3339(&s) {
-        return Ok(Some(dt.with_timezone(&Utc)));
+    #[tokio::test]
+    async fn hung_navigation_is_retried_then_succeeds() {
+        let (capturer, log) = capturer(vec![Step::Hang, Step::Html("<html>ok</html>")], 1);
+        let capturer = capturer.with_options(CaptureOptions {
+            nav_timeout: Duration::from_millis(20),
+            retries: 1,
+            screenshot: true,
+        });
+        let page = capture(&capturer).await.unwrap();
+        assert_eq!(page.html, "<html>ok</html>");
+        assert!(page.screenshot_png.is_some());
+        let log = log.lock().unwrap();
+        assert_eq!((log.attempts, log.screenshots, log.closed), (2, 1, true));
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_fail_cleanly_and_close_the_driver() {
+        let (capturer, log) = capturer(vec![Step::Fail, Step::Hang, Step::Hang], 2);
+        let err = capture(&capturer).await.unwrap_err();
+        assert!(format!("{err:#}").contains("timed out"), "{err:#}");
+        let log = log.lock().unwrap();
+        assert_eq!((log.attempts, log.closed), (3, true));
+    }
+
+    /// A session that only knows its own number and current stealth profile.
+    struct Session(usize, StealthProfile);
+
+    #[async_trait::async_trait]
+    impl PageDriver for Session {
+        async fn goto(&mut self, _url: &str) -> Result<()> {
+            Ok(())
+        }
+
+        fn set_stealth_profile(&mut self, profile: StealthProfile) {
+            self.1 = profile;
+        }
+
+        async fn get_content(&mut self) -> Result<String> {
+            Ok(String::new())
+        }
+
+        async fn screenshot(&mut self) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        async fn close(self) -> Result<()> {
+            Ok(())
+        }
     }
 
-    // Try naive "YYYY-MM-DDTHH:MM:SS" as UTC
-    if let Ok(ndt) = NaiveDateTime::parse_from_str(&s, "%Y-%m-%dT%H:%M:%S") {
-        return Ok(Some(DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc)));
+    /// Numbers the sessions it opens.
+    #[derive(Default)]
+    struct Numbered(Mutex<usize>);
+
+    #[async_trait::async_trait]
+    impl PageDriverFactory for Numbered {
+        type Driver = Session;
+
+        async fn open(&self, _headless: bool, profile: StealthProfile) -> Result<Session> {
+            let mut opened = self.0.lock().unwrap();
+            *opened += 1;
+            Ok(Session(*opened - 1, profile))
+        }
     }
 
-    // Try "YYYY-MM-DD" as midnight UTC
-    if let Ok(date) = chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
-        let ndt = date
-            .and_hms_opt(0, 0, 0)
-            .unwrap_or_else(|| NaiveDateTime::MIN);
-        return Ok(Some(DateTime::<Utc>::from_naive_utc_and_offset(ndt, Utc)));
+    #[tokio::test]
+    async fn pool_reuses_sessions_up_to_its_size_and_replaces_unhealthy_ones() {
+        let pool = DriverPool::new(Numbered::default(), 2);
+        let checkout = || pool.checkout(true, StealthProfile::Balanced);
+        let opened = || *pool.drivers.0.lock().unwrap();
+
+        let first = checkout().await.unwrap();
+        assert_eq!(first.driver.0, 0);
+        pool.checkin(first, true).await;
+        let first = checkout().await.unwrap();
+        assert_eq!((first.driver.0, opened()), (0, 1), "idle session is reused");
+
+        // Under contention a second session is opened, and a third capture waits.
+        let second = checkout().await.unwrap();
+        assert_eq!((second.driver.0, opened()), (1, 2));
+        let waiting = tokio::time::timeout(Duration::from_millis(50), checkout()).await;
+        assert!(waiting.is_err(), "pool must not exceed its size");
+
+        pool.checkin(first, true).await;
+        let third = checkout().await.unwrap();
+        assert_eq!((third.driver.0, opened()), (0, 2));
+
+        pool.checkin(second, false).await;
+        let replacement = checkout().await.unwrap();
+        assert_eq!(
+            (replacement.driver.0, opened()),
+            (2, 3),
+            "failed session is replaced"
+        );
     }
 
-    Err(anyhow!("unrecognized date format: {}", s))
+    #[tokio::test]
+    async fn pooled_session_switches_profile_instead_of_reopening() {
+        let pool = DriverPool::new(Numbered::default(), 1);
+        let light = pool
+            .checkout(true, StealthProfile::Lightweight)
+            .await
+            .unwrap();
+        assert_eq!(light.driver.1, StealthProfile::Lightweight);
+        pool.checkin(light, true).await;
+
+        let heavy = pool.checkout(true, StealthProfile::Maximum).await.unwrap();
+        assert_eq!(heavy.driver.0, 0, "same session");
+        assert_eq!(heavy.driver.1, StealthProfile::Maximum);
+        assert_eq!(*pool.drivers.0.lock().unwrap(), 1, "nothing reopened");
+    }
 }
@@ -0,0 +1,321 @@
+//! Archival capture of web hits before normalization.
+//!
+//! [`WebCaptureActor`] takes web `RawArtifact`s (see [`crate::brave::artifact`]), renders
+//! each page with a [`BrowserCapturer`] with screenshots on, writes the HTML and PNG under
+//! an archive directory and records their paths in the payload's `capture` object. The
+//! artifact then goes on to the LLM actor, which copies the paths into its `Provenance`, so
+//! the stored row points at the evidence. A failed capture is logged and the artifact is
+//! forwarded without it.
+use crate::browser::BrowserCapturer;
+use anyhow::{Result, anyhow};
+use chrono::Utc;
+use nowhere_actors::actor::{Actor, Addr, Context};
+use nowhere_actors::llm::LlmActor;
+use nowhere_actors::store::ArtifactCap;
+use nowhere_actors::{LlmMsg, RawArtifact};
+use nowhere_common::{NowhereConfig, StealthLevel};
+use nowhere_drivers::nowhere_browser::stealth::StealthProfile;
+use nowhere_llm::traits::LlmClient;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use url::Url;
+
+/// Captures run at the same time by default.
+pub const DEFAULT_CAPTURE_CONCURRENCY: usize = 2;
+
+/// Browser profile for a configured stealth level.
+pub fn stealth_profile(level: &StealthLevel) -> StealthProfile {
+    match level {
+        StealthLevel::Lightweight => StealthProfile::Lightweight,
+        StealthLevel::Balanced => StealthProfile::Balanced,
+        StealthLevel::Maximum => StealthProfile::Maximum,
+    }
+}
+
+pub struct WebCaptureActor<A: Actor<Msg = LlmMsg> = LlmActor> {
+    capturer: Arc<dyn BrowserCapturer>,
+    llm: Arc<dyn LlmClient + Send + Sync>,
+    out: Addr<A>,
+    archive_dir: PathBuf,
+    headless: bool,
+    profile: StealthProfile,
+    slots: Arc<Semaphore>,
+    artifact_cap: Option<ArtifactCap>,
+}
+
+impl<A: Actor<Msg = LlmMsg>> WebCaptureActor<A> {
+    /// `capturer` should be configured to take screenshots; pages are archived under
+    /// `archive_dir` and then forwarded to `out`.
+    pub fn new(
+        capturer: Arc<dyn BrowserCapturer>,
+        llm: Arc<dyn LlmClient + Send + Sync>,
+        out: Addr<A>,
+        archive_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            capturer,
+            llm,
+            out,
+            archive_dir: archive_dir.into(),
+            headless: true,
+            profile: StealthProfile::Balanced,
+            slots: Arc::new(Semaphore::new(DEFAULT_CAPTURE_CONCURRENCY)),
+            artifact_cap: None,
+        }
+    }
+
+    /// Take the stealth level, headless flag and capture concurrency from the runtime
+    /// config; pair with [`crate::browser::FantocciniCapturer::from_config`] so every
+    /// running capture can hold a pooled session.
+    pub fn with_config(mut self, cfg: &NowhereConfig) -> Self {
+        self.profile = stealth_profile(&cfg.stealth_level);
+        self.headless = cfg.headless;
+        match cfg.browser_sessions {
+            0 => self,
+            n => self.with_concurrency(n),
+        }
+    }
+
+    /// Run at most `n` captures (browser sessions) at once.
+    pub fn with_concurrency(mut self, n: usize) -> Self {
+        self.slots = Arc::new(Semaphore::new(n.max(1)));
+        self
+    }
+
+    /// Drop hits for a claim, uncaptured, once the store holds `cap.max` artifacts for it.
+    pub fn with_artifact_cap(mut self, cap: ArtifactCap) -> Self {
+        self.artifact_cap = Some(cap);
+        self
+    }
+}
+
+/// Everything one capture task needs, cloned out of the actor.
+struct CaptureJob<A: Actor<Msg = LlmMsg>> {
+    capturer: Arc<dyn BrowserCapturer>,
+    llm: Arc<dyn LlmClient + Send + Sync>,
+    out: Addr<A>,
+    archive_dir: PathBuf,
+    headless: bool,
+    profile: StealthProfile,
+}
+
+impl<A: Actor<Msg = LlmMsg>> CaptureJob<A> {
+    async fn run(self, mut artifact: RawArtifact) -> Result<()> {
+        if !artifact.cancel.is_cancelled() {
+            match self.archive(&artifact).await {
+                Ok(capture) => {
+                    if let Some(payload) = artifact.payload.as_object_mut() {
+                        payload.insert("capture".into(), capture);
+                    }
+                }
+                Err(err) => tracing::warn!(
+                    external_id = %artifact.external_id,
+                    error = %format!("{err:#}"),
+                    "web.capture.failed"
+                ),
+            }
+        }
+        let external_id = artifact.external_id.clone();
+        self.out
+            .send(LlmMsg::NormalizeArtifact(artifact))
+            .await
+            .map_err(|_| anyhow!("normalize actor mailbox dropped (artifact={external_id})"))
+    }
+
+    /// Capture the artifact's page and write it out; returns the `capture` payload object.
+    async fn archive(&self, artifact: &RawArtifact) -> Result<serde_json::Value> {
+        let url = artifact
+            .payload
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("web artifact has no url"))?;
+        let url = Url::parse(url)?;
+        let page = self
+            .capturer
+            .capture(&url, self.headless, self.profile.clone(), self.llm.as_ref())
+            .await?;
+
+        tokio::fs::create_dir_all(&self.archive_dir).await?;
+        let stem = blake3::hash(artifact.external_id.as_bytes()).to_hex();
+        let html_path = self.archive_dir.join(format!("{stem}.html"));
+        tokio::fs::write(&html_path, &page.html).await?;
+        let screenshot_path = match &page.screenshot_png {
+            Some(png) => {
+                let path = self.archive_dir.join(format!("{stem}.png"));
+                tokio::fs::write(&path, png).await?;
+                Some(path)
+            }
+            None => None,
+        };
+        tracing::info!(
+            external_id = %artifact.external_id,
+            html_path = %html_path.display(),
+            screenshot = screenshot_path.is_some(),
+            "web.capture.archived"
+        );
+        Ok(json!({
+            "html_path": path_string(&html_path),
+            "screenshot_path": screenshot_path.as_deref().map(path_string),
+            "captured_at": Utc::now(),
+        }))
+    }
+}
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+#[async_trait::async_trait]
+impl<A: Actor<Msg = LlmMsg>> Actor for WebCaptureActor<A> {
+    type Msg = RawArtifact;
+
+    async fn handle(&mut self, artifact: Self::Msg, _ctx: &mut Context<Self>) -> Result<()> {
+        if let Some(cap) = &self.artifact_cap
+            && cap.stored(artifact.claim.id).await? >= cap.max
+        {
+            tracing::info!(
+                claim_id = %artifact.claim.id,
+                external_id = %artifact.external_id,
+                "web.capture.cap_reached"
+            );
+            return Ok(());
+        }
+        // Waiting for a slot here holds back the mailbox, so senders feel the limit.
+        let slot = self.slots.clone().acquire_owned().await?;
+        let job = CaptureJob {
+            capturer: self.capturer.clone(),
+            llm: self.llm.clone(),
+            out: self.out.clone(),
+            archive_dir: self.archive_dir.clone(),
+            headless: self.headless,
+            profile: self.profile.clone(),
+        };
+        tokio::spawn(async move {
+            if let Err(err) = job.run(artifact).await {
+                tracing::warn!(error = %err, "web.capture.forward_failed");
+            }
+            drop(slot);
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brave::artifact::hit_to_artifact;
+    use crate::brave::types::BraveHit;
+    use crate::browser::{CaptureOptions, FantocciniCapturer, PageDriver, PageDriverFactory};
+    use nowhere_actors::ClaimContext;
+    use nowhere_actors::actor::spawn_actor;
+    use nowhere_llm::mock::MockLlmClient;
+    use std::sync::Mutex;
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+    use uuid::Uuid;
+
+    /// Serves one fixed page.
+    struct FakeDriver;
+
+    #[async_trait::async_trait]
+    impl PageDriver for FakeDriver {
+        async fn goto(&mut self, _url: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn get_content(&mut self) -> Result<String> {
+            Ok("<html>bridge closed</html>".into())
+        }
+
+        async fn screenshot(&mut self) -> Result<Vec<u8>> {
+            Ok(b"\x89PNG".to_vec())
+        }
+
+        async fn close(self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Records the stealth profile of every session it opens.
+    #[derive(Default)]
+    struct FakeDrivers(Mutex<Vec<String>>);
+
+    #[async_trait::async_trait]
+    impl PageDriverFactory for Arc<FakeDrivers> {
+        type Driver = FakeDriver;
+
+        async fn open(&self, _headless: bool, profile: StealthProfile) -> Result<FakeDriver> {
+            self.0.lock().unwrap().push(format!("{profile:?}"));
+            Ok(FakeDriver)
+        }
+    }
+
+    /// Stands in for the LLM actor and hands over whatever it receives.
+    struct Recorder(mpsc::Sender<LlmMsg>);
+
+    #[async_trait::async_trait]
+    impl Actor for Recorder {
+        type Msg = LlmMsg;
+
+        async fn handle(&mut self, msg: LlmMsg, _ctx: &mut Context<Self>) -> Result<()> {
+            self.0.send(msg).await.ok();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn capture_is_attached_to_the_artifact_before_normalization() {
+        let drivers = Arc::new(FakeDrivers::default());
+        let capturer =
+            FantocciniCapturer::with_drivers(drivers.clone()).with_options(CaptureOptions {
+                screenshot: true,
+                ..CaptureOptions::default()
+            });
+        let (tx, mut forwarded) = mpsc::channel(4);
+        let out = spawn_actor(Recorder(tx), 4).addr;
+        let dir = std::env::temp_dir().join(format!("nowhere-capture-{}", Uuid::new_v4()));
+        let cfg = NowhereConfig {
+            stealth_level: StealthLevel::Maximum,
+            ..NowhereConfig::default()
+        };
+        let actor = WebCaptureActor::new(
+            Arc::new(capturer),
+            Arc::new(MockLlmClient::new()),
+            out,
+            &dir,
+        )
+        .with_config(&cfg);
+        let capture = spawn_actor(actor, 4).addr;
+
+        let hit = BraveHit {
+            rank: 1,
+            title: "Bridge closed".into(),
+            url: "https://news.example/bridge".into(),
+            description: None,
+        };
+        let claim = ClaimContext {
+            id: Uuid::new_v4(),
+            text: "the bridge is closed".into(),
+            tags: Vec::new(),
+        };
+        let artifact = hit_to_artifact(&hit, &claim, &CancellationToken::new());
+        capture.send(artifact).await.ok().unwrap();
+
+        let Some(LlmMsg::NormalizeArtifact(artifact)) = forwarded.recv().await else {
+            panic!("expected the artifact to be forwarded");
+        };
+        let html_path = artifact.payload["capture"]["html_path"].as_str().unwrap();
+        let png_path = artifact.payload["capture"]["screenshot_path"]
+            .as_str()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(html_path).unwrap(),
+            "<html>bridge closed</html>"
+        );
+        assert_eq!(std::fs::read(png_path).unwrap(), b"\x89PNG");
+        assert_eq!(*drivers.0.lock().unwrap(), ["Maximum"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
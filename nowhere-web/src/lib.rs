@@ -2,6 +2,8 @@
 //!
 //! - Brave Search API client (`brave`) for discovery
 //! - Browser capture trait and Fantoccini-backed implementation (`browser`)
+//! - Archival capture of web hits ahead of normalization (`capture`)
+//! - Brave search for a claim, forwarding hits to capture or normalization (`search`)
 //! - Lightweight HTML extraction (`extract`)
 //!
 //! Note: content extraction is intentionally minimal in v0.1; see FIXMEs in
@@ -9,4 +11,6 @@
 
 pub mod brave;
 pub mod browser;
+pub mod capture;
 pub mod extract;
+pub mod search;
@@ -0,0 +1,218 @@
+//! Brave web search next to the Twitter search.
+//!
+//! [`WebSearchActor`] takes the same `SearchCmd` as the Twitter workers, asks Brave for one
+//! page with its [`BraveSearchOptions`] and hands each hit on as a web `RawArtifact`:
+//! straight to the LLM actor, or to a [`WebCaptureActor`] that archives the page first.
+//! Brave narrows results by `freshness`, not by the command's dates. A failed search is
+//! logged and reported; the worker keeps serving the next claim.
+use crate::brave::artifact::hit_to_artifact;
+use crate::brave::client::{BraveApi, BraveSearchOptions, collect_brave_hits};
+use crate::capture::WebCaptureActor;
+use anyhow::{Result, anyhow};
+use nowhere_actors::actor::{Actor, Addr, Context};
+use nowhere_actors::llm::LlmActor;
+use nowhere_actors::store::ArtifactCap;
+use nowhere_actors::{LlmMsg, RawArtifact, SearchCmd, SearchOutcome};
+
+/// Where a web search sends its hits.
+pub enum WebOut {
+    /// Normalize each hit as Brave returned it.
+    Normalize(Addr<LlmActor>),
+    /// Archive each hit's page first; the capture actor forwards it for normalization.
+    Capture(Addr<WebCaptureActor>),
+}
+
+pub struct WebSearchActor {
+    api: BraveApi,
+    options: BraveSearchOptions,
+    out: WebOut,
+    artifact_cap: Option<ArtifactCap>,
+}
+
+impl WebSearchActor {
+    pub fn new(api: BraveApi, out: WebOut) -> Self {
+        Self {
+            api,
+            options: BraveSearchOptions::default(),
+            out,
+            artifact_cap: None,
+        }
+    }
+
+    /// Result count, freshness and verticals for every search (default: Brave's own).
+    pub fn with_options(mut self, options: BraveSearchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Forward no more hits for a claim once the store holds `cap.max` artifacts for it.
+    pub fn with_artifact_cap(mut self, cap: ArtifactCap) -> Self {
+        self.artifact_cap = Some(cap);
+        self
+    }
+
+    async fn forward(&self, artifact: RawArtifact) -> Result<()> {
+        let external_id = artifact.external_id.clone();
+        let delivered = match &self.out {
+            WebOut::Normalize(llm) => llm.send(LlmMsg::NormalizeArtifact(artifact)).await.is_ok(),
+            WebOut::Capture(capture) => capture.send(artifact).await.is_ok(),
+        };
+        if delivered {
+            Ok(())
+        } else {
+            Err(anyhow!("web hit mailbox dropped (artifact={external_id})"))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Actor for WebSearchActor {
+    type Msg = SearchCmd;
+
+    async fn handle(&mut self, msg: Self::Msg, _ctx: &mut Context<Self>) -> Result<()> {
+        let SearchCmd {
+            query,
+            claim,
+            cancel,
+            report,
+            budget,
+            ..
+        } = msg;
+        let report = |outcome: SearchOutcome| {
+            if let Some(tx) = &report {
+                let _ = tx.try_send(outcome);
+            }
+        };
+
+        if budget.as_ref().is_some_and(|b| b.enforce()) {
+            tracing::info!(claim_id = %claim.id, "web.search.budget_exhausted");
+            return Ok(());
+        }
+        let room = match &self.artifact_cap {
+            Some(cap) => Some(cap.max.saturating_sub(cap.stored(claim.id).await?)),
+            None => None,
+        };
+        if room == Some(0) {
+            tracing::info!(claim_id = %claim.id, "web.search.cap_reached");
+            report(SearchOutcome::CapReached(Vec::new()));
+            return Ok(());
+        }
+
+        let resp = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => {
+                tracing::info!(claim_id = %claim.id, "web.search.cancelled");
+                return Ok(());
+            }
+            resp = self.api.search(&query, &self.options) => resp,
+        };
+        let resp = match resp {
+            Ok(resp) => resp,
+            Err(err) => {
+                tracing::warn!(claim_id = %claim.id, error = %format!("{err:#}"), "web.search.failed");
+                report(SearchOutcome::Failed(format!("{err:#}")));
+                return Ok(());
+            }
+        };
+        let hits = collect_brave_hits(&resp);
+        tracing::info!(claim_id = %claim.id, hits = hits.len(), "web.search.outcome");
+
+        let mut artifacts: Vec<_> = hits
+            .iter()
+            .map(|hit| hit_to_artifact(hit, &claim, &cancel))
+            .collect();
+        let capped = room.is_some_and(|room| artifacts.len() >= room);
+        if let Some(room) = room {
+            artifacts.truncate(room);
+        }
+        let mut forwarded = Vec::with_capacity(artifacts.len());
+        for artifact in artifacts {
+            let external_id = artifact.external_id.clone();
+            self.forward(artifact).await?;
+            forwarded.push(external_id);
+        }
+        report(if capped {
+            SearchOutcome::CapReached(forwarded)
+        } else {
+            SearchOutcome::Done(forwarded)
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nowhere_actors::ClaimContext;
+    use nowhere_actors::actor::{probe, spawn_actor};
+    use tokio::sync::mpsc;
+    use tokio_util::sync::CancellationToken;
+    use uuid::Uuid;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn configured_options_reach_brave_and_hits_go_to_normalization() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/res/v1/web/search"))
+            .and(query_param("q", "bridge closed"))
+            .and(query_param("count", "5"))
+            .and(query_param("freshness", "pw"))
+            .and(query_param("result_filter", "web,news"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "type": "search",
+                "web": { "type": "search", "family_friendly": true, "results": [{
+                    "type": "search_result",
+                    "subtype": "generic",
+                    "is_live": false,
+                    "title": "Bridge closed",
+                    "url": "https://news.example/bridge",
+                    "description": "Closed on Monday."
+                }]},
+                "mixed": { "main": [{ "type": "web", "index": 0 }] }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let (llm, mut normalized) = probe::<LlmActor>(4);
+        let actor = WebSearchActor::new(
+            BraveApi::with_base_url("token".into(), &server.uri()).unwrap(),
+            WebOut::Normalize(llm),
+        )
+        .with_options(BraveSearchOptions {
+            count: Some(5),
+            freshness: "pw".parse().unwrap(),
+            result_filter: Some("web,news".parse().unwrap()),
+        });
+
+        let (tx, mut outcome) = mpsc::channel(1);
+        let now = chrono::Utc::now();
+        let cmd = SearchCmd {
+            query: "bridge closed".into(),
+            date_from: now - chrono::Duration::days(7),
+            date_to: now,
+            claim: ClaimContext {
+                id: Uuid::new_v4(),
+                text: "the bridge is closed".into(),
+                tags: Vec::new(),
+            },
+            cancel: CancellationToken::new(),
+            report: Some(tx),
+            budget: None,
+        };
+        let search = spawn_actor(actor, 1).addr;
+        search.send(cmd).await.ok().unwrap();
+
+        let Some(LlmMsg::NormalizeArtifact(artifact)) = normalized.recv().await else {
+            panic!("expected the hit to be normalized");
+        };
+        assert_eq!(artifact.external_id, "https://news.example/bridge");
+        assert_eq!(
+            outcome.recv().await,
+            Some(SearchOutcome::Done(vec![
+                "https://news.example/bridge".into()
+            ]))
+        );
+    }
+}